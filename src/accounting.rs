@@ -0,0 +1,322 @@
+use crate::archive;
+use crate::report::cpu_time;
+use crate::{format_bytes, format_uptime, freshness, truncate_name, App};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// psacct-like totals per command name over days, from the history archive,
+// so they span restarts. Processes that start and exit between two refreshes
+// are not seen.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum AccountingPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl AccountingPeriod {
+    fn days(&self) -> i64 {
+        match self {
+            AccountingPeriod::Day => 1,
+            AccountingPeriod::Week => 7,
+            AccountingPeriod::Month => 30,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            AccountingPeriod::Day => "last 24 hours",
+            AccountingPeriod::Week => "last 7 days",
+            AccountingPeriod::Month => "last 30 days",
+        }
+    }
+
+    fn next(&self) -> AccountingPeriod {
+        match self {
+            AccountingPeriod::Day => AccountingPeriod::Week,
+            AccountingPeriod::Week => AccountingPeriod::Month,
+            AccountingPeriod::Month => AccountingPeriod::Day,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccountingSort {
+    Cpu,
+    Read,
+    Written,
+}
+
+impl AccountingSort {
+    fn label(&self) -> &'static str {
+        match self {
+            AccountingSort::Cpu => "CPU time",
+            AccountingSort::Read => "bytes read",
+            AccountingSort::Written => "bytes written",
+        }
+    }
+
+    fn next(&self) -> AccountingSort {
+        match self {
+            AccountingSort::Cpu => AccountingSort::Read,
+            AccountingSort::Read => AccountingSort::Written,
+            AccountingSort::Written => AccountingSort::Cpu,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommandUsage {
+    pub command: String,
+    pub cpu_seconds: f64,
+    pub read_bytes: f64,
+    pub written_bytes: f64,
+    // Minutes in which the command used any CPU
+    pub active_minutes: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Usage {
+    pub commands: Vec<CommandUsage>,
+    // Archived minutes in the period; gaps are times the monitor wasn't running
+    pub minutes: usize,
+}
+
+impl Usage {
+    pub fn sorted(&self, sort: AccountingSort) -> Vec<&CommandUsage> {
+        let key = |usage: &CommandUsage| match sort {
+            AccountingSort::Cpu => usage.cpu_seconds,
+            AccountingSort::Read => usage.read_bytes,
+            AccountingSort::Written => usage.written_bytes,
+        };
+        let mut commands: Vec<&CommandUsage> = self.commands.iter().collect();
+        commands.sort_by(|a, b| key(b).total_cmp(&key(a)));
+        commands
+    }
+
+    fn total_cpu(&self) -> f64 {
+        self.commands.iter().map(|c| c.cpu_seconds).sum()
+    }
+}
+
+pub fn aggregate(period: AccountingPeriod) -> Usage {
+    let to = chrono::Utc::now().timestamp();
+    let records = archive::read(to - period.days() * 86400, to);
+    let mut commands: BTreeMap<&str, CommandUsage> = BTreeMap::new();
+    for record in &records {
+        for (command, seconds) in &record.cpu_seconds {
+            let usage = commands.entry(command).or_default();
+            usage.cpu_seconds += seconds;
+            usage.active_minutes += 1;
+        }
+        for (command, bytes) in &record.read_bytes {
+            commands.entry(command).or_default().read_bytes += bytes;
+        }
+        for (command, bytes) in &record.written_bytes {
+            commands.entry(command).or_default().written_bytes += bytes;
+        }
+    }
+    let commands = commands
+        .into_iter()
+        .map(|(command, usage)| CommandUsage { command: command.to_string(), ..usage })
+        .collect();
+    Usage { commands, minutes: records.len() }
+}
+
+// Reading a month of archive takes a while, so it happens off the UI thread
+// and is redone at most once a minute while the tab is open
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct Loaded {
+    usage: Option<(AccountingPeriod, Usage, Instant)>,
+    loading: bool,
+}
+
+pub struct Accounting {
+    pub period: AccountingPeriod,
+    pub sort: AccountingSort,
+    pub selected: usize,
+    loaded: Arc<Mutex<Loaded>>,
+}
+
+impl Default for Accounting {
+    fn default() -> Self {
+        Accounting {
+            period: AccountingPeriod::Week,
+            sort: AccountingSort::Cpu,
+            selected: 0,
+            loaded: Arc::new(Mutex::new(Loaded::default())),
+        }
+    }
+}
+
+impl Accounting {
+    // Called on refresh while the tab is open
+    pub fn reload_if_stale(&self) {
+        let mut loaded = self.loaded.lock().unwrap();
+        let fresh = loaded
+            .usage
+            .as_ref()
+            .is_some_and(|(period, _, at)| *period == self.period && at.elapsed() < RELOAD_INTERVAL);
+        if fresh || loaded.loading {
+            return;
+        }
+        loaded.loading = true;
+        let period = self.period;
+        let shared = Arc::clone(&self.loaded);
+        thread::spawn(move || {
+            let usage = aggregate(period);
+            let mut loaded = shared.lock().unwrap();
+            loaded.usage = Some((period, usage, Instant::now()));
+            loaded.loading = false;
+        });
+    }
+
+    pub fn next_period(&mut self) {
+        self.period = self.period.next();
+        self.selected = 0;
+        self.reload_if_stale();
+    }
+
+    pub fn next_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.selected = 0;
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let count = self.usage().map_or(0, |(usage, _)| usage.commands.len());
+        self.selected = self.selected.saturating_add_signed(delta).min(count.saturating_sub(1));
+    }
+
+    // The loaded usage if it is for the selected period, and when it was read
+    fn usage(&self) -> Option<(Usage, Instant)> {
+        let loaded = self.loaded.lock().unwrap();
+        loaded
+            .usage
+            .as_ref()
+            .filter(|(period, _, _)| *period == self.period)
+            .map(|(_, usage, at)| (usage.clone(), *at))
+    }
+
+    fn loading(&self) -> bool {
+        self.loaded.lock().unwrap().loading
+    }
+}
+
+fn average_cpu(usage: &CommandUsage, minutes: usize) -> String {
+    if minutes == 0 {
+        return "-".to_string();
+    }
+    format!("{:.1}%", usage.cpu_seconds / (minutes as f64 * 60.0) * 100.0)
+}
+
+fn cpu_share(usage: &CommandUsage, total: f64) -> String {
+    if total <= 0.0 {
+        return "-".to_string();
+    }
+    format!("{:.0}%", usage.cpu_seconds / total * 100.0)
+}
+
+pub fn draw_accounting_tab(f: &mut Frame, area: Rect, app: &App) {
+    let accounting = &app.accounting;
+    let block = Block::default().borders(Borders::ALL);
+    if app.archive.is_none() {
+        let hint = Paragraph::new(
+            "Process accounting adds up CPU time and disk I/O per command from the history archive. \
+             Turn the archive on with [archive] enabled = true (a [report] table does too).",
+        )
+        .wrap(Wrap { trim: true })
+        .block(block.title("📒 Accounting"));
+        f.render_widget(hint, area);
+        return;
+    }
+    let Some((usage, read_at)) = accounting.usage() else {
+        let title = freshness::title("📒 Accounting", "read", None, accounting.loading());
+        f.render_widget(Paragraph::new("Reading the history archive…").block(block.title(title)), area);
+        return;
+    };
+
+    let total = usage.total_cpu();
+    let header = Row::new(vec!["Command", "CPU time", "Share", "Avg CPU", "Read", "Written", "Active"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = usage
+        .sorted(accounting.sort)
+        .into_iter()
+        .map(|c| {
+            Row::new(vec![
+                c.command.clone(),
+                cpu_time(c.cpu_seconds),
+                cpu_share(c, total),
+                average_cpu(c, usage.minutes),
+                format_bytes(c.read_bytes as u64),
+                format_bytes(c.written_bytes as u64),
+                format_uptime(c.active_minutes * 60),
+            ])
+        })
+        .collect();
+    let title = format!(
+        "📒 Accounting — {}, {} of {} minutes recorded, by {} · t period · s sort",
+        accounting.period.label(),
+        usage.minutes,
+        accounting.period.days() * 1440,
+        accounting.sort.label()
+    );
+    let title = freshness::title(&title, "read", Some(read_at), accounting.loading());
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),
+            Constraint::Length(10),
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header)
+    .block(block.title(title))
+    .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+    .column_spacing(1);
+    let mut state = TableState::default().with_selected((!usage.commands.is_empty()).then_some(accounting.selected));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+// --accounting: the busiest commands as plain text
+pub fn print(period: AccountingPeriod) {
+    let usage = aggregate(period);
+    let total = usage.total_cpu();
+    println!(
+        "Process accounting, {}: {} of {} minutes recorded\n",
+        period.label(),
+        usage.minutes,
+        period.days() * 1440
+    );
+    println!(
+        "{:<24} {:>10} {:>6} {:>8} {:>10} {:>10} {:>10}",
+        "Command", "CPU time", "Share", "Avg CPU", "Read", "Written", "Active"
+    );
+    for c in usage.sorted(AccountingSort::Cpu).into_iter().take(25) {
+        println!(
+            "{:<24} {:>10} {:>6} {:>8} {:>10} {:>10} {:>10}",
+            truncate_name(&c.command, 24),
+            cpu_time(c.cpu_seconds),
+            cpu_share(c, total),
+            average_cpu(c, usage.minutes),
+            format_bytes(c.read_bytes as u64),
+            format_bytes(c.written_bytes as u64),
+            format_uptime(c.active_minutes * 60)
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::kill::ActionResult;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use sysinfo::{Cpu, Pid, Process};
+
+// The cores the selected process may run on, from A on the Processes tab,
+// and a popup to change them: pinning a noisy neighbour to a few cores keeps
+// the others free for a latency-sensitive service. Linux keeps the mask per
+// thread, so applying it sets every thread, like taskset -a. Anyone may
+// change the affinity of their own processes; other users' need root (or
+// CAP_SYS_NICE). Linux only. Rows are the online CPUs by their kernel ids,
+// which skip offline ones, so "3" in the popup is CPU 3 to taskset too.
+pub const PER_LINE: usize = 8;
+// CPUs a cpu_set_t holds; higher ids can't be set
+#[cfg(target_os = "linux")]
+const SET_SIZE: usize = libc::CPU_SETSIZE as usize;
+
+pub struct AffinityEditor {
+    pub pid: Pid,
+    name: String,
+    start_time: u64,
+    // Kernel id of each online core, ascending
+    ids: Vec<usize>,
+    // One entry per online core, as read when the popup opened
+    allowed: Vec<bool>,
+    edited: Vec<bool>,
+    cursor: usize,
+    pub error: Option<String>,
+}
+
+impl AffinityEditor {
+    // cpus is sysinfo's count, for when the online list can't be read
+    pub fn open(pid: Pid, process: &Process, cpus: usize) -> AffinityEditor {
+        let ids = std::fs::read_to_string("/sys/devices/system/cpu/online")
+            .ok()
+            .and_then(|online| parse_cpu_list(online.trim()))
+            .unwrap_or_else(|| (0..cpus).collect());
+        let (allowed, error) = match get_affinity(pid, &ids) {
+            Ok(allowed) => (allowed, None),
+            Err(err) => (vec![false; ids.len()], Some(err)),
+        };
+        AffinityEditor {
+            pid,
+            name: process.name().to_string_lossy().into_owned(),
+            start_time: process.start_time(),
+            ids,
+            edited: allowed.clone(),
+            allowed,
+            cursor: 0,
+            error,
+        }
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        self.cursor = self.cursor.saturating_add_signed(delta).min(self.edited.len().saturating_sub(1));
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(allowed) = self.edited.get_mut(self.cursor) {
+            *allowed = !*allowed;
+        }
+    }
+
+    pub fn select_all(&mut self) {
+        self.edited.iter_mut().for_each(|allowed| *allowed = true);
+    }
+
+    pub fn invert(&mut self) {
+        self.edited.iter_mut().for_each(|allowed| *allowed = !*allowed);
+    }
+
+    // Enter; the process must still be the one the popup opened for
+    pub fn apply(&self, process: Option<&Process>) -> ActionResult {
+        let label = format!("{} ({})", self.name, self.pid);
+        let Some(process) = process.filter(|process| process.start_time() == self.start_time) else {
+            return ActionResult::new(format!("❌ Setting the CPU affinity of {} failed: it already exited", label), true);
+        };
+        if !self.edited.contains(&true) {
+            return ActionResult::new(format!("❌ {} needs at least one core to run on", label), true);
+        }
+        if self.edited == self.allowed {
+            return ActionResult::new(format!("{} keeps cores {}", label, cpu_list(&self.cores(&self.allowed))), false);
+        }
+        match set_affinity(self.pid, process, &self.cores(&self.edited)) {
+            Ok(()) => ActionResult::new(
+                format!(
+                    "📌 CPU affinity of {}: {} → {}",
+                    label,
+                    cpu_list(&self.cores(&self.allowed)),
+                    cpu_list(&self.cores(&self.edited))
+                ),
+                false,
+            ),
+            Err(err) => ActionResult::new(format!("❌ Setting the CPU affinity of {} failed: {}", label, err), true),
+        }
+    }
+
+    // Kernel ids of the rows that are set
+    fn cores(&self, allowed: &[bool]) -> Vec<usize> {
+        self.ids.iter().zip(allowed).filter(|(_, allowed)| **allowed).map(|(id, _)| *id).collect()
+    }
+
+    // cpus are sysinfo's, one per online core in the same order as the rows
+    pub fn draw(&self, f: &mut Frame, area: Rect, cpus: &[Cpu], read_only: bool) {
+        let mut lines = vec![Line::from(format!("Allowed now: {}", cpu_list(&self.cores(&self.allowed)))), Line::from("")];
+        match &self.error {
+            Some(error) => lines.push(Line::styled(error.clone(), Style::default().fg(Color::Red))),
+            None => {
+                // "[x] 0  12%" per core, PER_LINE to a line, with each core's load to pick quiet ones
+                for (start, chunk) in self.edited.chunks(PER_LINE).enumerate().map(|(row, chunk)| (row * PER_LINE, chunk)) {
+                    let spans: Vec<Span> = chunk
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, allowed)| {
+                            let core = start + offset;
+                            let usage = cpus.get(core).map_or(0.0, |cpu| cpu.cpu_usage());
+                            let text = format!("[{}] {:<3}{:>4.0}%  ", if *allowed { 'x' } else { ' ' }, self.ids[core], usage);
+                            let mut style = match (*allowed, self.allowed.get(core) == Some(allowed)) {
+                                (_, false) => Style::default().fg(Color::Yellow),
+                                (true, true) => Style::default().fg(Color::Green),
+                                (false, true) => Style::default().fg(Color::DarkGray),
+                            };
+                            if core == self.cursor {
+                                style = style.add_modifier(Modifier::REVERSED);
+                            }
+                            Span::styled(text, style)
+                        })
+                        .collect();
+                    lines.push(Line::from(spans));
+                }
+                if self.edited != self.allowed {
+                    lines.push(Line::from(""));
+                    lines.push(Line::styled(format!("New: {}", cpu_list(&self.cores(&self.edited))), Style::default().fg(Color::Yellow)));
+                }
+            }
+        }
+        lines.push(Line::from(""));
+        let keys = if read_only || self.error.is_some() {
+            "Esc close"
+        } else {
+            "←/→ ↑/↓ move · Space toggle · a all · i invert · Enter apply to all threads · Esc cancel"
+        };
+        lines.push(Line::styled(keys, Style::default().fg(Color::Yellow)));
+        let dialog = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .title(format!("📌 CPU affinity of {} ({})", self.name, self.pid))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(dialog, area);
+    }
+}
+
+// "0-3,6" for ascending core ids, like taskset -c
+fn cpu_list(cores: &[usize]) -> String {
+    let mut ranges: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < cores.len() {
+        let start = i;
+        while i + 1 < cores.len() && cores[i + 1] == cores[i] + 1 {
+            i += 1;
+        }
+        ranges.push(if start == i { cores[i].to_string() } else { format!("{}-{}", cores[start], cores[i]) });
+        i += 1;
+    }
+    if ranges.is_empty() {
+        "none".to_string()
+    } else {
+        ranges.join(",")
+    }
+}
+
+// The kernel's "0-3,6,8-11" format of /sys/devices/system/cpu/online
+fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+    let mut cores = Vec::new();
+    for range in list.split(',') {
+        match range.split_once('-') {
+            Some((start, end)) => cores.extend(start.parse::<usize>().ok()?..=end.parse().ok()?),
+            None => cores.push(range.parse().ok()?),
+        }
+    }
+    cores.sort_unstable();
+    cores.dedup();
+    (!cores.is_empty()).then_some(cores)
+}
+
+#[cfg(target_os = "linux")]
+fn get_affinity(pid: Pid, ids: &[usize]) -> Result<Vec<bool>, String> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::sched_getaffinity(pid.as_u32() as libc::pid_t, std::mem::size_of::<libc::cpu_set_t>(), &mut set) };
+    if result != 0 {
+        return Err(affinity_error(std::io::Error::last_os_error()));
+    }
+    Ok(ids.iter().map(|id| *id < SET_SIZE && unsafe { libc::CPU_ISSET(*id, &set) }).collect())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_affinity(_pid: Pid, _ids: &[usize]) -> Result<Vec<bool>, String> {
+    Err(format!("CPU affinity is not supported on {}", crate::capabilities::platform()))
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(pid: Pid, process: &Process, cores: &[usize]) -> Result<(), String> {
+    if let Some(core) = cores.iter().find(|core| **core >= SET_SIZE) {
+        return Err(format!("CPU {} is beyond the {} an affinity mask holds", core, SET_SIZE));
+    }
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for core in cores {
+        unsafe { libc::CPU_SET(*core, &mut set) };
+    }
+    let apply = |pid: Pid| unsafe { libc::sched_setaffinity(pid.as_u32() as libc::pid_t, std::mem::size_of::<libc::cpu_set_t>(), &set) } == 0;
+    // The main thread decides; a thread that exits meanwhile doesn't matter
+    if !apply(pid) {
+        return Err(affinity_error(std::io::Error::last_os_error()));
+    }
+    for task in process.tasks().into_iter().flatten().filter(|task| **task != pid) {
+        apply(*task);
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_affinity(_pid: Pid, _process: &Process, _cores: &[usize]) -> Result<(), String> {
+    Err("not supported on this platform".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn affinity_error(err: std::io::Error) -> String {
+    match err.raw_os_error() {
+        Some(libc::EPERM) => format!("it belongs to another user ({})", crate::privilege::root_hint()),
+        Some(libc::ESRCH) => "it already exited".to_string(),
+        // Cores outside its cpuset cgroup
+        Some(libc::EINVAL) => "none of those cores are in its cpuset".to_string(),
+        _ => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_cores_as_ranges() {
+        assert_eq!(cpu_list(&[]), "none");
+        assert_eq!(cpu_list(&[2]), "2");
+        assert_eq!(cpu_list(&[0, 1, 2, 3]), "0-3");
+        assert_eq!(cpu_list(&[0, 1, 2, 3, 6, 8, 9]), "0-3,6,8-9");
+        // Offline cores in between break the range
+        assert_eq!(cpu_list(&[0, 2, 3, 5]), "0,2-3,5");
+    }
+
+    #[test]
+    fn parses_the_kernel_cpu_list() {
+        assert_eq!(parse_cpu_list("0"), Some(vec![0]));
+        assert_eq!(parse_cpu_list("0-3,6,8-9"), Some(vec![0, 1, 2, 3, 6, 8, 9]));
+        assert_eq!(parse_cpu_list("4,0-1"), Some(vec![0, 1, 4]));
+        assert_eq!(parse_cpu_list(""), None);
+        assert_eq!(parse_cpu_list("0-x"), None);
+    }
+
+    #[test]
+    fn cpu_list_reverses_parsing() {
+        for list in ["0-7", "0,2-3,5", "1-2,1024-1027"] {
+            assert_eq!(cpu_list(&parse_cpu_list(list).unwrap()), list);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use crate::{freshness, steal, App};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table, TableState},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+
+// Conditions that need attention right now. They are re-evaluated after every
+// refresh and shown in the title bar until the condition clears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub severity: Severity,
+    // Stable identity of the condition, e.g. "disk:/home"; the message may change
+    pub source: String,
+    pub message: String,
+}
+
+// An alert that started (or changed severity) or cleared since the previous
+// evaluation; notifications are sent for these, not for every refresh
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertChange {
+    Fired(Alert),
+    Resolved(Alert),
+}
+
+// Alert source patterns: exact, or a prefix with a trailing *
+pub fn source_matches(pattern: &str, source: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => source.starts_with(prefix),
+        None => source == pattern,
+    }
+}
+
+pub fn changes(previous: &[Alert], current: &[Alert]) -> Vec<AlertChange> {
+    let fired = current
+        .iter()
+        .filter(|alert| !previous.iter().any(|p| p.source == alert.source && p.severity == alert.severity))
+        .cloned()
+        .map(AlertChange::Fired);
+    let resolved = previous
+        .iter()
+        .filter(|alert| !current.iter().any(|c| c.source == alert.source))
+        .cloned()
+        .map(AlertChange::Resolved);
+    fired.chain(resolved).collect()
+}
+
+// Retransmit percentages over a handful of segments are just noise
+const MIN_SEGMENTS_FOR_RETRANSMIT_ALERT: u64 = 100;
+
+pub fn evaluate(app: &App) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    let thresholds = &app.thresholds;
+
+    let sockets = &app.sockets;
+    if sockets.segments_sent >= MIN_SEGMENTS_FOR_RETRANSMIT_ALERT {
+        let severity = if sockets.retransmit_percent >= thresholds.retransmit_crit {
+            Some(Severity::Critical)
+        } else if sockets.retransmit_percent >= thresholds.retransmit_warn {
+            Some(Severity::Warning)
+        } else {
+            None
+        };
+        if let Some(severity) = severity {
+            alerts.push(Alert {
+                severity,
+                source: "retransmits".to_string(),
+                message: format!("TCP retransmits at {:.1}%", sockets.retransmit_percent),
+            });
+        }
+    }
+
+    for disk in &app.disks {
+        let total = disk.total_space();
+        if total == 0 {
+            continue;
+        }
+        let usage = (total - disk.available_space()) as f64 / total as f64 * 100.0;
+        let severity = if usage >= thresholds.disk_crit {
+            Severity::Critical
+        } else if usage >= thresholds.disk_warn {
+            Severity::Warning
+        } else {
+            continue;
+        };
+        let mount = disk.mount_point().display().to_string();
+        alerts.push(Alert {
+            severity,
+            source: format!("disk:{}", mount),
+            message: format!("{} is {:.0}% full", mount, usage),
+        });
+    }
+
+    // Latency per mounted device; partitions of one disk each get their own
+    for disk in &app.disks {
+        let Some(latency) = app.disk_latency.for_device(&disk.name().to_string_lossy()) else { continue };
+        let Some(average) = latency.average else { continue };
+        let severity = if average >= thresholds.io_latency_crit {
+            Severity::Critical
+        } else if average >= thresholds.io_latency_warn {
+            Severity::Warning
+        } else {
+            continue;
+        };
+        let mount = disk.mount_point().display().to_string();
+        alerts.push(Alert {
+            severity,
+            source: format!("io_latency:{}", mount),
+            message: format!("{} I/O takes {:.1} ms on average", mount, average),
+        });
+    }
+
+    // Always checked: a full /run or /tmp breaks things whatever tab is open
+    for tmpfs in &app.tmpfs.mounts {
+        let usage = tmpfs.usage();
+        let severity = if usage >= thresholds.tmpfs_crit {
+            Severity::Critical
+        } else if usage >= thresholds.tmpfs_warn {
+            Severity::Warning
+        } else {
+            continue;
+        };
+        let what = if tmpfs.inodes_fuller() { "of its inodes used" } else { "full" };
+        alerts.push(Alert {
+            severity,
+            source: format!("tmpfs:{}", tmpfs.mount),
+            message: format!("{} (tmpfs) is {:.0}% {}", tmpfs.mount, usage, what),
+        });
+    }
+
+    // Sustained rather than momentary, and named for what it is: people on a
+    // VPS tend to blame their own processes for a busy host
+    if let Some(steal) = steal::sustained(&app.history) {
+        let severity = if steal >= thresholds.steal_crit {
+            Some(Severity::Critical)
+        } else if steal >= thresholds.steal_warn {
+            Some(Severity::Warning)
+        } else {
+            None
+        };
+        if let Some(severity) = severity {
+            alerts.push(Alert {
+                severity,
+                source: "steal".to_string(),
+                message: format!(
+                    "CPU steal {:.1}% over 5 min: host contention from other VMs, not load inside this one",
+                    steal
+                ),
+            });
+        }
+    }
+
+    for rule in &app.rules {
+        if rule.matches(app) {
+            alerts.push(Alert {
+                severity: rule.severity,
+                source: format!("rule:{}", rule.name),
+                message: rule.message.clone(),
+            });
+        }
+    }
+
+    for anomaly in app.anomalies.anomalies() {
+        alerts.push(Alert {
+            severity: Severity::Warning,
+            source: format!("anomaly:{}", anomaly.metric),
+            message: anomaly.message.clone(),
+        });
+    }
+
+    for check in &app.health_checks {
+        if let Some(error) = check.last().and_then(|r| r.error) {
+            alerts.push(Alert {
+                severity: Severity::Critical,
+                source: format!("health:{}", check.title()),
+                message: format!("{} is down: {}", check.title(), error),
+            });
+        }
+    }
+
+    for check in &app.port_checks {
+        if let Some(error) = check.last().and_then(|r| r.error) {
+            alerts.push(Alert {
+                severity: Severity::Critical,
+                source: format!("port:{}", check.title()),
+                message: format!("{} is unreachable: {}", check.title(), error),
+            });
+        }
+    }
+
+    if let Some(kubernetes) = &app.kubernetes {
+        alerts.extend(kubernetes.alerts());
+    }
+    alerts.extend(app.ssh.alerts());
+    alerts.extend(app.power.alerts());
+    if let Some(cloud) = &app.cloud {
+        alerts.extend(cloud.alerts());
+    }
+
+    alerts.sort_by_key(|a| std::cmp::Reverse(a.severity));
+    alerts
+}
+
+pub fn draw_alerts_tab(f: &mut Frame, area: Rect, app: &App) {
+    let silences = &app.silences;
+    let silence_rows = silences.manual.len() + silences.windows.len();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(6), Constraint::Length(silence_rows.clamp(1, 10) as u16 + 3)])
+        .split(area);
+
+    let header = Row::new(vec!["Severity", "Source", "Message", "Silenced"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = app
+        .alerts
+        .iter()
+        .map(|alert| {
+            let silenced = silences.silenced_by(alert);
+            let color = match (&silenced, alert.severity) {
+                (Some(_), _) => Color::DarkGray,
+                (None, Severity::Critical) => Color::Red,
+                (None, Severity::Warning) => Color::Yellow,
+            };
+            Row::new(vec![
+                Cell::from(alert.severity.name()),
+                Cell::from(alert.source.clone()),
+                Cell::from(alert.message.clone()),
+                Cell::from(silenced.map(|s| format!("🔕 {}", s)).unwrap_or_default()),
+            ])
+            .style(Style::default().fg(color))
+        })
+        .collect();
+    let title = if app.alerts.is_empty() {
+        "🚨 Alerts — all clear".to_string()
+    } else {
+        format!(
+            "🚨 Alerts — z silence {m}m · Z silence all {m}m · u/U lift",
+            m = app.config.silence_minutes
+        )
+    };
+    // Re-evaluated on every refresh
+    let title = freshness::title(&title, "checked", Some(app.last_update), false);
+    let table = Table::new(
+        rows,
+        [Constraint::Length(9), Constraint::Length(24), Constraint::Min(20), Constraint::Length(24)],
+    )
+    .header(header)
+    .block(Block::default().title(title).borders(Borders::ALL))
+    .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+    .column_spacing(1);
+    let mut state = TableState::default().with_selected((!app.alerts.is_empty()).then_some(app.selected_alert));
+    f.render_stateful_widget(table, chunks[0], &mut state);
+
+    let header = Row::new(vec!["Match", "Until / schedule", "Source"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let now = chrono::Local::now();
+    let manual = silences.manual.iter().map(|silence| {
+        Row::new(vec![silence.pattern.clone(), silence.until(), "TUI".to_string()])
+            .style(Style::default().fg(Color::Magenta))
+    });
+    let windows = silences.windows.iter().map(|window| {
+        let active = window.active_at(now);
+        Row::new(vec![
+            window.pattern.clone(),
+            window.schedule(),
+            format!("window {}{}", window.name, if active { " (active)" } else { "" }),
+        ])
+        .style(Style::default().fg(if active { Color::Magenta } else { Color::Gray }))
+    });
+    let table = Table::new(
+        manual.chain(windows).collect::<Vec<_>>(),
+        [Constraint::Length(24), Constraint::Length(28), Constraint::Min(20)],
+    )
+    .header(header)
+    .block(Block::default().title("🔕 Silences & maintenance windows").borders(Borders::ALL))
+    .column_spacing(1);
+    f.render_widget(table, chunks[1]);
+}
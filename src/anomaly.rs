@@ -0,0 +1,403 @@
+use crate::format_bytes;
+use crate::history::History;
+use crate::Collector;
+use chrono::{Local, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+// Flags history metrics that stray from their usual level, learned per hour
+// of the day from one-minute averages, so "busy at 3am" stands out without
+// a threshold. The baseline is kept across restarts in
+// ~/.local/share/system-monitor/baseline.json.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AnomalyConfig {
+    pub enabled: bool,
+    // History metrics to watch: cpu, memory, swap, load, net_rx, net_tx
+    pub metrics: Vec<String>,
+    // How many standard deviations from the usual level count as unusual
+    pub sigma: f64,
+    // Minutes of data an hour of the day needs before it is used; until then
+    // the whole-day baseline stands in
+    pub warmup_minutes: u64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        AnomalyConfig {
+            enabled: true,
+            metrics: ["cpu", "memory", "load", "net_rx", "net_tx"].map(String::from).to_vec(),
+            sigma: 3.0,
+            warmup_minutes: 30,
+        }
+    }
+}
+
+impl AnomalyConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(metric) = self.metrics.iter().find(|m| metric(m).is_none()) {
+            return Err(format!(
+                "anomaly: unknown metric '{}' (known: {})",
+                metric,
+                METRICS.iter().map(|m| m.name).collect::<Vec<_>>().join(", ")
+            ));
+        }
+        if self.sigma.is_nan() || self.sigma <= 0.0 {
+            return Err("anomaly: sigma must be above 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+// History metrics with a display name and unit
+pub struct Metric {
+    pub name: &'static str,
+    pub label: &'static str,
+    // Spread below this is treated as this, so a metric that barely moves
+    // isn't flagged for a change nobody would notice
+    min_deviation: f64,
+    pub format: fn(f64) -> String,
+}
+
+pub const METRICS: &[Metric] = &[
+    Metric { name: "cpu", label: "CPU", min_deviation: 2.0, format: percent },
+    Metric { name: "memory", label: "Memory", min_deviation: 1.0, format: percent },
+    Metric { name: "swap", label: "Swap", min_deviation: 1.0, format: percent },
+    Metric { name: "load", label: "Load", min_deviation: 0.2, format: load },
+    Metric { name: "net_rx", label: "Network receive", min_deviation: 10_240.0, format: rate },
+    Metric { name: "net_tx", label: "Network send", min_deviation: 10_240.0, format: rate },
+];
+
+pub fn metric(name: &str) -> Option<&'static Metric> {
+    METRICS.iter().find(|m| m.name == name)
+}
+
+fn percent(value: f64) -> String {
+    format!("{:.0}%", value)
+}
+
+fn load(value: f64) -> String {
+    format!("{:.2}", value)
+}
+
+fn rate(value: f64) -> String {
+    format!("{}/s", format_bytes(value.max(0.0) as u64))
+}
+
+// Exponentially weighted mean and variance. Early on every sample counts the
+// same; later the oldest fade out so the baseline follows gradual change.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+struct Bucket {
+    mean: f64,
+    variance: f64,
+    count: u64,
+}
+
+// Weight of a new one-minute average once the bucket is warm: 1% keeps
+// roughly the last few days of that hour
+const ALPHA: f64 = 0.01;
+
+impl Bucket {
+    fn update(&mut self, value: f64) {
+        self.count += 1;
+        let alpha = (1.0 / self.count as f64).max(ALPHA);
+        let diff = value - self.mean;
+        self.mean += alpha * diff;
+        self.variance = (1.0 - alpha) * (self.variance + alpha * diff * diff);
+    }
+}
+
+// 24 buckets, one per local hour, then one for the whole day
+const WHOLE_DAY: usize = 24;
+
+#[derive(Debug, Clone)]
+pub struct Anomaly {
+    pub metric: &'static str,
+    pub message: String,
+}
+
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    baseline: BTreeMap<String, Vec<Bucket>>,
+    // Minute (Unix seconds / 60) whose average is learned once it is over
+    minute: i64,
+    anomalies: Vec<Anomaly>,
+    last_save: Instant,
+}
+
+// Saved now and then as well as on exit, so a crash or headless run keeps most of it
+const SAVE_INTERVAL: Duration = Duration::from_secs(600);
+
+impl AnomalyDetector {
+    pub fn new(config: AnomalyConfig) -> AnomalyDetector {
+        let baseline = if config.enabled { load_baseline() } else { BTreeMap::new() };
+        AnomalyDetector {
+            config,
+            baseline,
+            minute: Local::now().timestamp() / 60,
+            anomalies: Vec::new(),
+            last_save: Instant::now(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled && !self.config.metrics.is_empty()
+    }
+
+    pub fn anomalies(&self) -> &[Anomaly] {
+        &self.anomalies
+    }
+
+    pub fn collectors(&self) -> Vec<Collector> {
+        if self.enabled() && self.config.metrics.iter().any(|m| m.starts_with("net_")) {
+            vec![Collector::Networks]
+        } else {
+            Vec::new()
+        }
+    }
+
+    // Called after every history sample
+    pub fn update(&mut self, history: &History) {
+        if !self.enabled() {
+            return;
+        }
+        let now = Local::now();
+        let minute = now.timestamp() / 60;
+        if minute != self.minute {
+            self.learn(history, self.minute);
+            self.minute = minute;
+        }
+
+        let since = now.timestamp() - 60;
+        let hour = now.hour() as usize;
+        self.anomalies = self
+            .config
+            .metrics
+            .iter()
+            .filter_map(|name| {
+                let metric = metric(name)?;
+                let current = average(history, name, since, i64::MAX)?;
+                let buckets = self.baseline.get(name)?;
+                let warm = |b: &&Bucket| b.count >= self.config.warmup_minutes.max(1);
+                let (bucket, when) = match Some(&buckets[hour]).filter(warm) {
+                    Some(bucket) => (bucket, " for this time of day"),
+                    None => (Some(&buckets[WHOLE_DAY]).filter(warm)?, ""),
+                };
+                let deviation = bucket.variance.sqrt().max(metric.min_deviation);
+                let sigmas = (current - bucket.mean) / deviation;
+                if sigmas.abs() < self.config.sigma {
+                    return None;
+                }
+                Some(Anomaly {
+                    metric: metric.name,
+                    message: format!(
+                        "{} is {:.1}σ {} its usual level{} ({} vs usual {})",
+                        metric.label,
+                        sigmas.abs(),
+                        if sigmas > 0.0 { "above" } else { "below" },
+                        when,
+                        (metric.format)(current),
+                        (metric.format)(bucket.mean),
+                    ),
+                })
+            })
+            .collect();
+
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.last_save = Instant::now();
+            let _ = self.save();
+        }
+    }
+
+    // Fold a finished minute's averages into its hour and the whole day
+    fn learn(&mut self, history: &History, minute: i64) {
+        let Some(hour) = Local.timestamp_opt(minute * 60, 0).single().map(|t| t.hour() as usize) else {
+            return;
+        };
+        for name in &self.config.metrics {
+            let Some(value) = average(history, name, minute * 60, minute * 60 + 60) else {
+                continue;
+            };
+            let buckets = self
+                .baseline
+                .entry(name.clone())
+                .or_insert_with(|| vec![Bucket::default(); WHOLE_DAY + 1]);
+            buckets[hour].update(value);
+            buckets[WHOLE_DAY].update(value);
+        }
+    }
+
+    // One line for the System Information panel
+    pub fn status(&self) -> String {
+        let warmup = self.config.warmup_minutes.max(1);
+        let learned = self
+            .config
+            .metrics
+            .iter()
+            .filter_map(|name| self.baseline.get(name))
+            .map(|buckets| buckets[WHOLE_DAY].count)
+            .min()
+            .unwrap_or(0);
+        if learned < warmup {
+            format!("📐 Anomaly detection: learning, {} of {} minutes", learned, warmup)
+        } else {
+            format!("📐 Anomaly detection: {} metrics, {} unusual", self.config.metrics.len(), self.anomalies.len())
+        }
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("system-monitor").join("baseline.json"))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        if !self.enabled() {
+            return Ok(());
+        }
+        let path = AnomalyDetector::path().ok_or("no data directory available")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::write_atomic(&path, serde_json::to_string(&self.baseline)?)?;
+        Ok(())
+    }
+}
+
+// An unreadable baseline is relearned rather than refusing to start
+fn load_baseline() -> BTreeMap<String, Vec<Bucket>> {
+    AnomalyDetector::path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str::<BTreeMap<String, Vec<Bucket>>>(&contents).ok())
+        .map(|baseline| baseline.into_iter().filter(|(_, buckets)| buckets.len() == WHOLE_DAY + 1).collect())
+        .unwrap_or_default()
+}
+
+fn average(history: &History, metric: &str, from: i64, to: i64) -> Option<f64> {
+    let values: Vec<f64> = history
+        .series(metric)?
+        .iter()
+        .filter(|(t, _)| *t >= from && *t < to)
+        .map(|(_, v)| *v)
+        .filter(|v| v.is_finite())
+        .collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(mean: f64, deviation: f64, count: u64) -> Bucket {
+        Bucket { mean, variance: deviation * deviation, count }
+    }
+
+    // Watching cpu, with this hour's bucket and the whole day's
+    fn detector(hour: Bucket, whole_day: Bucket) -> AnomalyDetector {
+        let mut buckets = vec![Bucket::default(); WHOLE_DAY + 1];
+        buckets[Local::now().hour() as usize] = hour;
+        buckets[WHOLE_DAY] = whole_day;
+        AnomalyDetector {
+            config: AnomalyConfig { metrics: vec!["cpu".to_string()], ..AnomalyConfig::default() },
+            baseline: [("cpu".to_string(), buckets)].into(),
+            // A minute with no samples, so the first update learns nothing
+            minute: 0,
+            anomalies: Vec::new(),
+            last_save: Instant::now(),
+        }
+    }
+
+    fn messages(detector: &mut AnomalyDetector, cpu: f64) -> Vec<String> {
+        let mut history = History::default();
+        history.record("cpu", cpu);
+        detector.update(&history);
+        detector.anomalies().iter().map(|anomaly| anomaly.message.clone()).collect()
+    }
+
+    #[test]
+    fn early_samples_count_the_same() {
+        let mut bucket = Bucket::default();
+        for value in [2.0, 4.0, 6.0] {
+            bucket.update(value);
+        }
+        assert_eq!(bucket.count, 3);
+        assert!((bucket.mean - 4.0).abs() < 1e-9);
+        assert!(bucket.variance > 0.0);
+    }
+
+    #[test]
+    fn later_samples_only_nudge_the_mean() {
+        let mut bucket = bucket(10.0, 0.0, 1000);
+        bucket.update(110.0);
+        assert!((bucket.mean - 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn flags_values_far_from_this_hour() {
+        let mut detector = detector(bucket(10.0, 2.0, 60), bucket(40.0, 2.0, 60));
+        assert_eq!(
+            messages(&mut detector, 50.0),
+            ["CPU is 20.0σ above its usual level for this time of day (50% vs usual 10%)"]
+        );
+        assert_eq!(messages(&mut detector, 5.0), Vec::<String>::new());
+        assert_eq!(messages(&mut detector, 15.0), Vec::<String>::new());
+    }
+
+    #[test]
+    fn flags_values_below_too() {
+        let mut detector = detector(bucket(80.0, 5.0, 60), bucket(80.0, 5.0, 60));
+        assert_eq!(
+            messages(&mut detector, 20.0),
+            ["CPU is 12.0σ below its usual level for this time of day (20% vs usual 80%)"]
+        );
+    }
+
+    #[test]
+    fn a_quiet_metric_needs_a_noticeable_change() {
+        // No spread at all: the 2% floor stands in
+        let mut detector = detector(bucket(10.0, 0.0, 60), bucket(10.0, 0.0, 60));
+        assert!(messages(&mut detector, 15.0).is_empty());
+        assert_eq!(messages(&mut detector, 17.0).len(), 1);
+    }
+
+    #[test]
+    fn a_cold_hour_falls_back_to_the_whole_day() {
+        let mut warm_day = detector(bucket(10.0, 2.0, 5), bucket(40.0, 2.0, 60));
+        assert_eq!(messages(&mut warm_day, 10.0), ["CPU is 15.0σ below its usual level (10% vs usual 40%)"]);
+        // Nothing warm yet: no verdict
+        let mut cold = detector(bucket(10.0, 2.0, 5), bucket(40.0, 2.0, 29));
+        assert!(messages(&mut cold, 100.0).is_empty());
+        assert_eq!(cold.status(), "📐 Anomaly detection: learning, 29 of 30 minutes");
+    }
+
+    #[test]
+    fn learns_a_finished_minute_into_its_hour_and_the_day() {
+        let mut detector = detector(Bucket::default(), Bucket::default());
+        let mut history = History::default();
+        history.record("cpu", 30.0);
+        history.record("cpu", 50.0);
+        detector.learn(&history, Local::now().timestamp() / 60);
+        let buckets = &detector.baseline["cpu"];
+        let hour = &buckets[Local::now().hour() as usize];
+        assert_eq!((hour.count, hour.mean), (1, 40.0));
+        assert_eq!((buckets[WHOLE_DAY].count, buckets[WHOLE_DAY].mean), (1, 40.0));
+    }
+
+    #[test]
+    fn validates_metrics_and_sigma() {
+        assert!(AnomalyConfig::default().validate().is_ok());
+        let unknown = AnomalyConfig { metrics: vec!["gpu".to_string()], ..AnomalyConfig::default() };
+        assert!(unknown.validate().unwrap_err().contains("unknown metric 'gpu'"));
+        for sigma in [0.0, -1.0, f64::NAN] {
+            assert!(AnomalyConfig { sigma, ..AnomalyConfig::default() }.validate().is_err());
+        }
+    }
+}
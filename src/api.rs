@@ -0,0 +1,149 @@
+use crate::{history::History, schema::SCHEMA_VERSION, sorted_processes, App, ProcessSort};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+    thread,
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// Embedded JSON API (cargo feature `api`) plus a small web dashboard built on
+// it. The UI thread publishes a snapshot after every refresh and the server
+// thread only ever reads that, so requests never wait for collection. Only
+// new history samples are copied over, and turned into JSON on request. The
+// responses follow schema.rs, whose version is in an X-Schema-Version header
+// since /processes is a bare array.
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+#[derive(Serialize)]
+struct Cpu {
+    usage: f32,
+    cores: Vec<f32>,
+    load: Load,
+}
+
+#[derive(Serialize)]
+struct Load {
+    one: f64,
+    five: f64,
+    fifteen: f64,
+}
+
+#[derive(Serialize)]
+struct Memory {
+    total: u64,
+    used: u64,
+    available: u64,
+    swap_total: u64,
+    swap_used: u64,
+}
+
+#[derive(Serialize)]
+struct ApiProcess {
+    pid: u32,
+    name: String,
+    // Percent of one core
+    cpu: f32,
+    // Resident bytes
+    memory: u64,
+}
+
+#[derive(Debug, Default)]
+struct Snapshot {
+    cpu: Value,
+    memory: Value,
+    processes: Value,
+    history: History,
+}
+
+pub struct ApiServer {
+    snapshot: Arc<Mutex<Snapshot>>,
+    pub address: String,
+}
+
+impl ApiServer {
+    pub fn start(address: &str) -> Result<ApiServer, Box<dyn Error + Send + Sync>> {
+        let server = Server::http(address)?;
+        let snapshot = Arc::new(Mutex::new(Snapshot::default()));
+        let thread_snapshot = Arc::clone(&snapshot);
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                if *request.method() == Method::Get && request.url() == "/" {
+                    respond(request, 200, "text/html; charset=utf-8", DASHBOARD.to_string());
+                    continue;
+                }
+                let (status, body) = route(&request, &thread_snapshot.lock().unwrap());
+                respond(request, status, "application/json", body.to_string());
+            }
+        });
+        Ok(ApiServer { snapshot, address: address.to_string() })
+    }
+
+    pub fn publish(&self, app: &App) {
+        let load = sysinfo::System::load_average();
+        let cpu = json!(Cpu {
+            usage: app.system.global_cpu_usage(),
+            cores: app.system.cpus().iter().map(|c| c.cpu_usage()).collect(),
+            load: Load { one: load.one, five: load.five, fifteen: load.fifteen },
+        });
+        let memory = json!(Memory {
+            total: app.system.total_memory(),
+            used: app.system.used_memory(),
+            available: app.system.available_memory(),
+            swap_total: app.system.total_swap(),
+            swap_used: app.system.used_swap(),
+        });
+        let processes: Vec<ApiProcess> = sorted_processes(&app.processes, ProcessSort::Cpu)
+            .into_iter()
+            .map(|(pid, process)| ApiProcess {
+                pid: pid.as_u32(),
+                name: process.name().to_string_lossy().into_owned(),
+                cpu: process.cpu_usage(),
+                memory: process.memory(),
+            })
+            .collect();
+
+        let mut snapshot = self.snapshot.lock().unwrap();
+        snapshot.cpu = cpu;
+        snapshot.memory = memory;
+        snapshot.processes = json!(processes);
+        snapshot.history.catch_up(&app.history);
+    }
+}
+
+fn route(request: &Request, snapshot: &Snapshot) -> (u16, Value) {
+    if *request.method() != Method::Get {
+        return (405, json!({ "error": "only GET is supported" }));
+    }
+    let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+    match path {
+        "/api" => (
+            200,
+            json!({ "endpoints": ["/cpu", "/memory", "/processes", "/history?metric=<name>"], "schema_version": SCHEMA_VERSION }),
+        ),
+        "/cpu" => (200, snapshot.cpu.clone()),
+        "/memory" => (200, snapshot.memory.clone()),
+        "/processes" => (200, snapshot.processes.clone()),
+        "/history" => {
+            let metric = query.split('&').find_map(|pair| pair.strip_prefix("metric="));
+            match metric {
+                None => (200, json!({ "metrics": snapshot.history.metrics().collect::<Vec<_>>() })),
+                Some(metric) => match snapshot.history.series(metric) {
+                    // [[unix time, value], ...]
+                    Some(samples) => (200, json!({ "metric": metric, "samples": samples })),
+                    None => (404, json!({ "error": format!("unknown metric '{}'", metric) })),
+                },
+            }
+        }
+        _ => (404, json!({ "error": "not found" })),
+    }
+}
+
+fn respond(request: Request, status: u16, content_type: &str, body: String) {
+    let header = Header::from_bytes("Content-Type", content_type).expect("static header is valid");
+    let version = Header::from_bytes("X-Schema-Version", SCHEMA_VERSION.to_string()).expect("a number is a valid header");
+    let response = Response::from_string(body).with_status_code(status).with_header(header).with_header(version);
+    // The client may already be gone; nothing to do about it
+    let _ = request.respond(response);
+}
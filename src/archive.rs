@@ -0,0 +1,268 @@
+use crate::alerts::{AlertChange, Severity};
+use crate::history::History;
+use crate::schema::SCHEMA_VERSION;
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+use sysinfo::{Disks, Pid, System};
+
+// Minute summaries kept on disk for reports and process accounting, one JSON
+// line per minute in ~/.local/share/system-monitor/history/YYYY-MM-DD.jsonl
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    // Also on whenever [report] is set
+    pub enabled: bool,
+    pub keep_days: u64,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        ArchiveConfig { enabled: false, keep_days: 35 }
+    }
+}
+
+impl ArchiveConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.keep_days < 7 {
+            return Err("archive: keep_days must be at least 7 to cover a week".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MinuteRecord {
+    // 0 on lines from before versioning, which match version 1
+    pub schema_version: u32,
+    // Start of the minute, Unix seconds
+    pub time: i64,
+    // Average and highest value of each history metric
+    pub average: BTreeMap<String, f64>,
+    pub peak: BTreeMap<String, f64>,
+    // Used bytes per mount point at the end of the minute
+    pub disks: BTreeMap<String, u64>,
+    // CPU seconds and disk I/O bytes per process name, the busiest only
+    pub cpu_seconds: BTreeMap<String, f64>,
+    pub read_bytes: BTreeMap<String, f64>,
+    pub written_bytes: BTreeMap<String, f64>,
+    pub alerts: Vec<ArchivedAlert>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ArchivedAlert {
+    pub severity: Severity,
+    pub source: String,
+    pub message: String,
+}
+
+// Processes per minute worth keeping; the rest would only bloat the files
+const TOP_PROCESSES: usize = 20;
+
+// A process's CPU time (ms) and disk totals when last seen, with its start
+// time to tell a reused PID apart
+struct Totals {
+    start_time: u64,
+    cpu_ms: u64,
+    read: u64,
+    written: u64,
+}
+
+pub struct Archive {
+    keep_days: u64,
+    // Minute (Unix seconds / 60) being accumulated
+    minute: i64,
+    totals: HashMap<Pid, Totals>,
+    cpu_seconds: BTreeMap<String, f64>,
+    read_bytes: BTreeMap<String, f64>,
+    written_bytes: BTreeMap<String, f64>,
+    alerts: Vec<ArchivedAlert>,
+    error: Option<String>,
+}
+
+impl Archive {
+    pub fn new(keep_days: u64) -> Archive {
+        let archive = Archive {
+            keep_days,
+            minute: Local::now().timestamp() / 60,
+            totals: HashMap::new(),
+            cpu_seconds: BTreeMap::new(),
+            read_bytes: BTreeMap::new(),
+            written_bytes: BTreeMap::new(),
+            alerts: Vec::new(),
+            error: None,
+        };
+        archive.prune();
+        archive
+    }
+
+    pub fn dir() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("system-monitor").join("history"))
+    }
+
+    // Called after every refresh; writes the previous minute once it is over.
+    // processes is there when the processes collector ran this refresh; what
+    // each process used since it was last seen is added up from its totals.
+    pub fn update(
+        &mut self,
+        processes: Option<&System>,
+        changes: &[AlertChange],
+        history: &History,
+        disks: &Disks,
+    ) {
+        let minute = Local::now().timestamp() / 60;
+        if minute != self.minute {
+            let record = self.record(history, disks);
+            self.error = self.append(&record).err().map(|e| e.to_string());
+            // New day, new file: drop the ones past keep_days
+            if day_of(minute * 60) != day_of(self.minute * 60) {
+                self.prune();
+            }
+            self.minute = minute;
+        }
+
+        if let Some(processes) = processes {
+            let mut seen = HashMap::with_capacity(self.totals.len());
+            for (pid, process) in processes.processes().iter().filter(|(_, process)| process.thread_kind().is_none()) {
+                let io = process.disk_usage();
+                let now = Totals {
+                    start_time: process.start_time(),
+                    cpu_ms: process.accumulated_cpu_time(),
+                    read: io.total_read_bytes,
+                    written: io.total_written_bytes,
+                };
+                // A new process counts from the refresh it was first seen in
+                if let Some(last) = self.totals.get(pid).filter(|last| last.start_time == now.start_time) {
+                    let name = process.name().to_string_lossy();
+                    for (totals, value) in [
+                        (&mut self.cpu_seconds, now.cpu_ms.saturating_sub(last.cpu_ms) as f64 / 1000.0),
+                        (&mut self.read_bytes, now.read.saturating_sub(last.read) as f64),
+                        (&mut self.written_bytes, now.written.saturating_sub(last.written) as f64),
+                    ] {
+                        if value > 0.0 {
+                            *totals.entry(name.to_string()).or_default() += value;
+                        }
+                    }
+                }
+                seen.insert(*pid, now);
+            }
+            self.totals = seen;
+        }
+        for change in changes {
+            if let AlertChange::Fired(alert) = change {
+                self.alerts.push(ArchivedAlert {
+                    severity: alert.severity,
+                    source: alert.source.clone(),
+                    message: alert.message.clone(),
+                });
+            }
+        }
+    }
+
+    fn record(&mut self, history: &History, disks: &Disks) -> MinuteRecord {
+        let (from, to) = (self.minute * 60, self.minute * 60 + 60);
+        let mut record = MinuteRecord { schema_version: SCHEMA_VERSION, time: from, ..MinuteRecord::default() };
+        for metric in history.metrics() {
+            let values: Vec<f64> = history
+                .series(metric)
+                .into_iter()
+                .flatten()
+                .filter(|(t, v)| *t >= from && *t < to && v.is_finite())
+                .map(|(_, v)| *v)
+                .collect();
+            if values.is_empty() {
+                continue;
+            }
+            record.average.insert(metric.to_string(), values.iter().sum::<f64>() / values.len() as f64);
+            record.peak.insert(metric.to_string(), values.iter().copied().fold(f64::MIN, f64::max));
+        }
+        for disk in disks.iter().filter(|d| d.total_space() > 0) {
+            record
+                .disks
+                .insert(disk.mount_point().display().to_string(), disk.total_space() - disk.available_space());
+        }
+        record.cpu_seconds = busiest(std::mem::take(&mut self.cpu_seconds));
+        record.read_bytes = busiest(std::mem::take(&mut self.read_bytes));
+        record.written_bytes = busiest(std::mem::take(&mut self.written_bytes));
+        record.alerts = std::mem::take(&mut self.alerts);
+        record
+    }
+
+    // On exit: write the minute in progress. A restart within the same minute
+    // writes a second record for it, which readers add up like any other.
+    pub fn flush(&mut self, history: &History, disks: &Disks) -> Result<(), Box<dyn std::error::Error>> {
+        let record = self.record(history, disks);
+        self.append(&record)
+    }
+
+    fn append(&self, record: &MinuteRecord) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = Archive::dir().ok_or("no data directory available")?;
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.jsonl", day_of(record.time).format("%Y-%m-%d")));
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        // On disk even if the machine goes down next; a line cut short by a
+        // crash is skipped when reading
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn prune(&self) {
+        let Some(dir) = Archive::dir() else { return };
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        let oldest = Local::now().date_naive() - chrono::Days::new(self.keep_days);
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let day = name.strip_suffix(".jsonl").and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+            if day.is_some_and(|day| day < oldest) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    // One line for the System Information panel
+    pub fn status(&self) -> String {
+        let dir = Archive::dir().map(|d| d.display().to_string()).unwrap_or_default();
+        match &self.error {
+            None => format!("🗄️ History archive → {}", dir),
+            Some(error) => format!("🗄️ History archive → {} ❌ {}", dir, error),
+        }
+    }
+}
+
+fn busiest(totals: BTreeMap<String, f64>) -> BTreeMap<String, f64> {
+    let mut totals: Vec<(String, f64)> = totals.into_iter().collect();
+    totals.sort_by(|a, b| b.1.total_cmp(&a.1));
+    totals.into_iter().take(TOP_PROCESSES).collect()
+}
+
+fn day_of(time: i64) -> NaiveDate {
+    Local.timestamp_opt(time, 0).single().unwrap_or_else(Local::now).date_naive()
+}
+
+// Records between two Unix times, oldest first; days without a file are skipped
+pub fn read(from: i64, to: i64) -> Vec<MinuteRecord> {
+    let Some(dir) = Archive::dir() else { return Vec::new() };
+    let (first, last) = (day_of(from), day_of(to));
+    let mut records = Vec::new();
+    for day in first.iter_days().take_while(|day| *day <= last) {
+        if let Ok(contents) = fs::read_to_string(dir.join(format!("{}.jsonl", day.format("%Y-%m-%d")))) {
+            records.extend(
+                contents
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<MinuteRecord>(line).ok())
+                    // Written by a newer monitor in a layout this one doesn't know
+                    .filter(|record| record.schema_version <= SCHEMA_VERSION)
+                    .filter(|record| record.time >= from && record.time < to),
+            );
+        }
+    }
+    records.sort_by_key(|record| record.time);
+    records
+}
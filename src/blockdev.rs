@@ -0,0 +1,360 @@
+use crate::{format_bytes, App};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+// The block device stack as a tree, physical disk → partition → LUKS →
+// LVM logical volume → filesystem, like lsblk. Built from the holders and
+// slaves links in /sys/class/block; device mapper targets are told apart by
+// their dm UUID prefix. A device with several parents (an LV over two PVs, a
+// RAID member set) shows up under each of them.
+const SYS_BLOCK: &str = "/sys/class/block";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Disk,
+    Partition,
+    Crypt,
+    Lvm,
+    Raid,
+    Loop,
+    DeviceMapper,
+}
+
+impl Kind {
+    fn label(&self) -> &'static str {
+        match self {
+            Kind::Disk => "disk",
+            Kind::Partition => "part",
+            Kind::Crypt => "crypt",
+            Kind::Lvm => "lvm",
+            Kind::Raid => "raid",
+            Kind::Loop => "loop",
+            Kind::DeviceMapper => "dm",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockDevice {
+    // Kernel name, e.g. "sda2" or "dm-1"
+    pub name: String,
+    // Name people know it by, e.g. "cryptroot" or "vg0-home" for dm devices
+    pub label: String,
+    pub kind: Kind,
+    pub size: u64,
+    // Mount points, or "[SWAP]"
+    pub mounts: Vec<String>,
+    pub children: Vec<BlockDevice>,
+    // USB sticks and disks, card readers: whole disks only
+    pub removable: bool,
+    // Vendor and model as the device reports them
+    pub model: Option<String>,
+}
+
+impl BlockDevice {
+    // "SanDisk Cruzer (sdb)", or just the label
+    pub fn description(&self) -> String {
+        match &self.model {
+            Some(model) => format!("{} ({})", model, self.label),
+            None => self.label.clone(),
+        }
+    }
+
+    fn mounted(&self) -> Vec<String> {
+        let mut mounts = self.mounts.clone();
+        for child in &self.children {
+            mounts.extend(child.mounted());
+        }
+        mounts
+    }
+}
+
+#[derive(Default)]
+pub struct Topology {
+    pub roots: Vec<BlockDevice>,
+}
+
+impl Topology {
+    pub fn removable(&self) -> Vec<&BlockDevice> {
+        self.roots.iter().filter(|device| device.removable).collect()
+    }
+}
+
+impl Topology {
+    pub fn update(&mut self) {
+        let mounts = mounts();
+        let Ok(entries) = fs::read_dir(SYS_BLOCK) else { return };
+        let mut names: Vec<String> = entries.flatten().map(|e| e.file_name().to_string_lossy().into_owned()).collect();
+        names.sort();
+        // Whole disks that sit on nothing else
+        self.roots = names
+            .iter()
+            .filter(|name| !is_partition(name) && links(name, "slaves").is_empty())
+            .map(|name| device(name, &mounts, 0))
+            .filter(|device| device.size > 0 && !device.name.starts_with("ram"))
+            .collect();
+    }
+}
+
+fn sys(name: &str) -> PathBuf {
+    Path::new(SYS_BLOCK).join(name)
+}
+
+fn read(path: PathBuf) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn is_partition(name: &str) -> bool {
+    sys(name).join("partition").exists()
+}
+
+// Entries of a holders or slaves directory
+fn links(name: &str, dir: &str) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(sys(name).join(dir))
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn device(name: &str, mounts: &HashMap<String, Vec<String>>, depth: usize) -> BlockDevice {
+    let dm_name = read(sys(name).join("dm/name"));
+    let kind = if is_partition(name) {
+        Kind::Partition
+    } else if name.starts_with("md") {
+        Kind::Raid
+    } else if name.starts_with("loop") {
+        Kind::Loop
+    } else if let Some(uuid) = read(sys(name).join("dm/uuid")) {
+        match uuid.split('-').next() {
+            Some("CRYPT") => Kind::Crypt,
+            Some("LVM") => Kind::Lvm,
+            _ => Kind::DeviceMapper,
+        }
+    } else {
+        Kind::Disk
+    };
+    let size = read(sys(name).join("size")).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0) * 512;
+    // USB disks often claim to be fixed, so their bus counts too
+    let removable = kind == Kind::Disk
+        && (read(sys(name).join("removable")).as_deref() == Some("1")
+            || fs::canonicalize(sys(name)).is_ok_and(|path| path.to_string_lossy().contains("/usb")));
+    let model = [read(sys(name).join("device/vendor")), read(sys(name).join("device/model"))]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .reduce(|vendor, model| format!("{} {}", vendor, model));
+
+    let mut children = Vec::new();
+    // A stack deeper than this is a loop in sysfs, not a real setup
+    if depth < 8 {
+        if kind != Kind::Partition {
+            // Partitions are subdirectories named after the disk
+            let mut partitions: Vec<String> = fs::read_dir(sys(name))
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|e| e.file_name().to_string_lossy().into_owned())
+                        .filter(|child| child.starts_with(name) && is_partition(child))
+                        .collect()
+                })
+                .unwrap_or_default();
+            partitions.sort();
+            children.extend(partitions.iter().map(|child| device(child, mounts, depth + 1)));
+        }
+        children.extend(links(name, "holders").iter().map(|holder| device(holder, mounts, depth + 1)));
+    }
+
+    BlockDevice {
+        name: name.to_string(),
+        label: dm_name.unwrap_or_else(|| name.to_string()),
+        kind,
+        size,
+        mounts: mounts.get(name).cloned().unwrap_or_default(),
+        children,
+        removable,
+        model,
+    }
+}
+
+// Mount points and swap areas by kernel device name
+fn mounts() -> HashMap<String, Vec<String>> {
+    let mut mounts: HashMap<String, Vec<String>> = HashMap::new();
+    let kernel_name = |device: &str| {
+        let path = fs::canonicalize(device).ok()?;
+        Some(path.file_name()?.to_string_lossy().into_owned())
+    };
+    if let Ok(contents) = fs::read_to_string("/proc/self/mounts") {
+        for line in contents.lines() {
+            let mut fields = line.split_whitespace();
+            let (Some(device), Some(mount)) = (fields.next(), fields.next()) else { continue };
+            if !device.starts_with("/dev/") {
+                continue;
+            }
+            if let Some(name) = kernel_name(device) {
+                // Spaces in mount points are escaped as \040
+                mounts.entry(name).or_default().push(mount.replace("\\040", " "));
+            }
+        }
+    }
+    if let Ok(contents) = fs::read_to_string("/proc/swaps") {
+        for line in contents.lines().skip(1) {
+            let Some(device) = line.split_whitespace().next() else { continue };
+            if let Some(name) = kernel_name(device) {
+                mounts.entry(name).or_default().push("[SWAP]".to_string());
+            }
+        }
+    }
+    mounts
+}
+
+// Tree lines, e.g. "│ └─", and the device for each table row
+fn flatten<'a>(devices: &'a [BlockDevice], prefix: &str, rows: &mut Vec<(String, &'a BlockDevice)>) {
+    for (index, device) in devices.iter().enumerate() {
+        let last = index == devices.len() - 1;
+        let branch = if last { "└─" } else { "├─" };
+        rows.push((format!("{}{}", prefix, branch), device));
+        let continuation = if last { "  " } else { "│ " };
+        flatten(&device.children, &format!("{}{}", prefix, continuation), rows);
+    }
+}
+
+pub fn draw_devices_tab(f: &mut Frame, area: Rect, app: &App) {
+    if !app.capabilities.block_devices.available() {
+        let message = Paragraph::new(format!("Block devices: {}", app.capabilities.block_devices.placeholder()))
+            .block(Block::default().title("🧱 Block devices").borders(Borders::ALL))
+            .style(Style::default().fg(Color::Gray));
+        f.render_widget(message, area);
+        return;
+    }
+    let removable = app.topology.removable();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(removable.len().max(1) as u16 + 3), Constraint::Min(0)])
+        .split(area);
+    draw_removable(f, chunks[0], app, &removable);
+
+    let mut flat = Vec::new();
+    for root in &app.topology.roots {
+        rows_for_root(root, &mut flat);
+    }
+    let rows: Vec<Row> = flat
+        .iter()
+        .map(|(tree, device)| {
+            // Mounted: filesystem usage; otherwise the share the layers above take up
+            let filesystem = device.mounts.iter().find_map(|mount| {
+                app.disks.iter().find(|disk| disk.mount_point() == Path::new(mount))
+            });
+            let (used, percent) = match filesystem {
+                Some(disk) if disk.total_space() > 0 => {
+                    let used = disk.total_space() - disk.available_space();
+                    (Some(used), Some(used as f64 / disk.total_space() as f64 * 100.0))
+                }
+                _ if !device.children.is_empty() && device.size > 0 => {
+                    let given: u64 = device.children.iter().map(|child| child.size).sum();
+                    (Some(given.min(device.size)), Some(given.min(device.size) as f64 / device.size as f64 * 100.0))
+                }
+                _ => (None, None),
+            };
+            let percent_style = match percent {
+                Some(percent) if filesystem.is_some() && percent >= app.thresholds.disk_crit => Style::default().fg(Color::Red),
+                Some(percent) if filesystem.is_some() && percent >= app.thresholds.disk_warn => Style::default().fg(Color::Yellow),
+                _ => Style::default(),
+            };
+            let latency = app.disk_latency.get(&device.name);
+            let milliseconds = |ms: Option<f64>| ms.map(|ms| format!("{:.1}", ms)).unwrap_or_else(|| "-".to_string());
+            Row::new(vec![
+                Cell::from(format!("{}{}", tree, device.label)),
+                Cell::from(device.kind.label()),
+                Cell::from(format_bytes(device.size)),
+                Cell::from(used.map(format_bytes).unwrap_or_default()),
+                Cell::from(percent.map(|p| format!("{:.1}%", p)).unwrap_or_default()).style(percent_style),
+                Cell::from(milliseconds(latency.and_then(|l| l.read))),
+                Cell::from(milliseconds(latency.and_then(|l| l.write))),
+                Cell::from(device.mounts.join(" ")),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["Device", "Type", "Size", "Used", "Use %", "Read ms", "Write ms", "Mounted on"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Min(24),
+        Constraint::Length(6),
+        Constraint::Length(11),
+        Constraint::Length(11),
+        Constraint::Length(7),
+        Constraint::Length(8),
+        Constraint::Length(9),
+        Constraint::Min(20),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .title("🧱 Block devices · Used: filesystem usage, or space taken by the layers above")
+                .borders(Borders::ALL),
+        )
+        .column_spacing(1);
+    f.render_widget(table, chunks[1]);
+}
+
+// Removable drives on their own, with their mount state and the eject result
+fn draw_removable(f: &mut Frame, area: Rect, app: &App, removable: &[&BlockDevice]) {
+    let rows: Vec<Row> = if removable.is_empty() {
+        vec![Row::new(vec!["No removable drives attached"]).style(Style::default().fg(Color::Gray))]
+    } else {
+        removable
+            .iter()
+            .map(|drive| {
+                let mounted = drive.mounted();
+                let (state, style) = if mounted.is_empty() {
+                    ("not mounted".to_string(), Style::default().fg(Color::Gray))
+                } else {
+                    (format!("mounted on {}", mounted.join(", ")), Style::default().fg(Color::Green))
+                };
+                Row::new(vec![
+                    Cell::from(drive.description()),
+                    Cell::from(format!("/dev/{}", drive.name)),
+                    Cell::from(format_bytes(drive.size)),
+                    Cell::from(state).style(style),
+                ])
+            })
+            .collect()
+    };
+    let title = if app.read_only {
+        "🔌 Removable drives".to_string()
+    } else {
+        "🔌 Removable drives — ↑/↓ select · e safe eject".to_string()
+    };
+    let mut block = Block::default().title(title).borders(Borders::ALL);
+    if let Some(eject) = &app.eject {
+        let (text, color) = eject.status();
+        block = block.title_bottom(Line::styled(text, Style::default().fg(color)));
+    }
+    let header = Row::new(vec!["Drive", "Device", "Size", "State"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [Constraint::Min(24), Constraint::Length(12), Constraint::Length(11), Constraint::Min(20)];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(block)
+        .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .column_spacing(1);
+    let mut state = TableState::default().with_selected((!removable.is_empty()).then_some(app.selected_removable));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+// A root device without a tree line, its children below it
+fn rows_for_root<'a>(root: &'a BlockDevice, rows: &mut Vec<(String, &'a BlockDevice)>) {
+    rows.push((String::new(), root));
+    flatten(&root.children, "", rows);
+}
@@ -0,0 +1,147 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Row, Table},
+    Frame,
+};
+use std::{collections::BTreeMap, fs, path::Path};
+
+// Threads in uninterruptible sleep ("D" state), usually waiting for a disk or
+// a network filesystem, and what they wait on where /proc tells: the kernel
+// function (wchan) and, when the first argument of the blocked syscall is an
+// open file descriptor, that file and the device its filesystem lives on.
+// The syscall needs the same access as ptrace, so other users' threads only
+// show their wait channel without root. Scanned while the Processes tab is
+// open; the panel only appears while something is blocked.
+#[derive(Debug, Clone)]
+pub struct BlockedTask {
+    pub pid: u32,
+    pub tid: u32,
+    pub name: String,
+    pub wchan: Option<String>,
+    pub file: Option<String>,
+    // Mount source of the file's filesystem, e.g. "/dev/sdb1" or "nas:/export"
+    pub device: Option<String>,
+}
+
+#[derive(Default)]
+pub struct Blocked {
+    pub tasks: Vec<BlockedTask>,
+}
+
+impl Blocked {
+    pub fn update(&mut self) {
+        let Ok(processes) = fs::read_dir("/proc") else { return };
+        let mounts = mounts();
+        let mut tasks = Vec::new();
+        for process in processes.flatten() {
+            let Some(pid) = process.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else { continue };
+            let Ok(threads) = fs::read_dir(process.path().join("task")) else { continue };
+            for thread in threads.flatten() {
+                let Some(tid) = thread.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else { continue };
+                let dir = thread.path();
+                let Ok(stat) = fs::read_to_string(dir.join("stat")) else { continue };
+                // "tid (comm) S ...", comm may contain spaces and parentheses
+                let Some((head, rest)) = stat.rsplit_once(')') else { continue };
+                if rest.split_whitespace().next() != Some("D") {
+                    continue;
+                }
+                let name = head.split_once('(').map(|(_, name)| name.to_string()).unwrap_or_default();
+                let wchan = fs::read_to_string(dir.join("wchan")).ok().filter(|w| !w.is_empty() && w != "0");
+                let file = waited_file(pid, &dir);
+                let device = file.as_deref().and_then(|file| device_of(file, &mounts));
+                tasks.push(BlockedTask { pid, tid, name, wchan, file, device });
+            }
+        }
+        tasks.sort_by(|a, b| a.device.cmp(&b.device).then(a.pid.cmp(&b.pid)).then(a.tid.cmp(&b.tid)));
+        self.tasks = tasks;
+    }
+
+    // "3 threads · 2 on /dev/sdb1, 1 on nas:/export"
+    fn summary(&self) -> String {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for device in self.tasks.iter().filter_map(|task| task.device.as_deref()) {
+            *counts.entry(device).or_default() += 1;
+        }
+        let threads = match self.tasks.len() {
+            1 => "1 thread".to_string(),
+            n => format!("{} threads", n),
+        };
+        if counts.is_empty() {
+            return threads;
+        }
+        let devices: Vec<String> = counts.iter().map(|(device, count)| format!("{} on {}", count, device)).collect();
+        format!("{} · {}", threads, devices.join(", "))
+    }
+}
+
+// The file behind the first syscall argument, if it is an open descriptor.
+// /proc/<pid>/task/<tid>/syscall reads "nr arg1 arg2 ... sp pc".
+fn waited_file(pid: u32, task: &Path) -> Option<String> {
+    let syscall = fs::read_to_string(task.join("syscall")).ok()?;
+    let first = syscall.split_whitespace().nth(1)?;
+    let fd = u64::from_str_radix(first.trim_start_matches("0x"), 16).ok()?;
+    let target = fs::read_link(format!("/proc/{}/fd/{}", pid, fd)).ok()?;
+    Some(target.to_string_lossy().into_owned())
+}
+
+// (mount point, source) of every mount, longest mount point first
+fn mounts() -> Vec<(String, String)> {
+    let mut mounts: Vec<(String, String)> = fs::read_to_string("/proc/self/mounts")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let source = fields.next()?.to_string();
+            let mount = fields.next()?.replace("\\040", " ");
+            Some((mount, source))
+        })
+        .collect();
+    mounts.sort_by_key(|(mount, _)| std::cmp::Reverse(mount.len()));
+    mounts
+}
+
+fn device_of(file: &str, mounts: &[(String, String)]) -> Option<String> {
+    // Sockets, pipes and the like aren't paths
+    if !file.starts_with('/') {
+        return None;
+    }
+    mounts
+        .iter()
+        .find(|(mount, _)| {
+            mount == "/" || file == mount || file.strip_prefix(mount.as_str()).is_some_and(|rest| rest.starts_with('/'))
+        })
+        .map(|(_, source)| source.clone())
+}
+
+pub fn draw(f: &mut Frame, area: Rect, blocked: &Blocked) {
+    let rows: Vec<Row> = blocked
+        .tasks
+        .iter()
+        .map(|task| {
+            let pid = if task.pid == task.tid { task.pid.to_string() } else { format!("{}/{}", task.pid, task.tid) };
+            Row::new(vec![
+                pid,
+                task.name.clone(),
+                task.wchan.clone().unwrap_or_else(|| "-".to_string()),
+                task.device.clone().unwrap_or_else(|| "-".to_string()),
+                task.file.clone().unwrap_or_else(|| "-".to_string()),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["PID/TID", "Name", "Waiting in", "Device", "File"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(13),
+        Constraint::Length(16),
+        Constraint::Length(24),
+        Constraint::Length(18),
+        Constraint::Min(20),
+    ];
+    let title = format!("⏳ Blocked on I/O (D state): {}", blocked.summary());
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL).border_style(Style::default().fg(Color::Red)))
+        .column_spacing(1);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+// A time budget per collector, so one slow source (a hung NFS mount under
+// Disks, a slow sensor bus) can't make the whole monitor sluggish. The
+// refresh waits for a collector until its budget is up and then goes on
+// without it; it finishes in the background and isn't run again until it's
+// back, with its panels showing the data's age in their titles. A
+// collector that took longer than its budget is skipped for the next refresh,
+// then for 3, 7 and so on while it keeps overrunning, and turned off after
+// `strikes` overruns in a row, except for the processes collector, which only
+// backs off. The status bar says what happened. A collector
+// that runs within its budget again starts over; turned-off ones come back
+// when the config is reloaded (saving config.toml is enough).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    pub enabled: bool,
+    // For each collector not listed under collectors
+    pub budget_ms: u64,
+    // Overruns in a row before a collector is turned off
+    pub strikes: u32,
+    // Budgets of single collectors, e.g. disks = 2000
+    pub collectors: BTreeMap<String, u64>,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        BudgetConfig { enabled: true, budget_ms: 500, strikes: 3, collectors: BTreeMap::new() }
+    }
+}
+
+impl BudgetConfig {
+    pub fn validate(&self, known: impl Fn(&str) -> bool) -> Result<(), String> {
+        let in_range = |ms: u64| (10..=60_000).contains(&ms);
+        if !in_range(self.budget_ms) {
+            return Err("collector_budget: budget_ms must be between 10 and 60000".to_string());
+        }
+        if self.strikes == 0 {
+            return Err("collector_budget: strikes must be at least 1".to_string());
+        }
+        for (name, ms) in &self.collectors {
+            if !known(name) {
+                return Err(format!("collector_budget: unknown collector '{}'", name));
+            }
+            if !in_range(*ms) {
+                return Err(format!("collector_budget: {} must be between 10 and 60000", name));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn budget(&self, collector: &str) -> Duration {
+        Duration::from_millis(self.collectors.get(collector).copied().unwrap_or(self.budget_ms))
+    }
+}
+
+// Refreshes skipped after an overrun are capped at this
+const MAX_SKIP: u32 = 30;
+// The process tables are what the monitor is for, so this one keeps backing
+// off instead
+const NEVER_OFF: &[&str] = &["processes"];
+// How long a backoff stays in the status bar
+const NOTICE_FOR: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    // Overruns in a row
+    strikes: u32,
+    // Refreshes left to skip
+    skip: u32,
+    off: bool,
+    // Still running past its budget
+    late: bool,
+    last: Duration,
+}
+
+pub struct Budgets {
+    config: BudgetConfig,
+    states: HashMap<&'static str, State>,
+    // The latest backoff
+    notice: Option<(String, Instant)>,
+}
+
+impl Budgets {
+    pub fn new(config: &BudgetConfig) -> Budgets {
+        Budgets { config: config.clone(), states: HashMap::new(), notice: None }
+    }
+
+    pub fn budget(&self, collector: &str) -> Duration {
+        self.config.budget(collector)
+    }
+
+    // Whether this is a collector that has run, rather than another timing
+    pub fn tracks(&self, name: &str) -> bool {
+        self.states.contains_key(name)
+    }
+
+    // Before each refresh of a collector; a skipped refresh counts down
+    pub fn should_run(&mut self, collector: &'static str) -> bool {
+        let state = self.states.entry(collector).or_default();
+        if state.off {
+            return false;
+        }
+        if state.skip > 0 {
+            state.skip -= 1;
+            return false;
+        }
+        true
+    }
+
+    // When the refresh stops waiting for a collector
+    pub fn overdue(&mut self, collector: &'static str) {
+        let budget = self.config.budget(collector);
+        self.states.entry(collector).or_default().late = true;
+        self.notice = Some((
+            format!(
+                "⏱ The {} collector is still running after its {} ms budget; its panels update once it's done",
+                collector,
+                budget.as_millis()
+            ),
+            Instant::now(),
+        ));
+    }
+
+    pub fn record(&mut self, collector: &'static str, elapsed: Duration) {
+        let budget = self.config.budget(collector);
+        let strikes = self.config.strikes;
+        let state = self.states.entry(collector).or_default();
+        state.late = false;
+        if !self.config.enabled {
+            return;
+        }
+        state.last = elapsed;
+        if elapsed <= budget {
+            state.strikes = 0;
+            return;
+        }
+        state.strikes += 1;
+        if state.strikes >= strikes && !NEVER_OFF.contains(&collector) {
+            state.off = true;
+            return;
+        }
+        state.skip = 2u32.saturating_pow(state.strikes).saturating_sub(1).min(MAX_SKIP);
+        let skipping = match state.skip {
+            1 => "its next refresh".to_string(),
+            n => format!("its next {} refreshes", n),
+        };
+        self.notice = Some((
+            format!(
+                "⏱ The {} collector took {} ms, over its {} ms budget; skipping {}",
+                collector,
+                elapsed.as_millis(),
+                budget.as_millis(),
+                skipping
+            ),
+            Instant::now(),
+        ));
+    }
+
+    // "off", "running late" or "skipping 3" for the F12 panel, None while it runs normally
+    pub fn state(&self, collector: &str) -> Option<String> {
+        let state = self.states.get(collector)?;
+        if state.late {
+            Some("running late".to_string())
+        } else if state.off {
+            Some("off".to_string())
+        } else if state.skip > 0 {
+            Some(format!("skipping {}", state.skip))
+        } else {
+            None
+        }
+    }
+
+    // Status bar warning: a collector that is off, until the config is
+    // reloaded, or else the latest backoff for a few seconds
+    pub fn notice(&self) -> Option<(String, bool)> {
+        let mut off: Vec<(&str, &State)> = self.states.iter().filter(|(_, state)| state.off).map(|(name, state)| (*name, state)).collect();
+        off.sort_by_key(|(name, _)| *name);
+        if let Some((name, state)) = off.first() {
+            let others = match off.len() {
+                1 => String::new(),
+                n => format!(" (and {} more)", n - 1),
+            };
+            return Some((
+                format!(
+                    "⚠ The {} collector is off{}: over its {} ms budget {} times in a row, last {} ms; its panels show old data until the config is reloaded",
+                    name,
+                    others,
+                    self.config.budget(name).as_millis(),
+                    self.config.strikes,
+                    state.last.as_millis()
+                ),
+                true,
+            ));
+        }
+        self.notice
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < NOTICE_FOR)
+            .map(|(text, _)| (text.clone(), true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budgets() -> Budgets {
+        Budgets::new(&BudgetConfig { budget_ms: 100, strikes: 3, ..BudgetConfig::default() })
+    }
+
+    // Refreshes skipped before the collector runs again
+    fn skipped(budgets: &mut Budgets) -> u32 {
+        (0..).take_while(|_| !budgets.should_run("disks")).count() as u32
+    }
+
+    #[test]
+    fn backs_off_then_turns_off() {
+        let mut budgets = budgets();
+        let slow = Duration::from_millis(150);
+        assert!(budgets.should_run("disks"));
+        budgets.record("disks", slow);
+        assert_eq!(budgets.state("disks"), Some("skipping 1".to_string()));
+        assert_eq!(skipped(&mut budgets), 1);
+        budgets.record("disks", slow);
+        assert_eq!(skipped(&mut budgets), 3);
+        budgets.record("disks", slow);
+        assert_eq!(budgets.state("disks"), Some("off".to_string()));
+        assert!((0..100).all(|_| !budgets.should_run("disks")));
+        assert!(budgets.notice().is_some_and(|(text, _)| text.contains("disks collector is off")));
+    }
+
+    #[test]
+    fn processes_back_off_but_stay_on() {
+        let mut budgets = budgets();
+        for _ in 0..10 {
+            budgets.record("processes", Duration::from_millis(150));
+        }
+        assert_eq!(budgets.state("processes"), Some(format!("skipping {}", MAX_SKIP)));
+        assert!(budgets.notice().is_some_and(|(text, _)| text.ends_with("skipping its next 30 refreshes")));
+    }
+
+    #[test]
+    fn notice_counts_refreshes() {
+        let mut budgets = budgets();
+        budgets.record("disks", Duration::from_millis(150));
+        assert!(budgets.notice().is_some_and(|(text, _)| text.ends_with("skipping its next refresh")));
+        skipped(&mut budgets);
+        budgets.record("disks", Duration::from_millis(150));
+        assert!(budgets.notice().is_some_and(|(text, _)| text.ends_with("skipping its next 3 refreshes")));
+    }
+
+    #[test]
+    fn a_run_within_budget_starts_over() {
+        let mut budgets = budgets();
+        budgets.record("disks", Duration::from_millis(150));
+        assert_eq!(skipped(&mut budgets), 1);
+        budgets.record("disks", Duration::from_millis(50));
+        assert_eq!(budgets.state("disks"), None);
+        budgets.record("disks", Duration::from_millis(150));
+        assert_eq!(skipped(&mut budgets), 1);
+    }
+
+    #[test]
+    fn other_collectors_keep_running() {
+        let mut budgets = budgets();
+        budgets.record("disks", Duration::from_millis(150));
+        assert!(budgets.should_run("sensors"));
+    }
+
+    #[test]
+    fn skips_are_capped() {
+        let mut budgets = Budgets::new(&BudgetConfig { budget_ms: 100, strikes: 10, ..BudgetConfig::default() });
+        for _ in 0..8 {
+            budgets.record("disks", Duration::from_millis(150));
+        }
+        assert_eq!(skipped(&mut budgets), MAX_SKIP);
+    }
+
+    #[test]
+    fn late_until_recorded() {
+        let mut budgets = budgets();
+        budgets.overdue("disks");
+        assert_eq!(budgets.state("disks"), Some("running late".to_string()));
+        assert!(budgets.notice().is_some_and(|(text, _)| text.contains("still running")));
+        budgets.record("disks", Duration::from_millis(50));
+        assert_eq!(budgets.state("disks"), None);
+    }
+
+    #[test]
+    fn disabled_budgets_never_skip() {
+        let mut budgets = Budgets::new(&BudgetConfig { enabled: false, ..BudgetConfig::default() });
+        budgets.record("disks", Duration::from_secs(10));
+        assert!(budgets.should_run("disks"));
+    }
+
+    #[test]
+    fn validates_ranges_and_names() {
+        let known = |name: &str| name == "disks";
+        assert!(BudgetConfig::default().validate(known).is_ok());
+        assert!(BudgetConfig { budget_ms: 5, ..BudgetConfig::default() }.validate(known).is_err());
+        assert!(BudgetConfig { strikes: 0, ..BudgetConfig::default() }.validate(known).is_err());
+        let unknown = BudgetConfig { collectors: [("gpu".to_string(), 100)].into(), ..BudgetConfig::default() };
+        assert!(unknown.validate(known).is_err());
+    }
+}
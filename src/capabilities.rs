@@ -0,0 +1,185 @@
+use crate::{privilege, virt::Environment, wsl::Wsl};
+use std::path::Path;
+use sysinfo::Components;
+
+// Which optional data sources work on this platform and permission level,
+// probed once at start-up so panels can say why they are empty instead of
+// just being empty. `--capabilities` prints the whole matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Support {
+    Available,
+    NeedsRoot,
+    // Not on this operating system at all
+    Unsupported,
+    // Something to install or turn on first
+    Missing(&'static str),
+}
+
+impl Support {
+    pub fn available(&self) -> bool {
+        *self == Support::Available
+    }
+
+    // What a panel shows instead of its data
+    pub fn placeholder(&self) -> String {
+        match self {
+            Support::Available => "available".to_string(),
+            Support::NeedsRoot => privilege::root_hint().to_string(),
+            Support::Unsupported => format!("not supported on {}", platform()),
+            Support::Missing(what) => what.to_string(),
+        }
+    }
+}
+
+pub struct Capabilities {
+    // Temperature sensors for the CPU temperature line and temp: pins
+    pub sensors: Support,
+    // /proc/net socket tables for the Sockets tab
+    pub sockets: Support,
+    // /proc/<pid>/stack for the S stack sampler
+    pub kernel_stacks: Support,
+    // ptrace for the strace-lite view
+    pub ptrace: Support,
+    // Kernel process connector for --trace-exec
+    pub exec_trace: Support,
+    // /proc/stat and /proc/net/dev for --high-res
+    pub high_res: Support,
+    // systemctl for failed units in the health score
+    pub systemd: Support,
+    // /proc/<pid>/ns/net for the network namespace switcher
+    pub netns: Support,
+    // /proc/interrupts and /proc/softirqs for the Interrupts tab
+    pub interrupts: Support,
+    // /sys/class/block for the Devices tab
+    pub block_devices: Support,
+    #[cfg(feature = "ebpf")]
+    pub ebpf: Support,
+}
+
+impl Capabilities {
+    // Without `allow_privileged` root-only sources count as missing even for root
+    pub fn detect(allow_privileged: bool) -> Capabilities {
+        let linux = cfg!(target_os = "linux");
+        let root = privilege::is_root();
+        // On Linux, something under /proc or /run that has to exist
+        let linux_path = |path: &str, missing: &'static str| match (linux, Path::new(path).exists()) {
+            (false, _) => Support::Unsupported,
+            (true, false) => Support::Missing(missing),
+            (true, true) => Support::Available,
+        };
+        let needs_root = |support: Support| match support {
+            Support::Available if !allow_privileged => Support::Missing("privileged collectors are turned off"),
+            Support::Available if !root => Support::NeedsRoot,
+            support => support,
+        };
+
+        let environment = Environment::detect().unwrap_or_default();
+        let sensors = if Wsl::detect().is_some() {
+            Support::Missing("WSL can't see the host's sensors")
+        } else if environment.in_container() {
+            // hwmon may be visible, but it is the host's and often half of it
+            Support::Missing("a container can't see the host's sensors")
+        } else if Components::new_with_refreshed_list().is_empty() {
+            match environment.hypervisor {
+                Some(_) => Support::Missing("virtual machines have no temperature sensors"),
+                None => Support::Missing("no temperature sensors found"),
+            }
+        } else {
+            Support::Available
+        };
+        let ptrace = match (linux, ptrace_scope()) {
+            (false, _) => Support::Unsupported,
+            (true, Some(3)) => Support::Missing("ptrace is disabled (kernel.yama.ptrace_scope = 3)"),
+            // Scope 1 and 2 only let root attach to processes it didn't start
+            (true, Some(scope)) if scope > 0 => needs_root(Support::Available),
+            (true, _) => Support::Available,
+        };
+
+        Capabilities {
+            sensors,
+            sockets: linux_path("/proc/net/tcp", "/proc/net is not mounted"),
+            kernel_stacks: needs_root(linux_path("/proc/self/stack", "the kernel has no /proc/<pid>/stack")),
+            ptrace,
+            exec_trace: needs_root(linux_path("/proc/self", "/proc is not mounted")),
+            high_res: linux_path("/proc/stat", "/proc is not mounted"),
+            systemd: linux_path("/run/systemd/system", "systemd is not running"),
+            netns: linux_path("/proc/self/ns/net", "the kernel has no network namespaces"),
+            interrupts: linux_path("/proc/interrupts", "/proc is not mounted"),
+            block_devices: linux_path("/sys/class/block", "/sys is not mounted"),
+            #[cfg(feature = "ebpf")]
+            ebpf: match linux {
+                false => Support::Unsupported,
+                true if !bpftrace_installed() => Support::Missing("bpftrace is not installed"),
+                true => needs_root(Support::Available),
+            },
+        }
+    }
+
+    fn rows(&self) -> Vec<(&'static str, &Support)> {
+        vec![
+            ("Temperature sensors", &self.sensors),
+            ("Socket tables", &self.sockets),
+            ("Kernel stack sampler", &self.kernel_stacks),
+            ("strace-lite", &self.ptrace),
+            ("Exec tracing", &self.exec_trace),
+            ("High resolution sampling", &self.high_res),
+            ("Failed systemd units", &self.systemd),
+            ("Network namespaces", &self.netns),
+            ("Interrupt counters", &self.interrupts),
+            ("Block device topology", &self.block_devices),
+            #[cfg(feature = "ebpf")]
+            ("eBPF", &self.ebpf),
+        ]
+    }
+
+    // One line for the System Information panel while anything is missing
+    pub fn status(&self) -> Option<String> {
+        let rows = self.rows();
+        let available = rows.iter().filter(|(_, support)| support.available()).count();
+        (available < rows.len()).then(|| {
+            format!(
+                "🧭 Data sources: {} of {} available here, see --capabilities",
+                available,
+                rows.len()
+            )
+        })
+    }
+}
+
+// "Linux", "macOS", ...
+pub fn platform() -> &'static str {
+    match std::env::consts::OS {
+        "linux" => "Linux",
+        "macos" => "macOS",
+        "windows" => "Windows",
+        "freebsd" => "FreeBSD",
+        other => other,
+    }
+}
+
+fn ptrace_scope() -> Option<u32> {
+    std::fs::read_to_string("/proc/sys/kernel/yama/ptrace_scope").ok()?.trim().parse().ok()
+}
+
+#[cfg(feature = "ebpf")]
+fn bpftrace_installed() -> bool {
+    std::process::Command::new("bpftrace").arg("--version").output().is_ok()
+}
+
+// --capabilities: the matrix as plain text
+pub fn print(capabilities: &Capabilities) {
+    let user = if privilege::is_root() { "root" } else { "user" };
+    match Environment::detect() {
+        Some(environment) => println!("Data sources on {} ({}) as {}:\n", platform(), environment.describe(), user),
+        None => println!("Data sources on {} as {}:\n", platform(), user),
+    }
+    for (name, support) in capabilities.rows() {
+        let mark = if support.available() { "✅" } else { "❌" };
+        // The usual hint is about a key in the UI
+        let text = match support {
+            Support::NeedsRoot => "needs root".to_string(),
+            support => support.placeholder(),
+        };
+        println!("{} {:<26} {}", mark, name, text);
+    }
+}
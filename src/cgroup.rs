@@ -0,0 +1,150 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// A process's cgroup and the limits it runs under: memory limit, CPU quota
+// and how much of its time the quota throttled, which is often the answer to
+// "my service is slow" while the machine looks idle. Read from cgroup v2, or
+// the memory and cpu hierarchies of cgroup v1. Limits set on a parent (a
+// slice) bind too, so the tightest one up to the root counts.
+const ROOT: &str = "/sys/fs/cgroup";
+
+#[derive(Debug, Clone, Default)]
+pub struct Cgroup {
+    // As in /proc/<pid>/cgroup, e.g. "/system.slice/nginx.service"
+    pub path: String,
+    // Directories of the process's cgroup; the same one twice on v2
+    memory_dir: Option<PathBuf>,
+    cpu_dir: Option<PathBuf>,
+    v2: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Usage {
+    pub memory: Option<u64>,
+    // With the cgroup (path) it is set on, if not the process's own
+    pub memory_limit: Option<(u64, Option<String>)>,
+    // v2 memory.high, where reclaim and throttling start
+    pub memory_high: Option<u64>,
+    // Processes the OOM killer ended in the cgroup
+    pub oom_kills: Option<u64>,
+    // In CPUs, e.g. 1.5
+    pub cpu_quota: Option<(f64, Option<String>)>,
+    pub throttling: Option<Throttling>,
+}
+
+// cpu.stat counters, cumulative
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Throttling {
+    pub periods: u64,
+    pub throttled_periods: u64,
+    pub throttled_usec: u64,
+}
+
+impl Cgroup {
+    // pid as a number, or "self"
+    pub fn of(pid: &str) -> Option<Cgroup> {
+        let membership = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+        let mut cgroup = Cgroup::default();
+        for line in membership.lines() {
+            // "0::/path" on v2, "4:memory:/path" or "3:cpu,cpuacct:/path" on v1
+            let mut fields = line.splitn(3, ':');
+            let (_, controllers, path) = (fields.next()?, fields.next()?, fields.next()?);
+            let relative = path.trim_start_matches('/');
+            if controllers.is_empty() && Path::new(ROOT).join("cgroup.controllers").exists() {
+                let dir = Path::new(ROOT).join(relative);
+                cgroup = Cgroup { path: path.to_string(), memory_dir: Some(dir.clone()), cpu_dir: Some(dir), v2: true };
+                break;
+            }
+            let controllers: Vec<&str> = controllers.split(',').collect();
+            if controllers.contains(&"memory") {
+                cgroup.memory_dir = Some(Path::new(ROOT).join("memory").join(relative));
+                cgroup.path = path.to_string();
+            }
+            if controllers.contains(&"cpu") {
+                cgroup.cpu_dir = Some(Path::new(ROOT).join("cpu").join(relative));
+                if cgroup.path.is_empty() {
+                    cgroup.path = path.to_string();
+                }
+            }
+        }
+        (cgroup.memory_dir.is_some() || cgroup.cpu_dir.is_some()).then_some(cgroup)
+    }
+
+    pub fn usage(&self) -> Usage {
+        let mut usage = Usage::default();
+        if let Some(dir) = &self.memory_dir {
+            // v1 counts OOM kills in memory.oom_control since Linux 4.13
+            let (current, limit, events) = if self.v2 {
+                ("memory.current", "memory.max", "memory.events")
+            } else {
+                ("memory.usage_in_bytes", "memory.limit_in_bytes", "memory.oom_control")
+            };
+            usage.memory = read_number(&dir.join(current));
+            usage.memory_limit = self.tightest(dir, |dir| read_number(&dir.join(limit)).map(|limit| limit as f64)).map(|(limit, at)| (limit as u64, at));
+            usage.oom_kills = stat(&dir.join(events), "oom_kill");
+            if self.v2 {
+                usage.memory_high = read_number(&dir.join("memory.high"));
+            }
+        }
+        if let Some(dir) = &self.cpu_dir {
+            usage.cpu_quota = self.tightest(dir, |dir| cpu_quota(dir, self.v2));
+            let stat_file = dir.join("cpu.stat");
+            usage.throttling = Some(Throttling {
+                periods: stat(&stat_file, "nr_periods").unwrap_or(0),
+                throttled_periods: stat(&stat_file, "nr_throttled").unwrap_or(0),
+                // v1 counts nanoseconds
+                throttled_usec: match stat(&stat_file, "throttled_usec") {
+                    Some(usec) => usec,
+                    None => stat(&stat_file, "throttled_time").map_or(0, |ns| ns / 1000),
+                },
+            });
+        }
+        usage
+    }
+
+    // The smallest limit in this cgroup and its parents, with the parent's
+    // path when it comes from one
+    fn tightest(&self, dir: &Path, read: impl Fn(&Path) -> Option<f64>) -> Option<(f64, Option<String>)> {
+        let hierarchy_root = match self.v2 {
+            true => PathBuf::from(ROOT),
+            // /sys/fs/cgroup/memory
+            false => dir.ancestors().find(|dir| dir.parent() == Some(Path::new(ROOT)))?.to_path_buf(),
+        };
+        let mut tightest: Option<(f64, Option<String>)> = None;
+        for ancestor in dir.ancestors().take_while(|ancestor| ancestor.starts_with(&hierarchy_root)) {
+            let Some(limit) = read(ancestor) else { continue };
+            if tightest.as_ref().is_none_or(|(smallest, _)| limit < *smallest) {
+                let at = (ancestor != dir).then(|| format!("/{}", ancestor.strip_prefix(&hierarchy_root).unwrap_or(ancestor).display()));
+                tightest = Some((limit, at));
+            }
+        }
+        tightest
+    }
+}
+
+// v2 cpu.max "150000 100000" (quota and period, or "max"), v1
+// cpu.cfs_quota_us and cpu.cfs_period_us with -1 for none; in CPUs
+fn cpu_quota(dir: &Path, v2: bool) -> Option<f64> {
+    let (quota, period) = if v2 {
+        let max = fs::read_to_string(dir.join("cpu.max")).ok()?;
+        let mut fields = max.split_whitespace();
+        (fields.next()?.parse::<f64>().ok()?, fields.next()?.parse::<f64>().ok()?)
+    } else {
+        let read = |file: &str| fs::read_to_string(dir.join(file)).ok()?.trim().parse::<f64>().ok();
+        (read("cpu.cfs_quota_us")?, read("cpu.cfs_period_us")?)
+    };
+    (quota > 0.0 && period > 0.0).then(|| quota / period)
+}
+
+// A byte count; "max" on v2 and v1's near-2^63 "unlimited" are None
+fn read_number(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok().filter(|value| *value < 1 << 60)
+}
+
+// "key value" lines of cpu.stat and memory.events
+fn stat(path: &Path, key: &str) -> Option<u64> {
+    let stat = fs::read_to_string(path).ok()?;
+    stat.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix(' ')?.trim().parse().ok())
+}
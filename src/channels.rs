@@ -0,0 +1,701 @@
+use crate::alerts::{source_matches, Alert, AlertChange, Severity};
+use crate::email::EmailConfig;
+use crate::health::transport_error;
+use crate::mqtt::MqttConfig;
+use crate::report::Report;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::{HashMap, VecDeque},
+    io::Write,
+    process::{Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Alert delivery. Each configured channel only knows how to send one
+// notification; the Notifier decides what reaches it (routes, dedup, rate
+// limit) and retries failed sends on a background thread.
+pub trait AlertChannel: Send + Sync {
+    fn deliver(&self, notification: &Notification) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    // "alert", "resolved" or "report"
+    pub event: &'static str,
+    pub severity: Severity,
+    pub source: String,
+    pub message: String,
+    pub host: String,
+    pub time: String,
+    // Notifications this channel dropped to the rate limit since the last one sent
+    pub suppressed: usize,
+    // Full text of a report, HTML if `html`; empty for alerts
+    pub detail: String,
+    pub html: bool,
+}
+
+impl Notification {
+    // A scheduled report; the title is the message
+    pub fn report(report: &Report, html: bool) -> Notification {
+        Notification {
+            event: "report",
+            severity: Severity::Warning,
+            source: "report".to_string(),
+            message: report.title.clone(),
+            host: sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string()),
+            time: chrono::Local::now().format("%Y-%m-%d %H:%M:%S %Z").to_string(),
+            suppressed: 0,
+            detail: report.body.clone(),
+            html,
+        }
+    }
+
+    // "warning"/"critical", "resolved" once the alert cleared, or "report"
+    pub fn label(&self) -> &'static str {
+        match self.event {
+            "resolved" | "report" => self.event,
+            _ => self.severity.name(),
+        }
+    }
+
+    // Fill {host}, {severity}, {message}, {source}, {event} and {time}
+    pub fn fill(&self, template: &str) -> String {
+        template
+            .replace("{host}", &self.host)
+            .replace("{severity}", self.label())
+            .replace("{message}", &self.message)
+            .replace("{source}", &self.source)
+            .replace("{event}", self.event)
+            .replace("{time}", &self.time)
+    }
+
+    pub fn summary(&self) -> String {
+        self.fill("[{host}] {severity}: {message}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ChannelConfig {
+    // How routes refer to the channel, defaults to the type
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub kind: ChannelKind,
+    #[serde(flatten)]
+    pub policy: DeliveryPolicy,
+}
+
+impl ChannelConfig {
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(self.kind.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ChannelKind {
+    Desktop,
+    Webhook(WebhookConfig),
+    Email(EmailConfig),
+    Mqtt(MqttConfig),
+    Command(CommandConfig),
+}
+
+impl ChannelKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChannelKind::Desktop => "desktop",
+            ChannelKind::Webhook(_) => "webhook",
+            ChannelKind::Email(_) => "email",
+            ChannelKind::Mqtt(_) => "mqtt",
+            ChannelKind::Command(_) => "command",
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            ChannelKind::Desktop => Ok(()),
+            ChannelKind::Webhook(config) => config.validate(),
+            ChannelKind::Email(config) => config.validate(),
+            ChannelKind::Mqtt(config) => config.validate(),
+            ChannelKind::Command(config) => config.validate(),
+        }
+    }
+
+    fn build(&self) -> Arc<dyn AlertChannel> {
+        match self {
+            ChannelKind::Desktop => Arc::new(Desktop),
+            ChannelKind::Webhook(config) => Arc::new(config.clone()),
+            ChannelKind::Email(config) => Arc::new(config.clone()),
+            ChannelKind::Mqtt(config) => Arc::new(config.clone()),
+            ChannelKind::Command(config) => Arc::new(config.clone()),
+        }
+    }
+}
+
+// Per-channel limits, so a noisy host can't flood a pager or inbox
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DeliveryPolicy {
+    pub send_resolved: bool,
+    // The same alert at the same severity is sent at most once per window
+    pub repeat_minutes: u64,
+    pub max_per_hour: usize,
+    // Extra attempts after a failed send, waiting retry_delay_ms, then twice that, ...
+    pub retries: u32,
+    pub retry_delay_ms: u64,
+}
+
+impl Default for DeliveryPolicy {
+    fn default() -> Self {
+        DeliveryPolicy {
+            send_resolved: true,
+            repeat_minutes: 60,
+            max_per_hour: 10,
+            retries: 3,
+            retry_delay_ms: 2000,
+        }
+    }
+}
+
+// Which channels get which alerts. Without routes every channel gets everything.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AlertRoute {
+    // Alert source, e.g. "disk:/home"; a trailing * matches any suffix
+    #[serde(rename = "match")]
+    pub pattern: String,
+    pub min_severity: Severity,
+    pub channels: Vec<String>,
+}
+
+impl Default for AlertRoute {
+    fn default() -> Self {
+        AlertRoute { pattern: "*".to_string(), min_severity: Severity::Warning, channels: Vec::new() }
+    }
+}
+
+impl AlertRoute {
+    fn matches(&self, alert: &Alert) -> bool {
+        source_matches(&self.pattern, &alert.source) && alert.severity >= self.min_severity
+    }
+}
+
+pub fn validate(channels: &[ChannelConfig], routes: &[AlertRoute]) -> Result<(), String> {
+    for (i, channel) in channels.iter().enumerate() {
+        let name = channel.name();
+        if channels[..i].iter().any(|c| c.name() == name) {
+            return Err(format!("alert channel '{}' is defined twice, give each a distinct name", name));
+        }
+        channel.kind.validate().map_err(|e| format!("alert channel '{}': {}", name, e))?;
+    }
+    for route in routes {
+        if route.channels.is_empty() {
+            return Err(format!("alert route '{}': no channels listed", route.pattern));
+        }
+        if let Some(unknown) = route.channels.iter().find(|name| !channels.iter().any(|c| c.name() == *name)) {
+            return Err(format!("alert route '{}': unknown channel '{}'", route.pattern, unknown));
+        }
+    }
+    Ok(())
+}
+
+struct ChannelState {
+    name: String,
+    channel: Arc<dyn AlertChannel>,
+    policy: DeliveryPolicy,
+    // When each alert source was last sent, and at which severity
+    last_sent: HashMap<String, (Severity, Instant)>,
+    // Send times within the last hour, for max_per_hour
+    sent: VecDeque<Instant>,
+    suppressed: usize,
+    // Outcome of the latest delivery, written by the sending thread
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl ChannelState {
+    // Dedup and rate limit; true if this change should go out
+    fn admit(&mut self, change: &AlertChange) -> bool {
+        match change {
+            AlertChange::Fired(alert) => {
+                let repeat = Duration::from_secs(self.policy.repeat_minutes * 60);
+                let recent = self
+                    .last_sent
+                    .get(&alert.source)
+                    .is_some_and(|(severity, at)| *severity == alert.severity && at.elapsed() < repeat);
+                if recent {
+                    return false;
+                }
+            }
+            // Only worth sending if the alert itself was sent
+            AlertChange::Resolved(alert) => {
+                if !self.policy.send_resolved || !self.last_sent.contains_key(&alert.source) {
+                    return false;
+                }
+            }
+        }
+
+        while self.sent.front().is_some_and(|at| at.elapsed() >= Duration::from_secs(3600)) {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= self.policy.max_per_hour {
+            self.suppressed += 1;
+            return false;
+        }
+        self.sent.push_back(Instant::now());
+        match change {
+            AlertChange::Fired(alert) => {
+                self.last_sent.insert(alert.source.clone(), (alert.severity, Instant::now()));
+            }
+            AlertChange::Resolved(alert) => {
+                self.last_sent.remove(&alert.source);
+            }
+        }
+        true
+    }
+
+    fn send(&mut self, mut notification: Notification) {
+        notification.suppressed = std::mem::take(&mut self.suppressed);
+        let channel = Arc::clone(&self.channel);
+        let policy = self.policy.clone();
+        let error = Arc::clone(&self.error);
+        // Deliveries block on the network and retries sleep, keep them off the UI thread
+        thread::spawn(move || {
+            let mut delay = Duration::from_millis(policy.retry_delay_ms);
+            for attempt in 0..=policy.retries {
+                match channel.deliver(&notification) {
+                    Ok(()) => {
+                        *error.lock().unwrap() = None;
+                        return;
+                    }
+                    Err(e) => {
+                        *error.lock().unwrap() = Some(e.split(" (os error").next().unwrap_or_default().to_string());
+                        if attempt < policy.retries {
+                            thread::sleep(delay);
+                            delay *= 2;
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+pub struct Notifier {
+    channels: Vec<ChannelState>,
+    routes: Vec<AlertRoute>,
+    host: String,
+}
+
+impl Notifier {
+    pub fn new(channels: Vec<ChannelConfig>, routes: Vec<AlertRoute>) -> Notifier {
+        let channels = channels
+            .into_iter()
+            .map(|config| ChannelState {
+                name: config.name().to_string(),
+                channel: config.kind.build(),
+                policy: config.policy,
+                last_sent: HashMap::new(),
+                sent: VecDeque::new(),
+                suppressed: 0,
+                error: Arc::new(Mutex::new(None)),
+            })
+            .collect();
+        Notifier { channels, routes, host: sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string()) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    pub fn notify(&mut self, changes: &[AlertChange]) {
+        let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S %Z").to_string();
+        for change in changes {
+            let (event, alert) = match change {
+                AlertChange::Fired(alert) => ("alert", alert),
+                AlertChange::Resolved(alert) => ("resolved", alert),
+            };
+            let routed: Vec<&str> = self
+                .routes
+                .iter()
+                .filter(|route| route.matches(alert))
+                .flat_map(|route| route.channels.iter().map(String::as_str))
+                .collect();
+            for channel in &mut self.channels {
+                if !self.routes.is_empty() && !routed.contains(&channel.name.as_str()) {
+                    continue;
+                }
+                if !channel.admit(change) {
+                    continue;
+                }
+                channel.send(Notification {
+                    event,
+                    severity: alert.severity,
+                    source: alert.source.clone(),
+                    message: alert.message.clone(),
+                    host: self.host.clone(),
+                    time: time.clone(),
+                    suppressed: 0,
+                    detail: String::new(),
+                    html: false,
+                });
+            }
+        }
+    }
+
+    // Straight to the named channels, outside routes, dedup and rate limits
+    pub fn send(&mut self, names: &[String], notification: Notification) {
+        for channel in self.channels.iter_mut().filter(|c| names.contains(&c.name)) {
+            channel.send(notification.clone());
+        }
+    }
+
+    // One line for the System Information panel, naming failing channels
+    pub fn status(&self) -> String {
+        let names: Vec<&str> = self.channels.iter().map(|c| c.name.as_str()).collect();
+        let errors: Vec<String> = self
+            .channels
+            .iter()
+            .filter_map(|c| c.error.lock().unwrap().as_ref().map(|e| format!("{}: {}", c.name, e)))
+            .collect();
+        if errors.is_empty() {
+            format!("🔔 Alerts → {}", names.join(", "))
+        } else {
+            format!("🔔 Alerts → {} ❌ {}", names.join(", "), errors.join("; "))
+        }
+    }
+}
+
+// notify-send on Linux/BSD, osascript on macOS
+struct Desktop;
+
+impl AlertChannel for Desktop {
+    fn deliver(&self, notification: &Notification) -> Result<(), String> {
+        let title = format!("System Monitor: {}", notification.label());
+        let mut command = if cfg!(target_os = "macos") {
+            let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+            let script = format!(
+                "display notification \"{}\" with title \"{}\"",
+                quote(&notification.message),
+                quote(&title)
+            );
+            let mut command = Command::new("osascript");
+            command.arg("-e").arg(script);
+            command
+        } else {
+            let mut command = Command::new("notify-send");
+            let urgency = if notification.event == "alert" && notification.severity == Severity::Critical {
+                "critical"
+            } else {
+                "normal"
+            };
+            command.args(["--app-name", "system-monitor", "--urgency", urgency, &title, &notification.message]);
+            command
+        };
+        run(&mut command, None)
+    }
+}
+
+// JSON POST; the "text" field makes Slack/Mattermost incoming webhooks work as-is
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub timeout_ms: u64,
+}
+
+impl WebhookConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !self.url.starts_with("http://") && !self.url.starts_with("https://") {
+            return Err("url must start with http:// or https://".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl AlertChannel for WebhookConfig {
+    fn deliver(&self, notification: &Notification) -> Result<(), String> {
+        // Chat webhooks show "text", so a plain text report goes there in full
+        let text = if notification.detail.is_empty() || notification.html {
+            notification.summary()
+        } else {
+            format!("{}\n{}", notification.summary(), notification.detail)
+        };
+        let body = json!({
+            "text": text,
+            "event": notification.event,
+            "severity": notification.label(),
+            "source": notification.source,
+            "message": notification.message,
+            "host": notification.host,
+            "time": notification.time,
+            "suppressed": notification.suppressed,
+            "detail": notification.detail,
+        });
+        let timeout = if self.timeout_ms > 0 { self.timeout_ms } else { 10_000 };
+        ureq::post(&self.url)
+            .timeout(Duration::from_millis(timeout))
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())
+            .map(|_| ())
+            .map_err(|e| match e {
+                ureq::Error::Status(code, _) => format!("HTTP {}", code),
+                ureq::Error::Transport(transport) => transport_error(&transport),
+            })
+    }
+}
+
+// Runs through sh -c with the notification in SYSTEM_MONITOR_* variables and
+// as JSON on stdin
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CommandConfig {
+    pub command: String,
+}
+
+impl CommandConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.command.trim().is_empty() {
+            return Err("command is empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl AlertChannel for CommandConfig {
+    fn deliver(&self, notification: &Notification) -> Result<(), String> {
+        let input = json!({
+            "event": notification.event,
+            "severity": notification.label(),
+            "source": notification.source,
+            "message": notification.message,
+            "host": notification.host,
+            "time": notification.time,
+            "detail": notification.detail,
+        });
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(&self.command)
+            .env("SYSTEM_MONITOR_EVENT", notification.event)
+            .env("SYSTEM_MONITOR_SEVERITY", notification.label())
+            .env("SYSTEM_MONITOR_SOURCE", &notification.source)
+            .env("SYSTEM_MONITOR_MESSAGE", &notification.message)
+            .env("SYSTEM_MONITOR_HOST", &notification.host);
+        run(&mut command, Some(input.to_string()))
+    }
+}
+
+// Run to completion, killing it after 30 seconds
+fn run(command: &mut Command, input: Option<String>) -> Result<(), String> {
+    let mut child = command
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("{} not found", command.get_program().to_string_lossy()),
+            _ => e.to_string(),
+        })?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // The command may not read its input at all
+        let _ = stdin.write_all(input.as_bytes());
+    }
+    let started = Instant::now();
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => return Err(format!("exited with {}", status)),
+            None if started.elapsed() > Duration::from_secs(30) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("timed out after 30s".to_string());
+            }
+            None => thread::sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Remembers what it was asked to deliver, failing the first `failures` times
+    struct Recorder {
+        delivered: Arc<Mutex<Vec<Notification>>>,
+        failures: Mutex<u32>,
+    }
+
+    impl AlertChannel for Recorder {
+        fn deliver(&self, notification: &Notification) -> Result<(), String> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err("connection refused (os error 111)".to_string());
+            }
+            self.delivered.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    type Delivered = Arc<Mutex<Vec<Notification>>>;
+
+    fn channel(name: &str, policy: DeliveryPolicy, failures: u32) -> (ChannelState, Delivered) {
+        let delivered = Delivered::default();
+        let state = ChannelState {
+            name: name.to_string(),
+            channel: Arc::new(Recorder { delivered: Arc::clone(&delivered), failures: Mutex::new(failures) }),
+            policy,
+            last_sent: HashMap::new(),
+            sent: VecDeque::new(),
+            suppressed: 0,
+            error: Arc::new(Mutex::new(None)),
+        };
+        (state, delivered)
+    }
+
+    fn notifier(names: &[&str], routes: Vec<AlertRoute>) -> (Notifier, Vec<Delivered>) {
+        let (channels, delivered) = names.iter().map(|name| channel(name, DeliveryPolicy::default(), 0)).unzip();
+        (Notifier { channels, routes, host: "box".to_string() }, delivered)
+    }
+
+    fn alert(severity: Severity, source: &str) -> Alert {
+        Alert { severity, source: source.to_string(), message: format!("{} is full", source) }
+    }
+
+    // Sources delivered once the sending threads are done
+    fn sources(delivered: &Delivered, expected: usize) -> Vec<String> {
+        let started = Instant::now();
+        while delivered.lock().unwrap().len() < expected && started.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(5));
+        }
+        thread::sleep(Duration::from_millis(20));
+        delivered.lock().unwrap().iter().map(|notification| notification.source.clone()).collect()
+    }
+
+    #[test]
+    fn routes_pick_the_channels() {
+        let routes = vec![
+            AlertRoute { pattern: "disk:*".to_string(), channels: vec!["pager".to_string()], ..AlertRoute::default() },
+            AlertRoute { min_severity: Severity::Critical, channels: vec!["mail".to_string()], ..AlertRoute::default() },
+        ];
+        let (mut notifier, delivered) = notifier(&["pager", "mail", "chat"], routes);
+        notifier.notify(&[
+            AlertChange::Fired(alert(Severity::Warning, "disk:/home")),
+            AlertChange::Fired(alert(Severity::Critical, "cpu")),
+            AlertChange::Fired(alert(Severity::Critical, "disk:/")),
+            AlertChange::Fired(alert(Severity::Warning, "memory")),
+        ]);
+        assert_eq!(sources(&delivered[0], 2), ["disk:/home", "disk:/"]);
+        assert_eq!(sources(&delivered[1], 2), ["cpu", "disk:/"]);
+        assert!(sources(&delivered[2], 0).is_empty());
+    }
+
+    #[test]
+    fn without_routes_every_channel_gets_everything() {
+        let (mut notifier, delivered) = notifier(&["pager", "mail"], Vec::new());
+        notifier.notify(&[AlertChange::Fired(alert(Severity::Warning, "memory"))]);
+        for delivered in &delivered {
+            assert_eq!(sources(delivered, 1), ["memory"]);
+        }
+    }
+
+    #[test]
+    fn repeats_wait_for_the_window_unless_the_severity_changes() {
+        let (mut state, _) = channel("pager", DeliveryPolicy::default(), 0);
+        let warning = AlertChange::Fired(alert(Severity::Warning, "disk:/"));
+        assert!(state.admit(&warning));
+        assert!(!state.admit(&warning));
+        assert!(state.admit(&AlertChange::Fired(alert(Severity::Critical, "disk:/"))));
+        assert!(state.admit(&AlertChange::Fired(alert(Severity::Warning, "disk:/home"))));
+
+        let (mut state, _) = channel("pager", DeliveryPolicy { repeat_minutes: 0, ..DeliveryPolicy::default() }, 0);
+        assert!(state.admit(&warning));
+        assert!(state.admit(&warning));
+    }
+
+    #[test]
+    fn resolved_only_follows_a_sent_alert() {
+        let (mut state, _) = channel("pager", DeliveryPolicy::default(), 0);
+        let resolved = AlertChange::Resolved(alert(Severity::Warning, "disk:/"));
+        assert!(!state.admit(&resolved));
+        assert!(state.admit(&AlertChange::Fired(alert(Severity::Warning, "disk:/"))));
+        assert!(state.admit(&resolved));
+        // Cleared, so a second resolve and the next firing are both news
+        assert!(!state.admit(&resolved));
+        assert!(state.admit(&AlertChange::Fired(alert(Severity::Warning, "disk:/"))));
+
+        let (mut state, _) = channel("pager", DeliveryPolicy { send_resolved: false, ..DeliveryPolicy::default() }, 0);
+        assert!(state.admit(&AlertChange::Fired(alert(Severity::Warning, "disk:/"))));
+        assert!(!state.admit(&resolved));
+    }
+
+    #[test]
+    fn rate_limit_counts_what_it_held_back() {
+        let policy = DeliveryPolicy { max_per_hour: 2, ..DeliveryPolicy::default() };
+        let (mut state, delivered) = channel("pager", policy, 0);
+        for source in ["a", "b", "c", "d"] {
+            assert_eq!(state.admit(&AlertChange::Fired(alert(Severity::Warning, source))), source < "c");
+        }
+        assert_eq!(state.suppressed, 2);
+        // The next one sent says how many were dropped, then starts over
+        let notification = Notification::report(&Report { title: "Daily".to_string(), body: String::new() }, false);
+        state.send(notification.clone());
+        state.send(notification);
+        sources(&delivered, 2);
+        let suppressed: Vec<usize> = delivered.lock().unwrap().iter().map(|n| n.suppressed).collect();
+        assert_eq!(suppressed, [2, 0]);
+    }
+
+    #[test]
+    fn retries_until_delivered() {
+        let policy = DeliveryPolicy { retries: 2, retry_delay_ms: 1, ..DeliveryPolicy::default() };
+        let (mut state, delivered) = channel("pager", policy.clone(), 2);
+        state.send(Notification::report(&Report { title: "Daily".to_string(), body: String::new() }, false));
+        assert_eq!(sources(&delivered, 1), ["report"]);
+        assert_eq!(*state.error.lock().unwrap(), None);
+
+        // Out of retries: the error stays for the status line, without the errno
+        let (mut state, delivered) = channel("pager", policy, 3);
+        state.send(Notification::report(&Report { title: "Daily".to_string(), body: String::new() }, false));
+        let started = Instant::now();
+        while Arc::strong_count(&state.error) > 1 && started.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(delivered.lock().unwrap().is_empty());
+        assert_eq!(state.error.lock().unwrap().as_deref(), Some("connection refused"));
+    }
+
+    #[test]
+    fn validates_names_and_routes() {
+        let desktop = |name: Option<&str>| ChannelConfig {
+            name: name.map(str::to_string),
+            kind: ChannelKind::Desktop,
+            policy: DeliveryPolicy::default(),
+        };
+        let route = |channels: &[&str]| AlertRoute {
+            channels: channels.iter().map(|name| name.to_string()).collect(),
+            ..AlertRoute::default()
+        };
+        assert!(validate(&[desktop(None), desktop(Some("laptop"))], &[route(&["desktop", "laptop"])]).is_ok());
+        let twice = validate(&[desktop(None), desktop(Some("desktop"))], &[]).unwrap_err();
+        assert!(twice.contains("defined twice"));
+        let unknown = validate(&[desktop(None)], &[route(&["pager"])]).unwrap_err();
+        assert!(unknown.contains("unknown channel 'pager'"));
+        assert!(validate(&[desktop(None)], &[route(&[])]).unwrap_err().contains("no channels"));
+    }
+
+    #[test]
+    fn fills_templates() {
+        let (mut notifier, delivered) = notifier(&["pager"], Vec::new());
+        notifier.notify(&[AlertChange::Fired(alert(Severity::Critical, "disk:/"))]);
+        sources(&delivered[0], 1);
+        let notification = delivered[0].lock().unwrap()[0].clone();
+        assert_eq!(notification.summary(), "[box] critical: disk:/ is full");
+        assert_eq!(notification.fill("{event} {source} {severity}"), "alert disk:/ critical");
+        let resolved = Notification { event: "resolved", ..notification };
+        assert_eq!(resolved.label(), "resolved");
+    }
+}
@@ -0,0 +1,89 @@
+use crate::columns::{Column, ColumnSetting};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    widgets::{Cell, Row},
+};
+use std::collections::{HashMap, HashSet};
+use sysinfo::{Pid, System, Uid};
+
+// Process churn on the Processes tab: processes that appeared since the
+// previous refresh are green for one refresh, and ones that exited stay at
+// the bottom of the full table, struck through, red at first and grey as
+// they fade over FADE refreshes. A build or a crash-looping service shows at
+// a glance. A PID reused by a new process counts as an exit and a start.
+pub const FADE: u32 = 3;
+// Exited rows shown at most, newest first
+pub const MAX_SHOWN: usize = 5;
+// Exits remembered at most, for when the / filter or u user hides some
+const MAX_KEPT: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct Exited {
+    pub pid: Pid,
+    pub name: String,
+    pub user: Option<Uid>,
+    // Refreshes since it exited
+    age: u32,
+}
+
+#[derive(Default, Clone)]
+pub struct Churn {
+    // Every process at the last refresh: start time, name and user, the
+    // last two kept for when it exits
+    known: HashMap<Pid, (u64, String, Option<Uid>)>,
+    new: HashSet<Pid>,
+    pub exited: Vec<Exited>,
+}
+
+impl Churn {
+    // After each process refresh; names are only read for new processes
+    pub fn update(&mut self, system: &System) {
+        let processes = system.processes();
+        self.exited.iter_mut().for_each(|exited| exited.age += 1);
+        self.exited.retain(|exited| exited.age < FADE);
+        let first = self.known.is_empty();
+        let mut gone: Vec<Exited> = Vec::new();
+        self.known.retain(|pid, (start_time, name, user)| {
+            let alive = processes.get(pid).is_some_and(|process| process.start_time() == *start_time);
+            if !alive {
+                gone.push(Exited { pid: *pid, name: std::mem::take(name), user: user.take(), age: 0 });
+            }
+            alive
+        });
+        gone.sort_by_key(|exited| std::cmp::Reverse(exited.pid));
+        self.exited.splice(0..0, gone);
+        self.exited.truncate(MAX_KEPT);
+        self.new.clear();
+        for (pid, process) in processes.iter().filter(|(_, process)| process.thread_kind().is_none()) {
+            if self.known.contains_key(pid) {
+                continue;
+            }
+            // Everything is new the first time
+            if !first {
+                self.new.insert(*pid);
+            }
+            let name = process.name().to_string_lossy().into_owned();
+            self.known.insert(*pid, (process.start_time(), name, process.user_id().cloned()));
+        }
+    }
+
+    pub fn is_new(&self, pid: Pid) -> bool {
+        self.new.contains(&pid)
+    }
+}
+
+// PID, user and name of an exited process, the other cells left empty
+pub fn exited_row(exited: &Exited, columns: &[ColumnSetting], user: String) -> Row<'static> {
+    let color = match exited.age {
+        0 => Color::Red,
+        1 => Color::LightRed,
+        _ => Color::DarkGray,
+    };
+    Row::new(columns.iter().map(|c| match c.column {
+        Column::Pid => Cell::from(exited.pid.to_string()),
+        Column::User => Cell::from(user.clone()),
+        Column::Name => Cell::from(format!("✖ {} (exited)", exited.name)),
+        _ => Cell::from(""),
+    }))
+    .style(Style::default().fg(color).add_modifier(Modifier::CROSSED_OUT))
+}
@@ -0,0 +1,78 @@
+use crate::accounting::AccountingPeriod;
+use crate::report::ReportPeriod;
+use clap::{CommandFactory, Parser, Subcommand};
+use std::io;
+
+// Command line options; anything here overrides the config file
+#[derive(Debug, Parser)]
+#[command(name = "system-monitor", version, about = "Real-time system monitor TUI")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Disable destructive actions (kill, renice, restarts, eject, journal vacuum) and settings, e.g. for a shared dashboard
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Drop unneeded capabilities and apply seccomp/Landlock before any thread starts (Linux)
+    #[arg(long)]
+    pub harden: bool,
+
+    /// Also sample CPU and network every 200 ms (or [high_resolution] interval_ms) to catch short bursts
+    #[arg(long)]
+    pub high_res: bool,
+
+    /// List processes that exit between refreshes under the Processes tab (Linux, root)
+    #[arg(long)]
+    pub trace_exec: bool,
+
+    /// Print which data sources work on this platform and permission level and exit
+    #[arg(long)]
+    pub capabilities: bool,
+
+    /// Print a report of the last day or week from the archived history and exit
+    #[arg(long, value_name = "PERIOD")]
+    pub report: Option<ReportPeriod>,
+
+    /// Print CPU time and disk I/O per command over the last day, week or month and exit
+    #[arg(long, value_name = "PERIOD")]
+    pub accounting: Option<AccountingPeriod>,
+
+    /// Serve metrics as JSON over HTTP on this address, e.g. 127.0.0.1:9100
+    #[cfg(feature = "api")]
+    #[arg(long, value_name = "ADDR")]
+    pub api: Option<String>,
+
+    /// Run without the terminal UI and only serve the API
+    #[cfg(feature = "api")]
+    #[arg(long)]
+    pub headless: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print one value for scripts, e.g. cpu.usage, process[name=nginx].rss or disk[/].free
+    Query {
+        /// target.field, or target[selector].field for process, disk and net
+        expr: String,
+    },
+    /// Print the completion script for bash, zsh, fish, elvish or powershell
+    Completions {
+        shell: clap_complete::Shell,
+    },
+    /// Print the man page in roff format
+    Man,
+    /// Print the versioned schema of the exported files and the JSON API, as JSON
+    Schema,
+}
+
+// Completions and the man page come from the definitions above, so they
+// can't fall behind the options
+pub fn print_completions(shell: clap_complete::Shell) {
+    let mut command = Cli::command();
+    clap_complete::generate(shell, &mut command, "system-monitor", &mut io::stdout());
+}
+
+pub fn print_man() -> io::Result<()> {
+    clap_mangen::Man::new(Cli::command()).render(&mut io::stdout())
+}
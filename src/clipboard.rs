@@ -0,0 +1,33 @@
+use crate::format_bytes;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::{
+    env,
+    io::{self, Write},
+};
+
+// Copying with the OSC 52 escape sequence: the terminal showing the monitor
+// puts the text on its own clipboard, so copying works over SSH and in
+// containers where there is no clipboard tool or display. Inside tmux the
+// sequence is sent both as is, which tmux takes with `set-clipboard on`, and
+// wrapped for `allow-passthrough on`; GNU screen only passes it on wrapped.
+// Terminals without OSC 52 (GNOME Terminal and other VTE ones) ignore it.
+// Some terminals drop longer sequences
+const MAX_ENCODED: usize = 100_000;
+
+pub fn copy(text: &str) -> Result<(), String> {
+    let encoded = STANDARD.encode(text);
+    if encoded.len() > MAX_ENCODED {
+        return Err(format!("{} is too much for the terminal's clipboard", format_bytes(text.len() as u64)));
+    }
+    let osc = format!("\x1b]52;c;{}\x07", encoded);
+    let sequence = if env::var_os("TMUX").is_some() {
+        // Escapes inside the passthrough are doubled
+        format!("{}\x1bPtmux;{}\x1b\\", osc, osc.replace('\x1b', "\x1b\x1b"))
+    } else if env::var("TERM").is_ok_and(|term| term.starts_with("screen")) {
+        format!("\x1bP{}\x1b\\", osc)
+    } else {
+        osc
+    };
+    let mut stdout = io::stdout();
+    stdout.write_all(sequence.as_bytes()).and_then(|()| stdout.flush()).map_err(|err| err.to_string())
+}
@@ -0,0 +1,266 @@
+use crate::alerts::{Alert, Severity};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+// Instance type, region, spot/preemptible status and IAM role from the cloud
+// provider's metadata service, plus the notice a spot or preemptible VM gets
+// a minute or two before it is taken away, raised as a critical alert.
+// Opt-in, since it means talking to 169.254.169.254: the first provider to
+// answer is used, and the notice endpoint is then polled every few seconds.
+const METADATA: &str = "http://169.254.169.254";
+const TIMEOUT: Duration = Duration::from_secs(2);
+// AWS asks for checks every 5 seconds; its notice comes two minutes ahead
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// [cloud] in the config
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CloudConfig {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Provider {
+    Aws,
+    Gcp,
+    Azure,
+}
+
+#[derive(Debug, Clone)]
+struct Instance {
+    provider: Provider,
+    instance_type: String,
+    region: String,
+    // Spot (AWS, Azure) or preemptible/Spot (GCP)
+    spot: bool,
+    role: Option<String>,
+}
+
+#[derive(Default)]
+struct State {
+    instance: Option<Result<Instance, String>>,
+    // What the provider announced, e.g. "terminate at 2026-10-16T10:42:00Z"
+    notice: Option<String>,
+}
+
+pub struct Cloud {
+    state: Arc<Mutex<State>>,
+}
+
+impl Cloud {
+    pub fn start() -> Cloud {
+        let state = Arc::new(Mutex::new(State::default()));
+        let thread_state = Arc::clone(&state);
+        thread::spawn(move || {
+            let agent = ureq::AgentBuilder::new().timeout(TIMEOUT).redirects(0).build();
+            let instance = detect(&agent);
+            let provider = instance.as_ref().ok().map(|instance| instance.provider);
+            let spot = instance.as_ref().is_ok_and(|instance| instance.spot);
+            thread_state.lock().unwrap().instance = Some(instance);
+            // Only spot and preemptible VMs get notices
+            let Some(provider) = provider.filter(|_| spot) else { return };
+            loop {
+                // A failed poll keeps the last notice rather than clearing it
+                if let Ok(notice) = termination_notice(&agent, provider) {
+                    thread_state.lock().unwrap().notice = notice;
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+        Cloud { state }
+    }
+
+    // "🌎 Cloud: AWS m5.large in eu-west-1 · spot · IAM role web"
+    pub fn status(&self) -> String {
+        let state = self.state.lock().unwrap();
+        match &state.instance {
+            None => "🌎 Cloud: asking the metadata service…".to_string(),
+            Some(Err(err)) => format!("🌎 Cloud: {}", err),
+            Some(Ok(instance)) => {
+                let provider = match instance.provider {
+                    Provider::Aws => "AWS",
+                    Provider::Gcp => "GCP",
+                    Provider::Azure => "Azure",
+                };
+                let mut status = format!("🌎 Cloud: {} {} in {}", provider, instance.instance_type, instance.region);
+                status.push_str(match (instance.spot, instance.provider) {
+                    (false, _) => " · on-demand",
+                    (true, Provider::Gcp) => " · preemptible",
+                    (true, _) => " · spot",
+                });
+                if let Some(role) = &instance.role {
+                    let label = if instance.provider == Provider::Gcp { "service account" } else { "IAM role" };
+                    status.push_str(&format!(" · {} {}", label, role));
+                }
+                if let Some(notice) = &state.notice {
+                    status.push_str(&format!(" · ⚠️ {}", notice));
+                }
+                status
+            }
+        }
+    }
+
+    pub fn alerts(&self) -> Vec<Alert> {
+        let state = self.state.lock().unwrap();
+        state
+            .notice
+            .iter()
+            .map(|notice| Alert {
+                severity: Severity::Critical,
+                source: "cloud:termination".to_string(),
+                message: format!("This instance is being reclaimed: {}", notice),
+            })
+            .collect()
+    }
+}
+
+fn error(e: ureq::Error) -> String {
+    match e {
+        ureq::Error::Status(code, _) => format!("HTTP {}", code),
+        ureq::Error::Transport(transport) => crate::health::transport_error(&transport),
+    }
+}
+
+// Each provider has its own header, which the others reject
+fn detect(agent: &ureq::Agent) -> Result<Instance, String> {
+    if let Ok(token) = aws_token(agent) {
+        return aws_instance(agent, &token);
+    }
+    let gcp = agent
+        .get(&format!("{}/computeMetadata/v1/instance/machine-type", METADATA))
+        .set("Metadata-Flavor", "Google")
+        .call();
+    if let Ok(response) = gcp {
+        if response.header("Metadata-Flavor") == Some("Google") {
+            let machine_type = response.into_string().map_err(|e| e.to_string())?;
+            return gcp_instance(agent, &machine_type);
+        }
+    }
+    match azure(agent, "instance?api-version=2021-02-01") {
+        Ok(metadata) => Ok(azure_instance(&metadata)),
+        Err(_) => Err("no metadata service answered, not a cloud VM?".to_string()),
+    }
+}
+
+// IMDSv2 session token, good for six hours; a fresh one is taken every time
+fn aws_token(agent: &ureq::Agent) -> Result<String, String> {
+    agent
+        .put(&format!("{}/latest/api/token", METADATA))
+        .set("X-aws-ec2-metadata-token-ttl-seconds", "21600")
+        .call()
+        .map_err(error)?
+        .into_string()
+        .map_err(|e| e.to_string())
+}
+
+fn aws_request(agent: &ureq::Agent, token: &str, path: &str) -> ureq::Request {
+    agent.get(&format!("{}/latest/meta-data/{}", METADATA, path)).set("X-aws-ec2-metadata-token", token)
+}
+
+fn aws_get(agent: &ureq::Agent, token: &str, path: &str) -> Result<String, String> {
+    let response = aws_request(agent, token, path).call().map_err(error)?;
+    Ok(response.into_string().unwrap_or_default())
+}
+
+fn aws_instance(agent: &ureq::Agent, token: &str) -> Result<Instance, String> {
+    let instance_type = aws_get(agent, token, "instance-type")?;
+    let region = aws_get(agent, token, "placement/region").unwrap_or_default();
+    let spot = aws_get(agent, token, "instance-life-cycle").is_ok_and(|cycle| cycle == "spot");
+    // "arn:aws:iam::123456789012:instance-profile/web" when a role is attached
+    let role = aws_get(agent, token, "iam/info")
+        .ok()
+        .and_then(|info| serde_json::from_str::<Value>(&info).ok())
+        .and_then(|info| info["InstanceProfileArn"].as_str().map(|arn| arn.rsplit('/').next().unwrap_or(arn).to_string()));
+    Ok(Instance { provider: Provider::Aws, instance_type, region, spot, role })
+}
+
+fn gcp_get(agent: &ureq::Agent, path: &str) -> Result<String, String> {
+    let response = agent
+        .get(&format!("{}/computeMetadata/v1/instance/{}", METADATA, path))
+        .set("Metadata-Flavor", "Google")
+        .call()
+        .map_err(error)?;
+    Ok(response.into_string().unwrap_or_default())
+}
+
+// Machine type and zone come as "projects/123/machineTypes/e2-medium"
+fn gcp_instance(agent: &ureq::Agent, machine_type: &str) -> Result<Instance, String> {
+    let last = |path: &str| path.rsplit('/').next().unwrap_or(path).to_string();
+    let zone = gcp_get(agent, "zone").map(|zone| last(&zone)).unwrap_or_default();
+    // Preemptible VMs say TRUE, Spot VMs name their provisioning model
+    let preemptible = gcp_get(agent, "scheduling/preemptible").is_ok_and(|value| value.trim() == "TRUE");
+    let spot = gcp_get(agent, "scheduling/provisioning-model").is_ok_and(|value| value.trim() == "SPOT");
+    let role = gcp_get(agent, "service-accounts/default/email").ok().filter(|email| !email.is_empty());
+    Ok(Instance {
+        provider: Provider::Gcp,
+        instance_type: last(machine_type),
+        region: zone,
+        spot: preemptible || spot,
+        role,
+    })
+}
+
+fn azure(agent: &ureq::Agent, path: &str) -> Result<Value, String> {
+    let body = agent
+        .get(&format!("{}/metadata/{}", METADATA, path))
+        .set("Metadata", "true")
+        .call()
+        .map_err(error)?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| e.to_string())
+}
+
+fn azure_instance(metadata: &Value) -> Instance {
+    let compute = &metadata["compute"];
+    let text = |key: &str| compute[key].as_str().unwrap_or_default().to_string();
+    Instance {
+        provider: Provider::Azure,
+        instance_type: text("vmSize"),
+        region: text("location"),
+        spot: text("priority") == "Spot",
+        // Managed identities don't show up in instance metadata
+        role: None,
+    }
+}
+
+// None while no reclaim is scheduled
+fn termination_notice(agent: &ureq::Agent, provider: Provider) -> Result<Option<String>, String> {
+    match provider {
+        Provider::Aws => {
+            let token = aws_token(agent)?;
+            // 404 until the instance is marked for interruption
+            match aws_request(agent, &token, "spot/instance-action").call() {
+                Ok(response) => {
+                    let action = response.into_string().map_err(|e| e.to_string())?;
+                    let action: Value = serde_json::from_str(&action).map_err(|e| e.to_string())?;
+                    Ok(Some(format!(
+                        "{} at {}",
+                        action["action"].as_str().unwrap_or("terminate"),
+                        action["time"].as_str().unwrap_or("?")
+                    )))
+                }
+                Err(ureq::Error::Status(404, _)) => Ok(None),
+                Err(e) => Err(error(e)),
+            }
+        }
+        Provider::Gcp => {
+            let preempted = gcp_get(agent, "preempted")?;
+            Ok((preempted.trim() == "TRUE").then(|| "preempted, shutting down within 30 seconds".to_string()))
+        }
+        Provider::Azure => {
+            let events = azure(agent, "scheduledevents?api-version=2020-07-01")?;
+            let preempt = events["Events"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|event| event["EventType"].as_str() == Some("Preempt"));
+            Ok(preempt.map(|event| format!("evicted at {}", event["NotBefore"].as_str().unwrap_or("?"))))
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+
+// Tables whose columns can be chosen, ordered and sized by the user
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableKind {
+    Processes,
+    Network,
+    Disks,
+}
+
+impl TableKind {
+    pub const ALL: [TableKind; 3] = [TableKind::Processes, TableKind::Network, TableKind::Disks];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            TableKind::Processes => "Processes",
+            TableKind::Network => "Network",
+            TableKind::Disks => "Disks",
+        }
+    }
+
+    // Every column the table knows how to render, in default order
+    pub fn available(&self) -> &'static [Column] {
+        match self {
+            TableKind::Processes => &[
+                Column::Pid,
+                Column::User,
+                Column::Name,
+                Column::Cpu,
+                Column::Memory,
+                Column::Threads,
+                Column::Fds,
+                Column::DiskRead,
+                Column::DiskWrite,
+                Column::Started,
+                Column::CpuTime,
+            ],
+            TableKind::Network => &[Column::Interface, Column::Rx, Column::Tx, Column::TotalRx, Column::TotalTx],
+            TableKind::Disks => &[
+                Column::Disk,
+                Column::Mount,
+                Column::Usage,
+                Column::Used,
+                Column::Total,
+                Column::Growth,
+                Column::ReadLatency,
+                Column::WriteLatency,
+                Column::Busy,
+                Column::Queue,
+            ],
+        }
+    }
+
+    // Columns shown when the config doesn't say otherwise
+    fn default_visible(&self) -> &'static [Column] {
+        match self {
+            TableKind::Processes => &[Column::Pid, Column::User, Column::Name, Column::Cpu, Column::Memory, Column::Threads, Column::Fds, Column::DiskRead, Column::DiskWrite],
+            TableKind::Network => &[Column::Interface, Column::Rx, Column::Tx],
+            TableKind::Disks => &[
+                Column::Disk,
+                Column::Usage,
+                Column::Used,
+                Column::Total,
+                Column::Growth,
+                Column::ReadLatency,
+                Column::WriteLatency,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    Pid,
+    User,
+    Name,
+    Cpu,
+    Memory,
+    Threads,
+    // Open file descriptors
+    Fds,
+    DiskRead,
+    DiskWrite,
+    // How long ago it started
+    Started,
+    // Accumulated CPU time, TIME+ in top
+    CpuTime,
+    Interface,
+    Rx,
+    Tx,
+    TotalRx,
+    TotalTx,
+    Disk,
+    Mount,
+    Usage,
+    Used,
+    Total,
+    Growth,
+    ReadLatency,
+    WriteLatency,
+    Busy,
+    Queue,
+}
+
+impl Column {
+    fn all() -> impl Iterator<Item = Column> {
+        TableKind::ALL.iter().flat_map(|t| t.available().iter().copied())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Pid => "pid",
+            Column::User => "user",
+            Column::Name => "name",
+            Column::Cpu => "cpu",
+            Column::Memory => "memory",
+            Column::Threads => "threads",
+            Column::Fds => "fds",
+            Column::DiskRead => "disk_read",
+            Column::DiskWrite => "disk_write",
+            Column::Started => "started",
+            Column::CpuTime => "cpu_time",
+            Column::Interface => "interface",
+            Column::Rx => "rx",
+            Column::Tx => "tx",
+            Column::TotalRx => "total_rx",
+            Column::TotalTx => "total_tx",
+            Column::Disk => "disk",
+            Column::Mount => "mount",
+            Column::Usage => "usage",
+            Column::Used => "used",
+            Column::Total => "total",
+            Column::Growth => "growth",
+            Column::ReadLatency => "read_latency",
+            Column::WriteLatency => "write_latency",
+            Column::Busy => "busy",
+            Column::Queue => "queue",
+        }
+    }
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::User => "User",
+            Column::Name => "Name",
+            Column::Cpu => "CPU %",
+            Column::Memory => "Memory",
+            Column::Threads => "Threads",
+            Column::Fds => "FDs",
+            Column::DiskRead => "Read/s",
+            Column::DiskWrite => "Write/s",
+            Column::Started => "Started",
+            Column::CpuTime => "TIME+",
+            Column::Interface => "Interface",
+            Column::Rx => "RX/s",
+            Column::Tx => "TX/s",
+            Column::TotalRx => "Total RX",
+            Column::TotalTx => "Total TX",
+            Column::Disk => "Disk",
+            Column::Mount => "Mount",
+            Column::Usage => "Use %",
+            Column::Used => "Used",
+            Column::Total => "Total",
+            Column::Growth => "Growth",
+            Column::ReadLatency => "Read ms",
+            Column::WriteLatency => "Write ms",
+            Column::Busy => "Busy %",
+            Column::Queue => "Queue",
+        }
+    }
+
+    // Name-like columns stretch to fill the remaining space by default
+    fn default_constraint(&self) -> Constraint {
+        match self {
+            Column::Pid | Column::Cpu | Column::Threads | Column::Fds | Column::Usage | Column::Busy | Column::Queue => Constraint::Length(8),
+            Column::ReadLatency | Column::WriteLatency => Constraint::Length(9),
+            Column::User => Constraint::Length(12),
+            Column::Name | Column::Mount => Constraint::Min(20),
+            Column::Interface | Column::Disk => Constraint::Min(10),
+            _ => Constraint::Length(11),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnSetting {
+    pub column: Column,
+    pub width: Option<u16>,
+    pub visible: bool,
+}
+
+impl ColumnSetting {
+    pub fn constraint(&self) -> Constraint {
+        match self.width {
+            Some(width) => Constraint::Length(width),
+            None => self.column.default_constraint(),
+        }
+    }
+
+    fn spec(&self) -> String {
+        match self.width {
+            Some(width) => format!("{}:{}", self.column.name(), width),
+            None => self.column.name().to_string(),
+        }
+    }
+}
+
+// Column lists as written in config/workspace, e.g. ["pid", "name:30", "cpu"].
+// Listed columns are shown in that order, everything else is hidden.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ColumnSpecs {
+    pub processes: Option<Vec<String>>,
+    pub network: Option<Vec<String>>,
+    pub disks: Option<Vec<String>>,
+}
+
+impl ColumnSpecs {
+    fn get(&self, table: TableKind) -> Option<&Vec<String>> {
+        match table {
+            TableKind::Processes => self.processes.as_ref(),
+            TableKind::Network => self.network.as_ref(),
+            TableKind::Disks => self.disks.as_ref(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for table in TableKind::ALL {
+            for spec in self.get(table).into_iter().flatten() {
+                parse_spec(table, spec)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_spec(table: TableKind, spec: &str) -> Result<ColumnSetting, String> {
+    let (name, width) = match spec.split_once(':') {
+        Some((name, width)) => {
+            let width = width
+                .parse::<u16>()
+                .map_err(|_| format!("column '{}': invalid width '{}'", name, width))?;
+            (name, Some(width))
+        }
+        None => (spec, None),
+    };
+    let column = Column::all()
+        .find(|c| c.name() == name)
+        .filter(|c| table.available().contains(c))
+        .ok_or_else(|| format!("unknown {} column '{}'", table.title().to_lowercase(), name))?;
+    Ok(ColumnSetting { column, width, visible: true })
+}
+
+// The current column arrangement of every table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnLayout {
+    processes: Vec<ColumnSetting>,
+    network: Vec<ColumnSetting>,
+    disks: Vec<ColumnSetting>,
+    // Horizontal scroll position per table, in columns after the frozen first one
+    offsets: [usize; 3],
+}
+
+impl ColumnLayout {
+    // Invalid entries are skipped; config specs are validated at startup
+    pub fn from_specs(specs: &ColumnSpecs) -> ColumnLayout {
+        let build = |table: TableKind| {
+            let mut settings: Vec<ColumnSetting> = match specs.get(table) {
+                Some(list) => list.iter().filter_map(|s| parse_spec(table, s).ok()).collect(),
+                None => table
+                    .default_visible()
+                    .iter()
+                    .map(|&column| ColumnSetting { column, width: None, visible: true })
+                    .collect(),
+            };
+            for &column in table.available() {
+                if !settings.iter().any(|s| s.column == column) {
+                    settings.push(ColumnSetting { column, width: None, visible: false });
+                }
+            }
+            settings
+        };
+        ColumnLayout {
+            processes: build(TableKind::Processes),
+            network: build(TableKind::Network),
+            disks: build(TableKind::Disks),
+            offsets: [0; 3],
+        }
+    }
+
+    pub fn to_specs(&self) -> ColumnSpecs {
+        let specs = |table: TableKind| {
+            Some(self.all(table).iter().filter(|s| s.visible).map(|s| s.spec()).collect())
+        };
+        ColumnSpecs {
+            processes: specs(TableKind::Processes),
+            network: specs(TableKind::Network),
+            disks: specs(TableKind::Disks),
+        }
+    }
+
+    // All columns including hidden ones, in display order
+    pub fn all(&self, table: TableKind) -> &Vec<ColumnSetting> {
+        match table {
+            TableKind::Processes => &self.processes,
+            TableKind::Network => &self.network,
+            TableKind::Disks => &self.disks,
+        }
+    }
+
+    fn all_mut(&mut self, table: TableKind) -> &mut Vec<ColumnSetting> {
+        match table {
+            TableKind::Processes => &mut self.processes,
+            TableKind::Network => &mut self.network,
+            TableKind::Disks => &mut self.disks,
+        }
+    }
+
+    pub fn visible(&self, table: TableKind) -> Vec<ColumnSetting> {
+        self.all(table).iter().filter(|s| s.visible).copied().collect()
+    }
+
+    fn offset_index(table: TableKind) -> usize {
+        TableKind::ALL.iter().position(|t| *t == table).unwrap_or(0)
+    }
+
+    pub fn offset(&self, table: TableKind) -> usize {
+        self.offsets[ColumnLayout::offset_index(table)]
+    }
+
+    // Scroll horizontally; the first column always stays in place
+    pub fn scroll(&mut self, table: TableKind, right: bool) {
+        let max = self.visible(table).len().saturating_sub(2);
+        let offset = &mut self.offsets[ColumnLayout::offset_index(table)];
+        *offset = if right { (*offset + 1).min(max) } else { offset.saturating_sub(1) };
+    }
+
+    // Visible columns after applying the horizontal scroll offset
+    pub fn displayed(&self, table: TableKind) -> Vec<ColumnSetting> {
+        let visible = self.visible(table);
+        let offset = self.offset(table).min(visible.len().saturating_sub(2));
+        visible
+            .iter()
+            .take(1)
+            .chain(visible.iter().skip(1 + offset))
+            .copied()
+            .collect()
+    }
+}
+
+// Runtime column chooser popup (F2)
+pub struct ColumnChooser {
+    pub table: TableKind,
+    pub selected: usize,
+}
+
+impl ColumnChooser {
+    pub fn new(table: TableKind) -> ColumnChooser {
+        ColumnChooser { table, selected: 0 }
+    }
+
+    pub fn next_table(&mut self) {
+        let index = TableKind::ALL.iter().position(|t| *t == self.table).unwrap_or(0);
+        self.table = TableKind::ALL[(index + 1) % TableKind::ALL.len()];
+        self.selected = 0;
+    }
+
+    pub fn move_selection(&mut self, layout: &ColumnLayout, down: bool) {
+        let len = layout.all(self.table).len();
+        self.selected = if down { (self.selected + 1) % len } else { (self.selected + len - 1) % len };
+    }
+
+    pub fn toggle(&self, layout: &mut ColumnLayout) {
+        let settings = layout.all_mut(self.table);
+        let visible_count = settings.iter().filter(|s| s.visible).count();
+        let setting = &mut settings[self.selected];
+        // Keep at least one column so the table never disappears
+        if !setting.visible || visible_count > 1 {
+            setting.visible = !setting.visible;
+        }
+    }
+
+    // Move the selected column earlier or later in the table
+    pub fn shift(&mut self, layout: &mut ColumnLayout, later: bool) {
+        let settings = layout.all_mut(self.table);
+        let target = if later { self.selected + 1 } else { self.selected.wrapping_sub(1) };
+        if target < settings.len() {
+            settings.swap(self.selected, target);
+            self.selected = target;
+        }
+    }
+
+    pub fn resize(&self, layout: &mut ColumnLayout, grow: bool) {
+        let setting = &mut layout.all_mut(self.table)[self.selected];
+        let current = setting.width.unwrap_or(match setting.column.default_constraint() {
+            Constraint::Length(n) | Constraint::Min(n) => n,
+            _ => 10,
+        });
+        setting.width = Some(if grow { current.saturating_add(1).min(80) } else { current.saturating_sub(1).max(3) });
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect, layout: &ColumnLayout) {
+        let items: Vec<ListItem> = layout
+            .all(self.table)
+            .iter()
+            .map(|s| {
+                let width = s.width.map(|w| format!(" ({})", w)).unwrap_or_default();
+                let mark = if s.visible { "[x]" } else { "[ ]" };
+                ListItem::new(format!("{} {}{}", mark, s.column.header(), width))
+            })
+            .collect();
+
+        let title = format!(
+            "🧩 {} columns — Space toggle, J/K move, +/- width, Tab table, Esc close",
+            self.table.title()
+        );
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .style(Style::default().fg(Color::White))
+            .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .highlight_symbol("▶ ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut state);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::anomaly::AnomalyConfig;
+use crate::archive::ArchiveConfig;
+use crate::budget::BudgetConfig;
+use crate::channels::{AlertRoute, ChannelConfig, ChannelKind};
+use crate::cloud::CloudConfig;
+use crate::columns::ColumnSpecs;
+use crate::email::EmailSection;
+use crate::events::EventLogConfig;
+use crate::exectrace::ExecTraceConfig;
+use crate::gauges::GaugeConfig;
+use crate::health::{HealthCheckConfig, PortCheckConfig};
+use crate::highres::HighResConfig;
+use crate::kubernetes::KubernetesConfig;
+use crate::power::PowerConfig;
+use crate::report::ReportConfig;
+use crate::rules::AlertRuleConfig;
+use crate::score::ScoreConfig;
+use crate::silence::MaintenanceWindow;
+use crate::sinks::SinkConfig;
+use crate::theme::Theme;
+use crate::units::UnitsConfig;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error, fs, path::PathBuf};
+
+// User configuration, read from ~/.config/system-monitor/config.toml
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    // Metrics shown in the title bar on every tab, e.g. "cpu", "net:eth0:tx"
+    pub pinned: Vec<String>,
+    // Processes kept in the Watched section of the Processes tab, each a PID
+    // or a name pattern, e.g. "postgres", "celery worker"
+    pub watched: Vec<String>,
+    // Profile to start with, defaults to the first one by name
+    pub profile: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+    // Tabs to show and their order, unless the active profile overrides it
+    pub tabs: Vec<String>,
+    // Visible columns per table, in order, optionally with a width ("name:30")
+    pub columns: ColumnSpecs,
+    // Colors for dark or light terminals, or none
+    pub theme: Theme,
+    // Binary or decimal sizes, °C or °F
+    pub units: UnitsConfig,
+    // Smoothing and peak hold of the Overview gauges
+    pub gauges: GaugeConfig,
+    // Sub-second CPU and network sampling for short bursts
+    pub high_resolution: HighResConfig,
+    // How long each collector may take before it is skipped or turned off
+    pub collector_budget: BudgetConfig,
+    // Same as --read-only: refuse kill/renice/restart/eject/vacuum/power actions
+    // and the settings editor
+    pub read_only: bool,
+    // Same as --harden
+    pub harden: bool,
+    // Root-only collectors (eBPF, exec tracing, stack sampling, strace) and
+    // the ! restart through sudo; off keeps the monitor unprivileged
+    pub allow_privileged: bool,
+    // How long the S stack sampler records
+    pub sampler_seconds: u64,
+    // What J on the Overview shrinks the systemd journal to, as journalctl
+    // --vacuum-size takes it
+    pub journal_vacuum_size: String,
+    // Recently exited processes under the Processes tab
+    pub exec_trace: ExecTraceConfig,
+    // URLs polled for the Health tab
+    pub health_checks: Vec<HealthCheckConfig>,
+    // host:port pairs probed with a TCP connect, also on the Health tab
+    pub port_checks: Vec<PortCheckConfig>,
+    // Node name and kubeconfig for the Kubernetes panel on the Health tab
+    pub kubernetes: KubernetesConfig,
+    // Instance details and spot termination notices from the cloud metadata service
+    pub cloud: CloudConfig,
+    // Reboot and power-off from the Overview
+    pub power: PowerConfig,
+    // Metric export to StatsD (UDP) / Graphite (plaintext TCP)
+    pub statsd: Option<SinkConfig>,
+    pub graphite: Option<SinkConfig>,
+    // Alert transitions and OOM kills sent to syslog/journald
+    pub event_log: EventLogConfig,
+    // Alert conditions written as expressions, on top of the threshold alerts
+    pub alert_rules: Vec<AlertRuleConfig>,
+    // Where alert notifications go, and which alerts go where
+    pub alert_channels: Vec<ChannelConfig>,
+    pub alert_routes: Vec<AlertRoute>,
+    // Shorthand for an email channel named "email"
+    pub email: Option<EmailSection>,
+    // Recurring times when matching alerts send no notifications
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+    // How long z / Z on the Alerts tab silence for
+    pub silence_minutes: u64,
+    // Weights of the title bar health score
+    pub health_score: ScoreConfig,
+    // Alerts for metrics far from their usual level at this time of day
+    pub anomaly: AnomalyConfig,
+    // Minute history kept on disk, for reports and process accounting
+    pub archive: ArchiveConfig,
+    // Daily/weekly summaries from the archive
+    pub report: Option<ReportConfig>,
+    // Same as --api: address for the JSON API server
+    #[cfg(feature = "api")]
+    pub api_listen: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            pinned: Vec::new(),
+            watched: Vec::new(),
+            profile: None,
+            profiles: BTreeMap::new(),
+            tabs: vec!["overview".to_string(), "processes".to_string()],
+            columns: ColumnSpecs::default(),
+            theme: Theme::default(),
+            units: UnitsConfig::default(),
+            gauges: GaugeConfig::default(),
+            high_resolution: HighResConfig::default(),
+            collector_budget: BudgetConfig::default(),
+            read_only: false,
+            harden: false,
+            allow_privileged: true,
+            sampler_seconds: 5,
+            journal_vacuum_size: "500M".to_string(),
+            exec_trace: ExecTraceConfig::default(),
+            health_checks: Vec::new(),
+            port_checks: Vec::new(),
+            kubernetes: KubernetesConfig::default(),
+            cloud: CloudConfig::default(),
+            power: PowerConfig::default(),
+            statsd: None,
+            graphite: None,
+            event_log: EventLogConfig::default(),
+            alert_rules: Vec::new(),
+            alert_channels: Vec::new(),
+            alert_routes: Vec::new(),
+            email: None,
+            maintenance_windows: Vec::new(),
+            silence_minutes: 60,
+            health_score: ScoreConfig::default(),
+            anomaly: AnomalyConfig::default(),
+            archive: ArchiveConfig::default(),
+            report: None,
+            #[cfg(feature = "api")]
+            api_listen: None,
+        }
+    }
+}
+
+// A named layout, e.g. "server" or "laptop", switchable at runtime
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Profile {
+    pub tabs: Option<Vec<String>>,
+    pub refresh_ms: u64,
+    pub thresholds: Thresholds,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile {
+            tabs: None,
+            refresh_ms: 1000,
+            thresholds: Thresholds::default(),
+        }
+    }
+}
+
+// Percent, °C and ms levels where indicators turn yellow (warn) and red (crit)
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Thresholds {
+    pub swap_warn: f64,
+    pub swap_crit: f64,
+    pub temp_warn: f32,
+    pub temp_crit: f32,
+    // Share of sent TCP segments that were retransmitted, also raises an alert
+    pub retransmit_warn: f64,
+    pub retransmit_crit: f64,
+    // Disk usage percent, raises an alert
+    pub disk_warn: f64,
+    pub disk_crit: f64,
+    // Average milliseconds per I/O on a mounted device, raises an alert
+    pub io_latency_warn: f64,
+    pub io_latency_crit: f64,
+    // tmpfs usage percent (space or inodes), raises an alert
+    pub tmpfs_warn: f64,
+    pub tmpfs_crit: f64,
+    // CPU steal percent averaged over 5 minutes, raises an alert
+    pub steal_warn: f64,
+    pub steal_crit: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            swap_warn: 10.0,
+            swap_crit: 50.0,
+            temp_warn: 70.0,
+            temp_crit: 80.0,
+            retransmit_warn: 2.0,
+            retransmit_crit: 5.0,
+            disk_warn: 90.0,
+            disk_crit: 95.0,
+            io_latency_warn: 20.0,
+            io_latency_crit: 100.0,
+            tmpfs_warn: 80.0,
+            tmpfs_crit: 90.0,
+            steal_warn: 5.0,
+            steal_crit: 15.0,
+        }
+    }
+}
+
+impl Thresholds {
+    pub fn validate(&self) -> Result<(), String> {
+        let pairs = [
+            ("swap", self.swap_warn, self.swap_crit),
+            ("temp", self.temp_warn as f64, self.temp_crit as f64),
+            ("retransmit", self.retransmit_warn, self.retransmit_crit),
+            ("disk", self.disk_warn, self.disk_crit),
+            ("io_latency", self.io_latency_warn, self.io_latency_crit),
+            ("tmpfs", self.tmpfs_warn, self.tmpfs_crit),
+            ("steal", self.steal_warn, self.steal_crit),
+        ];
+        for (name, warn, crit) in pairs {
+            if warn < 0.0 || crit < 0.0 {
+                return Err(format!("{}_warn and {}_crit can't be negative", name, name));
+            }
+            if warn >= crit {
+                return Err(format!("{}_warn must be below {}_crit", name, name));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("system-monitor").join("config.toml"))
+    }
+
+    // A missing config file is not an error, we just use the defaults
+    pub fn load() -> Result<Config, Box<dyn Error>> {
+        let path = match Config::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Config::default()),
+        };
+
+        let contents = fs::read_to_string(&path)?;
+        toml::from_str(&contents)
+            .map_err(|e| format!("invalid config {}: {}", path.display(), e).into())
+    }
+
+    // Names of all profiles; with none configured there is a single "default"
+    pub fn profile_names(&self) -> Vec<String> {
+        if self.profiles.is_empty() {
+            vec!["default".to_string()]
+        } else {
+            self.profiles.keys().cloned().collect()
+        }
+    }
+
+    pub fn initial_profile(&self) -> String {
+        match &self.profile {
+            Some(name) => name.clone(),
+            None => self.profile_names().remove(0),
+        }
+    }
+
+    pub fn get_profile(&self, name: &str) -> Profile {
+        self.profiles.get(name).cloned().unwrap_or_default()
+    }
+
+    // Tabs for a profile, falling back to the top-level list
+    pub fn profile_tabs(&self, name: &str) -> Vec<String> {
+        self.get_profile(name).tabs.unwrap_or_else(|| self.tabs.clone())
+    }
+
+    // [[alert_channels]] plus the [email] shorthand
+    pub fn channels(&self) -> Vec<ChannelConfig> {
+        let mut channels = self.alert_channels.clone();
+        if let Some(section) = &self.email {
+            channels.push(ChannelConfig {
+                name: Some("email".to_string()),
+                kind: ChannelKind::Email(section.email.clone()),
+                policy: section.policy.clone(),
+            });
+        }
+        channels
+    }
+
+    // Reports are built from the archive, so they switch it on
+    pub fn archive_enabled(&self) -> bool {
+        self.archive.enabled || self.report.is_some()
+    }
+}
@@ -0,0 +1,353 @@
+use crate::privilege;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+use std::{
+    collections::HashMap,
+    env,
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// D-Bus traffic per connection, for desktop sessions where some service
+// floods the bus and burns CPU in itself, the bus daemon and every listener.
+// dbus-monitor --profile prints one line per message, which a reader thread
+// counts by sender and destination; busctl list names the connections every
+// 10 seconds (well-known names, PID and process). The session bus is watched
+// when the monitor runs inside one, the system bus only as root, since
+// monitoring it is root-only. Starts the first time the tab is opened.
+const NAMES_INTERVAL: Duration = Duration::from_secs(10);
+// Messages a second above which a connection is highlighted
+const BUSY: f64 = 100.0;
+// The bus daemon itself sends as this name
+const DAEMON: &str = "org.freedesktop.DBus";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bus {
+    Session,
+    System,
+}
+
+impl Bus {
+    fn title(&self) -> &'static str {
+        match self {
+            Bus::Session => "Session bus",
+            Bus::System => "System bus",
+        }
+    }
+
+    // dbus-monitor and busctl spell it differently
+    fn monitor_flag(&self) -> &'static str {
+        match self {
+            Bus::Session => "--session",
+            Bus::System => "--system",
+        }
+    }
+
+    fn busctl_flag(&self) -> &'static str {
+        match self {
+            Bus::Session => "--user",
+            Bus::System => "--system",
+        }
+    }
+}
+
+// Messages since the last update, by unique name
+#[derive(Default)]
+struct Counts {
+    sent: HashMap<String, u64>,
+    received: HashMap<String, u64>,
+    // "interface.Member" each connection sent
+    members: HashMap<String, HashMap<String, u64>>,
+    total: u64,
+    error: Option<String>,
+}
+
+// Who is behind a unique name like ":1.42"
+#[derive(Debug, Clone, Default)]
+struct Owner {
+    names: Vec<String>,
+    pid: Option<u32>,
+    process: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub unique: String,
+    pub names: Vec<String>,
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+    pub sent: f64,
+    pub received: f64,
+    // The message it sent most, e.g. "org.freedesktop.NetworkManager.PropertiesChanged"
+    pub busiest: Option<String>,
+}
+
+struct BusMonitor {
+    bus: Bus,
+    counts: Arc<Mutex<Counts>>,
+    owners: Arc<Mutex<HashMap<String, Owner>>>,
+    child: Option<Child>,
+    // Rates over the last update interval, busiest first
+    connections: Vec<Connection>,
+    messages: f64,
+    counted: Instant,
+}
+
+pub struct DbusMonitor {
+    buses: Vec<BusMonitor>,
+}
+
+impl DbusMonitor {
+    pub fn start() -> DbusMonitor {
+        let mut buses = Vec::new();
+        if env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some() {
+            buses.push(BusMonitor::start(Bus::Session));
+        }
+        buses.push(BusMonitor::start(Bus::System));
+        DbusMonitor { buses }
+    }
+
+    // On every refresh: rates since the last one
+    pub fn update(&mut self) {
+        for bus in &mut self.buses {
+            bus.update();
+        }
+    }
+}
+
+impl BusMonitor {
+    fn start(bus: Bus) -> BusMonitor {
+        let counts = Arc::new(Mutex::new(Counts::default()));
+        let owners = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = BusMonitor {
+            bus,
+            counts: Arc::clone(&counts),
+            owners: Arc::clone(&owners),
+            child: None,
+            connections: Vec::new(),
+            messages: 0.0,
+            counted: Instant::now(),
+        };
+        if bus == Bus::System && !privilege::is_root() {
+            counts.lock().unwrap().error = Some(format!("Monitoring the system bus {}.", privilege::root_hint()));
+            return monitor;
+        }
+        let spawned = Command::new("dbus-monitor")
+            .args([bus.monitor_flag(), "--profile"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(err) => {
+                counts.lock().unwrap().error = Some(format!("could not start dbus-monitor: {}", err));
+                return monitor;
+            }
+        };
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        thread::spawn(move || {
+            if let Some(stdout) = stdout {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    count(&mut counts.lock().unwrap(), &line);
+                }
+            }
+            // dbus-monitor exited: keep its last complaint for the panel
+            let mut message = String::new();
+            if let Some(stderr) = stderr {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    message = line;
+                }
+            }
+            counts.lock().unwrap().error = Some(if message.is_empty() { "dbus-monitor exited".to_string() } else { message });
+        });
+        thread::spawn(move || loop {
+            if let Some(listed) = list_owners(bus) {
+                *owners.lock().unwrap() = listed;
+            }
+            thread::sleep(NAMES_INTERVAL);
+        });
+        monitor.child = Some(child);
+        monitor
+    }
+
+    fn update(&mut self) {
+        let mut counts = {
+            let mut shared = self.counts.lock().unwrap();
+            // The error stays for the panel
+            let error = shared.error.clone();
+            std::mem::replace(&mut *shared, Counts { error, ..Counts::default() })
+        };
+        let secs = self.counted.elapsed().as_secs_f64().max(0.001);
+        self.counted = Instant::now();
+        let owners = self.owners.lock().unwrap();
+        // Calls to a well-known name count for the connection that owns it
+        let received = std::mem::take(&mut counts.received);
+        for (destination, received) in received {
+            let unique = match destination.starts_with(':') {
+                true => None,
+                false => owners.iter().find(|(_, owner)| owner.names.contains(&destination)).map(|(unique, _)| unique.clone()),
+            };
+            *counts.received.entry(unique.unwrap_or(destination)).or_default() += received;
+        }
+        let mut uniques: Vec<&String> = counts.sent.keys().chain(counts.received.keys()).collect();
+        uniques.sort();
+        uniques.dedup();
+        self.connections = uniques
+            .into_iter()
+            .map(|unique| {
+                let owner = owners.get(unique).cloned().unwrap_or_default();
+                let busiest = counts.members.get(unique).and_then(|members| members.iter().max_by_key(|(_, count)| **count)).map(|(member, _)| member.clone());
+                Connection {
+                    unique: unique.clone(),
+                    names: owner.names,
+                    pid: owner.pid,
+                    process: owner.process,
+                    sent: counts.sent.get(unique).copied().unwrap_or(0) as f64 / secs,
+                    received: counts.received.get(unique).copied().unwrap_or(0) as f64 / secs,
+                    busiest,
+                }
+            })
+            .collect();
+        self.connections.sort_by(|a, b| (b.sent + b.received).total_cmp(&(a.sent + a.received)));
+        self.messages = counts.total as f64 / secs;
+    }
+
+    fn error(&self) -> Option<String> {
+        self.counts.lock().unwrap().error.clone()
+    }
+}
+
+impl Drop for BusMonitor {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+// "mc 1792150946.370251 2 :1.1 org.freedesktop.DBus /path iface Member",
+// tab separated; method returns and errors end after the reply serial.
+// Broadcast signals go to "<none>".
+fn count(counts: &mut Counts, line: &str) {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let ["sig" | "mc" | "mr" | "err", _, _, sender, destination, rest @ ..] = fields.as_slice() else { return };
+    counts.total += 1;
+    *counts.sent.entry(sender.to_string()).or_default() += 1;
+    if *destination != "<none>" {
+        *counts.received.entry(destination.to_string()).or_default() += 1;
+    }
+    if let [_, interface, member, ..] = rest {
+        let members = counts.members.entry(sender.to_string()).or_default();
+        *members.entry(format!("{}.{}", interface, member)).or_default() += 1;
+    }
+}
+
+// busctl list: "NAME PID PROCESS USER CONNECTION UNIT SESSION DESCRIPTION",
+// one row per unique and well-known name, and per activatable name nobody
+// owns yet, with "(activatable)" as the connection
+fn list_owners(bus: Bus) -> Option<HashMap<String, Owner>> {
+    let output = Command::new("busctl")
+        .args([bus.busctl_flag(), "list", "--no-legend", "--no-pager"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let mut owners: HashMap<String, Owner> = HashMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [name, pid, process, _, connection, ..] = fields.as_slice() else { continue };
+        if connection.starts_with('(') {
+            continue;
+        }
+        // The daemon owns its name without a connection
+        let connection = if *name == DAEMON { DAEMON } else { connection };
+        let owner = owners.entry(connection.to_string()).or_default();
+        owner.pid = owner.pid.or(pid.parse().ok());
+        if !matches!(*process, "-" | "n/a") {
+            owner.process = Some(process.to_string());
+        }
+        if !name.starts_with(':') {
+            owner.names.push(name.to_string());
+        }
+    }
+    Some(owners)
+}
+
+pub fn draw_dbus_tab(f: &mut Frame, area: Rect, dbus: Option<&DbusMonitor>) {
+    let Some(dbus) = dbus else { return };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(dbus.buses.iter().map(|_| Constraint::Ratio(1, dbus.buses.len() as u32)))
+        .split(area);
+    for (bus, area) in dbus.buses.iter().zip(chunks.iter()) {
+        draw_bus(f, *area, bus);
+    }
+}
+
+fn draw_bus(f: &mut Frame, area: Rect, bus: &BusMonitor) {
+    let block = Block::default().borders(Borders::ALL);
+    if let Some(error) = bus.error() {
+        let hint = Paragraph::new(format!("❌ {}\n\nD-Bus monitoring needs dbus-monitor, and busctl for the names.", error))
+            .wrap(Wrap { trim: true })
+            .block(block.title(format!("🚌 {}", bus.bus.title())))
+            .style(Style::default().fg(Color::Gray));
+        f.render_widget(hint, area);
+        return;
+    }
+    let rows: Vec<Row> = bus
+        .connections
+        .iter()
+        .map(|connection| {
+            let name = match connection.names.first() {
+                Some(name) if connection.names.len() > 1 => format!("{} +{}", name, connection.names.len() - 1),
+                Some(name) => name.clone(),
+                None => connection.unique.clone(),
+            };
+            let process = match (&connection.process, connection.pid) {
+                (Some(process), Some(pid)) => format!("{} ({})", process, pid),
+                (None, Some(pid)) => pid.to_string(),
+                _ => "-".to_string(),
+            };
+            let row = Row::new(vec![
+                name,
+                process,
+                format!("{:.1}", connection.sent),
+                format!("{:.1}", connection.received),
+                connection.busiest.clone().unwrap_or_default(),
+            ]);
+            if connection.sent + connection.received >= BUSY {
+                row.style(Style::default().fg(Color::Yellow))
+            } else {
+                row
+            }
+        })
+        .collect();
+    let header = Row::new(vec!["Connection", "Process", "Sent/s", "Received/s", "Sends most"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let title = format!("🚌 {} — {:.1} messages/s, busiest first", bus.bus.title(), bus.messages);
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(40),
+            Constraint::Length(24),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .block(block.title(title))
+    .column_spacing(1);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,422 @@
+use crate::cgroup::{Cgroup, Throttling};
+use crate::report::cpu_time;
+use crate::{format_bytes, format_uptime, privilege, renice};
+use chrono::{Local, TimeZone};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+use sysinfo::{Pid, Process, Users};
+
+// Everything known about the selected process, from Enter on the Processes
+// tab. The values are live, looked up again on every draw; the start time
+// tells the process apart from a new one that got its PID after it exited.
+// On Linux it also shows what the process may do, for security reviews of
+// running services: whether it runs as root, its effective capabilities,
+// seccomp and no_new_privs, all from /proc/<pid>/status, which anyone can
+// read, and the memory limit and CPU quota of its cgroup with how much the
+// quota throttled it lately. e switches to its environment, to check which
+// settings a service really started with.
+const THROTTLE_WINDOW: Duration = Duration::from_secs(2);
+
+pub struct ProcessDetails {
+    pub pid: Pid,
+    start_time: u64,
+    // Resolved once, the process table only has the UID
+    user: String,
+    cgroup: Option<Cgroup>,
+    // The cpu.stat reading the throttling is measured from, and the
+    // throttling over the window before it
+    throttle_base: Cell<Option<(Instant, Throttling)>>,
+    throttle_recent: Cell<Option<(Duration, Throttling)>>,
+    // e in the popup
+    pub environment: Option<Environment>,
+}
+
+// The environment the process started with, from /proc/<pid>/environ: later
+// setenv() calls inside it don't show. / searches the variables by name or
+// value, ignoring case. Other users' processes need root.
+pub struct Environment {
+    variables: Vec<(String, String)>,
+    pub search: String,
+    // Typing after /
+    pub editing: bool,
+    scroll: usize,
+    // Other users' environments can't be read without root
+    hidden: bool,
+}
+
+impl Environment {
+    fn new(process: &Process) -> Environment {
+        let mut variables: Vec<(String, String)> = process
+            .environ()
+            .iter()
+            .map(|variable| {
+                let variable = variable.to_string_lossy();
+                match variable.split_once('=') {
+                    Some((name, value)) => (name.to_string(), value.to_string()),
+                    None => (variable.into_owned(), String::new()),
+                }
+            })
+            .collect();
+        variables.sort();
+        let hidden = variables.is_empty() && process.user_id().is_some_and(privilege::is_other_user) && !privilege::is_root();
+        Environment { variables, search: String::new(), editing: false, scroll: 0, hidden }
+    }
+
+    fn matching(&self) -> Vec<&(String, String)> {
+        let search = self.search.to_lowercase();
+        self.variables
+            .iter()
+            .filter(|(name, value)| name.to_lowercase().contains(&search) || value.to_lowercase().contains(&search))
+            .collect()
+    }
+
+    pub fn scroll(&mut self, delta: isize) {
+        let last = self.matching().len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(last);
+    }
+
+    // Typing after /: Enter keeps the search, Esc clears it
+    pub fn handle_search_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.editing = false,
+            KeyCode::Esc => {
+                self.editing = false;
+                self.search.clear();
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+            }
+            KeyCode::Char(c) => self.search.push(c),
+            _ => return,
+        }
+        self.scroll = 0;
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, block: Block) {
+        let matching = self.matching();
+        let mut lines = vec![Line::from(vec![
+            Span::styled("Search: ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("{}{}", self.search, if self.editing { "▏" } else { "" })),
+            Span::styled(
+                format!("  {} of {} variables", matching.len(), self.variables.len()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])];
+        lines.push(Line::from(""));
+        if self.hidden {
+            lines.push(Line::styled(format!("The environment of another user's process {}", privilege::root_hint()), Style::default().fg(Color::Red)));
+        } else if self.variables.is_empty() {
+            lines.push(Line::from("No environment variables (a kernel thread, or one that cleared its environment)."));
+        }
+        lines.extend(matching.iter().skip(self.scroll).map(|(name, value)| {
+            Line::from(vec![Span::styled(name.clone(), Style::default().fg(Color::Cyan)), Span::raw(format!("={}", value))])
+        }));
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), area);
+    }
+
+    // NAME=value lines, as filtered
+    fn text(&self) -> String {
+        self.matching().iter().map(|(name, value)| format!("{}={}\n", name, value)).collect()
+    }
+}
+
+impl ProcessDetails {
+    pub fn new(pid: Pid, process: &Process) -> ProcessDetails {
+        let user = match process.user_id() {
+            Some(uid) => Users::new_with_refreshed_list()
+                .get_user_by_id(uid)
+                .map(|user| format!("{} ({})", user.name(), **uid))
+                .unwrap_or_else(|| uid.to_string()),
+            None => "?".to_string(),
+        };
+        ProcessDetails {
+            pid,
+            start_time: process.start_time(),
+            user,
+            cgroup: Cgroup::of(&pid.to_string()),
+            throttle_base: Cell::new(None),
+            throttle_recent: Cell::new(None),
+            environment: None,
+        }
+    }
+
+    // process must have been refreshed with its environment
+    pub fn toggle_environment(&mut self, process: Option<&Process>) {
+        self.environment = match (&self.environment, process.filter(|process| process.start_time() == self.start_time)) {
+            (None, Some(process)) => Some(Environment::new(process)),
+            _ => None,
+        };
+    }
+
+    // "Cgroup", "Memory limit" and "CPU quota" values, the last with whether
+    // the quota throttled the cgroup in the last window
+    fn cgroup_lines(&self, cgroup: &Cgroup) -> (String, String, String, bool) {
+        let usage = cgroup.usage();
+        let set_on = |at: &Option<String>| at.as_ref().map(|at| format!(" set on {}", at)).unwrap_or_default();
+        let mut memory = match (&usage.memory_limit, usage.memory) {
+            (Some((limit, at)), Some(used)) => format!(
+                "{} of {} used ({:.0}%){}",
+                format_bytes(used),
+                format_bytes(*limit),
+                used as f64 / *limit as f64 * 100.0,
+                set_on(at)
+            ),
+            (Some((limit, at)), None) => format!("{}{}", format_bytes(*limit), set_on(at)),
+            (None, Some(used)) => format!("none, {} used", format_bytes(used)),
+            (None, None) => "none".to_string(),
+        };
+        if let Some(high) = usage.memory_high {
+            memory.push_str(&format!(", reclaimed above {}", format_bytes(high)));
+        }
+        if let Some(kills) = usage.oom_kills.filter(|kills| *kills > 0) {
+            memory.push_str(&format!(", {} OOM {}", kills, if kills == 1 { "kill" } else { "kills" }));
+        }
+
+        let Some(throttling) = usage.throttling else {
+            return (cgroup.path.clone(), memory, "none".to_string(), false);
+        };
+        let now = Instant::now();
+        match self.throttle_base.get() {
+            Some((at, base)) if now.duration_since(at) >= THROTTLE_WINDOW => {
+                let delta = Throttling {
+                    periods: throttling.periods.saturating_sub(base.periods),
+                    throttled_periods: throttling.throttled_periods.saturating_sub(base.throttled_periods),
+                    throttled_usec: throttling.throttled_usec.saturating_sub(base.throttled_usec),
+                };
+                self.throttle_recent.set(Some((now.duration_since(at), delta)));
+                self.throttle_base.set(Some((now, throttling)));
+            }
+            Some(_) => {}
+            None => self.throttle_base.set(Some((now, throttling))),
+        }
+        let mut cpu = match &usage.cpu_quota {
+            Some((cpus, at)) => format!("{:.2} CPUs{}", cpus, set_on(at)),
+            None => "none".to_string(),
+        };
+        let mut throttled = false;
+        if usage.cpu_quota.is_some() || throttling.throttled_usec > 0 {
+            match self.throttle_recent.get() {
+                Some((window, recent)) => {
+                    throttled = recent.throttled_usec > 0;
+                    cpu.push_str(&format!(
+                        ", throttled {} ms of the last {:.0} s ({} of {} periods)",
+                        recent.throttled_usec / 1000,
+                        window.as_secs_f64(),
+                        recent.throttled_periods,
+                        recent.periods
+                    ));
+                }
+                None => cpu.push_str(", measuring throttling…"),
+            }
+            cpu.push_str(&format!(", {} in total", cpu_time(throttling.throttled_usec as f64 / 1_000_000.0)));
+        }
+        (cgroup.path.clone(), memory, cpu, throttled)
+    }
+
+    // process is what has the PID now, if anything; deleted the files it
+    // still runs that an upgrade removed
+    pub fn draw(&self, f: &mut Frame, area: Rect, process: Option<&Process>, deleted: Option<Vec<String>>) {
+        let title = match self.environment {
+            Some(_) => format!("🔎 Environment of process {} · / search · ↑/↓ scroll · y copy · Esc back", self.pid),
+            None => format!("🔎 Process {} · e environment · y copy · Esc close", self.pid),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+        f.render_widget(Clear, area);
+        if let Some(environment) = &self.environment {
+            environment.draw(f, area, block);
+            return;
+        }
+        let Some(fields) = self.fields(process, deleted) else {
+            f.render_widget(Paragraph::new("The process has exited.").block(block), area);
+            return;
+        };
+        let lines: Vec<Line> = fields
+            .into_iter()
+            .map(|(name, value, color)| {
+                let line = Line::from(vec![
+                    Span::styled(format!("{:<13}", name), Style::default().fg(Color::Yellow)),
+                    Span::raw(value),
+                ]);
+                match color {
+                    Some(color) => line.style(Style::default().fg(color)),
+                    None => line,
+                }
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), area);
+    }
+
+    // y in the popup: the same fields as plain text, None once it exited;
+    // the variables shown while the environment is
+    pub fn text(&self, process: Option<&Process>, deleted: Option<Vec<String>>) -> Option<String> {
+        if let Some(environment) = &self.environment {
+            return Some(environment.text());
+        }
+        let fields = self.fields(process, deleted)?;
+        let mut text = format!("Process {}\n", self.pid);
+        for (name, value, _) in fields {
+            text.push_str(&format!("{:<13}{}\n", name, value));
+        }
+        Some(text)
+    }
+
+    // Name, value and the color of lines that need attention
+    fn fields(&self, process: Option<&Process>, deleted: Option<Vec<String>>) -> Option<Vec<(&'static str, String, Option<Color>)>> {
+        let process = process.filter(|process| process.start_time() == self.start_time)?;
+        let field = |name: &'static str, value: String| (name, value, None);
+        // Another user's paths and I/O counters can't be read without root
+        let hidden = process.user_id().is_some_and(privilege::is_other_user) && !privilege::is_root();
+        let unknown = if hidden { format!("? ({})", privilege::root_hint()) } else { "-".to_string() };
+        let path = |path: Option<&std::path::Path>| path.map(|path| path.display().to_string()).unwrap_or_else(|| unknown.clone());
+        let arguments: Vec<String> = process.cmd().iter().map(|arg| arg.to_string_lossy().into_owned()).collect();
+        let started = Local
+            .timestamp_opt(process.start_time() as i64, 0)
+            .single()
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let disk = process.disk_usage();
+        let io = |total: u64, recent: u64| {
+            if hidden {
+                unknown.clone()
+            } else {
+                format!("{} in total, {} since the last refresh", format_bytes(total), format_bytes(recent))
+            }
+        };
+        let mut lines = vec![
+            field("Name", process.name().to_string_lossy().into_owned()),
+            field("Executable", path(process.exe())),
+            field("Working dir", path(process.cwd())),
+            field("User", self.user.clone()),
+            field("Parent PID", process.parent().map(|pid| pid.to_string()).unwrap_or_else(|| "-".to_string())),
+            field("Started", format!("{} ({} ago)", started, format_uptime(process.run_time()))),
+            field("Status", process.status().to_string()),
+            field("Nice", renice::nice(self.pid).map(|nice| nice.to_string()).unwrap_or_else(|| "-".to_string())),
+            // tasks() leaves out the main thread
+            field("Threads", process.tasks().map(|tasks| (tasks.len() + 1).to_string()).unwrap_or_else(|| "-".to_string())),
+            field("CPU", format!("{:.1}% now, {} in total", process.cpu_usage(), cpu_time(process.accumulated_cpu_time() as f64 / 1000.0))),
+            field("Memory", format!("{} resident, {} virtual", format_bytes(process.memory()), format_bytes(process.virtual_memory()))),
+            field("Disk read", io(disk.total_read_bytes, disk.read_bytes)),
+            field("Disk written", io(disk.total_written_bytes, disk.written_bytes)),
+        ];
+        if let Some(deleted) = deleted {
+            lines.insert(2, ("Outdated", format!("runs deleted {}, restart it", deleted.join(", ")), Some(Color::Yellow)));
+        }
+        if let Some(cgroup) = &self.cgroup {
+            let (path, memory, cpu, throttled) = self.cgroup_lines(cgroup);
+            lines.push(field("Cgroup", path));
+            lines.push(field("Memory limit", memory));
+            lines.push(("CPU quota", cpu, throttled.then_some(Color::Red)));
+        }
+        if let Some(privileges) = Privileges::read(self.pid) {
+            lines.push(field("Runs as", privileges.runs_as()));
+            lines.push(field("Capabilities", privileges.capabilities()));
+            lines.push(field("Seccomp", privileges.seccomp()));
+            lines.push(field("No new privs", if privileges.no_new_privs { "yes" } else { "no" }.to_string()));
+        }
+        // Last, it can wrap over many lines
+        lines.push(field("Command", if arguments.is_empty() { "-".to_string() } else { arguments.join(" ") }));
+        Some(lines)
+    }
+}
+
+// The privilege lines of /proc/<pid>/status
+struct Privileges {
+    real_uid: u32,
+    effective_uid: u32,
+    // CapEff bits
+    capabilities: u64,
+    no_new_privs: bool,
+    // 0 off, 1 strict, 2 filter
+    seccomp: u8,
+    // Number of filters, on kernels that say (5.9+)
+    seccomp_filters: Option<u32>,
+}
+
+impl Privileges {
+    #[cfg(target_os = "linux")]
+    fn read(pid: Pid) -> Option<Privileges> {
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+        let value = |key: &str| status.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix(':')).map(str::trim);
+        // "Uid:	1000	1000	1000	1000": real, effective, saved, filesystem
+        let mut uids = value("Uid")?.split_whitespace().map(|uid| uid.parse::<u32>().ok());
+        Some(Privileges {
+            real_uid: uids.next()??,
+            effective_uid: uids.next()??,
+            capabilities: u64::from_str_radix(value("CapEff")?, 16).ok()?,
+            no_new_privs: value("NoNewPrivs") == Some("1"),
+            seccomp: value("Seccomp").and_then(|mode| mode.parse().ok()).unwrap_or(0),
+            seccomp_filters: value("Seccomp_filters").and_then(|filters| filters.parse().ok()),
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read(_pid: Pid) -> Option<Privileges> {
+        None
+    }
+
+    fn runs_as(&self) -> String {
+        match (self.real_uid, self.effective_uid) {
+            (0, 0) => "root".to_string(),
+            (real, 0) => format!("root through setuid (real UID {})", real),
+            (0, effective) => format!("UID {}, switched from root", effective),
+            (_, effective) => format!("unprivileged (UID {})", effective),
+        }
+    }
+
+    // Effective capabilities by name, like "cap_net_bind_service, cap_net_raw";
+    // a mostly full set as the ones missing
+    #[cfg(target_os = "linux")]
+    fn capabilities(&self) -> String {
+        let mut all: Vec<caps::Capability> = caps::all().into_iter().collect();
+        all.sort_by_key(caps::Capability::index);
+        let known = all.iter().fold(0, |mask, cap| mask | cap.bitmask());
+        let names = |held: bool| {
+            let names: Vec<String> = all
+                .iter()
+                .filter(|cap| (self.capabilities & cap.bitmask() != 0) == held)
+                .map(|cap| cap.to_string().to_lowercase())
+                .collect();
+            names.join(", ")
+        };
+        let held = all.iter().filter(|cap| self.capabilities & cap.bitmask() != 0).count();
+        let mut text = match held {
+            0 => "none".to_string(),
+            held if held == all.len() => format!("all {} (full root)", held),
+            held if held > all.len() / 2 => format!("all but {}", names(false)),
+            _ => names(true),
+        };
+        // Bits newer than the caps crate knows
+        if self.capabilities & !known != 0 {
+            text.push_str(&format!(", unknown bits {:#x}", self.capabilities & !known));
+        }
+        text
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn capabilities(&self) -> String {
+        format!("{:#x}", self.capabilities)
+    }
+
+    fn seccomp(&self) -> String {
+        match (self.seccomp, self.seccomp_filters) {
+            (0, _) => "off".to_string(),
+            (1, _) => "strict mode (read, write, exit only)".to_string(),
+            (_, Some(1)) => "filtered (1 filter)".to_string(),
+            (_, Some(filters)) => format!("filtered ({} filters)", filters),
+            (_, None) => "filtered".to_string(),
+        }
+    }
+}
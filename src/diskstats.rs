@@ -0,0 +1,102 @@
+use std::{collections::HashMap, fs, path::Path, time::Instant};
+
+// Average I/O latency per block device from /proc/diskstats: time spent on
+// reads and writes divided by the number completed since the previous
+// refresh, plus how busy the device was and its average queue depth (from
+// the weighted I/O ticks). Throughput alone hides a disk that is struggling.
+
+// Fewer I/Os than this between two refreshes give no latency, and no alert
+const MIN_IOS: u64 = 10;
+
+#[derive(Default, Clone, Copy)]
+struct Counters {
+    reads: u64,
+    read_ms: u64,
+    writes: u64,
+    write_ms: u64,
+    // Time with I/O in flight, and the same weighted by the number in flight
+    io_ms: u64,
+    weighted_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeviceLatency {
+    // Milliseconds per completed I/O, None when there were too few
+    pub read: Option<f64>,
+    pub write: Option<f64>,
+    // Both directions together, for thresholds
+    pub average: Option<f64>,
+    // Percent of the interval with I/O in flight
+    pub busy: f64,
+    pub queue: f64,
+}
+
+#[derive(Default)]
+pub struct DiskLatency {
+    previous: Option<(Instant, HashMap<String, Counters>)>,
+    // By kernel name, e.g. "sda1" or "dm-0"
+    devices: HashMap<String, DeviceLatency>,
+}
+
+impl DiskLatency {
+    pub fn update(&mut self) {
+        let Ok(contents) = fs::read_to_string("/proc/diskstats") else { return };
+        let now = Instant::now();
+        let counters: HashMap<String, Counters> = contents
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let value = |index: usize| fields.get(index).and_then(|v| v.parse().ok()).unwrap_or(0);
+                let name = fields.get(2)?.to_string();
+                Some((
+                    name,
+                    Counters {
+                        reads: value(3),
+                        read_ms: value(6),
+                        writes: value(7),
+                        write_ms: value(10),
+                        io_ms: value(12),
+                        weighted_ms: value(13),
+                    },
+                ))
+            })
+            .collect();
+
+        if let Some((at, previous)) = &self.previous {
+            let elapsed_ms = now.duration_since(*at).as_secs_f64() * 1000.0;
+            if elapsed_ms > 0.0 {
+                self.devices = counters
+                    .iter()
+                    .filter_map(|(name, now)| {
+                        let before = previous.get(name)?;
+                        let reads = now.reads.saturating_sub(before.reads);
+                        let writes = now.writes.saturating_sub(before.writes);
+                        let read_ms = now.read_ms.saturating_sub(before.read_ms);
+                        let write_ms = now.write_ms.saturating_sub(before.write_ms);
+                        let per_io = |ms: u64, ios: u64| (ios >= MIN_IOS).then(|| ms as f64 / ios as f64);
+                        let latency = DeviceLatency {
+                            read: per_io(read_ms, reads),
+                            write: per_io(write_ms, writes),
+                            average: per_io(read_ms + write_ms, reads + writes),
+                            busy: (now.io_ms.saturating_sub(before.io_ms) as f64 / elapsed_ms * 100.0).min(100.0),
+                            queue: now.weighted_ms.saturating_sub(before.weighted_ms) as f64 / elapsed_ms,
+                        };
+                        Some((name.clone(), latency))
+                    })
+                    .collect();
+            }
+        }
+        self.previous = Some((now, counters));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DeviceLatency> {
+        self.devices.get(name)
+    }
+
+    // Latency of the device behind a mounted filesystem, e.g. "/dev/sda1" or
+    // "/dev/mapper/root" (a link to a dm-N device)
+    pub fn for_device(&self, device: &str) -> Option<&DeviceLatency> {
+        let path = fs::canonicalize(device).unwrap_or_else(|_| Path::new(device).to_path_buf());
+        self.get(path.file_name()?.to_str()?)
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{freshness, App};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Table},
+    Frame,
+};
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::Instant,
+};
+
+// eBPF insights (cargo feature `ebpf`). Probes are attached by running a
+// bpftrace script as a child process; it prints its maps as JSON once per
+// second and a reader thread folds them into `EbpfData`. Needs root and
+// bpftrace installed.
+const SCRIPT: &str = r#"
+tracepoint:raw_syscalls:sys_enter { @syscalls[pid] = count(); }
+tracepoint:block:block_rq_issue { @start[args->dev, args->sector] = nsecs; }
+tracepoint:block:block_rq_complete /@start[args->dev, args->sector]/ {
+    @io_usecs = hist((nsecs - @start[args->dev, args->sector]) / 1000);
+    delete(@start[args->dev, args->sector]);
+}
+tracepoint:tcp:tcp_retransmit_skb { @retransmits = count(); }
+interval:s:1 {
+    print(@syscalls, 15);
+    print(@io_usecs);
+    print(@retransmits);
+    printf("{\"type\": \"tick\"}\n");
+    clear(@syscalls);
+    clear(@io_usecs);
+    clear(@retransmits);
+}
+END { clear(@start); }
+"#;
+
+#[derive(Debug, Default, Clone)]
+pub struct EbpfData {
+    // (pid, syscalls in the last second), busiest first
+    pub syscalls: Vec<(u32, u64)>,
+    // Block I/O completion latency histogram buckets: (low µs, high µs, count)
+    pub io_latency: Vec<(u64, u64, u64)>,
+    pub retransmits: u64,
+    // When bpftrace last reported
+    pub updated: Option<Instant>,
+    pub error: Option<String>,
+    // Values being collected for the second in progress
+    pending: Option<Box<EbpfData>>,
+}
+
+pub struct EbpfTracer {
+    data: Arc<Mutex<EbpfData>>,
+    child: Option<Child>,
+}
+
+impl EbpfTracer {
+    pub fn start() -> EbpfTracer {
+        let data = Arc::new(Mutex::new(EbpfData::default()));
+        let spawned = Command::new("bpftrace")
+            .args(["-f", "json", "-e", SCRIPT])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(err) => {
+                data.lock().unwrap().error = Some(format!("could not start bpftrace: {}", err));
+                return EbpfTracer { data, child: None };
+            }
+        };
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let reader_data = Arc::clone(&data);
+        thread::spawn(move || {
+            if let Some(stdout) = stdout {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    if let Ok(value) = serde_json::from_str::<Value>(&line) {
+                        apply_output(&mut reader_data.lock().unwrap(), &value);
+                    }
+                }
+            }
+            // bpftrace exited: keep its last complaint for the panel
+            let mut data = reader_data.lock().unwrap();
+            if data.error.is_none() {
+                let mut message = String::new();
+                if let Some(stderr) = stderr {
+                    for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                        message = line;
+                    }
+                }
+                data.error = Some(if message.is_empty() { "bpftrace exited".to_string() } else { message });
+            }
+        });
+
+        EbpfTracer { data, child: Some(child) }
+    }
+
+    pub fn snapshot(&self) -> EbpfData {
+        self.data.lock().unwrap().clone()
+    }
+}
+
+impl Drop for EbpfTracer {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+fn apply_output(data: &mut EbpfData, value: &Value) {
+    let kind = value.get("type").and_then(Value::as_str).unwrap_or("");
+    let pending = data.pending.get_or_insert_with(Default::default);
+
+    match kind {
+        "map" | "hist" => {
+            let Some(maps) = value.get("data").and_then(Value::as_object) else {
+                return;
+            };
+            for (name, map) in maps {
+                match name.as_str() {
+                    "@syscalls" => {
+                        let mut rates: Vec<(u32, u64)> = map
+                            .as_object()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|(pid, count)| Some((pid.parse().ok()?, count.as_u64()?)))
+                            .collect();
+                        rates.sort_by_key(|r| std::cmp::Reverse(r.1));
+                        pending.syscalls = rates;
+                    }
+                    "@io_usecs" => {
+                        pending.io_latency = map
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|bucket| {
+                                let min = bucket.get("min").and_then(Value::as_u64).unwrap_or(0);
+                                let max = bucket.get("max").and_then(Value::as_u64).unwrap_or(min);
+                                Some((min, max, bucket.get("count")?.as_u64()?))
+                            })
+                            .collect();
+                    }
+                    "@retransmits" => pending.retransmits = map.as_u64().unwrap_or(0),
+                    _ => {}
+                }
+            }
+        }
+        // End of one second: publish what was gathered
+        "tick" => {
+            let done = data.pending.take().unwrap_or_default();
+            data.syscalls = done.syscalls;
+            data.io_latency = done.io_latency;
+            data.retransmits = done.retransmits;
+            data.updated = Some(Instant::now());
+        }
+        _ => {}
+    }
+}
+
+pub fn draw_ebpf_tab(f: &mut Frame, area: Rect, app: &App) {
+    let mut data = app.ebpf.as_ref().map(EbpfTracer::snapshot).unwrap_or_default();
+    if !app.capabilities.ebpf.available() {
+        data.error = Some(app.capabilities.ebpf.placeholder());
+    }
+
+    if let Some(error) = &data.error {
+        let message = Paragraph::new(format!(
+            "❌ {}\n\neBPF panels need root and bpftrace (https://github.com/bpftrace/bpftrace).",
+            error
+        ))
+        .block(Block::default().title("🐝 eBPF").borders(Borders::ALL))
+        .style(Style::default().fg(Color::Gray));
+        f.render_widget(message, area);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(chunks[1]);
+
+    // Per-process syscall rates
+    let header = Row::new(vec!["PID", "Name", "Syscalls/s"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = data
+        .syscalls
+        .iter()
+        .map(|(pid, count)| {
+            let name = app
+                .processes
+                .process(sysinfo::Pid::from_u32(*pid))
+                .map(|p| p.name().to_string_lossy().into_owned())
+                .unwrap_or_default();
+            Row::new(vec![Cell::from(pid.to_string()), Cell::from(name), Cell::from(count.to_string())])
+        })
+        .collect();
+    let table = Table::new(rows, [Constraint::Length(8), Constraint::Min(15), Constraint::Length(12)])
+        .header(header)
+        .block(Block::default().title(freshness::title("🐝 Syscalls per Process", "", data.updated, false)).borders(Borders::ALL))
+        .column_spacing(1);
+    f.render_widget(table, chunks[0]);
+
+    // TCP retransmits
+    let retransmit_color = if data.retransmits > 0 { Color::Yellow } else { Color::Green };
+    let retransmits = Paragraph::new(format!("{} retransmits/s", data.retransmits))
+        .block(Block::default().title(freshness::title("🔁 TCP Retransmits", "", data.updated, false)).borders(Borders::ALL))
+        .style(Style::default().fg(retransmit_color));
+    f.render_widget(retransmits, right[0]);
+
+    // Block I/O latency histogram, drawn as text bars
+    let max_count = data.io_latency.iter().map(|b| b.2).max().unwrap_or(0).max(1);
+    let bar_width = right[1].width.saturating_sub(30) as u64;
+    let items: Vec<ListItem> = data
+        .io_latency
+        .iter()
+        .map(|(low, high, count)| {
+            let bar = "█".repeat((count * bar_width / max_count) as usize);
+            ListItem::new(format!("{:>7}-{:<7}µs {:>6} {}", low, high, count, bar))
+        })
+        .collect();
+    let histogram = List::new(items)
+        .block(Block::default().title(freshness::title("💽 Block I/O Latency", "", data.updated, false)).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White));
+    f.render_widget(histogram, right[1]);
+}
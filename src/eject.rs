@@ -0,0 +1,153 @@
+use crate::blockdev::BlockDevice;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::{
+    fs,
+    process::Command,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+// Safe removal of a removable drive: unmount every filesystem on it, deepest
+// first, then power it off. udisksctl does both without root where polkit
+// allows it; without udisksctl, umount(8) and the sysfs delete file are used,
+// which need root and are blocked by --harden. Runs in the background,
+// unmounting flushes pending writes.
+#[derive(Debug, Clone)]
+pub struct EjectRequest {
+    // Kernel name of the whole disk, e.g. "sdb"
+    pub disk: String,
+    pub label: String,
+    // (kernel name, mount point) of every mounted filesystem on it
+    pub mounts: Vec<(String, String)>,
+}
+
+impl EjectRequest {
+    pub fn new(drive: &BlockDevice) -> EjectRequest {
+        let mut mounts = Vec::new();
+        collect_mounts(drive, &mut mounts);
+        // Nested mounts go first
+        mounts.sort_by_key(|(_, mount)| std::cmp::Reverse(mount.len()));
+        EjectRequest {
+            disk: drive.name.clone(),
+            label: drive.model.clone().unwrap_or_else(|| drive.label.clone()),
+            mounts,
+        }
+    }
+}
+
+fn collect_mounts(device: &BlockDevice, mounts: &mut Vec<(String, String)>) {
+    for child in &device.children {
+        collect_mounts(child, mounts);
+    }
+    for mount in device.mounts.iter().filter(|mount| *mount != "[SWAP]") {
+        mounts.push((device.name.clone(), mount.clone()));
+    }
+}
+
+pub struct Eject {
+    label: String,
+    result: Arc<Mutex<Option<Result<String, String>>>>,
+}
+
+impl Eject {
+    pub fn start(request: EjectRequest) -> Eject {
+        let result = Arc::new(Mutex::new(None));
+        let thread_result = Arc::clone(&result);
+        let label = request.label.clone();
+        thread::spawn(move || {
+            let outcome = eject(&request);
+            *thread_result.lock().unwrap() = Some(outcome);
+        });
+        Eject { label, result }
+    }
+
+    // One line for the removable drives section, red on failure
+    pub fn status(&self) -> (String, Color) {
+        match &*self.result.lock().unwrap() {
+            None => (format!("⏏️ Ejecting {}: unmounting and flushing writes…", self.label), Color::Yellow),
+            Some(Ok(message)) => (format!("✅ {}", message), Color::Green),
+            Some(Err(err)) => (format!("❌ Ejecting {} failed: {}", self.label, err), Color::Red),
+        }
+    }
+}
+
+fn udisksctl_installed() -> bool {
+    Command::new("udisksctl").arg("help").output().is_ok()
+}
+
+// First line of stderr, or the exit status
+fn run(program: &str, args: &[&str]) -> Result<(), String> {
+    let output = Command::new(program).args(args).output().map_err(|e| format!("{}: {}", program, e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| output.status.to_string()))
+}
+
+fn eject(request: &EjectRequest) -> Result<String, String> {
+    let udisks = udisksctl_installed();
+    // seccomp blocks umount2 and Landlock the sysfs write
+    if !udisks && crate::harden::active() {
+        return Err(
+            "without udisksctl, --harden blocks unmounting and powering off; install udisks2".to_string()
+        );
+    }
+    for (device, mount) in &request.mounts {
+        let result = if udisks {
+            run("udisksctl", &["unmount", "--no-user-interaction", "-b", &format!("/dev/{}", device)])
+        } else {
+            run("umount", &[mount])
+        };
+        result.map_err(|e| format!("unmounting {}: {}", mount, e))?;
+    }
+
+    let power_off = if udisks {
+        run("udisksctl", &["power-off", "--no-user-interaction", "-b", &format!("/dev/{}", request.disk)])
+    } else {
+        fs::write(format!("/sys/block/{}/device/delete", request.disk), "1").map_err(|e| {
+            let e = e.to_string();
+            format!("{} (install udisks2 or run as root)", e.split(" (os error").next().unwrap_or_default())
+        })
+    };
+    let unmounted = if request.mounts.is_empty() { "has nothing mounted" } else { "was unmounted" };
+    Ok(match power_off {
+        Ok(()) => format!("{} was ejected and can be removed", request.label),
+        // Unmounted is what matters for the data
+        Err(err) => format!("{} {} and can be removed, but not powered off: {}", request.label, unmounted, err),
+    })
+}
+
+// y/Enter confirms, n/Esc cancels
+pub fn draw_confirm(f: &mut Frame, area: Rect, request: &EjectRequest) {
+    let mut lines = vec![Line::from(format!("Eject {} (/dev/{})?", request.label, request.disk)), Line::from("")];
+    if request.mounts.is_empty() {
+        lines.push(Line::from("Nothing on it is mounted; it will be powered off."));
+    } else {
+        lines.push(Line::from("These filesystems will be unmounted first:"));
+        lines.extend(
+            request.mounts.iter().map(|(device, mount)| Line::from(format!("  {}  (/dev/{})", mount, device))),
+        );
+        lines.push(Line::from("Programs with open files on them make the unmount fail."));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::styled("y / Enter eject · n / Esc cancel", Style::default().fg(Color::Yellow)));
+    let dialog = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title("⏏️ Safe eject")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(dialog, area);
+}
@@ -0,0 +1,126 @@
+use crate::channels::{AlertChannel, DeliveryPolicy, Notification};
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    Message, SmtpTransport, Transport,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Alert mails over SMTP, for places without a chat webhook
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub server: String,
+    // Defaults to 587 for starttls, 465 for tls and 25 for none
+    pub port: Option<u16>,
+    pub tls: EmailTls,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    // Templates; {host}, {severity}, {message}, {source}, {event} and {time} are filled in
+    pub subject: String,
+    pub body: String,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        EmailConfig {
+            server: String::new(),
+            port: None,
+            tls: EmailTls::Starttls,
+            username: None,
+            password: None,
+            from: String::new(),
+            to: Vec::new(),
+            subject: "[{host}] {severity}: {message}".to_string(),
+            body: "{message}\n\nHost: {host}\nSeverity: {severity}\nSource: {source}\nTime: {time}\n".to_string(),
+        }
+    }
+}
+
+impl EmailConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.server.is_empty() {
+            return Err("server is required".to_string());
+        }
+        if self.to.is_empty() {
+            return Err("at least one recipient in 'to' is required".to_string());
+        }
+        for address in std::iter::once(&self.from).chain(&self.to) {
+            address
+                .parse::<Mailbox>()
+                .map_err(|e| format!("invalid address '{}': {}", address, e))?;
+        }
+        if self.username.is_some() != self.password.is_some() {
+            return Err("username and password must be set together".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailTls {
+    // Plain connection upgraded with STARTTLS, which is required
+    Starttls,
+    // TLS from the first byte (SMTPS)
+    Tls,
+    // No encryption, for a local relay only
+    None,
+}
+
+// The [email] table: shorthand for an alert channel named "email"
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct EmailSection {
+    #[serde(flatten)]
+    pub email: EmailConfig,
+    #[serde(flatten)]
+    pub policy: DeliveryPolicy,
+}
+
+impl AlertChannel for EmailConfig {
+    fn deliver(&self, notification: &Notification) -> Result<(), String> {
+        let subject = notification.fill(&self.subject).replace(['\r', '\n'], " ");
+        // Reports are mailed as they are, in place of the alert body template
+        let mut body = if notification.detail.is_empty() {
+            notification.fill(&self.body)
+        } else {
+            notification.detail.clone()
+        };
+        if notification.suppressed > 0 {
+            body.push_str(&format!(
+                "\n{} earlier notification(s) were dropped by the rate limit.\n",
+                notification.suppressed
+            ));
+        }
+
+        let mut message = Message::builder()
+            .from(self.from.parse::<Mailbox>().map_err(|e| e.to_string())?)
+            .subject(subject)
+            .header(if notification.html { ContentType::TEXT_HTML } else { ContentType::TEXT_PLAIN });
+        for to in &self.to {
+            message = message.to(to.parse::<Mailbox>().map_err(|e| e.to_string())?);
+        }
+        let message = message.body(body).map_err(|e| e.to_string())?;
+
+        let mut transport = match self.tls {
+            EmailTls::Starttls => SmtpTransport::starttls_relay(&self.server).map_err(|e| e.to_string())?,
+            EmailTls::Tls => SmtpTransport::relay(&self.server).map_err(|e| e.to_string())?,
+            EmailTls::None => SmtpTransport::builder_dangerous(&self.server),
+        };
+        if let Some(port) = self.port {
+            transport = transport.port(port);
+        }
+        if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        transport
+            .timeout(Some(Duration::from_secs(15)))
+            .build()
+            .send(&message)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,187 @@
+use crate::alerts::{AlertChange, Severity};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+// Structured copies of alert transitions and kernel OOM kills for log
+// aggregation. Entries go to the local syslog socket (RFC 5424) and/or
+// straight to journald with searchable SYSTEM_MONITOR_* fields.
+const IDENTIFIER: &str = "system-monitor";
+const SYSLOG_SOCKET: &str = "/dev/log";
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+// Private enterprise number for the structured data ID, as in RFC 5424 examples
+const SD_ID: &str = "sysmon@32473";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EventLogConfig {
+    pub syslog: bool,
+    pub journald: bool,
+}
+
+impl EventLogConfig {
+    pub fn enabled(&self) -> bool {
+        self.syslog || self.journald
+    }
+}
+
+// Syslog severities
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Priority {
+    Critical = 2,
+    Warning = 4,
+    Notice = 5,
+}
+
+impl From<Severity> for Priority {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Warning => Priority::Warning,
+            Severity::Critical => Priority::Critical,
+        }
+    }
+}
+
+struct Event<'a> {
+    // "alert", "resolved" or "oom"
+    kind: &'static str,
+    source: &'a str,
+    severity: &'a str,
+    priority: Priority,
+    message: String,
+}
+
+pub struct EventLog {
+    config: EventLogConfig,
+    oom_kills: Option<u64>,
+    error: Option<String>,
+}
+
+impl EventLog {
+    pub fn new(config: EventLogConfig) -> EventLog {
+        EventLog { config, oom_kills: read_oom_kills(), error: None }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled()
+    }
+
+    // Emit what changed since the previous refresh
+    pub fn update(&mut self, changes: &[AlertChange]) {
+        if !self.enabled() {
+            return;
+        }
+        let mut events: Vec<Event> = changes
+            .iter()
+            .map(|change| match change {
+                AlertChange::Fired(alert) => Event {
+                    kind: "alert",
+                    source: &alert.source,
+                    severity: alert.severity.name(),
+                    priority: alert.severity.into(),
+                    message: alert.message.clone(),
+                },
+                AlertChange::Resolved(alert) => Event {
+                    kind: "resolved",
+                    source: &alert.source,
+                    severity: "ok",
+                    priority: Priority::Notice,
+                    message: format!("Resolved: {}", alert.message),
+                },
+            })
+            .collect();
+
+        let oom_kills = read_oom_kills();
+        if let (Some(before), Some(now)) = (self.oom_kills, oom_kills) {
+            if now > before {
+                events.push(Event {
+                    kind: "oom",
+                    source: "kernel",
+                    severity: "critical",
+                    priority: Priority::Critical,
+                    message: format!("Kernel OOM killer ran, {} kill(s) since last refresh", now - before),
+                });
+            }
+        }
+        self.oom_kills = oom_kills;
+
+        for event in &events {
+            if let Err(e) = self.send(event) {
+                self.error = Some(e.to_string().split(" (os error").next().unwrap_or_default().to_string());
+            }
+        }
+    }
+
+    fn send(&self, event: &Event) -> std::io::Result<()> {
+        if self.config.journald {
+            send_datagram(JOURNALD_SOCKET, journald_entry(event).as_bytes())?;
+        }
+        if self.config.syslog {
+            send_datagram(SYSLOG_SOCKET, syslog_entry(event).as_bytes())?;
+        }
+        Ok(())
+    }
+
+    // One line for the System Information panel
+    pub fn status(&self) -> String {
+        let targets: Vec<&str> = [("syslog", self.config.syslog), ("journald", self.config.journald)]
+            .iter()
+            .filter(|(_, on)| *on)
+            .map(|(name, _)| *name)
+            .collect();
+        match &self.error {
+            None => format!("📜 Event log → {}", targets.join(", ")),
+            Some(error) => format!("📜 Event log → {} ❌ {}", targets.join(", "), error),
+        }
+    }
+}
+
+// Cumulative count of OOM kills since boot, Linux 4.13+
+fn read_oom_kills() -> Option<u64> {
+    let vmstat = fs::read_to_string("/proc/vmstat").ok()?;
+    vmstat.lines().find_map(|line| line.strip_prefix("oom_kill ")?.trim().parse().ok())
+}
+
+// Native journal protocol: one KEY=value per line, values without newlines
+fn journald_entry(event: &Event) -> String {
+    let field = |value: &str| value.replace('\n', " ");
+    format!(
+        "MESSAGE={}\nPRIORITY={}\nSYSLOG_IDENTIFIER={}\nSYSTEM_MONITOR_EVENT={}\nSYSTEM_MONITOR_SOURCE={}\nSYSTEM_MONITOR_SEVERITY={}\n",
+        field(&event.message),
+        event.priority as u8,
+        IDENTIFIER,
+        event.kind,
+        field(event.source),
+        event.severity
+    )
+}
+
+// RFC 5424 with facility daemon (3)
+fn syslog_entry(event: &Event) -> String {
+    let escape = |value: &str| value.replace('\\', "\\\\").replace('"', "\\\"").replace(']', "\\]");
+    let host = sysinfo::System::host_name().unwrap_or_else(|| "-".to_string());
+    format!(
+        "<{}>1 {} {} {} {} {} [{} event=\"{}\" source=\"{}\" severity=\"{}\"] {}",
+        3 * 8 + event.priority as u8,
+        chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        host,
+        IDENTIFIER,
+        std::process::id(),
+        event.kind,
+        SD_ID,
+        event.kind,
+        escape(event.source),
+        event.severity,
+        event.message.replace('\n', " ")
+    )
+}
+
+#[cfg(unix)]
+fn send_datagram(path: &str, data: &[u8]) -> std::io::Result<()> {
+    let socket = std::os::unix::net::UnixDatagram::unbound()?;
+    socket.send_to(data, path).map(|_| ())
+}
+
+#[cfg(not(unix))]
+fn send_datagram(_path: &str, _data: &[u8]) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "event log needs a Unix socket"))
+}
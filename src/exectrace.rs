@@ -0,0 +1,352 @@
+use crate::capabilities::Support;
+use crate::report::cpu_time;
+use crate::App;
+use chrono::{DateTime, Local};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Processes that start and exit between two refreshes never show up in the
+// process tables. With exec tracing on, the kernel's process connector
+// (netlink) reports every exec and exit, and the ones that exited are listed
+// under the Processes tab. Linux only, needs root.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ExecTraceConfig {
+    // Same as --trace-exec
+    pub enabled: bool,
+    // Exited processes remembered
+    pub keep: usize,
+}
+
+impl Default for ExecTraceConfig {
+    fn default() -> Self {
+        ExecTraceConfig { enabled: false, keep: 200 }
+    }
+}
+
+impl ExecTraceConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.keep == 0 {
+            return Err("exec_trace: keep must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExitedProcess {
+    pub pid: u32,
+    pub name: String,
+    pub command: String,
+    pub exited: DateTime<Local>,
+    pub lifetime: Duration,
+    // "exit 0", "SIGKILL", ...
+    pub status: String,
+}
+
+// Seen exec'ing, not exited yet
+struct Running {
+    name: String,
+    command: String,
+    started: Instant,
+}
+
+#[derive(Default)]
+struct TraceState {
+    running: HashMap<u32, Running>,
+    // Newest first
+    exited: VecDeque<ExitedProcess>,
+    error: Option<String>,
+}
+
+pub struct ExecTracer {
+    state: Arc<Mutex<TraceState>>,
+}
+
+impl ExecTracer {
+    // Without the capability nothing listens; the panel says why
+    pub fn start(config: &ExecTraceConfig, support: &Support) -> ExecTracer {
+        let state = Arc::new(Mutex::new(TraceState::default()));
+        if !support.available() {
+            return ExecTracer { state };
+        }
+        let shared = Arc::clone(&state);
+        let keep = config.keep;
+        thread::spawn(move || {
+            if let Err(error) = listen(&shared, keep) {
+                shared.lock().unwrap().error = Some(error);
+            }
+        });
+        ExecTracer { state }
+    }
+
+    pub fn exited(&self) -> Vec<ExitedProcess> {
+        self.state.lock().unwrap().exited.iter().cloned().collect()
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.state.lock().unwrap().error.clone()
+    }
+}
+
+impl TraceState {
+    fn exec(&mut self, pid: u32) {
+        // Read right away: the process may be gone before the next refresh
+        let name = std::fs::read_to_string(format!("/proc/{}/comm", pid)).unwrap_or_default();
+        let command = std::fs::read(format!("/proc/{}/cmdline", pid))
+            .map(|bytes| String::from_utf8_lossy(&bytes).replace('\0', " ").trim_end().to_string())
+            .unwrap_or_default();
+        let name = name.trim_end().to_string();
+        self.running.insert(pid, Running { name, command, started: Instant::now() });
+    }
+
+    fn exit(&mut self, pid: u32, wait_status: u32, keep: usize) {
+        // Only processes we saw start; the rest were already in the tables
+        let Some(running) = self.running.remove(&pid) else { return };
+        self.exited.push_front(ExitedProcess {
+            pid,
+            name: running.name,
+            command: running.command,
+            exited: Local::now(),
+            lifetime: running.started.elapsed(),
+            status: describe_status(wait_status),
+        });
+        self.exited.truncate(keep);
+    }
+}
+
+// A wait(2) status as "exit N" or the signal that ended the process
+fn describe_status(status: u32) -> String {
+    let signal = status & 0x7f;
+    if signal == 0 {
+        return format!("exit {}", (status >> 8) & 0xff);
+    }
+    let name = match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        6 => "SIGABRT",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        15 => "SIGTERM",
+        _ => return format!("signal {}", signal),
+    };
+    name.to_string()
+}
+
+#[cfg(target_os = "linux")]
+fn listen(state: &Mutex<TraceState>, keep: usize) -> Result<(), String> {
+    // <linux/connector.h> and <linux/cn_proc.h>
+    const CN_IDX_PROC: u32 = 1;
+    const CN_VAL_PROC: u32 = 1;
+    const PROC_CN_MCAST_LISTEN: u32 = 1;
+    const PROC_EVENT_EXEC: u32 = 0x2;
+    const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+    // nlmsghdr, then cn_msg, then proc_event's what/cpu/timestamp before its data
+    const NLMSG_HEADER: usize = 16;
+    const CN_MSG: usize = 20;
+    const EVENT_DATA: usize = NLMSG_HEADER + CN_MSG + 16;
+
+    let denied = |what: &str| {
+        let err = std::io::Error::last_os_error();
+        match err.kind() {
+            std::io::ErrorKind::PermissionDenied => format!("exec tracing {}", crate::privilege::root_hint()),
+            _ => format!("{}: {}", what, err.to_string().split(" (os error").next().unwrap_or_default()),
+        }
+    };
+
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_CONNECTOR) };
+    if fd < 0 {
+        return Err(denied("could not open the process connector"));
+    }
+    let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    address.nl_pid = 0;
+    address.nl_groups = CN_IDX_PROC;
+    let bound = unsafe {
+        libc::bind(
+            fd,
+            &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if bound < 0 {
+        let error = denied("could not bind the process connector");
+        unsafe { libc::close(fd) };
+        return Err(error);
+    }
+
+    // Subscribe: nlmsghdr + cn_msg + the listen op
+    let mut request = Vec::with_capacity(NLMSG_HEADER + CN_MSG + 4);
+    let length = (NLMSG_HEADER + CN_MSG + 4) as u32;
+    request.extend_from_slice(&length.to_ne_bytes());
+    request.extend_from_slice(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+    request.extend_from_slice(&0u16.to_ne_bytes());
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    for field in [CN_IDX_PROC, CN_VAL_PROC, 0, 0] {
+        request.extend_from_slice(&field.to_ne_bytes());
+    }
+    request.extend_from_slice(&4u16.to_ne_bytes());
+    request.extend_from_slice(&0u16.to_ne_bytes());
+    request.extend_from_slice(&PROC_CN_MCAST_LISTEN.to_ne_bytes());
+    let sent = unsafe { libc::send(fd, request.as_ptr() as *const libc::c_void, request.len(), 0) };
+    if sent < 0 {
+        let error = denied("could not subscribe to process events");
+        unsafe { libc::close(fd) };
+        return Err(error);
+    }
+
+    let read_u32 = |buffer: &[u8], offset: usize| {
+        buffer.get(offset..offset + 4).map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let mut buffer = vec![0u8; 8192];
+    loop {
+        let received = unsafe { libc::recv(fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+        if received < 0 {
+            let err = std::io::Error::last_os_error();
+            match err.raw_os_error() {
+                Some(libc::EINTR) => continue,
+                // The socket buffer overflowed and events were lost: forget
+                // processes whose exit may have been among them
+                Some(libc::ENOBUFS) => {
+                    let mut state = state.lock().unwrap();
+                    state.running.retain(|pid, _| std::path::Path::new(&format!("/proc/{}", pid)).exists());
+                    continue;
+                }
+                _ => {
+                    unsafe { libc::close(fd) };
+                    return Err(format!("process connector: {}", err.to_string().split(" (os error").next().unwrap_or_default()));
+                }
+            }
+        }
+        let message = &buffer[..received as usize];
+        let (Some(what), Some(pid), Some(tgid)) = (
+            read_u32(message, NLMSG_HEADER + CN_MSG),
+            read_u32(message, EVENT_DATA),
+            read_u32(message, EVENT_DATA + 4),
+        ) else {
+            continue;
+        };
+        // Threads exec and exit too; only whole processes matter here
+        if pid != tgid {
+            continue;
+        }
+        match what {
+            PROC_EVENT_EXEC => state.lock().unwrap().exec(pid),
+            PROC_EVENT_EXIT => {
+                let status = read_u32(message, EVENT_DATA + 8).unwrap_or(0);
+                state.lock().unwrap().exit(pid, status, keep);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn listen(_state: &Mutex<TraceState>, _keep: usize) -> Result<(), String> {
+    Err("exec tracing is only available on Linux".to_string())
+}
+
+// "35 ms", "2.4 s", then minutes as for CPU time
+fn format_lifetime(lifetime: Duration) -> String {
+    let millis = lifetime.as_millis();
+    if millis < 1000 {
+        format!("{} ms", millis)
+    } else if millis < 60_000 {
+        format!("{:.1} s", lifetime.as_secs_f64())
+    } else {
+        cpu_time(lifetime.as_secs_f64())
+    }
+}
+
+// Recently exited panel below the process tables
+pub fn draw_exited(f: &mut Frame, area: Rect, app: &App, tracer: &ExecTracer) {
+    let block = Block::default().borders(Borders::ALL);
+    let unavailable = Some(&app.capabilities.exec_trace).filter(|s| !s.available()).map(|s| s.placeholder());
+    if let Some(error) = unavailable.or_else(|| tracer.error()) {
+        let message = Paragraph::new(format!("❌ {}", error))
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Color::Gray))
+            .block(block.title("🕑 Recently exited"));
+        f.render_widget(message, area);
+        return;
+    }
+
+    let exited = tracer.exited();
+    // These never appeared in the process tables
+    let unseen = exited.iter().filter(|p| p.lifetime < app.refresh_interval).count();
+    let header = Row::new(vec!["Exited", "PID", "Name", "Runtime", "Status", "Command"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = exited
+        .iter()
+        .map(|p| {
+            let style = if p.lifetime < app.refresh_interval {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                p.exited.format("%H:%M:%S").to_string(),
+                p.pid.to_string(),
+                p.name.clone(),
+                format_lifetime(p.lifetime),
+                p.status.clone(),
+                p.command.clone(),
+            ])
+            .style(style)
+        })
+        .collect();
+    let title = format!(
+        "🕑 Recently exited: {}, {} shorter than a refresh (cyan)",
+        exited.len(),
+        unseen
+    );
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(16),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .block(block.title(title))
+    .column_spacing(1);
+    f.render_widget(table, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_exit_codes() {
+        assert_eq!(describe_status(0), "exit 0");
+        assert_eq!(describe_status(3 << 8), "exit 3");
+        assert_eq!(describe_status(255 << 8), "exit 255");
+    }
+
+    #[test]
+    fn describes_signals() {
+        assert_eq!(describe_status(9), "SIGKILL");
+        assert_eq!(describe_status(15), "SIGTERM");
+        // 0x80 marks a core dump
+        assert_eq!(describe_status(0x80 | 11), "SIGSEGV");
+        assert_eq!(describe_status(10), "signal 10");
+    }
+}
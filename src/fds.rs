@@ -0,0 +1,185 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+use std::{collections::HashMap, fs};
+use sysinfo::{Pid, Process, System};
+
+// The open file descriptors of one process, from o on the Processes tab, for
+// hunting descriptor leaks: number, kind and target of each from
+// /proc/<pid>/fd, read on every refresh while the panel is open. Leaked
+// descriptors tend to get the highest numbers, so those come first. The
+// title counts them by kind, against the process's limit and against when the
+// panel opened, since a leak shows as a climbing count. Other users'
+// processes need root. Linux only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FdKind {
+    File,
+    Socket,
+    Pipe,
+    Device,
+    // eventfd, epoll, inotify, timerfd...
+    AnonInode,
+    Other,
+}
+
+impl FdKind {
+    fn name(&self) -> &'static str {
+        match self {
+            FdKind::File => "file",
+            FdKind::Socket => "socket",
+            FdKind::Pipe => "pipe",
+            FdKind::Device => "device",
+            FdKind::AnonInode => "anon inode",
+            FdKind::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Fd {
+    pub fd: u32,
+    pub kind: FdKind,
+    // Where the link points, "socket:[12345]" and "anon_inode:[eventfd]" shortened
+    pub target: String,
+}
+
+pub struct FdList {
+    pub pid: Pid,
+    start_time: u64,
+    name: String,
+    pub fds: Vec<Fd>,
+    // Descriptors when the panel opened
+    first_count: Option<usize>,
+    // The soft RLIMIT_NOFILE
+    limit: Option<usize>,
+    pub error: Option<String>,
+}
+
+impl FdList {
+    pub fn open(pid: Pid, process: &Process) -> FdList {
+        let mut list = FdList {
+            pid,
+            start_time: process.start_time(),
+            name: process.name().to_string_lossy().into_owned(),
+            fds: Vec::new(),
+            first_count: None,
+            limit: process.open_files_limit(),
+            error: None,
+        };
+        list.update(Some(process));
+        list
+    }
+
+    // process is what has the PID now, if anything
+    pub fn update(&mut self, process: Option<&Process>) {
+        if process.is_none_or(|process| process.start_time() != self.start_time) {
+            self.fds.clear();
+            self.error = Some("The process has exited.".to_string());
+            return;
+        }
+        let entries = match fs::read_dir(format!("/proc/{}/fd", self.pid)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => {
+                self.error = Some(format!("Another user's process: {}", crate::privilege::root_hint()));
+                return;
+            }
+            Err(err) => {
+                self.error = Some(format!("/proc/{}/fd: {}", self.pid, err));
+                return;
+            }
+        };
+        let mut fds: Vec<Fd> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let fd = entry.file_name().to_str()?.parse::<u32>().ok()?;
+                // A descriptor closed since the directory was listed
+                let target = fs::read_link(entry.path()).ok()?;
+                let (kind, target) = classify(&target.to_string_lossy());
+                Some(Fd { fd, kind, target })
+            })
+            .collect();
+        fds.sort_by_key(|fd| std::cmp::Reverse(fd.fd));
+        self.first_count.get_or_insert(fds.len());
+        self.fds = fds;
+        self.error = None;
+    }
+
+    fn title(&self) -> String {
+        let count = self.fds.len();
+        let limit = self.limit.map(|limit| format!(" of {}", limit)).unwrap_or_default();
+        let change = match self.first_count.map(|first| count as i64 - first as i64) {
+            Some(change) if change != 0 => format!(" ({:+} since opened)", change),
+            _ => String::new(),
+        };
+        let mut kinds: Vec<(FdKind, usize)> = Vec::new();
+        for fd in &self.fds {
+            match kinds.iter_mut().find(|(kind, _)| *kind == fd.kind) {
+                Some((_, n)) => *n += 1,
+                None => kinds.push((fd.kind, 1)),
+            }
+        }
+        kinds.sort();
+        let kinds: Vec<String> = kinds.iter().map(|(kind, n)| format!("{} {}", n, kind.name())).collect();
+        let kinds = if kinds.is_empty() { String::new() } else { format!(" · {}", kinds.join(", ")) };
+        format!("📂 Open files of {} ({}): {}{}{}{}, highest first · o close", self.name, self.pid, count, limit, change, kinds)
+    }
+
+    // Within a tenth of the limit, when the next leak makes open() fail
+    fn near_limit(&self) -> bool {
+        self.limit.is_some_and(|limit| self.fds.len() * 10 >= limit * 9)
+    }
+}
+
+fn classify(target: &str) -> (FdKind, String) {
+    // "socket:[12345]", "pipe:[12345]", "anon_inode:[eventfd]", "anon_inode:inotify"
+    let inner = |prefix: &str| target.strip_prefix(prefix).map(|rest| rest.trim_start_matches('[').trim_end_matches(']').to_string());
+    if let Some(inode) = inner("socket:") {
+        (FdKind::Socket, format!("inode {}", inode))
+    } else if let Some(inode) = inner("pipe:") {
+        (FdKind::Pipe, format!("inode {}", inode))
+    } else if let Some(what) = inner("anon_inode:") {
+        (FdKind::AnonInode, what)
+    } else if target.starts_with("/dev/") {
+        (FdKind::Device, target.to_string())
+    } else if target.starts_with('/') {
+        (FdKind::File, target.to_string())
+    } else {
+        (FdKind::Other, target.to_string())
+    }
+}
+
+// For the FDs column, after each process refresh; processes whose
+// descriptors can't be read (other users', without root) are left out
+pub fn update_counts(counts: &mut HashMap<Pid, usize>, system: &System) {
+    counts.clear();
+    for (pid, process) in system.processes() {
+        // Threads share their process's descriptor table
+        if process.thread_kind().is_some() {
+            continue;
+        }
+        if let Some(count) = process.open_files() {
+            counts.insert(*pid, count);
+        }
+    }
+}
+
+pub fn draw(f: &mut Frame, area: Rect, list: &FdList) {
+    let color = if list.near_limit() { Color::Red } else { Color::Cyan };
+    let block = Block::default().title(list.title()).borders(Borders::ALL).border_style(Style::default().fg(color));
+    if let Some(error) = &list.error {
+        f.render_widget(Paragraph::new(error.as_str()).wrap(Wrap { trim: true }).block(block), area);
+        return;
+    }
+    let rows: Vec<Row> = list
+        .fds
+        .iter()
+        .map(|fd| Row::new(vec![fd.fd.to_string(), fd.kind.name().to_string(), fd.target.clone()]))
+        .collect();
+    let header = Row::new(vec!["FD", "Kind", "Target"]).style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [Constraint::Length(7), Constraint::Length(11), Constraint::Min(20)];
+    let table = Table::new(rows, widths).header(header).block(block).column_spacing(1);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,38 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// How old a panel's data is, shown in its title, plus a spinner while a
+// background collection for it is running
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+// "3s ago", "4m ago", "2h ago"
+pub fn ago(at: Instant) -> String {
+    let seconds = at.elapsed().as_secs();
+    match seconds {
+        0..=59 => format!("{}s ago", seconds),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        _ => format!("{}h ago", seconds / 3600),
+    }
+}
+
+// Advances every 100 ms, which is how often the UI redraws
+pub fn spinner() -> char {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis());
+    SPINNER[(millis / 100) as usize % SPINNER.len()]
+}
+
+// "🌐 Network I/O · 1s ago", "💽 Storage · scan 3m ago ⠙"; `what` names the
+// collection when it isn't the regular refresh
+pub fn title(title: &str, what: &str, at: Option<Instant>, busy: bool) -> String {
+    let what = if what.is_empty() { String::new() } else { format!("{} ", what) };
+    let age = match at {
+        Some(at) => format!("{} · {}{}", title, what, ago(at)),
+        None if busy => format!("{} · {}running", title, what),
+        None => format!("{} · {}pending", title, what),
+    };
+    if busy {
+        format!("{} {}", age, spinner())
+    } else {
+        age
+    }
+}
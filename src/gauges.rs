@@ -0,0 +1,117 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Gauge},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+// Overview gauges: optionally smoothed so they don't flicker, with a marker at
+// the highest recent sample so a short spike stays visible for a while
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GaugeConfig {
+    // Time constant of the exponential moving average; 0 shows raw samples
+    pub smoothing_seconds: f64,
+    // How long the peak marker holds the highest sample; 0 hides it
+    pub peak_hold_seconds: u64,
+}
+
+impl Default for GaugeConfig {
+    fn default() -> Self {
+        GaugeConfig { smoothing_seconds: 0.0, peak_hold_seconds: 10 }
+    }
+}
+
+impl GaugeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.smoothing_seconds.is_finite() || self.smoothing_seconds < 0.0 {
+            return Err("gauges: smoothing_seconds can't be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GaugeState {
+    // Smoothed percentage, None before the first sample
+    value: Option<f64>,
+    // Raw samples within the peak hold window
+    samples: VecDeque<(Instant, f64)>,
+}
+
+impl GaugeState {
+    // `elapsed` is the time since the previous sample, in seconds
+    pub fn update(&mut self, raw: f64, elapsed: f64, config: &GaugeConfig) {
+        let raw = if raw.is_finite() { raw.clamp(0.0, 100.0) } else { 0.0 };
+        self.value = Some(match self.value {
+            Some(value) if config.smoothing_seconds > 0.0 => {
+                let alpha = 1.0 - (-elapsed / config.smoothing_seconds).exp();
+                value + alpha * (raw - value)
+            }
+            _ => raw,
+        });
+        self.observe(Instant::now(), raw, config);
+    }
+
+    // A sample for the peak marker only, e.g. from the high resolution sampler
+    pub fn observe(&mut self, at: Instant, raw: f64, config: &GaugeConfig) {
+        let raw = if raw.is_finite() { raw.clamp(0.0, 100.0) } else { 0.0 };
+        self.samples.push_back((at, raw));
+        let hold = Duration::from_secs(config.peak_hold_seconds);
+        // Samples can arrive slightly out of order, so check them all
+        self.samples.retain(|(at, _)| at.elapsed() <= hold);
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value.unwrap_or(0.0)
+    }
+
+    pub fn peak(&self) -> Option<f64> {
+        self.samples.iter().map(|(_, v)| *v).reduce(f64::max)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Gauges {
+    pub cpu: GaugeState,
+    pub memory: GaugeState,
+    pub swap: GaugeState,
+}
+
+// A percentage gauge with the peak marker drawn over it
+pub fn draw(f: &mut Frame, area: Rect, block: Block, color: Color, state: &GaugeState, config: &GaugeConfig) {
+    let value = state.value();
+    // Only worth showing once the peak is visibly above the bar
+    let peak = state
+        .peak()
+        .filter(|peak| config.peak_hold_seconds > 0 && *peak >= value + 1.0);
+    let label = match peak {
+        Some(peak) => format!("{:.1}% · peak {:.0}%", value, peak),
+        None => format!("{:.1}%", value),
+    };
+    let inner = block.inner(area);
+    let gauge = Gauge::default()
+        .block(block)
+        .gauge_style(Style::default().fg(color))
+        .ratio(value / 100.0)
+        .label(label);
+    f.render_widget(gauge, area);
+
+    if let Some(peak) = peak {
+        if inner.width > 1 && inner.height > 0 {
+            let x = inner.x + ((peak / 100.0) * (inner.width - 1) as f64).round() as u16;
+            for y in inner.y..inner.y + inner.height {
+                let Some(cell) = f.buffer_mut().cell_mut((x, y)) else { continue };
+                // Leave the label readable
+                if !cell.symbol().chars().all(|c| c.is_ascii_graphic()) {
+                    cell.set_symbol("▕").set_fg(Color::Red);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{cgroup::Cgroup, format_bytes, thread_count};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Row, Table, TableState},
+    Frame,
+};
+use std::collections::HashMap;
+use sysinfo::{Pid, Process, System};
+
+// g on the Processes tab: processes added up per name or per application, so
+// a browser or an Electron app shows as "chrome (47 procs): 312% CPU, 8.2 GB"
+// instead of 47 rows. Applications are the systemd unit or scope a process
+// runs in, from its cgroup, which also catches helpers with other names;
+// elsewhere they fall back to the name. The / filter and u user still apply,
+// and k, +/- and E act on every process of the selected group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Grouping {
+    Name,
+    Application,
+}
+
+impl Grouping {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Grouping::Name => "name",
+            Grouping::Application => "application",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Grouping> {
+        [Grouping::Name, Grouping::Application].into_iter().find(|grouping| grouping.name() == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub key: String,
+    pub pids: Vec<Pid>,
+    pub cpu: f32,
+    pub memory: u64,
+    pub threads: usize,
+    // None until every process has a second sample
+    pub disk_rates: Option<(f64, f64)>,
+}
+
+#[derive(Default)]
+pub struct ProcessGroups {
+    pub grouping: Option<Grouping>,
+    selected: usize,
+    // Application of each process, read once per process (start time, application)
+    applications: HashMap<Pid, (u64, String)>,
+}
+
+impl ProcessGroups {
+    // Off, by name, by application, off again
+    pub fn next(&mut self) {
+        self.grouping = match self.grouping {
+            None => Some(Grouping::Name),
+            Some(Grouping::Name) => Some(Grouping::Application),
+            Some(Grouping::Application) => None,
+        };
+        self.selected = 0;
+    }
+
+    // After each process refresh; only processes not seen before are read
+    pub fn update(&mut self, system: &System) {
+        if self.grouping != Some(Grouping::Application) {
+            self.applications.clear();
+            return;
+        }
+        let processes = system.processes();
+        self.applications
+            .retain(|pid, (start_time, _)| processes.get(pid).is_some_and(|process| process.start_time() == *start_time));
+        for (pid, process) in processes {
+            if process.thread_kind().is_none() && !self.applications.contains_key(pid) {
+                self.applications.insert(*pid, (process.start_time(), application(*pid, process)));
+            }
+        }
+    }
+
+    // The table's rows, busiest first, or with the most memory first
+    pub fn groups(
+        &self,
+        processes: &[(Pid, &Process)],
+        by_memory: bool,
+        disk_rates: impl Fn(Pid) -> Option<(f64, f64)>,
+    ) -> Vec<Group> {
+        let Some(grouping) = self.grouping else { return Vec::new() };
+        let mut groups: HashMap<String, Group> = HashMap::new();
+        // Threads share their process's memory, so only processes count
+        for (pid, process) in processes.iter().filter(|(_, process)| process.thread_kind().is_none()) {
+            let key = match grouping {
+                Grouping::Name => process.name().to_string_lossy().into_owned(),
+                Grouping::Application => match self.applications.get(pid) {
+                    Some((_, application)) => application.clone(),
+                    None => process.name().to_string_lossy().into_owned(),
+                },
+            };
+            let group = groups.entry(key.clone()).or_insert_with(|| Group {
+                key,
+                pids: Vec::new(),
+                cpu: 0.0,
+                memory: 0,
+                threads: 0,
+                disk_rates: Some((0.0, 0.0)),
+            });
+            group.pids.push(*pid);
+            group.cpu += process.cpu_usage();
+            group.memory += process.memory();
+            group.threads += thread_count(process).unwrap_or(1);
+            group.disk_rates = group
+                .disk_rates
+                .zip(disk_rates(*pid))
+                .map(|((read, written), (more_read, more_written))| (read + more_read, written + more_written));
+        }
+        let mut groups: Vec<Group> = groups.into_values().collect();
+        if by_memory {
+            groups.sort_by(|a, b| b.memory.cmp(&a.memory).then(a.key.cmp(&b.key)));
+        } else {
+            groups.sort_by(|a, b| b.cpu.total_cmp(&a.cpu).then(b.memory.cmp(&a.memory)).then(a.key.cmp(&b.key)));
+        }
+        groups
+    }
+
+    pub fn selected(&self, count: usize) -> usize {
+        self.selected.min(count.saturating_sub(1))
+    }
+
+    pub fn move_selection(&mut self, delta: isize, count: usize) {
+        self.selected = self.selected(count).saturating_add_signed(delta).min(count.saturating_sub(1));
+    }
+}
+
+// The unit or scope a process runs in, e.g. "nginx.service", or for desktop
+// apps the name systemd gives their scope, "app-gnome-firefox-4521.scope"
+// giving "gnome-firefox". Kernel threads and processes outside any unit keep
+// their name.
+fn application(pid: Pid, process: &Process) -> String {
+    let unit = Cgroup::of(&pid.to_string()).and_then(|cgroup| {
+        cgroup
+            .path
+            .rsplit('/')
+            .find(|part| part.ends_with(".service") || part.ends_with(".scope"))
+            .map(str::to_string)
+    });
+    let Some(unit) = unit else { return process.name().to_string_lossy().into_owned() };
+    match unit.strip_prefix("app-") {
+        // "app-firefox-4521.scope", "app-org.gnome.Terminal@0f3c.service"
+        Some(app) => {
+            let app = app.trim_end_matches(".scope").trim_end_matches(".service");
+            let app = app.split('@').next().unwrap_or(app);
+            match app.rsplit_once('-') {
+                Some((name, instance)) if instance.chars().all(|c| c.is_ascii_hexdigit()) => name.to_string(),
+                _ => app.to_string(),
+            }
+        }
+        None => unit,
+    }
+}
+
+pub fn draw(f: &mut Frame, area: Rect, groups: &ProcessGroups, rows: &[Group], title: String) {
+    let table_rows: Vec<Row> = rows
+        .iter()
+        .map(|group| {
+            let (read, written) = match group.disk_rates {
+                Some((read, written)) => (format!("{}/s", format_bytes(read as u64)), format!("{}/s", format_bytes(written as u64))),
+                None => ("…".to_string(), "…".to_string()),
+            };
+            Row::new(vec![
+                group.key.clone(),
+                group.pids.len().to_string(),
+                format!("{:.1}%", group.cpu),
+                format_bytes(group.memory),
+                group.threads.to_string(),
+                read,
+                written,
+            ])
+        })
+        .collect();
+    let header = Row::new(vec![
+        match groups.grouping {
+            Some(Grouping::Application) => "Application",
+            _ => "Name",
+        },
+        "Procs",
+        "CPU %",
+        "Memory",
+        "Threads",
+        "Read/s",
+        "Write/s",
+    ])
+    .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(6),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(table_rows, widths)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .column_spacing(1);
+    let mut state = TableState::default().with_selected(Some(groups.selected(rows.len())));
+    f.render_stateful_widget(table, area, &mut state);
+}
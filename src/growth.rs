@@ -0,0 +1,61 @@
+use crate::format_bytes;
+use ratatui::style::{Color, Style};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use sysinfo::Disks;
+
+// How fast each filesystem has been growing since the monitor started, for
+// the disk table's growth column. Before a minute has passed, or below a
+// megabyte of change, the rate is noise and not shown.
+const SETTLE: Duration = Duration::from_secs(60);
+const MIN_CHANGE: u64 = 1024 * 1024;
+
+#[derive(Default)]
+pub struct DiskGrowth {
+    // Used bytes when each mount point was first seen
+    first: HashMap<PathBuf, (Instant, u64)>,
+}
+
+impl DiskGrowth {
+    pub fn update(&mut self, disks: &Disks) {
+        let now = Instant::now();
+        for disk in disks.iter() {
+            let used = disk.total_space().saturating_sub(disk.available_space());
+            self.first.entry(disk.mount_point().to_path_buf()).or_insert((now, used));
+        }
+    }
+
+    // Bytes per hour, negative when space was freed
+    pub fn per_hour(&self, mount: &Path, used: u64) -> Option<f64> {
+        let (at, first) = self.first.get(mount)?;
+        let elapsed = at.elapsed();
+        if elapsed < SETTLE {
+            return None;
+        }
+        let change = used as f64 - *first as f64;
+        if change.abs() < MIN_CHANGE as f64 {
+            return Some(0.0);
+        }
+        Some(change / elapsed.as_secs_f64() * 3600.0)
+    }
+}
+
+// "+1.2 GB/h", colored by how soon the filesystem fills up at that rate
+pub fn cell(per_hour: Option<f64>, available: u64) -> (String, Style) {
+    let Some(per_hour) = per_hour else { return ("…".to_string(), Style::default().fg(Color::Gray)) };
+    if per_hour == 0.0 {
+        return ("0".to_string(), Style::default());
+    }
+    let sign = if per_hour > 0.0 { "+" } else { "-" };
+    let text = format!("{}{}/h", sign, format_bytes(per_hour.abs() as u64));
+    let hours_left = available as f64 / per_hour;
+    let style = match hours_left {
+        hours if hours > 0.0 && hours < 24.0 => Style::default().fg(Color::Red),
+        hours if hours > 0.0 && hours < 24.0 * 7.0 => Style::default().fg(Color::Yellow),
+        _ => Style::default(),
+    };
+    (text, style)
+}
@@ -0,0 +1,161 @@
+use std::{
+    error::Error,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+// Optional hardening (--harden): before starting any thread the monitor drops
+// to the capabilities its collectors use, forbids gaining new privileges,
+// blocks system-altering syscalls with seccomp and limits writes to its own
+// config/data directories with Landlock. Meant for running it setuid or with
+// file capabilities to read SMART, other users' processes and so on.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+// Whether apply() has run: no_new_privs rules out the sudo restart, and
+// seccomp the umount fallback of eject
+pub fn active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+pub struct HardenReport {
+    pub kept_caps: usize,
+    pub landlock: &'static str,
+}
+
+impl HardenReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "🛡️ Hardened: {} capabilities kept, seccomp on, landlock {}",
+            self.kept_caps, self.landlock
+        )
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn apply(writable: &[PathBuf], read_only: bool) -> Result<HardenReport, Box<dyn Error>> {
+    // Capabilities, no_new_privs and Landlock only bind the calling thread,
+    // so a thread started earlier would keep all of them
+    let threads = std::fs::read_dir("/proc/self/task")?.count();
+    if threads > 1 {
+        let running = threads - 1;
+        return Err(format!("--harden must come before any thread starts, {running} running").into());
+    }
+
+    let kept_caps = drop_capabilities(read_only)?;
+
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    let landlock = restrict_filesystem(writable)?;
+    install_seccomp_filter()?;
+    ACTIVE.store(true, Ordering::Relaxed);
+
+    Ok(HardenReport { kept_caps, landlock })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn apply(_writable: &[PathBuf], _read_only: bool) -> Result<HardenReport, Box<dyn Error>> {
+    Err("--harden is only supported on Linux".into())
+}
+
+// Keep what collectors and actions need; kill/renice rights go in read-only mode
+#[cfg(target_os = "linux")]
+fn drop_capabilities(read_only: bool) -> Result<usize, Box<dyn Error>> {
+    use caps::{CapSet, Capability, CapsHashSet};
+
+    let mut keep: CapsHashSet = [
+        Capability::CAP_DAC_READ_SEARCH,
+        Capability::CAP_SYS_PTRACE,
+        Capability::CAP_SYS_RAWIO,
+        Capability::CAP_NET_ADMIN,
+    ]
+    .into_iter()
+    .collect();
+    if !read_only {
+        keep.insert(Capability::CAP_KILL);
+        keep.insert(Capability::CAP_SYS_NICE);
+    }
+
+    let permitted = caps::read(None, CapSet::Permitted)?;
+    let kept: CapsHashSet = permitted.intersection(&keep).copied().collect();
+
+    // The bounding set can only shrink with CAP_SETPCAP, so do it first
+    if permitted.contains(&Capability::CAP_SETPCAP) {
+        for cap in caps::all().difference(&keep) {
+            caps::drop(None, CapSet::Bounding, *cap)?;
+        }
+    }
+    caps::set(None, CapSet::Inheritable, &CapsHashSet::new())?;
+    caps::set(None, CapSet::Effective, &kept)?;
+    caps::set(None, CapSet::Permitted, &kept)?;
+    Ok(kept.len())
+}
+
+// Everything stays readable, only our own directories (and the terminal) writable
+#[cfg(target_os = "linux")]
+fn restrict_filesystem(writable: &[PathBuf]) -> Result<&'static str, Box<dyn Error>> {
+    use landlock::{
+        path_beneath_rules, Access, AccessFs, Ruleset, RulesetAttr, RulesetCreatedAttr,
+        RulesetStatus, ABI,
+    };
+
+    let abi = ABI::V2;
+    let status = Ruleset::default()
+        .handle_access(AccessFs::from_all(abi))?
+        .create()?
+        .add_rules(path_beneath_rules(["/"], AccessFs::from_read(abi)))?
+        .add_rules(path_beneath_rules(["/dev/tty", "/dev/null"], AccessFs::from_all(abi)))?
+        .add_rules(path_beneath_rules(writable, AccessFs::from_all(abi)))?
+        .restrict_self()?;
+
+    Ok(match status.ruleset {
+        RulesetStatus::FullyEnforced => "enforced",
+        RulesetStatus::PartiallyEnforced => "partially enforced",
+        RulesetStatus::NotEnforced => "not supported by this kernel",
+    })
+}
+
+// A monitor has no business mounting, loading modules, rebooting or changing identity
+#[cfg(target_os = "linux")]
+fn install_seccomp_filter() -> Result<(), Box<dyn Error>> {
+    use seccompiler::{BpfProgram, SeccompAction, SeccompFilter};
+
+    let denied = [
+        libc::SYS_mount,
+        libc::SYS_umount2,
+        libc::SYS_pivot_root,
+        libc::SYS_chroot,
+        libc::SYS_reboot,
+        libc::SYS_kexec_load,
+        libc::SYS_init_module,
+        libc::SYS_finit_module,
+        libc::SYS_delete_module,
+        libc::SYS_swapon,
+        libc::SYS_swapoff,
+        libc::SYS_acct,
+        libc::SYS_settimeofday,
+        libc::SYS_clock_settime,
+        libc::SYS_adjtimex,
+        libc::SYS_sethostname,
+        libc::SYS_setdomainname,
+        libc::SYS_setuid,
+        libc::SYS_setgid,
+        libc::SYS_setreuid,
+        libc::SYS_setregid,
+        libc::SYS_setresuid,
+        libc::SYS_setresgid,
+        libc::SYS_setgroups,
+        libc::SYS_unshare,
+        libc::SYS_setns,
+    ];
+    let filter = SeccompFilter::new(
+        denied.iter().map(|syscall| (*syscall, vec![])).collect(),
+        SeccompAction::Allow,
+        SeccompAction::Errno(libc::EPERM as u32),
+        std::env::consts::ARCH.try_into()?,
+    )?;
+    let program: BpfProgram = filter.try_into()?;
+    seccompiler::apply_filter_all_threads(&program)?;
+    Ok(())
+}
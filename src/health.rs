@@ -0,0 +1,335 @@
+use crate::{freshness, kubernetes, posture, App};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    net::{TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+// Results kept per check for the history column and availability
+const HISTORY: usize = 60;
+
+// One URL to poll, from [[health_checks]] in the config
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HealthCheckConfig {
+    pub name: String,
+    pub url: String,
+    pub interval_ms: u64,
+    pub timeout_ms: u64,
+    // Required status code; by default any 2xx/3xx counts as healthy
+    pub expect_status: Option<u16>,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        HealthCheckConfig {
+            name: String::new(),
+            url: String::new(),
+            interval_ms: 10_000,
+            timeout_ms: 5_000,
+            expect_status: None,
+        }
+    }
+}
+
+impl HealthCheckConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.url.starts_with("http://") && !self.url.starts_with("https://") {
+            return Err(format!("health check '{}': url must start with http:// or https://", self.title()));
+        }
+        Ok(())
+    }
+
+    fn title(&self) -> &str {
+        if self.name.is_empty() { &self.url } else { &self.name }
+    }
+}
+
+// A host:port to TCP-connect to, from [[port_checks]] in the config
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PortCheckConfig {
+    pub name: String,
+    pub address: String,
+    pub interval_ms: u64,
+    pub timeout_ms: u64,
+}
+
+impl Default for PortCheckConfig {
+    fn default() -> Self {
+        PortCheckConfig {
+            name: String::new(),
+            address: String::new(),
+            interval_ms: 10_000,
+            timeout_ms: 3_000,
+        }
+    }
+}
+
+impl PortCheckConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.address.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(()),
+            _ => Err(format!("port check '{}': address must be host:port", self.address)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    // When the check finished
+    pub at: Instant,
+    pub status: Option<u16>,
+    pub latency: Duration,
+    pub error: Option<String>,
+}
+
+impl CheckResult {
+    pub fn healthy(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+// A running HTTP or port check and its recent results
+pub struct HealthCheck {
+    title: String,
+    history: Arc<Mutex<VecDeque<CheckResult>>>,
+    // A probe is waiting for its answer
+    running: Arc<AtomicBool>,
+}
+
+impl HealthCheck {
+    pub fn http(config: HealthCheckConfig) -> HealthCheck {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .redirects(0)
+            .build();
+        let title = config.title().to_string();
+        HealthCheck::spawn(title, config.interval_ms, move || check_http(&agent, &config))
+    }
+
+    pub fn port(config: PortCheckConfig) -> HealthCheck {
+        let title = if config.name.is_empty() { config.address.clone() } else { config.name.clone() };
+        HealthCheck::spawn(title, config.interval_ms, move || check_port(&config))
+    }
+
+    // Each check polls from its own thread so a slow endpoint can't hold up the UI
+    fn spawn<F>(title: String, interval_ms: u64, mut probe: F) -> HealthCheck
+    where
+        F: FnMut() -> CheckResult + Send + 'static,
+    {
+        let history = Arc::new(Mutex::new(VecDeque::new()));
+        let running = Arc::new(AtomicBool::new(false));
+        let thread_history = Arc::clone(&history);
+        let thread_running = Arc::clone(&running);
+        thread::spawn(move || loop {
+            thread_running.store(true, Ordering::Relaxed);
+            let result = probe();
+            thread_running.store(false, Ordering::Relaxed);
+            {
+                let mut history = thread_history.lock().unwrap();
+                history.push_back(result);
+                if history.len() > HISTORY {
+                    history.pop_front();
+                }
+            }
+            thread::sleep(Duration::from_millis(interval_ms.max(1000)));
+        });
+        HealthCheck { title, history, running }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn last(&self) -> Option<CheckResult> {
+        self.history.lock().unwrap().back().cloned()
+    }
+
+    fn running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    fn history(&self) -> Vec<CheckResult> {
+        self.history.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+fn check_http(agent: &ureq::Agent, config: &HealthCheckConfig) -> CheckResult {
+    let started = Instant::now();
+    let response = agent.get(&config.url).call();
+    let latency = started.elapsed();
+    let status = match response {
+        Ok(response) => response.status(),
+        // 4xx/5xx still come with a status code
+        Err(ureq::Error::Status(code, _)) => code,
+        Err(ureq::Error::Transport(transport)) => {
+            return CheckResult {
+                at: Instant::now(),
+                status: None,
+                latency,
+                error: Some(transport_error(&transport)),
+            };
+        }
+    };
+    let healthy = match config.expect_status {
+        Some(expected) => status == expected,
+        None => (200..400).contains(&status),
+    };
+    let error = (!healthy).then(|| format!("HTTP {}", status));
+    CheckResult { at: Instant::now(), status: Some(status), latency, error }
+}
+
+fn check_port(config: &PortCheckConfig) -> CheckResult {
+    let started = Instant::now();
+    let timeout = Duration::from_millis(config.timeout_ms);
+    let connected = config
+        .address
+        .to_socket_addrs()
+        .and_then(|mut addrs| {
+            let addr = addrs
+                .next()
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no address found"))?;
+            TcpStream::connect_timeout(&addr, timeout)
+        });
+    let latency = started.elapsed();
+    let error = connected
+        .err()
+        .map(|e| e.to_string().split(" (os error").next().unwrap_or_default().to_string());
+    CheckResult { at: Instant::now(), status: None, latency, error }
+}
+
+// ureq's own message repeats the URL; the innermost cause is enough
+pub fn transport_error(transport: &ureq::Transport) -> String {
+    let mut cause = std::error::Error::source(transport);
+    let mut message = None;
+    while let Some(error) = cause {
+        message = Some(error.to_string());
+        cause = error.source();
+    }
+    match message {
+        Some(message) => message.split(" (os error").next().unwrap_or_default().to_string(),
+        None => transport.kind().to_string(),
+    }
+}
+
+// Latency as a bar height, failures as a cross
+fn history_bar(results: &[CheckResult], width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let shown = &results[results.len().saturating_sub(width)..];
+    let slowest = shown.iter().map(|r| r.latency.as_millis()).max().unwrap_or(0).max(1);
+    shown
+        .iter()
+        .map(|r| {
+            if !r.healthy() {
+                '✗'
+            } else {
+                BARS[(r.latency.as_millis() * (BARS.len() as u128 - 1) / slowest) as usize]
+            }
+        })
+        .collect()
+}
+
+pub fn draw_health_tab(f: &mut Frame, area: Rect, app: &App) {
+    // The node's own health first on Kubernetes nodes
+    let area = match &app.kubernetes {
+        Some(node) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(kubernetes::height(node)), Constraint::Min(0)])
+                .split(area);
+            kubernetes::draw(f, chunks[0], node);
+            chunks[1]
+        }
+        None => area,
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(app.posture.height())])
+        .split(area);
+    if app.posture.height() > 0 {
+        posture::draw(f, chunks[1], &app.posture);
+    }
+    let area = chunks[0];
+    if app.port_checks.is_empty() {
+        draw_checks(f, area, &app.health_checks, "🩺 Health Checks", "add [[health_checks]] entries to the config");
+        return;
+    }
+    let http_height = if app.health_checks.is_empty() { 0 } else { app.health_checks.len() as u16 + 3 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(http_height), Constraint::Min(0)])
+        .split(area);
+    draw_checks(f, chunks[0], &app.health_checks, "🩺 Health Checks", "");
+    draw_checks(f, chunks[1], &app.port_checks, "🔌 Port Checks", "");
+}
+
+fn draw_checks(f: &mut Frame, area: Rect, checks: &[HealthCheck], title: &str, hint: &str) {
+    let header = Row::new(vec!["Check", "Status", "Latency", "Up %", "History (oldest → newest)"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let history_width = area.width.saturating_sub(70) as usize;
+
+    let rows: Vec<Row> = checks
+        .iter()
+        .map(|check| {
+            let history = check.history();
+            let (status, latency, color) = match history.last() {
+                None => ("pending".to_string(), "-".to_string(), Color::Gray),
+                Some(last) => {
+                    let status = match (&last.error, last.status) {
+                        (None, Some(code)) => format!("✅ {}", code),
+                        (None, None) => "✅ open".to_string(),
+                        (Some(error), _) => format!("❌ {}", error),
+                    };
+                    let color = if last.healthy() { Color::Green } else { Color::Red };
+                    (status, format!("{} ms", last.latency.as_millis()), color)
+                }
+            };
+            let up = match history.len() {
+                0 => "-".to_string(),
+                n => format!("{:.0}%", history.iter().filter(|r| r.healthy()).count() as f64 / n as f64 * 100.0),
+            };
+            Row::new(vec![
+                Cell::from(check.title().to_string()),
+                Cell::from(status).style(Style::default().fg(color)),
+                Cell::from(latency),
+                Cell::from(up),
+                Cell::from(history_bar(&history, history_width)),
+            ])
+        })
+        .collect();
+
+    let title = if checks.is_empty() && !hint.is_empty() {
+        format!("{} — {}", title, hint)
+    } else {
+        // Age of the newest result, spinning while any probe is out
+        let newest = checks.iter().filter_map(|check| check.last().map(|r| r.at)).max();
+        freshness::title(title, "checked", newest, checks.iter().any(HealthCheck::running))
+    };
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),
+            Constraint::Length(28),
+            Constraint::Length(10),
+            Constraint::Length(6),
+            Constraint::Length(history_width as u16),
+        ],
+    )
+    .header(header)
+    .block(Block::default().title(title).borders(Borders::ALL))
+    .column_spacing(1);
+    f.render_widget(table, area);
+}
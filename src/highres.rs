@@ -0,0 +1,211 @@
+use crate::format_bytes;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Sub-second sampling of CPU and network only, for bursts a 1s refresh
+// averages away. A background thread reads /proc/stat and /proc/net/dev
+// through file handles it keeps open; the results feed the gauge peak
+// markers and a status line. Linux only.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HighResConfig {
+    // Same as --high-res
+    pub enabled: bool,
+    pub interval_ms: u64,
+}
+
+impl Default for HighResConfig {
+    fn default() -> Self {
+        HighResConfig { enabled: false, interval_ms: 200 }
+    }
+}
+
+impl HighResConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(50..=1000).contains(&self.interval_ms) {
+            return Err("high_resolution: interval_ms must be between 50 and 1000".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    pub at: Instant,
+    // Percent busy over the interval, all CPUs
+    pub cpu: f64,
+    // Bytes per second over the interval, all interfaces but loopback
+    pub rx: f64,
+    pub tx: f64,
+}
+
+// Samples kept for the status line and late readers
+const KEEP: Duration = Duration::from_secs(60);
+
+pub struct HighRes {
+    interval: Duration,
+    samples: Arc<Mutex<VecDeque<Sample>>>,
+    error: Arc<Mutex<Option<String>>>,
+    // Newest sample handed out by drain()
+    drained: Option<Instant>,
+}
+
+impl HighRes {
+    pub fn start(config: &HighResConfig) -> HighRes {
+        let high_res = HighRes {
+            interval: Duration::from_millis(config.interval_ms),
+            samples: Arc::new(Mutex::new(VecDeque::new())),
+            error: Arc::new(Mutex::new(None)),
+            drained: None,
+        };
+        if !cfg!(target_os = "linux") {
+            *high_res.error.lock().unwrap() = Some("needs Linux /proc".to_string());
+            return high_res;
+        }
+        let interval = high_res.interval;
+        let samples = Arc::clone(&high_res.samples);
+        let error = Arc::clone(&high_res.error);
+        thread::spawn(move || {
+            if let Err(e) = sample_loop(interval, &samples) {
+                *error.lock().unwrap() = Some(e.to_string().split(" (os error").next().unwrap_or_default().to_string());
+            }
+        });
+        high_res
+    }
+
+    // Samples taken since the previous call
+    pub fn drain(&mut self) -> Vec<Sample> {
+        let samples = self.samples.lock().unwrap();
+        let new: Vec<Sample> = samples
+            .iter()
+            .filter(|s| self.drained.is_none_or(|drained| s.at > drained))
+            .copied()
+            .collect();
+        if let Some(last) = new.last() {
+            self.drained = Some(last.at);
+        }
+        new
+    }
+
+    // Highest CPU, receive and send rate over the window
+    fn peaks(&self, window: Duration) -> Option<(f64, f64, f64)> {
+        let samples = self.samples.lock().unwrap();
+        samples
+            .iter()
+            .filter(|s| s.at.elapsed() <= window)
+            .map(|s| (s.cpu, s.rx, s.tx))
+            .reduce(|a, b| (a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)))
+    }
+
+    // One line for the System Information panel
+    pub fn status(&self, window: Duration) -> String {
+        let every = format!("⚡ {} ms sampling", self.interval.as_millis());
+        if let Some(error) = &*self.error.lock().unwrap() {
+            return format!("{} ❌ {}", every, error);
+        }
+        match self.peaks(window) {
+            Some((cpu, rx, tx)) => format!(
+                "{}, peaks over {}s: CPU {:.0}%, RX {}/s, TX {}/s",
+                every,
+                window.as_secs(),
+                cpu,
+                format_bytes(rx as u64),
+                format_bytes(tx as u64)
+            ),
+            None => format!("{}, starting", every),
+        }
+    }
+}
+
+// Counter readings the samples are the difference of
+struct Totals {
+    at: Instant,
+    // Busy and all jiffies
+    busy: u64,
+    jiffies: u64,
+    rx: u64,
+    tx: u64,
+}
+
+fn sample_loop(interval: Duration, samples: &Mutex<VecDeque<Sample>>) -> std::io::Result<()> {
+    let mut stat = File::open("/proc/stat")?;
+    let mut net = File::open("/proc/net/dev")?;
+    let mut buffer = String::with_capacity(4096);
+    let mut previous: Option<Totals> = None;
+    let mut next = Instant::now();
+    loop {
+        let (busy, jiffies) = read_cpu(&mut stat, &mut buffer)?;
+        let (rx, tx) = read_net(&mut net, &mut buffer)?;
+        let totals = Totals { at: Instant::now(), busy, jiffies, rx, tx };
+        if let Some(previous) = previous {
+            let seconds = totals.at.duration_since(previous.at).as_secs_f64().max(0.001);
+            let ticks = totals.jiffies.saturating_sub(previous.jiffies);
+            let sample = Sample {
+                at: totals.at,
+                cpu: if ticks > 0 { totals.busy.saturating_sub(previous.busy) as f64 / ticks as f64 * 100.0 } else { 0.0 },
+                rx: totals.rx.saturating_sub(previous.rx) as f64 / seconds,
+                tx: totals.tx.saturating_sub(previous.tx) as f64 / seconds,
+            };
+            let mut samples = samples.lock().unwrap();
+            samples.push_back(sample);
+            while samples.front().is_some_and(|s| s.at.elapsed() > KEEP) {
+                samples.pop_front();
+            }
+        }
+        previous = Some(totals);
+
+        // Fixed rate rather than fixed sleep, so slow reads don't stretch the interval
+        next += interval;
+        match next.checked_duration_since(Instant::now()) {
+            Some(wait) => thread::sleep(wait),
+            None => next = Instant::now(),
+        }
+    }
+}
+
+fn reread(file: &mut File, buffer: &mut String) -> std::io::Result<()> {
+    buffer.clear();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(buffer)?;
+    Ok(())
+}
+
+// (busy, total) jiffies from the aggregate "cpu" line
+fn read_cpu(file: &mut File, buffer: &mut String) -> std::io::Result<(u64, u64)> {
+    reread(file, buffer)?;
+    let line = buffer.lines().next().unwrap_or_default();
+    let (mut busy, mut total) = (0, 0);
+    // user nice system idle iowait irq softirq steal; guest time is already in user
+    for (index, field) in line.split_whitespace().skip(1).take(8).enumerate() {
+        let jiffies: u64 = field.parse().unwrap_or(0);
+        total += jiffies;
+        if index != 3 && index != 4 {
+            busy += jiffies;
+        }
+    }
+    Ok((busy, total))
+}
+
+// (received, transmitted) bytes over all interfaces except loopback
+fn read_net(file: &mut File, buffer: &mut String) -> std::io::Result<(u64, u64)> {
+    reread(file, buffer)?;
+    let mut totals = (0, 0);
+    // Two header lines, then "iface: rx_bytes packets ... tx_bytes ..."
+    for line in buffer.lines().skip(2) {
+        let Some((name, counters)) = line.split_once(':') else { continue };
+        if name.trim() == "lo" {
+            continue;
+        }
+        let mut counters = counters.split_whitespace().map(|f| f.parse::<u64>().unwrap_or(0));
+        totals.0 += counters.next().unwrap_or(0);
+        totals.1 += counters.nth(7).unwrap_or(0);
+    }
+    Ok(totals)
+}
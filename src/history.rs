@@ -0,0 +1,96 @@
+use chrono::Utc;
+use std::collections::{BTreeMap, VecDeque};
+
+// Recent values of the headline metrics, one sample per refresh, for charts
+// and the API. Timestamps are Unix seconds.
+const CAPACITY: usize = 3600;
+
+#[derive(Debug, Default)]
+pub struct History {
+    series: BTreeMap<&'static str, Series>,
+}
+
+#[derive(Debug, Default)]
+struct Series {
+    samples: VecDeque<(i64, f64)>,
+    // Samples ever recorded, dropped ones included
+    recorded: u64,
+}
+
+impl Series {
+    fn push(&mut self, sample: (i64, f64)) {
+        self.samples.push_back(sample);
+        self.recorded += 1;
+        if self.samples.len() > CAPACITY {
+            self.samples.pop_front();
+        }
+    }
+}
+
+impl History {
+    pub fn record(&mut self, metric: &'static str, value: f64) {
+        self.series.entry(metric).or_default().push((Utc::now().timestamp(), value));
+    }
+
+    // Copies the samples other recorded since the last catch-up, so a copy
+    // kept on another thread (the API's) costs only what's new each refresh
+    #[cfg(any(feature = "api", test))]
+    pub fn catch_up(&mut self, other: &History) {
+        for (metric, from) in &other.series {
+            let series = self.series.entry(metric).or_default();
+            let new = (from.recorded - series.recorded).min(from.samples.len() as u64) as usize;
+            for sample in from.samples.range(from.samples.len() - new..) {
+                series.push(*sample);
+            }
+            series.recorded = from.recorded;
+        }
+    }
+
+    pub fn metrics(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.series.keys().copied()
+    }
+
+    pub fn series(&self, metric: &str) -> Option<&VecDeque<(i64, f64)>> {
+        self.series.get(metric).map(|series| &series.samples)
+    }
+
+    pub fn latest(&self, metric: &str) -> Option<f64> {
+        self.series.get(metric)?.samples.back().map(|(_, value)| *value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(history: &History, metric: &str) -> Vec<f64> {
+        history.series(metric).map_or_else(Vec::new, |series| series.iter().map(|(_, value)| *value).collect())
+    }
+
+    #[test]
+    fn catch_up_copies_only_new_samples() {
+        let (mut history, mut copy) = (History::default(), History::default());
+        history.record("cpu", 1.0);
+        history.record("cpu", 2.0);
+        copy.catch_up(&history);
+        history.record("cpu", 3.0);
+        history.record("load", 0.5);
+        copy.catch_up(&history);
+        copy.catch_up(&history);
+        assert_eq!(values(&copy, "cpu"), [1.0, 2.0, 3.0]);
+        assert_eq!(values(&copy, "load"), [0.5]);
+    }
+
+    #[test]
+    fn catch_up_keeps_the_capacity() {
+        let (mut history, mut copy) = (History::default(), History::default());
+        history.record("cpu", 0.0);
+        copy.catch_up(&history);
+        for value in 1..=CAPACITY + 10 {
+            history.record("cpu", value as f64);
+        }
+        copy.catch_up(&history);
+        assert_eq!(values(&copy, "cpu"), values(&history, "cpu"));
+        assert_eq!(copy.latest("cpu"), Some((CAPACITY + 10) as f64));
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Size of the home directory and its biggest well-known subdirectories for
+// the Storage panel. Reading them can take a while on a slow or network home,
+// so it happens off the UI thread and is redone every few minutes while the
+// Overview tab is open.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(300);
+
+const COMMON_DIRS: [&str; 6] = ["Downloads", "Documents", "Pictures", "Videos", "Desktop", "Music"];
+
+#[derive(Debug, Clone)]
+pub struct HomeUsage {
+    pub path: PathBuf,
+    // None if the directory couldn't be read
+    pub size: Option<u64>,
+    // Non-empty subdirectories, largest first
+    pub largest: Vec<(&'static str, u64)>,
+}
+
+#[derive(Default)]
+struct ScanState {
+    usage: Option<(HomeUsage, Instant)>,
+    scanning: bool,
+}
+
+#[derive(Default)]
+pub struct HomeScan {
+    state: Arc<Mutex<ScanState>>,
+}
+
+impl HomeScan {
+    // Called on refresh while the Overview tab is open
+    pub fn rescan_if_stale(&self) {
+        let mut state = self.state.lock().unwrap();
+        let fresh = state.usage.as_ref().is_some_and(|(_, at)| at.elapsed() < RESCAN_INTERVAL);
+        if fresh || state.scanning {
+            return;
+        }
+        let Some(home) = dirs::home_dir() else { return };
+        state.scanning = true;
+        let shared = Arc::clone(&self.state);
+        thread::spawn(move || {
+            let usage = scan(home);
+            let mut state = shared.lock().unwrap();
+            state.usage = Some((usage, Instant::now()));
+            state.scanning = false;
+        });
+    }
+
+    // The last result and when it was taken
+    pub fn usage(&self) -> Option<(HomeUsage, Instant)> {
+        self.state.lock().unwrap().usage.clone()
+    }
+
+    pub fn scanning(&self) -> bool {
+        self.state.lock().unwrap().scanning
+    }
+}
+
+fn scan(home: PathBuf) -> HomeUsage {
+    let size = directory_size(&home).ok();
+    let mut largest: Vec<(&'static str, u64)> = COMMON_DIRS
+        .iter()
+        .filter_map(|name| Some((*name, directory_size(&home.join(name)).ok()?)))
+        .filter(|(_, size)| *size > 0)
+        .collect();
+    largest.sort_by_key(|d| std::cmp::Reverse(d.1));
+    HomeUsage { path: home, size, largest }
+}
+
+// Files directly in the directory; subdirectories aren't descended into
+fn directory_size(path: &Path) -> Result<u64, std::io::Error> {
+    if !path.is_dir() {
+        return Ok(0);
+    }
+    let mut total_size = 0u64;
+    for entry in fs::read_dir(path)?.flatten() {
+        if let Ok(metadata) = entry.metadata() {
+            if metadata.is_file() {
+                total_size += metadata.len();
+            }
+        }
+    }
+    Ok(total_size)
+}
@@ -0,0 +1,196 @@
+use crate::{freshness, softirq};
+use crate::App;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table},
+    Frame,
+};
+use std::{collections::HashMap, fs, time::Instant};
+
+// Interrupt rates per source and CPU from the counters in /proc/interrupts,
+// for spotting a NIC whose queues all land on one CPU or an interrupt storm.
+// Counters are read while the tab is open; rates cover the last refresh.
+const STORM_RATE: f64 = 10_000.0;
+
+pub struct IrqSource {
+    // IRQ number or a name like "LOC" or "NMI"
+    pub irq: String,
+    pub description: String,
+    // Interrupts per second on each CPU
+    pub per_cpu: Vec<f64>,
+    pub total: f64,
+}
+
+#[derive(Default)]
+pub struct Interrupts {
+    previous: Option<(Instant, HashMap<String, Vec<u64>>)>,
+    pub cpus: Vec<String>,
+    // Busiest first
+    pub sources: Vec<IrqSource>,
+    pub updated: Option<Instant>,
+}
+
+impl Interrupts {
+    pub fn update(&mut self) {
+        let Ok(contents) = fs::read_to_string("/proc/interrupts") else { return };
+        let now = Instant::now();
+        let mut lines = contents.lines();
+        // Offline CPUs have no column
+        let cpus: Vec<String> = lines.next().unwrap_or_default().split_whitespace().map(str::to_string).collect();
+        let mut counts = HashMap::new();
+        let mut descriptions = HashMap::new();
+        for line in lines {
+            let Some((irq, rest)) = line.split_once(':') else { continue };
+            let mut fields = rest.split_whitespace().peekable();
+            let mut per_cpu = Vec::with_capacity(cpus.len());
+            while per_cpu.len() < cpus.len() {
+                match fields.peek().and_then(|field| field.parse::<u64>().ok()) {
+                    Some(count) => per_cpu.push(count),
+                    None => break,
+                }
+                fields.next();
+            }
+            let irq = irq.trim().to_string();
+            descriptions.insert(irq.clone(), fields.collect::<Vec<_>>().join(" "));
+            counts.insert(irq, per_cpu);
+        }
+
+        if let Some((at, previous)) = &self.previous {
+            let seconds = now.duration_since(*at).as_secs_f64();
+            if seconds > 0.0 && previous.values().next().map(Vec::len) == counts.values().next().map(Vec::len) {
+                self.sources = counts
+                    .iter()
+                    .filter_map(|(irq, now)| {
+                        let before = previous.get(irq)?;
+                        let per_cpu: Vec<f64> = now
+                            .iter()
+                            .zip(before)
+                            .map(|(now, before)| now.saturating_sub(*before) as f64 / seconds)
+                            .collect();
+                        let total = per_cpu.iter().sum();
+                        Some(IrqSource {
+                            irq: irq.clone(),
+                            description: descriptions.remove(irq).unwrap_or_default(),
+                            per_cpu,
+                            total,
+                        })
+                    })
+                    .collect();
+                self.sources.sort_by(|a, b| b.total.total_cmp(&a.total).then_with(|| a.irq.cmp(&b.irq)));
+                self.updated = Some(now);
+            }
+        }
+        self.cpus = cpus;
+        self.previous = Some((now, counts));
+    }
+
+    // Interrupts per second on each CPU, all sources together
+    fn per_cpu(&self) -> Vec<f64> {
+        let mut totals = vec![0.0; self.cpus.len()];
+        for source in &self.sources {
+            for (total, rate) in totals.iter_mut().zip(&source.per_cpu) {
+                *total += rate;
+            }
+        }
+        totals
+    }
+}
+
+// "830" or "12.4k"
+pub fn rate(per_second: f64) -> String {
+    if per_second >= 10_000.0 {
+        format!("{:.1}k", per_second / 1000.0)
+    } else {
+        format!("{:.0}", per_second)
+    }
+}
+
+// Where a source's interrupts go, e.g. "CPU2 97% CPU0 3%"
+fn spread(cpus: &[String], source: &IrqSource) -> String {
+    if source.total <= 0.0 {
+        return String::new();
+    }
+    let mut shares: Vec<(&String, f64)> =
+        cpus.iter().zip(&source.per_cpu).map(|(cpu, rate)| (cpu, rate / source.total * 100.0)).collect();
+    shares.sort_by(|a, b| b.1.total_cmp(&a.1));
+    shares
+        .iter()
+        .take(4)
+        .filter(|(_, share)| *share >= 0.5)
+        .map(|(cpu, share)| format!("{} {:.0}%", cpu, share))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn draw_interrupts_tab(f: &mut Frame, area: Rect, app: &App) {
+    if !app.capabilities.interrupts.available() {
+        let message = Paragraph::new(format!("Interrupt counters: {}", app.capabilities.interrupts.placeholder()))
+            .block(Block::default().title("🔔 Interrupts").borders(Borders::ALL))
+            .style(Style::default().fg(Color::Gray));
+        f.render_widget(message, area);
+        return;
+    }
+    let interrupts = &app.interrupts;
+    // Softirqs below, up to half the height
+    let softirq_height = (app.softirqs.cpus.len() as u16 + 3).min(area.height / 2);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(softirq_height)])
+        .split(area);
+    softirq::draw(f, rows[1], &app.softirqs);
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(40), Constraint::Min(0)])
+        .split(rows[0]);
+    let header_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+
+    // Per-CPU load, to see at a glance whether one CPU takes them all
+    let per_cpu = interrupts.per_cpu();
+    let busiest = per_cpu.iter().copied().fold(0.0, f64::max);
+    let bar_width = chunks[0].width.saturating_sub(22) as f64;
+    let rows: Vec<Row> = interrupts
+        .cpus
+        .iter()
+        .zip(&per_cpu)
+        .map(|(cpu, total)| {
+            let bar = if busiest > 0.0 { "█".repeat((total / busiest * bar_width).round() as usize) } else { String::new() };
+            Row::new(vec![cpu.clone(), rate(*total), bar])
+        })
+        .collect();
+    let table = Table::new(rows, [Constraint::Length(7), Constraint::Length(8), Constraint::Min(0)])
+        .header(Row::new(vec!["CPU", "Per s", ""]).style(header_style))
+        .block(
+            Block::default()
+                .title(freshness::title("🔔 Interrupts per CPU", "", interrupts.updated, false))
+                .borders(Borders::ALL),
+        )
+        .column_spacing(1);
+    f.render_widget(table, chunks[0]);
+
+    // Sources, busiest first; idle ones are left out
+    let rows: Vec<Row> = interrupts
+        .sources
+        .iter()
+        .filter(|source| source.total > 0.0)
+        .map(|source| {
+            let style = if source.total >= STORM_RATE { Style::default().fg(Color::Red) } else { Style::default() };
+            Row::new(vec![
+                source.irq.clone(),
+                rate(source.total),
+                spread(&interrupts.cpus, source),
+                source.description.clone(),
+            ])
+            .style(style)
+        })
+        .collect();
+    let title = if interrupts.updated.is_none() { "🔔 Top sources · measuring…" } else { "🔔 Top sources" };
+    let table = Table::new(
+        rows,
+        [Constraint::Length(6), Constraint::Length(8), Constraint::Length(36), Constraint::Min(10)],
+    )
+    .header(Row::new(vec!["IRQ", "Per s", "CPUs", "Device"]).style(header_style))
+    .block(Block::default().title(title).borders(Borders::ALL))
+    .column_spacing(1);
+    f.render_widget(table, chunks[1]);
+}
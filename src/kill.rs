@@ -0,0 +1,235 @@
+use crate::privilege;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, Process, System};
+
+// Ending the selected process, or all marked ones, from the Processes tab.
+// SIGTERM asks it to exit and lets it clean up, SIGKILL can't be caught or
+// ignored. Each process is looked up again when the signal goes out and must
+// have the same start time, so one that exited while the dialog was open
+// can't be mistaken for a new process that got its PID.
+const RESULT_SHOWN_FOR: Duration = Duration::from_secs(10);
+const COMMAND_WIDTH: usize = 200;
+// Distinct names listed in the dialog for a batch
+const NAMES_LISTED: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Signal {
+    Term,
+    Kill,
+}
+
+impl Signal {
+    fn name(self) -> &'static str {
+        match self {
+            Signal::Term => "SIGTERM",
+            Signal::Kill => "SIGKILL",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Target {
+    pid: Pid,
+    name: String,
+    command: String,
+    start_time: u64,
+    other_user: bool,
+}
+
+impl Target {
+    fn new(pid: Pid, process: &Process) -> Target {
+        // Kernel threads and zombies have no command line
+        let arguments: Vec<String> = process.cmd().iter().map(|arg| arg.to_string_lossy().into_owned()).collect();
+        let command = match process.exe() {
+            Some(exe) if arguments.is_empty() => exe.display().to_string(),
+            _ => arguments.join(" "),
+        };
+        Target {
+            pid,
+            name: process.name().to_string_lossy().into_owned(),
+            command: command.chars().take(COMMAND_WIDTH).collect(),
+            start_time: process.start_time(),
+            other_user: process.user_id().is_some_and(privilege::is_other_user),
+        }
+    }
+}
+
+// Which processes k, +/- and E act on, for the wording of the dialog and result
+#[derive(Debug, Clone, PartialEq)]
+pub enum Batch {
+    Selected,
+    Marked,
+    // Every process of the selected group, by the group's key
+    Group(String),
+}
+
+impl Batch {
+    // "the 35 marked processes", "the 12 processes in the firefox group"
+    pub fn describe(&self, count: usize) -> String {
+        match self {
+            Batch::Selected => format!("the {} processes", count),
+            Batch::Marked => format!("the {} marked processes", count),
+            Batch::Group(key) => format!("the {} processes in the {} group", count, key),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KillRequest {
+    batch: Batch,
+    targets: Vec<Target>,
+}
+
+impl KillRequest {
+    // The selected process, the marked ones or a group's
+    pub fn new(processes: &[(Pid, &Process)], batch: Batch) -> KillRequest {
+        KillRequest { batch, targets: processes.iter().map(|(pid, process)| Target::new(*pid, process)).collect() }
+    }
+
+    // "python3 ×35, bash ×2", most common first
+    fn names(&self) -> String {
+        let mut names: Vec<(&str, usize)> = Vec::new();
+        for target in &self.targets {
+            match names.iter_mut().find(|(name, _)| *name == target.name) {
+                Some((_, count)) => *count += 1,
+                None => names.push((&target.name, 1)),
+            }
+        }
+        names.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        let mut listed: Vec<String> = names
+            .iter()
+            .take(NAMES_LISTED)
+            .map(|(name, count)| if *count > 1 { format!("{} ×{}", name, count) } else { name.to_string() })
+            .collect();
+        if names.len() > NAMES_LISTED {
+            listed.push(format!("{} more", names.len() - NAMES_LISTED));
+        }
+        listed.join(", ")
+    }
+}
+
+// What a k or renice did, shown above the process tables for a few seconds
+pub struct ActionResult {
+    message: String,
+    failed: bool,
+    at: Instant,
+}
+
+impl ActionResult {
+    pub fn new(message: String, failed: bool) -> ActionResult {
+        ActionResult { message, failed, at: Instant::now() }
+    }
+
+    pub fn text(&self) -> Option<(&str, bool)> {
+        (self.at.elapsed() < RESULT_SHOWN_FOR).then_some((self.message.as_str(), self.failed))
+    }
+}
+
+pub fn send(request: &KillRequest, signal: Signal, system: &System) -> ActionResult {
+    let mut failures: Vec<String> = Vec::new();
+    for target in &request.targets {
+        let outcome = match system.process(target.pid) {
+            Some(process) if process.start_time() == target.start_time => signal_process(process, signal),
+            _ => Err("it already exited".to_string()),
+        };
+        if let Err(err) = outcome {
+            failures.push(format!("{} ({}): {}", target.name, target.pid, err));
+        }
+    }
+    let total = request.targets.len();
+    let (message, failed) = match (&request.targets[..], failures.first()) {
+        ([target], None) => (format!("💀 Sent {} to {} ({})", signal.name(), target.name, target.pid), false),
+        ([target], Some(_)) => {
+            let err = failures[0].split_once(": ").map_or("", |(_, err)| err);
+            (format!("❌ {} to {} ({}) failed: {}", signal.name(), target.name, target.pid, err), true)
+        }
+        (_, None) => (format!("💀 Sent {} to {} processes", signal.name(), total), false),
+        (_, Some(first)) => {
+            let more = if failures.len() > 1 { format!(" and {} more", failures.len() - 1) } else { String::new() };
+            let message = format!("❌ {} reached {} of {} processes; {}{}", signal.name(), total - failures.len(), total, first, more);
+            (message, true)
+        }
+    };
+    ActionResult::new(message, failed)
+}
+
+#[cfg(unix)]
+fn signal_process(process: &Process, signal: Signal) -> Result<(), String> {
+    let number = match signal {
+        Signal::Term => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+    };
+    if unsafe { libc::kill(process.pid().as_u32() as libc::pid_t, number) } == 0 {
+        return Ok(());
+    }
+    let err = std::io::Error::last_os_error();
+    Err(match err.raw_os_error() {
+        Some(libc::EPERM) => format!("not permitted, it belongs to another user ({})", privilege::root_hint()),
+        Some(libc::ESRCH) => "it already exited".to_string(),
+        _ => err.to_string(),
+    })
+}
+
+// Windows has no SIGTERM; sysinfo says so by returning None
+#[cfg(not(unix))]
+fn signal_process(process: &Process, signal: Signal) -> Result<(), String> {
+    let signal = match signal {
+        Signal::Term => sysinfo::Signal::Term,
+        Signal::Kill => sysinfo::Signal::Kill,
+    };
+    match process.kill_with(signal) {
+        Some(true) => Ok(()),
+        Some(false) => Err("the process couldn't be ended".to_string()),
+        None => Err("not supported on this platform, use SIGKILL".to_string()),
+    }
+}
+
+// t/Enter sends SIGTERM, k SIGKILL, n/Esc cancels
+pub fn draw_confirm(f: &mut Frame, area: Rect, request: &KillRequest) {
+    let mut lines = match &request.targets[..] {
+        [target] => {
+            let mut lines = vec![Line::from(format!("End {} (PID {})?", target.name, target.pid))];
+            if !target.command.is_empty() {
+                lines.push(Line::styled(format!("  {}", target.command), Style::default().fg(Color::DarkGray)));
+            }
+            lines
+        }
+        targets => vec![
+            Line::from(format!("End {}?", request.batch.describe(targets.len()))),
+            Line::styled(format!("  {}", request.names()), Style::default().fg(Color::DarkGray)),
+        ],
+    };
+    let them = if request.targets.len() == 1 { "it" } else { "them" };
+    lines.push(Line::from(""));
+    lines.push(Line::from(format!("SIGTERM asks {} to exit and lets {} save and clean up first.", them, them)));
+    lines.push(Line::from(format!("SIGKILL ends {} at once; anything unsaved is lost.", them)));
+    let others = request.targets.iter().filter(|target| target.other_user).count();
+    if others > 0 && !privilege::is_root() {
+        let whose = match (request.targets.len(), others) {
+            (1, _) => "It belongs".to_string(),
+            (_, 1) => "1 belongs".to_string(),
+            (_, others) => format!("{} belong", others),
+        };
+        lines.push(Line::styled(
+            format!("{} to another user: signalling {}.", whose, privilege::root_hint()),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::styled("t / Enter SIGTERM · k SIGKILL · n / Esc cancel", Style::default().fg(Color::Yellow)));
+    let dialog = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(if request.targets.len() == 1 { "💀 End process" } else { "💀 End processes" })
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(dialog, area);
+}
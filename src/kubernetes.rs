@@ -0,0 +1,384 @@
+use crate::{
+    alerts::{Alert, Severity},
+    format_bytes, freshness,
+};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    path::Path,
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+// What an operator on a Kubernetes node wants to know first: whether the
+// kubelet and the container runtime are healthy, the node's pressure
+// conditions, and how much of its allocatable CPU, memory and pods the pods
+// scheduled here have requested. Only started on hosts with a kubelet. The
+// kubelet answers on its local healthz port; conditions and requests come
+// from the API server through kubectl, with the kubelet's own credentials
+// when they can be read (root).
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const KUBELET_HEALTHZ: &str = "http://127.0.0.1:10248/healthz";
+const KUBELET_DIR: &str = "/var/lib/kubelet";
+// kubeadm, then k3s
+const KUBECONFIGS: [&str; 2] = ["/etc/kubernetes/kubelet.conf", "/var/lib/rancher/k3s/agent/kubelet.kubeconfig"];
+const RUNTIME_SOCKETS: [(&str, &str); 3] = [
+    ("containerd", "/run/containerd/containerd.sock"),
+    ("containerd", "/run/k3s/containerd/containerd.sock"),
+    ("CRI-O", "/run/crio/crio.sock"),
+];
+
+// [kubernetes] in the config; both are detected when left out
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct KubernetesConfig {
+    // Node name, if it isn't the hostname
+    pub node: Option<String>,
+    pub kubeconfig: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Condition {
+    pub kind: String,
+    pub status: String,
+    pub message: String,
+}
+
+impl Condition {
+    // Ready should be True, the pressure conditions False
+    pub fn bad(&self) -> bool {
+        match self.kind.as_str() {
+            "Ready" => self.status != "True",
+            _ => self.status == "True",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Resource {
+    pub name: &'static str,
+    pub requested: f64,
+    pub allocatable: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeInfo {
+    pub conditions: Vec<Condition>,
+    // cpu in cores, memory in bytes, pods
+    pub resources: Vec<Resource>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeStatus {
+    pub at: Instant,
+    pub kubelet: Result<(), String>,
+    // Runtime name and whether its socket answers
+    pub runtime: (&'static str, Result<(), String>),
+    pub node: Result<NodeInfo, String>,
+}
+
+pub struct Kubernetes {
+    pub node: String,
+    status: Arc<Mutex<Option<NodeStatus>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl Kubernetes {
+    // None when this host isn't a Kubernetes node
+    pub fn start(config: &KubernetesConfig) -> Option<Kubernetes> {
+        if !Path::new(KUBELET_DIR).exists() {
+            return None;
+        }
+        let node = config
+            .node
+            .clone()
+            .or_else(sysinfo::System::host_name)
+            .unwrap_or_default()
+            .to_lowercase();
+        let kubeconfig = config
+            .kubeconfig
+            .clone()
+            .or_else(|| KUBECONFIGS.iter().find(|path| Path::new(path).exists()).map(|path| path.to_string()));
+        let status = Arc::new(Mutex::new(None));
+        let running = Arc::new(AtomicBool::new(false));
+        let thread_status = Arc::clone(&status);
+        let thread_running = Arc::clone(&running);
+        let thread_node = node.clone();
+        thread::spawn(move || {
+            let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(5)).build();
+            loop {
+                thread_running.store(true, Ordering::Relaxed);
+                let status = NodeStatus {
+                    kubelet: kubelet_health(&agent),
+                    runtime: runtime_health(),
+                    node: node_info(&thread_node, kubeconfig.as_deref()),
+                    at: Instant::now(),
+                };
+                *thread_status.lock().unwrap() = Some(status);
+                thread_running.store(false, Ordering::Relaxed);
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+        Some(Kubernetes { node, status, running })
+    }
+
+    pub fn status(&self) -> Option<NodeStatus> {
+        self.status.lock().unwrap().clone()
+    }
+
+    fn running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    // An unhealthy kubelet or runtime and a not ready node are critical,
+    // pressure is a warning; an API server kubectl can't reach raises none
+    pub fn alerts(&self) -> Vec<Alert> {
+        let Some(status) = self.status() else { return Vec::new() };
+        let mut alerts = Vec::new();
+        if let Err(err) = &status.kubelet {
+            alerts.push(Alert {
+                severity: Severity::Critical,
+                source: "k8s:kubelet".to_string(),
+                message: format!("kubelet is unhealthy: {}", err),
+            });
+        }
+        if let (runtime, Err(err)) = &status.runtime {
+            alerts.push(Alert {
+                severity: Severity::Critical,
+                source: "k8s:runtime".to_string(),
+                message: format!("{} is unhealthy: {}", runtime, err),
+            });
+        }
+        if let Ok(node) = &status.node {
+            for condition in node.conditions.iter().filter(|condition| condition.bad()) {
+                let (severity, message) = match condition.kind.as_str() {
+                    "Ready" => (Severity::Critical, format!("node {} is not ready", self.node)),
+                    kind => (Severity::Warning, format!("node {} has {}", self.node, kind)),
+                };
+                alerts.push(Alert { severity, source: format!("k8s:{}", condition.kind), message });
+            }
+        }
+        alerts
+    }
+}
+
+fn kubelet_health(agent: &ureq::Agent) -> Result<(), String> {
+    match agent.get(KUBELET_HEALTHZ).call() {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, _)) => Err(format!("healthz answered HTTP {}", code)),
+        Err(ureq::Error::Transport(transport)) => Err(crate::health::transport_error(&transport)),
+    }
+}
+
+fn runtime_health() -> (&'static str, Result<(), String>) {
+    let Some((name, socket)) = RUNTIME_SOCKETS.iter().find(|(_, socket)| Path::new(socket).exists()) else {
+        return ("container runtime", Err("no containerd or CRI-O socket found".to_string()));
+    };
+    (name, connect(socket))
+}
+
+#[cfg(unix)]
+fn connect(socket: &str) -> Result<(), String> {
+    std::os::unix::net::UnixStream::connect(socket)
+        .map(|_| ())
+        .map_err(|e| e.to_string().split(" (os error").next().unwrap_or_default().to_string())
+}
+
+#[cfg(not(unix))]
+fn connect(_socket: &str) -> Result<(), String> {
+    Err("not supported on this platform".to_string())
+}
+
+// kubectl get ... -o json
+fn kubectl(kubeconfig: Option<&str>, args: &[&str]) -> Result<Value, String> {
+    let mut command = Command::new("kubectl");
+    if let Some(kubeconfig) = kubeconfig {
+        command.arg("--kubeconfig").arg(kubeconfig);
+    }
+    let output = command
+        .args(args)
+        .args(["-o", "json", "--request-timeout=10s"])
+        .output()
+        .map_err(|_| "kubectl is not installed".to_string())?;
+    if !output.status.success() {
+        // The summary comes after client-go's log lines
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let error = stderr.lines().rev().find(|line| !line.trim().is_empty()).map(str::to_string);
+        return Err(error.unwrap_or_else(|| output.status.to_string()));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("kubectl output: {}", e))
+}
+
+fn node_info(node: &str, kubeconfig: Option<&str>) -> Result<NodeInfo, String> {
+    let object = kubectl(kubeconfig, &["get", "node", node])?;
+    let conditions = object["status"]["conditions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|condition| Condition {
+            kind: condition["type"].as_str().unwrap_or_default().to_string(),
+            status: condition["status"].as_str().unwrap_or_default().to_string(),
+            message: condition["message"].as_str().unwrap_or_default().to_string(),
+        })
+        .collect();
+
+    let selector = format!("spec.nodeName={}", node);
+    let pods = kubectl(kubeconfig, &["get", "pods", "--all-namespaces", "--field-selector", &selector])?;
+    // Finished pods don't hold on to their requests
+    let pods: Vec<&Value> = pods["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|pod| !matches!(pod["status"]["phase"].as_str(), Some("Succeeded" | "Failed")))
+        .collect();
+
+    let allocatable = &object["status"]["allocatable"];
+    let resources = ["cpu", "memory"]
+        .into_iter()
+        .map(|name| Resource {
+            name,
+            requested: pods.iter().map(|pod| pod_request(pod, name)).sum(),
+            allocatable: allocatable[name].as_str().and_then(quantity).unwrap_or(0.0),
+        })
+        .chain(std::iter::once(Resource {
+            name: "pods",
+            requested: pods.len() as f64,
+            allocatable: allocatable["pods"].as_str().and_then(quantity).unwrap_or(0.0),
+        }))
+        .collect();
+    Ok(NodeInfo { conditions, resources })
+}
+
+// Like the scheduler: all containers together, or the largest init
+// container if that is more, plus the pod overhead
+fn pod_request(pod: &Value, resource: &str) -> f64 {
+    let request = |container: &Value| container["resources"]["requests"][resource].as_str().and_then(quantity);
+    let spec = &pod["spec"];
+    let containers: f64 = spec["containers"].as_array().into_iter().flatten().filter_map(request).sum();
+    let init = spec["initContainers"].as_array().into_iter().flatten().filter_map(request).fold(0.0, f64::max);
+    let overhead = spec["overhead"][resource].as_str().and_then(quantity).unwrap_or(0.0);
+    containers.max(init) + overhead
+}
+
+// "250m" cores, "512Mi" bytes, "110" pods
+fn quantity(text: &str) -> Option<f64> {
+    const SUFFIXES: [(&str, f64); 14] = [
+        ("Ki", 1024.0),
+        ("Mi", 1024.0 * 1024.0),
+        ("Gi", 1024.0 * 1024.0 * 1024.0),
+        ("Ti", 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("Pi", 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("n", 1e-9),
+        ("u", 1e-6),
+        ("m", 1e-3),
+        ("k", 1e3),
+        ("M", 1e6),
+        ("G", 1e9),
+        ("T", 1e12),
+        ("P", 1e15),
+        ("E", 1e18),
+    ];
+    for (suffix, factor) in SUFFIXES {
+        if let Some(number) = text.strip_suffix(suffix) {
+            return number.parse::<f64>().ok().map(|number| number * factor);
+        }
+    }
+    text.parse().ok()
+}
+
+fn health_span(name: &str, health: &Result<(), String>) -> Span<'static> {
+    match health {
+        Ok(()) => Span::styled(format!("{} ✅ healthy", name), Style::default().fg(Color::Green)),
+        Err(err) => Span::styled(format!("{} ❌ {}", name, err), Style::default().fg(Color::Red)),
+    }
+}
+
+// Lines the panel takes, borders included
+pub fn height(kubernetes: &Kubernetes) -> u16 {
+    match kubernetes.status().map(|status| status.node) {
+        Some(Ok(node)) => 4 + node.resources.len() as u16,
+        _ => 5,
+    }
+}
+
+pub fn draw(f: &mut Frame, area: Rect, kubernetes: &Kubernetes) {
+    let status = kubernetes.status();
+    let title = freshness::title(
+        &format!("🚢 Kubernetes node {}", kubernetes.node),
+        "checked",
+        status.as_ref().map(|status| status.at),
+        kubernetes.running(),
+    );
+    let mut lines = Vec::new();
+    match &status {
+        None => lines.push(Line::from("Asking the kubelet and the API server…")),
+        Some(status) => {
+            lines.push(Line::from(vec![
+                health_span("kubelet", &status.kubelet),
+                Span::raw(" · "),
+                health_span(status.runtime.0, &status.runtime.1),
+            ]));
+            match &status.node {
+                Ok(node) => {
+                    let mut spans = Vec::new();
+                    for condition in &node.conditions {
+                        if !spans.is_empty() {
+                            spans.push(Span::raw(" · "));
+                        }
+                        spans.push(if condition.bad() {
+                            let message = if condition.message.is_empty() { &condition.status } else { &condition.message };
+                            Span::styled(format!("{} ❌ {}", condition.kind, message), Style::default().fg(Color::Red))
+                        } else {
+                            Span::styled(format!("{} ✅", condition.kind), Style::default().fg(Color::Green))
+                        });
+                    }
+                    lines.push(Line::from(spans));
+                    for resource in &node.resources {
+                        lines.push(resource_line(resource));
+                    }
+                }
+                Err(err) => {
+                    lines.push(Line::styled(format!("Node conditions and requests: {}", err), Style::default().fg(Color::Gray)));
+                    lines.push(Line::styled(
+                        "kubectl reads them with the kubelet's credentials (root) or [kubernetes] kubeconfig",
+                        Style::default().fg(Color::Gray),
+                    ));
+                }
+            }
+        }
+    }
+    let panel = Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(panel, area);
+}
+
+// "cpu      2.35 of 3.92 cores requested  60%", "pods     23 of 110 scheduled  21%"
+fn resource_line(resource: &Resource) -> Line<'static> {
+    let (requested, allocatable, what) = match resource.name {
+        "cpu" => (format!("{:.2}", resource.requested), format!("{:.2} cores", resource.allocatable), "requested"),
+        "memory" => (format_bytes(resource.requested as u64), format_bytes(resource.allocatable as u64), "requested"),
+        _ => (format!("{:.0}", resource.requested), format!("{:.0}", resource.allocatable), "scheduled"),
+    };
+    let percent = if resource.allocatable > 0.0 { resource.requested / resource.allocatable * 100.0 } else { 0.0 };
+    // Nothing more can be scheduled here once requests reach allocatable
+    let color = match percent {
+        p if p >= 100.0 => Color::Red,
+        p if p >= 90.0 => Color::Yellow,
+        _ => Color::White,
+    };
+    Line::styled(
+        format!("{:<8} {} of {} {}  {:.0}%", resource.name, requested, allocatable, what, percent),
+        Style::default().fg(color),
+    )
+}
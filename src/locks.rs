@@ -0,0 +1,251 @@
+use crate::freshness::ago;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+use std::{collections::HashMap, fs, time::Instant};
+
+// File locks from /proc/locks: flock(), POSIX (fcntl) and open file
+// description locks and leases, with the process holding each and the
+// processes blocked waiting for it. A cron job or package manager that hangs
+// for no visible reason is often waiting for a lock another process never
+// released; those contended locks are listed first, in red, and the Overview
+// names the longest wait. Read on every refresh, it is a short file. Paths are
+// found through the holder's open files, which needs root for other users.
+const LOCKS: &str = "/proc/locks";
+
+#[derive(Debug, Clone)]
+pub struct Lock {
+    // FLOCK, POSIX, OFDLCK, LEASE or DELEG
+    pub kind: String,
+    pub mandatory: bool,
+    // READ or WRITE (leases: BREAKING, ...)
+    pub access: String,
+    // None for OFD locks, which belong to an open file rather than a process
+    pub pid: Option<u32>,
+    pub name: String,
+    // "08:02", the kernel's hex major:minor, and the inode
+    pub device: String,
+    pub inode: u64,
+    pub path: Option<String>,
+    // Byte range, "EOF" for the rest of the file
+    pub start: String,
+    pub end: String,
+    pub waiters: Vec<Waiter>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Waiter {
+    pub pid: u32,
+    pub name: String,
+    pub access: String,
+    // When it was first seen waiting
+    pub since: Instant,
+}
+
+#[derive(Default)]
+pub struct FileLocks {
+    pub locks: Vec<Lock>,
+    pub error: Option<String>,
+    pub selected: usize,
+    // First sighting of each waiter, by waiting pid, device and inode
+    waiting_since: HashMap<(u32, String, u64), Instant>,
+    // Resolved paths, kept while the lock is held
+    paths: HashMap<(u32, String, u64), Option<String>>,
+}
+
+impl FileLocks {
+    pub fn update(&mut self) {
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+        let text = match fs::read_to_string(LOCKS) {
+            Ok(text) => text,
+            Err(err) => {
+                self.locks.clear();
+                self.error = Some(format!("{}: {}", LOCKS, err));
+                return;
+            }
+        };
+        self.error = None;
+        let now = Instant::now();
+        let mut locks: Vec<Lock> = Vec::new();
+        // A waiter follows the lock it waits for, with the same number
+        let mut numbers: Vec<String> = Vec::new();
+        for line in text.lines() {
+            let Some((number, rest)) = line.split_once(':') else { continue };
+            let fields: Vec<&str> = rest.split_whitespace().collect();
+            let (waiting, fields) = match fields.first() {
+                Some(&"->") => (true, &fields[1..]),
+                _ => (false, &fields[..]),
+            };
+            let Some(parsed) = parse(fields) else { continue };
+            if waiting {
+                let Some(lock) = numbers.iter().rposition(|held| held == number).map(|index| &mut locks[index]) else { continue };
+                let Some(pid) = parsed.pid else { continue };
+                let key = (pid, lock.device.clone(), lock.inode);
+                let since = *self.waiting_since.entry(key).or_insert(now);
+                lock.waiters.push(Waiter { pid, name: process_name(pid), access: parsed.access, since });
+            } else {
+                numbers.push(number.trim().to_string());
+                locks.push(parsed);
+            }
+        }
+        self.waiting_since.retain(|(pid, device, inode), _| {
+            locks.iter().any(|lock| lock.device == *device && lock.inode == *inode && lock.waiters.iter().any(|waiter| waiter.pid == *pid))
+        });
+        self.paths.retain(|(pid, device, inode), _| locks.iter().any(|lock| lock.pid == Some(*pid) && lock.device == *device && lock.inode == *inode));
+        for lock in &mut locks {
+            if let Some(pid) = lock.pid {
+                lock.name = process_name(pid);
+                let key = (pid, lock.device.clone(), lock.inode);
+                lock.path = self.paths.entry(key).or_insert_with(|| find_path(pid, &lock.device, lock.inode)).clone();
+            }
+        }
+        // Contended first, longest waits on top
+        locks.sort_by_key(|lock| lock.waiters.iter().map(|waiter| waiter.since).min().unwrap_or(now));
+        self.locks = locks;
+        self.selected = self.selected.min(self.locks.len().saturating_sub(1));
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.selected = self.selected.saturating_add_signed(delta).min(self.locks.len().saturating_sub(1));
+    }
+
+    // One line for the System Information panel while a process waits for a lock
+    pub fn status(&self) -> Option<String> {
+        let contended: Vec<&Lock> = self.locks.iter().filter(|lock| !lock.waiters.is_empty()).collect();
+        let lock = contended.first()?;
+        let waiter = lock.waiters.iter().min_by_key(|waiter| waiter.since)?;
+        let waiting: usize = contended.iter().map(|lock| lock.waiters.len()).sum();
+        let mut status = format!(
+            "🔒 Lock wait: {} ({}) started waiting {} for {}, held by {}",
+            waiter.name,
+            waiter.pid,
+            ago(waiter.since),
+            lock.file(),
+            lock.holder()
+        );
+        if waiting > 1 {
+            status.push_str(&format!(", {} processes waiting in all", waiting));
+        }
+        Some(status)
+    }
+}
+
+impl Lock {
+    fn holder(&self) -> String {
+        match self.pid {
+            Some(pid) => format!("{} ({})", self.name, pid),
+            None => "an open file".to_string(),
+        }
+    }
+
+    fn file(&self) -> String {
+        self.path.clone().unwrap_or_else(|| format!("inode {} on {}", self.inode, self.device))
+    }
+
+    fn range(&self) -> String {
+        match (self.start.as_str(), self.end.as_str()) {
+            ("0", "EOF") => "whole file".to_string(),
+            (start, "EOF") => format!("{}–end", start),
+            (start, end) => format!("{}–{}", start, end),
+        }
+    }
+}
+
+// "POSIX  ADVISORY  WRITE 1234 08:02:1315 0 EOF"; leases and some kernels
+// leave out the range
+fn parse(fields: &[&str]) -> Option<Lock> {
+    let [kind, mode, access, pid, file, ..] = fields else { return None };
+    let (device, inode) = file.rsplit_once(':')?;
+    Some(Lock {
+        kind: kind.to_string(),
+        mandatory: *mode == "MANDATORY",
+        access: access.to_string(),
+        pid: pid.parse::<u32>().ok().filter(|pid| *pid > 0),
+        name: String::new(),
+        device: device.to_string(),
+        inode: inode.parse().ok()?,
+        path: None,
+        start: fields.get(5).unwrap_or(&"0").to_string(),
+        end: fields.get(6).unwrap_or(&"EOF").to_string(),
+        waiters: Vec::new(),
+    })
+}
+
+// A flock() lock survives its process when a child inherited the file, so
+// the holder may be gone
+fn process_name(pid: u32) -> String {
+    fs::read_to_string(format!("/proc/{}/comm", pid)).map(|comm| comm.trim().to_string()).unwrap_or_else(|_| "exited".to_string())
+}
+
+// The holder's open file on that device with that inode
+#[cfg(target_os = "linux")]
+fn find_path(pid: u32, device: &str, inode: u64) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+    let entries = fs::read_dir(format!("/proc/{}/fd", pid)).ok()?;
+    entries.flatten().find_map(|entry| {
+        let metadata = fs::metadata(entry.path()).ok()?;
+        let dev = metadata.dev();
+        let matches = metadata.ino() == inode && format!("{:02x}:{:02x}", libc::major(dev), libc::minor(dev)) == device;
+        matches.then(|| fs::read_link(entry.path()).ok()).flatten().map(|path| path.display().to_string())
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn find_path(_pid: u32, _device: &str, _inode: u64) -> Option<String> {
+    None
+}
+
+pub fn draw_locks_tab(f: &mut Frame, area: Rect, locks: &FileLocks) {
+    let block = Block::default().borders(Borders::ALL);
+    if let Some(error) = &locks.error {
+        let hint = Paragraph::new(error.as_str()).wrap(Wrap { trim: true }).block(block.title("🔒 File Locks"));
+        f.render_widget(hint, area);
+        return;
+    }
+    let mut rows: Vec<Row> = Vec::new();
+    // Row of each lock, for the selection
+    let mut lock_rows: Vec<usize> = Vec::new();
+    for lock in &locks.locks {
+        lock_rows.push(rows.len());
+        let kind = if lock.mandatory { format!("{} mandatory", lock.kind) } else { lock.kind.clone() };
+        let row = Row::new(vec![kind, lock.access.clone(), lock.holder(), lock.file(), lock.range(), String::new()]);
+        rows.push(if lock.waiters.is_empty() { row } else { row.style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)) });
+        for waiter in &lock.waiters {
+            let row = Row::new(vec![
+                "  ⏳ waiting".to_string(),
+                waiter.access.clone(),
+                format!("{} ({})", waiter.name, waiter.pid),
+                String::new(),
+                String::new(),
+                ago(waiter.since),
+            ]);
+            rows.push(row.style(Style::default().fg(Color::Red)));
+        }
+    }
+    let header = Row::new(vec!["Type", "Access", "Process", "File", "Range", "Started waiting"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let contended = locks.locks.iter().filter(|lock| !lock.waiters.is_empty()).count();
+    let title = format!("🔒 File Locks ({}, {} contended) — contended first · ↑/↓ scroll", locks.locks.len(), contended);
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Length(7),
+            Constraint::Length(24),
+            Constraint::Min(20),
+            Constraint::Length(14),
+            Constraint::Length(15),
+        ],
+    )
+    .header(header)
+    .block(block.title(title))
+    .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+    .column_spacing(1);
+    let mut state = TableState::default().with_selected(lock_rows.get(locks.selected).copied());
+    f.render_stateful_widget(table, area, &mut state);
+}
@@ -0,0 +1,377 @@
+use crate::{format_uptime, freshness, ssh, App};
+use chrono::{Local, TimeZone};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Who was on this box and when: past logins from /var/log/wtmp (and wtmp.1,
+// the part logrotate moved aside last), paired into sessions the way `last`
+// does it. A login ends at the logout on its terminal or at the next shutdown
+// or boot. Beside them, each user's most recent login from /var/log/lastlog.
+// Both are read off the UI thread, again every 30 seconds while the tab is
+// open. Distributions that moved to wtmpdb and lastlog2 keep these in SQLite
+// instead and have neither file.
+const WTMP_FILES: [&str; 2] = ["/var/log/wtmp.1", "/var/log/wtmp"];
+const LASTLOG: &str = "/var/log/lastlog";
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+// Newest first; weeks of logins on a busy box
+const MAX_LOGINS: usize = 500;
+
+// glibc's struct utmp and struct lastlog as written to disk, which keep
+// 32-bit times so they are the same on 32- and 64-bit systems
+const UTMP_SIZE: usize = 384;
+const LASTLOG_SIZE: usize = 292;
+const RUN_LVL: i16 = 1;
+const BOOT_TIME: i16 = 2;
+const USER_PROCESS: i16 = 7;
+const DEAD_PROCESS: i16 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum End {
+    StillLoggedIn,
+    Logout(i64),
+    // Shutdown or boot while still logged in
+    Down(i64),
+    // No logout recorded and the session's process is gone
+    Gone,
+    // The row is a boot, not a login
+    Boot,
+}
+
+#[derive(Debug, Clone)]
+pub struct Login {
+    pub user: String,
+    pub tty: String,
+    // Remote host or address, empty for a local console
+    pub origin: String,
+    // Unix seconds
+    pub login: i64,
+    pub end: End,
+}
+
+#[derive(Debug, Clone)]
+pub struct LastLogin {
+    pub user: String,
+    pub origin: String,
+    pub at: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Records {
+    pub logins: Vec<Login>,
+    // None without /var/log/lastlog
+    pub last: Option<Vec<LastLogin>>,
+}
+
+#[derive(Default)]
+struct Loaded {
+    records: Option<(Result<Records, String>, Instant)>,
+    loading: bool,
+}
+
+#[derive(Default)]
+pub struct Logins {
+    pub selected: usize,
+    loaded: Arc<Mutex<Loaded>>,
+}
+
+impl Logins {
+    // Called on refresh while the tab is open
+    pub fn reload_if_stale(&self) {
+        let mut loaded = self.loaded.lock().unwrap();
+        let fresh = loaded.records.as_ref().is_some_and(|(_, at)| at.elapsed() < RELOAD_INTERVAL);
+        if fresh || loaded.loading {
+            return;
+        }
+        loaded.loading = true;
+        let shared = Arc::clone(&self.loaded);
+        thread::spawn(move || {
+            let records = read();
+            let mut loaded = shared.lock().unwrap();
+            loaded.records = Some((records, Instant::now()));
+            loaded.loading = false;
+        });
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let count = match self.records() {
+            Some((Ok(records), _)) => records.logins.len(),
+            _ => 0,
+        };
+        self.selected = self.selected.saturating_add_signed(delta).min(count.saturating_sub(1));
+    }
+
+    fn records(&self) -> Option<(Result<Records, String>, Instant)> {
+        self.loaded.lock().unwrap().records.clone()
+    }
+
+    fn loading(&self) -> bool {
+        self.loaded.lock().unwrap().loading
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read() -> Result<Records, String> {
+    let mut wtmp = Vec::new();
+    let mut found = false;
+    for path in WTMP_FILES {
+        match std::fs::read(path) {
+            Ok(bytes) => {
+                found = true;
+                wtmp.extend(bytes.chunks_exact(UTMP_SIZE).map(Utmp::parse));
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(format!("{}: {}", path, err)),
+        }
+    }
+    if !found {
+        return Err("No /var/log/wtmp. Systems using wtmpdb keep login history in SQLite; see `wtmpdb last`.".to_string());
+    }
+    Ok(Records { logins: sessions(wtmp), last: last_logins() })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read() -> Result<Records, String> {
+    Err("Login history is read from /var/log/wtmp, which only Linux has in this format.".to_string())
+}
+
+struct Utmp {
+    kind: i16,
+    pid: i32,
+    line: String,
+    user: String,
+    host: String,
+    time: i64,
+}
+
+impl Utmp {
+    // ut_type, ut_pid, ut_line[32], ut_id[4], ut_user[32], ut_host[256],
+    // ut_exit, ut_session, then ut_tv's seconds
+    fn parse(record: &[u8]) -> Utmp {
+        Utmp {
+            kind: i16::from_ne_bytes([record[0], record[1]]),
+            pid: i32::from_ne_bytes(record[4..8].try_into().unwrap()),
+            line: text(&record[8..40]),
+            user: text(&record[44..76]),
+            host: text(&record[76..332]),
+            // Unsigned, so it lasts past 2038
+            time: u32::from_ne_bytes(record[340..344].try_into().unwrap()) as i64,
+        }
+    }
+}
+
+// NUL-padded, not always NUL-terminated
+fn text(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+// Oldest first in, newest first out
+fn sessions(records: Vec<Utmp>) -> Vec<Login> {
+    let mut logins: Vec<Login> = Vec::new();
+    // Index of the open login on each terminal, with its process
+    let mut open: Vec<(String, usize, i32)> = Vec::new();
+    for record in records {
+        match record.kind {
+            USER_PROCESS => {
+                if let Some(position) = open.iter().position(|(line, _, _)| *line == record.line) {
+                    let (_, index, _) = open.remove(position);
+                    logins[index].end = End::Gone;
+                }
+                open.push((record.line.clone(), logins.len(), record.pid));
+                logins.push(Login {
+                    user: record.user,
+                    tty: record.line,
+                    origin: record.host,
+                    login: record.time,
+                    end: End::StillLoggedIn,
+                });
+            }
+            DEAD_PROCESS => {
+                if let Some(position) = open.iter().position(|(line, _, _)| *line == record.line) {
+                    let (_, index, _) = open.remove(position);
+                    logins[index].end = End::Logout(record.time);
+                }
+            }
+            BOOT_TIME | RUN_LVL if record.kind == BOOT_TIME || record.user == "shutdown" => {
+                for (_, index, _) in open.drain(..) {
+                    logins[index].end = End::Down(record.time);
+                }
+                if record.kind == BOOT_TIME {
+                    // ut_host holds the kernel release
+                    logins.push(Login {
+                        user: "reboot".to_string(),
+                        tty: "system boot".to_string(),
+                        origin: record.host,
+                        login: record.time,
+                        end: End::Boot,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    // Still open: logged in if the session's process is still there
+    for (_, index, pid) in open {
+        if !std::path::Path::new(&format!("/proc/{}", pid)).exists() {
+            logins[index].end = End::Gone;
+        }
+    }
+    logins.reverse();
+    logins.truncate(MAX_LOGINS);
+    logins
+}
+
+// lastlog has one record per UID at UID × 292 bytes, and is sparse, so the
+// users come from /etc/passwd: ll_time (32-bit), ll_line[32], then ll_host
+#[cfg(target_os = "linux")]
+fn last_logins() -> Option<Vec<LastLogin>> {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut file = std::fs::File::open(LASTLOG).ok()?;
+    let passwd = std::fs::read_to_string("/etc/passwd").unwrap_or_default();
+    let mut last = Vec::new();
+    for entry in passwd.lines() {
+        let fields: Vec<&str> = entry.split(':').collect();
+        let Some(uid) = fields.get(2).and_then(|uid| uid.parse::<u64>().ok()) else { continue };
+        let mut record = [0u8; LASTLOG_SIZE];
+        if file.seek(SeekFrom::Start(uid * LASTLOG_SIZE as u64)).is_err() || file.read_exact(&mut record).is_err() {
+            continue;
+        }
+        let at = u32::from_ne_bytes(record[0..4].try_into().unwrap()) as i64;
+        if at > 0 {
+            last.push(LastLogin {
+                user: fields[0].to_string(),
+                origin: text(&record[36..292]),
+                at,
+            });
+        }
+    }
+    last.sort_by_key(|last| std::cmp::Reverse(last.at));
+    Some(last)
+}
+
+fn local_time(time: i64, format: &str) -> String {
+    Local.timestamp_opt(time, 0).single().map(|time| time.format(format).to_string()).unwrap_or_default()
+}
+
+// "Mon 14 Oct 09:12"; the time alone when it's the same day as `since`
+fn when(time: i64, since: Option<i64>) -> String {
+    if since.is_some_and(|since| local_time(since, "%F") == local_time(time, "%F")) {
+        local_time(time, "%H:%M")
+    } else {
+        local_time(time, "%a %d %b %H:%M")
+    }
+}
+
+fn origin(origin: &str) -> String {
+    if origin.is_empty() {
+        "local".to_string()
+    } else {
+        origin.to_string()
+    }
+}
+
+pub fn draw_logins_tab(f: &mut Frame, area: Rect, app: &App) {
+    let sessions = app.ssh.sessions.len() as u16;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length((sessions + 3).clamp(4, 12)), Constraint::Min(0)])
+        .split(area);
+    ssh::draw(f, chunks[0], &app.ssh);
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(60), Constraint::Length(52)])
+        .split(chunks[1]);
+
+    let logins = &app.logins;
+    let block = Block::default().borders(Borders::ALL);
+    let (records, read_at) = match logins.records() {
+        Some((Ok(records), at)) => (records, at),
+        Some((Err(err), at)) => {
+            let title = freshness::title("🔐 Login History", "read", Some(at), logins.loading());
+            f.render_widget(Paragraph::new(err).wrap(Wrap { trim: true }).block(block.title(title)), chunks[0]);
+            return;
+        }
+        None => {
+            let title = freshness::title("🔐 Login History", "read", None, logins.loading());
+            f.render_widget(Paragraph::new("Reading /var/log/wtmp…").block(block.title(title)), chunks[0]);
+            return;
+        }
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let rows: Vec<Row> = records
+        .logins
+        .iter()
+        .map(|login| {
+            let (until, seconds) = match login.end {
+                End::StillLoggedIn => ("still logged in".to_string(), Some(now - login.login)),
+                End::Logout(at) => (when(at, Some(login.login)), Some(at - login.login)),
+                End::Down(at) => (format!("down {}", when(at, Some(login.login))), Some(at - login.login)),
+                End::Gone => ("gone, no logout".to_string(), None),
+                End::Boot => (String::new(), None),
+            };
+            let row = Row::new(vec![
+                login.user.clone(),
+                login.tty.clone(),
+                origin(&login.origin),
+                when(login.login, None),
+                until,
+                seconds.map(|seconds| format_uptime(seconds.max(0) as u64)).unwrap_or_default(),
+            ]);
+            match login.end {
+                End::StillLoggedIn => row.style(Style::default().fg(Color::Green)),
+                End::Boot => row.style(Style::default().fg(Color::DarkGray)),
+                _ => row,
+            }
+        })
+        .collect();
+    let header = Row::new(vec!["User", "TTY", "From", "Login", "Until", "Duration"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let title = format!("🔐 Login History ({}) · ↑/↓ scroll", records.logins.len());
+    let title = freshness::title(&title, "read", Some(read_at), logins.loading());
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Min(18),
+            Constraint::Length(16),
+            Constraint::Length(22),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header)
+    .block(Block::default().title(title).borders(Borders::ALL))
+    .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+    .column_spacing(1);
+    let mut state = TableState::default().with_selected(Some(logins.selected));
+    f.render_stateful_widget(table, chunks[0], &mut state);
+
+    let block = Block::default().title("🕘 Last Login per User").borders(Borders::ALL);
+    let Some(last) = records.last else {
+        let hint = Paragraph::new("No /var/log/lastlog. Systems using lastlog2 keep it in SQLite; see `lastlog2`.")
+            .wrap(Wrap { trim: true })
+            .block(block);
+        f.render_widget(hint, chunks[1]);
+        return;
+    };
+    let rows: Vec<Row> = last
+        .iter()
+        .map(|last| Row::new(vec![last.user.clone(), when(last.at, None), origin(&last.origin)]))
+        .collect();
+    let header = Row::new(vec!["User", "Last login", "From"]).style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let table = Table::new(rows, [Constraint::Length(12), Constraint::Length(16), Constraint::Min(10)])
+        .header(header)
+        .block(block)
+        .column_spacing(1);
+    f.render_widget(table, chunks[1]);
+}
@@ -0,0 +1,213 @@
+use crate::format_bytes;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::{
+    fs,
+    path::Path,
+    process::Command,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Space taken by logs: everything under /var/log, and the systemd journal on
+// its own (persistent in /var/log/journal, volatile in /run/log/journal).
+// Walked off the UI thread every minute while the Overview is open. Growth is
+// measured from the first walk, like the disk table's growth column, and set
+// against the growth of the filesystem /var/log is on, so runaway logging
+// shows up as the reason a disk is filling.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+const SETTLE: Duration = Duration::from_secs(60);
+const MIN_CHANGE: u64 = 1024 * 1024;
+
+pub const LOG_DIR: &str = "/var/log";
+const JOURNAL_DIRS: [&str; 2] = ["/var/log/journal", "/run/log/journal"];
+
+#[derive(Debug, Clone, Copy)]
+pub struct LogUsage {
+    // All of /var/log, the persistent journal included
+    pub total: u64,
+    pub journal: u64,
+}
+
+#[derive(Default)]
+struct ScanState {
+    usage: Option<(LogUsage, Instant)>,
+    first: Option<(LogUsage, Instant)>,
+    scanning: bool,
+}
+
+#[derive(Default)]
+pub struct LogScan {
+    state: Arc<Mutex<ScanState>>,
+}
+
+impl LogScan {
+    // Called on refresh while the Overview tab is open
+    pub fn rescan_if_stale(&self) {
+        let mut state = self.state.lock().unwrap();
+        let fresh = state.usage.as_ref().is_some_and(|(_, at)| at.elapsed() < RESCAN_INTERVAL);
+        if fresh || state.scanning {
+            return;
+        }
+        state.scanning = true;
+        let shared = Arc::clone(&self.state);
+        thread::spawn(move || {
+            let usage = scan();
+            let now = Instant::now();
+            let mut state = shared.lock().unwrap();
+            state.first.get_or_insert((usage, now));
+            state.usage = Some((usage, now));
+            state.scanning = false;
+        });
+    }
+
+    pub fn usage(&self) -> Option<LogUsage> {
+        self.state.lock().unwrap().usage.map(|(usage, _)| usage)
+    }
+
+    // Bytes per hour /var/log grew since the first walk, negative when logs
+    // were rotated away or vacuumed; None until a minute has passed
+    pub fn per_hour(&self) -> Option<f64> {
+        let state = self.state.lock().unwrap();
+        let ((first, first_at), (last, last_at)) = (state.first?, state.usage?);
+        let elapsed = last_at.duration_since(first_at);
+        if elapsed < SETTLE {
+            return None;
+        }
+        let change = last.total as f64 - first.total as f64;
+        if change.abs() < MIN_CHANGE as f64 {
+            return Some(0.0);
+        }
+        Some(change / elapsed.as_secs_f64() * 3600.0)
+    }
+
+    // "📜 Logs: 1.2 GB in /var/log · journal 800 MB · +12 MB/h, 40% of / growth",
+    // given the filesystem /var/log is on and how fast it grows
+    pub fn status(&self, disk: Option<(&str, Option<f64>)>) -> Option<String> {
+        let usage = self.usage()?;
+        let mut status = format!("📜 Logs: {} in {}", format_bytes(usage.total), LOG_DIR);
+        if usage.journal > 0 {
+            status.push_str(&format!(" · journal {}", format_bytes(usage.journal)));
+        }
+        match self.per_hour() {
+            None => {}
+            Some(0.0) => status.push_str(" · not growing"),
+            Some(per_hour) => {
+                let sign = if per_hour > 0.0 { "+" } else { "-" };
+                status.push_str(&format!(" · {}{}/h", sign, format_bytes(per_hour.abs() as u64)));
+                if let Some((mount, Some(disk_per_hour))) = disk {
+                    if per_hour > 0.0 && disk_per_hour > 0.0 {
+                        // Other files may shrink while logs grow
+                        let share = (per_hour / disk_per_hour * 100.0).min(100.0);
+                        status.push_str(&format!(", {:.0}% of {} growth", share, mount));
+                    }
+                }
+            }
+        }
+        Some(status)
+    }
+}
+
+fn scan() -> LogUsage {
+    LogUsage { total: tree_size(Path::new(LOG_DIR)), journal: journal_size() }
+}
+
+fn journal_size() -> u64 {
+    JOURNAL_DIRS.iter().map(|dir| tree_size(Path::new(dir))).sum()
+}
+
+// Apparent size of every file below path; symlinks aren't followed
+fn tree_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => tree_size(&entry.path()),
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+// journalctl --vacuum-size in the background. The active journal files are
+// rotated first so they can be vacuumed too; deleting needs root.
+pub struct Vacuum {
+    size: String,
+    result: Arc<Mutex<Option<Result<String, String>>>>,
+}
+
+impl Vacuum {
+    pub fn start(size: String) -> Vacuum {
+        let result = Arc::new(Mutex::new(None));
+        let thread_result = Arc::clone(&result);
+        let target = size.clone();
+        thread::spawn(move || {
+            let outcome = vacuum(&target);
+            *thread_result.lock().unwrap() = Some(outcome);
+        });
+        Vacuum { size, result }
+    }
+
+    // One line for the Storage panel, and its color
+    pub fn status(&self) -> (String, Color) {
+        match &*self.result.lock().unwrap() {
+            None => (format!("🧹 Vacuuming the journal to {}…", self.size), Color::Yellow),
+            Some(Ok(message)) => (format!("🧹 {}", message), Color::Green),
+            Some(Err(err)) => (format!("❌ Journal vacuum failed: {}", err), Color::Red),
+        }
+    }
+}
+
+fn vacuum(size: &str) -> Result<String, String> {
+    let before = journal_size();
+    // Fails without a running journald; vacuuming archived files still works
+    let _ = Command::new("journalctl").arg("--rotate").output();
+    let output = Command::new("journalctl")
+        .arg(format!("--vacuum-size={}", size))
+        .output()
+        .map_err(|e| format!("journalctl: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let error = stderr.lines().rev().find(|line| !line.trim().is_empty()).map(str::to_string);
+        return Err(error.unwrap_or_else(|| output.status.to_string()));
+    }
+    let after = journal_size();
+    Ok(format!(
+        "Journal vacuumed to {}: {} → {}, {} freed",
+        size,
+        format_bytes(before),
+        format_bytes(after),
+        format_bytes(before.saturating_sub(after))
+    ))
+}
+
+// y/Enter confirms, n/Esc cancels
+pub fn draw_confirm(f: &mut Frame, area: Rect, size: &str, usage: Option<LogUsage>) {
+    let current = match usage {
+        Some(usage) => format!("The journal takes {} now.", format_bytes(usage.journal)),
+        None => "The journal size hasn't been measured yet.".to_string(),
+    };
+    let lines = vec![
+        Line::from(format!("Vacuum the systemd journal down to {}?", size)),
+        Line::from(""),
+        Line::from(current),
+        Line::from("The oldest journal files are deleted until it fits; their logs are gone for good."),
+        Line::from("Set the size with journal_vacuum_size in config.toml."),
+        Line::from(""),
+        Line::styled("y / Enter vacuum · n / Esc cancel", Style::default().fg(Color::Yellow)),
+    ];
+    let dialog = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title("🧹 Journal vacuum")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(dialog, area);
+}
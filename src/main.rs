@@ -1,118 +1,2445 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEvent, MouseEventKind},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{enable_raw_mode, EnterAlternateScreen},
 };
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Flex, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{
-        Block, Borders, Cell, Gauge, List, ListItem, Paragraph, Row, Table, Tabs,
+        Block, Borders, Cell, List, ListItem, Paragraph, Row, Table, TableState, Tabs,
     },
     Frame, Terminal,
 };
-use sysinfo::{System, ProcessesToUpdate, Disks, Components, Networks};
+use sysinfo::{System, ProcessRefreshKind, ProcessesToUpdate, Disk, Disks, Components, Networks, Pid, Process, Uid, UpdateKind};
 use std::{
+    collections::HashMap,
     error::Error,
-    io,
+    io::{self, Write},
     time::{Duration, Instant},
     fs,
-    path::Path,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
 };
-use chrono::Local;
-use dirs;
+use clap::Parser;
+
+mod accounting;
+mod affinity;
+mod alerts;
+mod anomaly;
+#[cfg(feature = "api")]
+mod api;
+mod archive;
+mod blockdev;
+mod blocked;
+mod budget;
+mod capabilities;
+mod cgroup;
+mod channels;
+mod churn;
+mod cli;
+mod clipboard;
+mod cloud;
+mod columns;
+mod config;
+mod dbus;
+mod details;
+mod diskstats;
+#[cfg(feature = "ebpf")]
+mod ebpf;
+mod eject;
+mod email;
+mod events;
+mod exectrace;
+mod fds;
+mod freshness;
+mod gauges;
+mod groups;
+mod growth;
+mod harden;
+mod health;
+mod highres;
+mod homedir;
+mod history;
+mod interrupts;
+mod kill;
+mod kubernetes;
+mod locks;
+mod logins;
+mod logs;
+mod marks;
+mod modules;
+mod mqtt;
+mod netns;
+mod order;
+mod outdated;
+mod pagecache;
+mod pinned;
+mod posture;
+mod power;
+mod privilege;
+mod query;
+mod reload;
+mod renice;
+mod report;
+mod responsiveness;
+mod rowcache;
+mod rules;
+mod sampler;
+mod schema;
+mod selfmetrics;
+mod score;
+mod settings;
+mod setup;
+mod shutdown;
+mod silence;
+mod sinks;
+mod slices;
+mod softirq;
+mod sockets;
+mod ssh;
+mod steal;
+mod strace;
+mod theme;
+mod threads;
+mod tmpfs;
+mod units;
+mod users;
+mod virt;
+mod watched;
+mod windows;
+mod workspace;
+mod wsl;
+mod zombies;
+
+use accounting::Accounting;
+use affinity::AffinityEditor;
+use alerts::{Alert, Severity};
+use anomaly::AnomalyDetector;
+use archive::Archive;
+use blockdev::Topology;
+use blocked::Blocked;
+use budget::Budgets;
+use capabilities::{Capabilities, Support};
+use channels::Notifier;
+use churn::{Churn, Exited};
+use cli::{Cli, Command};
+use cloud::Cloud;
+use columns::{Column, ColumnChooser, ColumnLayout, ColumnSetting, TableKind};
+use config::{Config, Thresholds};
+use dbus::DbusMonitor;
+use details::ProcessDetails;
+use diskstats::DiskLatency;
+use eject::{Eject, EjectRequest};
+use events::EventLog;
+use exectrace::ExecTracer;
+use fds::FdList;
+use gauges::Gauges;
+use groups::{Group, Grouping, ProcessGroups};
+use growth::DiskGrowth;
+use health::HealthCheck;
+use highres::HighRes;
+use homedir::HomeScan;
+use history::History;
+use interrupts::Interrupts;
+use kill::{ActionResult, Batch, KillRequest, Signal};
+use marks::Marks;
+use kubernetes::Kubernetes;
+use locks::FileLocks;
+use logins::Logins;
+use logs::{LogScan, Vacuum};
+use modules::KernelModules;
+use netns::{InterfaceStat, NamespaceNetworks};
+use order::{ProcessOrders, SortKey};
+use outdated::Outdated;
+use pagecache::PageCache;
+use pinned::PinnedMetric;
+use posture::Posture;
+use power::{Power, PowerAction};
+use query::Query;
+use reload::{ConfigWatcher, Notice};
+use report::{ReportFormat, Reporter};
+use responsiveness::Responsiveness;
+use rowcache::{CacheKey, RowCache};
+use rules::AlertRule;
+use sampler::StackSampler;
+use selfmetrics::SelfMetrics;
+use settings::SettingsEditor;
+use setup::SetupWizard;
+use score::{FailedUnits, HealthScore};
+use silence::Silences;
+use sinks::{MetricSink, SinkKind};
+use slices::Slices;
+use softirq::Softirqs;
+use sockets::SocketStats;
+use ssh::SshSessions;
+use steal::Steal;
+use strace::StraceView;
+use threads::ThreadList;
+use tmpfs::Tmpfs;
+use users::{UserNames, UserPicker};
+use virt::Environment;
+use watched::Watched;
+use windows::Windows;
+use workspace::Workspace;
+use wsl::Wsl;
+use zombies::Zombies;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tab {
+    Overview,
+    Processes,
+    Sockets,
+    Health,
+    Alerts,
+    Accounting,
+    Logins,
+    Interrupts,
+    Devices,
+    Modules,
+    Locks,
+    Dbus,
+    Windows,
+    #[cfg(feature = "ebpf")]
+    Ebpf,
+}
+
+impl Tab {
+    // Every tab this build has, in display order
+    fn all() -> Vec<Tab> {
+        vec![
+            Tab::Overview,
+            Tab::Processes,
+            Tab::Sockets,
+            Tab::Health,
+            Tab::Alerts,
+            Tab::Accounting,
+            Tab::Logins,
+            Tab::Interrupts,
+            Tab::Devices,
+            Tab::Modules,
+            Tab::Locks,
+            Tab::Dbus,
+            Tab::Windows,
+            #[cfg(feature = "ebpf")]
+            Tab::Ebpf,
+        ]
+    }
+
+    fn from_name(name: &str) -> Option<Tab> {
+        match name {
+            "overview" => Some(Tab::Overview),
+            "processes" => Some(Tab::Processes),
+            "sockets" => Some(Tab::Sockets),
+            "health" => Some(Tab::Health),
+            "alerts" => Some(Tab::Alerts),
+            "accounting" => Some(Tab::Accounting),
+            "logins" => Some(Tab::Logins),
+            "interrupts" => Some(Tab::Interrupts),
+            "devices" => Some(Tab::Devices),
+            "modules" => Some(Tab::Modules),
+            "locks" => Some(Tab::Locks),
+            "dbus" => Some(Tab::Dbus),
+            "windows" => Some(Tab::Windows),
+            #[cfg(feature = "ebpf")]
+            "ebpf" => Some(Tab::Ebpf),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Tab::Overview => "overview",
+            Tab::Processes => "processes",
+            Tab::Sockets => "sockets",
+            Tab::Health => "health",
+            Tab::Alerts => "alerts",
+            Tab::Accounting => "accounting",
+            Tab::Logins => "logins",
+            Tab::Interrupts => "interrupts",
+            Tab::Devices => "devices",
+            Tab::Modules => "modules",
+            Tab::Locks => "locks",
+            Tab::Dbus => "dbus",
+            Tab::Windows => "windows",
+            #[cfg(feature = "ebpf")]
+            Tab::Ebpf => "ebpf",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Tab::Overview => "📊 Overview",
+            Tab::Processes => "💾 Processes",
+            Tab::Sockets => "🔌 Sockets",
+            Tab::Health => "🩺 Health",
+            Tab::Alerts => "🚨 Alerts",
+            Tab::Accounting => "📒 Accounting",
+            Tab::Logins => "🔐 Logins",
+            Tab::Interrupts => "🔔 Interrupts",
+            Tab::Devices => "🧱 Devices",
+            Tab::Modules => "🧩 Modules",
+            Tab::Locks => "🔒 Locks",
+            Tab::Dbus => "🚌 D-Bus",
+            Tab::Windows => "🪟 Windows",
+            #[cfg(feature = "ebpf")]
+            Tab::Ebpf => "🐝 eBPF",
+        }
+    }
+
+    fn collectors(&self) -> &'static [Collector] {
+        match self {
+            Tab::Overview => &[Collector::Disks, Collector::Components, Collector::Networks],
+            Tab::Processes => &[Collector::Processes],
+            Tab::Sockets => &[Collector::Sockets],
+            // Health checks run from startup so failures alert on any tab
+            Tab::Health => &[],
+            Tab::Alerts => &[],
+            // Reads the archive, which records from any tab
+            Tab::Accounting => &[],
+            // Reads wtmp and lastlog; SSH sessions update on every tab
+            Tab::Logins => &[],
+            // Read on every refresh while open
+            Tab::Interrupts => &[],
+            // Filesystem usage and I/O latency per layer
+            Tab::Devices => &[Collector::Disks],
+            // Read on every refresh while open
+            Tab::Modules => &[],
+            // Read on every refresh for the Overview line
+            Tab::Locks => &[],
+            Tab::Dbus => &[Collector::Dbus],
+            // Listed every 2 seconds while open; the processes for their usage
+            Tab::Windows => &[Collector::Processes],
+            #[cfg(feature = "ebpf")]
+            Tab::Ebpf => &[Collector::Processes, Collector::Ebpf],
+        }
+    }
+}
+
+// Data sources that are only set up the first time a tab or pinned metric needs them
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Collector {
+    Processes,
+    Disks,
+    Components,
+    Networks,
+    // TCP/UDP socket tables; the protocol counters are always read
+    Sockets,
+    // dbus-monitor on the session and system buses
+    Dbus,
+    #[cfg(feature = "ebpf")]
+    Ebpf,
+}
+
+impl Collector {
+    fn name(&self) -> &'static str {
+        match self {
+            Collector::Processes => "processes",
+            Collector::Disks => "disks",
+            Collector::Components => "sensors",
+            Collector::Networks => "networks",
+            Collector::Sockets => "sockets",
+            Collector::Dbus => "d-bus",
+            #[cfg(feature = "ebpf")]
+            Collector::Ebpf => "ebpf",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Collector> {
+        match name {
+            "processes" => Some(Collector::Processes),
+            "disks" => Some(Collector::Disks),
+            "sensors" => Some(Collector::Components),
+            "networks" => Some(Collector::Networks),
+            "sockets" => Some(Collector::Sockets),
+            "d-bus" => Some(Collector::Dbus),
+            #[cfg(feature = "ebpf")]
+            "ebpf" => Some(Collector::Ebpf),
+            _ => None,
+        }
+    }
+}
+
+// What the processes collector refreshes: the spare System and copies of
+// what's worked out from it, so App keeps showing the current ones meanwhile;
+// groups, marks and watches hold the user's choices, so they stay behind
+type ProcessSources = (System, UserNames, HashMap<Pid, ProcessIo>, HashMap<Pid, usize>, Churn);
+
+// A collector's part of App, moved to the collector's thread for a refresh
+// and back once it's done; App holds empty ones meanwhile
+enum Sources {
+    Processes(Box<ProcessSources>),
+    Disks(Box<(Disks, DiskLatency, DiskGrowth)>),
+    Components(Components),
+    Networks(Networks, Option<NamespaceNetworks>),
+    // With the /proc/<pid>/net to read
+    Sockets(Box<SocketStats>, String),
+    Dbus(Option<DbusMonitor>),
+    #[cfg(feature = "ebpf")]
+    Ebpf,
+}
+
+impl Sources {
+    fn refresh(&mut self) {
+        match self {
+            Sources::Processes(sources) => {
+                let (system, user_names, process_io, fd_counts, churn) = &mut **sources;
+                system.refresh_processes_specifics(ProcessesToUpdate::All, true, process_refresh_kind());
+                user_names.update(system);
+                update_process_io(process_io, system);
+                fds::update_counts(fd_counts, system);
+                churn.update(system);
+            }
+            Sources::Disks(sources) => {
+                let (disks, disk_latency, disk_growth) = &mut **sources;
+                disks.refresh(true);
+                disk_latency.update();
+                disk_growth.update(disks);
+            }
+            Sources::Components(components) => components.refresh(true),
+            Sources::Networks(networks, netns) => {
+                networks.refresh(true);
+                if let Some(netns) = netns {
+                    netns.refresh();
+                }
+            }
+            Sources::Sockets(sockets, proc_net) => sockets.refresh_sockets(proc_net),
+            Sources::Dbus(dbus) => {
+                if let Some(dbus) = dbus {
+                    dbus.update();
+                }
+            }
+            // bpftrace reports on its own schedule
+            #[cfg(feature = "ebpf")]
+            Sources::Ebpf => {}
+        }
+    }
+}
+
+// What a collector's thread sends back: its sources, or its panic, and how long it took
+type Finished = (Collector, thread::Result<Sources>, Duration);
+
+// Processes tab layout: one full-height table of every process, or a fixed
+// top-CPU/top-memory split
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ProcessView {
+    Split,
+    Single,
+}
+// Rows in each table of the split view
+const SPLIT_ROWS: usize = 15;
+// Characters [ and ] scroll command lines by
+const COMMAND_STEP: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ProcessSort {
+    Cpu,
+    Memory,
+    Threads,
+    Fds,
+    DiskRead,
+    DiskWrite,
+    Started,
+    CpuTime,
+    Pid,
+    Name,
+}
+
+impl ProcessSort {
+    const ALL: [ProcessSort; 10] = [
+        ProcessSort::Cpu,
+        ProcessSort::Memory,
+        ProcessSort::Threads,
+        ProcessSort::Fds,
+        ProcessSort::DiskRead,
+        ProcessSort::DiskWrite,
+        ProcessSort::Started,
+        ProcessSort::CpuTime,
+        ProcessSort::Pid,
+        ProcessSort::Name,
+    ];
+
+    fn from_name(name: &str) -> Option<ProcessSort> {
+        ProcessSort::ALL.into_iter().find(|s| s.name() == name)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ProcessSort::Cpu => "cpu",
+            ProcessSort::Memory => "memory",
+            ProcessSort::Threads => "threads",
+            ProcessSort::Fds => "fds",
+            ProcessSort::DiskRead => "disk_read",
+            ProcessSort::DiskWrite => "disk_write",
+            ProcessSort::Started => "started",
+            ProcessSort::CpuTime => "cpu_time",
+            ProcessSort::Pid => "pid",
+            ProcessSort::Name => "name",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            ProcessSort::Cpu => "CPU",
+            ProcessSort::Memory => "memory",
+            ProcessSort::Threads => "threads",
+            ProcessSort::Fds => "open files",
+            ProcessSort::DiskRead => "disk reads",
+            ProcessSort::DiskWrite => "disk writes",
+            ProcessSort::Started => "run time",
+            ProcessSort::CpuTime => "CPU time",
+            ProcessSort::Pid => "PID",
+            ProcessSort::Name => "name",
+        }
+    }
+
+    fn next(&self) -> ProcessSort {
+        let index = ProcessSort::ALL.iter().position(|s| s == self).unwrap_or(0);
+        ProcessSort::ALL[(index + 1) % ProcessSort::ALL.len()]
+    }
+
+    fn column(&self) -> Column {
+        match self {
+            ProcessSort::Cpu => Column::Cpu,
+            ProcessSort::Memory => Column::Memory,
+            ProcessSort::Threads => Column::Threads,
+            ProcessSort::Fds => Column::Fds,
+            ProcessSort::DiskRead => Column::DiskRead,
+            ProcessSort::DiskWrite => Column::DiskWrite,
+            ProcessSort::Started => Column::Started,
+            ProcessSort::CpuTime => Column::CpuTime,
+            ProcessSort::Pid => Column::Pid,
+            ProcessSort::Name => Column::Name,
+        }
+    }
+
+    fn from_column(column: Column) -> Option<ProcessSort> {
+        ProcessSort::ALL.into_iter().find(|s| s.column() == column)
+    }
+
+    // Before reversing: biggest consumers first, PIDs and names ascending
+    fn descending(&self) -> bool {
+        !matches!(self, ProcessSort::Pid | ProcessSort::Name)
+    }
+}
+
+// A process's disk counters at its last refresh and the rates since the one
+// before, None until it has been seen twice
+#[derive(Clone)]
+struct ProcessIo {
+    read: u64,
+    written: u64,
+    at: Instant,
+    rates: Option<(f64, f64)>,
+}
+
+// Why the main loop ended
+enum Exit {
+    Quit,
+    // Restart through sudo/pkexec to get root-only data
+    Elevate,
+}
 
 struct App {
+    // What this platform and user can collect, probed at start-up
+    capabilities: Capabilities,
+    // CPU and memory, refreshed on the main thread every refresh
     system: System,
+    // The processes collector's two Systems: the one everything reads, and
+    // the one it refreshes next. They take turns, so while the collector runs
+    // the last refresh's processes stay in place, however long it takes.
+    processes: System,
+    spare_processes: System,
     disks: Disks,
     components: Components,
     networks: Networks,
+    // Network namespace shown in the network panel instead of our own
+    netns: Option<NamespaceNetworks>,
+    sockets: SocketStats,
+    alerts: Vec<Alert>,
+    anomalies: AnomalyDetector,
+    silences: Silences,
+    // Row index into the Alerts tab table
+    selected_alert: usize,
+    health_score: Option<HealthScore>,
+    failed_units: FailedUnits,
+    show_score_breakdown: bool,
+    // F12
+    show_self_metrics: bool,
+    self_metrics: SelfMetrics,
+    budgets: Budgets,
+    health_checks: Vec<HealthCheck>,
+    // Only on Kubernetes nodes
+    kubernetes: Option<Kubernetes>,
+    // With [cloud] enabled
+    cloud: Option<Cloud>,
+    port_checks: Vec<HealthCheck>,
+    history: History,
+    gauges: Gauges,
+    // Sub-second CPU/network sampler, with --high-res
+    high_res: Option<HighRes>,
+    // Minute summaries on disk and the scheduled report, with [report]
+    archive: Option<Archive>,
+    reporter: Option<Reporter>,
+    accounting: Accounting,
+    sinks: Vec<MetricSink>,
+    events: EventLog,
+    notifier: Notifier,
+    #[cfg(feature = "api")]
+    api: Option<api::ApiServer>,
     last_update: Instant,
+    sample_secs: f64,
     tab_index: usize,
+    pinned: Vec<PinnedMetric>,
+    rules: Vec<AlertRule>,
+    config: Config,
+    profile_name: String,
+    tabs: Vec<Tab>,
+    refresh_interval: Duration,
+    thresholds: Thresholds,
+    columns: ColumnLayout,
+    column_chooser: Option<ColumnChooser>,
+    // First-run setup overlay, open until the config file is written
+    setup: Option<SetupWizard>,
+    settings: Option<SettingsEditor>,
+    config_watcher: ConfigWatcher,
+    // Outcome of the last config reload, for the status bar
+    config_notice: Option<Notice>,
+    // Workspace and anomaly baseline are saved every few minutes, not
+    // only on exit
+    state_saved: Instant,
+    process_view: ProcessView,
+    process_sort: ProcessSort,
+    // The full table in the opposite of the sort's usual order
+    process_sort_reversed: bool,
+    // w on the Processes tab: command lines in the Name column, scrolled
+    // sideways by command_offset characters with [ and ]
+    show_commands: bool,
+    command_offset: usize,
+    // g on the Processes tab: processes added up by name or application
+    process_groups: ProcessGroups,
+    collectors: Vec<Collector>,
+    // When each collector last refreshed, for the data age in panel titles
+    collected: Vec<(Collector, Instant)>,
+    // Collectors still running past their budget, their sources with them
+    in_flight: Vec<Collector>,
+    // Collectors' threads report here
+    finished: (mpsc::Sender<Finished>, mpsc::Receiver<Finished>),
+    // Late collectors that came back since the last refresh, and how long they took
+    late: Vec<(Collector, Duration)>,
+    home_scan: HomeScan,
+    log_scan: LogScan,
+    slices: Slices,
+    interrupts: Interrupts,
+    softirqs: Softirqs,
+    responsiveness: Responsiveness,
+    // Only inside WSL
+    wsl: Option<Wsl>,
+    // Bare metal, VM or container; None off Linux
+    environment: Option<Environment>,
+    page_cache: PageCache,
+    steal: Steal,
+    tmpfs: Tmpfs,
+    ssh: SshSessions,
+    logins: Logins,
+    posture: Posture,
+    modules: KernelModules,
+    locks: FileLocks,
+    dbus: Option<DbusMonitor>,
+    disk_latency: DiskLatency,
+    disk_growth: DiskGrowth,
+    blocked: Blocked,
+    zombies: Zombies,
+    topology: Topology,
+    selected_removable: usize,
+    // Waiting for y/n before ejecting
+    eject_confirm: Option<EjectRequest>,
+    eject: Option<Eject>,
+    // Waiting for y/n before vacuuming the journal
+    vacuum_confirm: bool,
+    vacuum: Option<Vacuum>,
+    // Waiting for a choice before rebooting or powering off
+    power_confirm: bool,
+    power: Power,
+    // Processes running deleted binaries or libraries
+    outdated: Outdated,
+    // Destructive actions (kill, renice, service restarts) are refused
+    read_only: bool,
+    // Summary of the --harden sandbox when active
+    hardened: Option<String>,
+    #[cfg(feature = "ebpf")]
+    ebpf: Option<ebpf::EbpfTracer>,
+    // Row index into the process table, or into top CPU then top memory
+    selected_process: usize,
+    // First row and number of rows the full table showed when last drawn, so
+    // it scrolls instead of jumping and PageUp/PageDown move a screenful
+    process_scroll: std::cell::Cell<(usize, usize)>,
+    // Header cells of the process tables when last drawn, for mouse clicks
+    process_headers: std::cell::RefCell<Vec<(Rect, Column)>>,
+    // Formatted cells of the process tables, kept until the next refresh
+    process_rows: std::cell::RefCell<RowCache>,
+    // The process table's order per sort, updated once per refresh
+    process_orders: std::cell::RefCell<ProcessOrders>,
+    // Set with / on the Processes tab, kept until Esc
+    process_filter: String,
+    filter_editing: bool,
+    // Chosen with u on the Processes tab, kept until Esc
+    process_user: Option<Uid>,
+    user_picker: Option<UserPicker>,
+    user_names: UserNames,
+    // Per-process disk read and write rates for the Read/s and Write/s columns
+    process_io: HashMap<Pid, ProcessIo>,
+    // Waiting for a signal choice before ending the selected process
+    kill_confirm: Option<KillRequest>,
+    // Tagged with Space for k, +/- and E
+    marks: Marks,
+    // New and recently exited processes
+    churn: Churn,
+    // Shown above the process tables, see watched.rs
+    watched: Watched,
+    windows: Windows,
+    // t on the Processes tab
+    thread_list: Option<ThreadList>,
+    // o on the Processes tab
+    fd_list: Option<FdList>,
+    // F on the Processes tab: (PID, start time, name) the selection stays on
+    followed: Option<(Pid, u64, String)>,
+    // For the FDs column
+    fd_counts: HashMap<Pid, usize>,
+    action_result: Option<ActionResult>,
+    // Enter on the Processes tab
+    process_details: Option<ProcessDetails>,
+    // A on the Processes tab
+    affinity: Option<AffinityEditor>,
+    stack_sampler: Option<StackSampler>,
+    // Processes seen starting and exiting, with --trace-exec
+    exec_tracer: Option<ExecTracer>,
+    strace: Option<StraceView>,
 }
 
 impl App {
-    fn new() -> App {
-        App {
-            system: System::new_all(),
-            disks: Disks::new_with_refreshed_list(),
-            components: Components::new_with_refreshed_list(),
-            networks: Networks::new_with_refreshed_list(),
+    fn new(config: Config, pinned: Vec<PinnedMetric>, rules: Vec<AlertRule>) -> App {
+        let mut system = System::new();
+        system.refresh_cpu_all();
+        system.refresh_memory();
+        let capabilities = Capabilities::detect(config.allow_privileged);
+
+        let mut app = App {
+            system,
+            processes: System::new(),
+            spare_processes: System::new(),
+            disks: Disks::new(),
+            components: Components::new(),
+            networks: Networks::new(),
+            netns: None,
+            sockets: SocketStats::default(),
+            alerts: Vec::new(),
+            anomalies: AnomalyDetector::new(config.anomaly.clone()),
+            silences: Silences::new(config.maintenance_windows.clone()),
+            selected_alert: 0,
+            health_score: None,
+            failed_units: if config.health_score.enabled && capabilities.systemd.available() {
+                FailedUnits::start()
+            } else {
+                FailedUnits::default()
+            },
+            show_score_breakdown: false,
+            show_self_metrics: false,
+            self_metrics: SelfMetrics::default(),
+            budgets: Budgets::new(&config.collector_budget),
+            health_checks: config.health_checks.iter().cloned().map(HealthCheck::http).collect(),
+            port_checks: config.port_checks.iter().cloned().map(HealthCheck::port).collect(),
+            kubernetes: Kubernetes::start(&config.kubernetes),
+            cloud: config.cloud.enabled.then(Cloud::start),
+            history: History::default(),
+            gauges: Gauges::default(),
+            high_res: None,
+            archive: config.archive_enabled().then(|| Archive::new(config.archive.keep_days)),
+            reporter: config.report.clone().and_then(Reporter::new),
+            accounting: Accounting::default(),
+            sinks: config
+                .statsd
+                .iter()
+                .map(|c| MetricSink::new(SinkKind::Statsd, c.clone()))
+                .chain(config.graphite.iter().map(|c| MetricSink::new(SinkKind::Graphite, c.clone())))
+                .collect(),
+            events: EventLog::new(config.event_log.clone()),
+            notifier: Notifier::new(config.channels(), config.alert_routes.clone()),
+            #[cfg(feature = "api")]
+            api: None,
             last_update: Instant::now(),
+            sample_secs: 1.0,
             tab_index: 0,
+            pinned,
+            rules,
+            profile_name: config.initial_profile(),
+            columns: ColumnLayout::from_specs(&config.columns),
+            column_chooser: None,
+            setup: None,
+            settings: None,
+            config_watcher: ConfigWatcher::new(),
+            config_notice: None,
+            state_saved: Instant::now(),
+            process_view: ProcessView::Single,
+            process_sort: ProcessSort::Cpu,
+            process_sort_reversed: false,
+            show_commands: false,
+            process_groups: ProcessGroups::default(),
+            command_offset: 0,
+            config,
+            tabs: Vec::new(),
+            refresh_interval: Duration::from_secs(1),
+            thresholds: Thresholds::default(),
+            collectors: Vec::new(),
+            collected: Vec::new(),
+            in_flight: Vec::new(),
+            finished: mpsc::channel(),
+            late: Vec::new(),
+            home_scan: HomeScan::default(),
+            log_scan: LogScan::default(),
+            slices: Slices::default(),
+            interrupts: Interrupts::default(),
+            softirqs: Softirqs::default(),
+            responsiveness: Responsiveness::start(),
+            wsl: Wsl::detect(),
+            environment: Environment::detect(),
+            page_cache: PageCache::default(),
+            steal: Steal::default(),
+            tmpfs: Tmpfs::default(),
+            ssh: SshSessions::default(),
+            logins: Logins::default(),
+            posture: Posture::default(),
+            modules: KernelModules::default(),
+            locks: FileLocks::default(),
+            dbus: None,
+            disk_latency: DiskLatency::default(),
+            disk_growth: DiskGrowth::default(),
+            blocked: Blocked::default(),
+            zombies: Zombies::default(),
+            topology: Topology::default(),
+            selected_removable: 0,
+            eject_confirm: None,
+            eject: None,
+            vacuum_confirm: false,
+            vacuum: None,
+            power_confirm: false,
+            power: Power::default(),
+            outdated: Outdated::default(),
+            read_only: false,
+            hardened: None,
+            #[cfg(feature = "ebpf")]
+            ebpf: None,
+            selected_process: 0,
+            process_scroll: std::cell::Cell::new((0, 0)),
+            process_headers: std::cell::RefCell::new(Vec::new()),
+            process_rows: std::cell::RefCell::new(RowCache::default()),
+            process_orders: std::cell::RefCell::new(ProcessOrders::new()),
+            process_filter: String::new(),
+            process_user: None,
+            user_picker: None,
+            user_names: UserNames::default(),
+            process_io: HashMap::new(),
+            filter_editing: false,
+            kill_confirm: None,
+            marks: Marks::default(),
+            churn: Churn::default(),
+            watched: Watched::default(),
+            windows: Windows::default(),
+            thread_list: None,
+            fd_list: None,
+            followed: None,
+            fd_counts: HashMap::new(),
+            process_details: None,
+            affinity: None,
+            action_result: None,
+            stack_sampler: None,
+            exec_tracer: None,
+            capabilities,
+            strace: None,
+        };
+        app.apply_profile(&app.profile_name.clone());
+        app
+    }
+
+    fn apply_profile(&mut self, name: &str) {
+        let profile = self.config.get_profile(name);
+        let current_tab = self.tabs.get(self.tab_index).copied();
+
+        self.tabs = self.config.profile_tabs(name).iter().filter_map(|t| Tab::from_name(t)).collect();
+        if self.tabs.is_empty() {
+            self.tabs = vec![Tab::Overview];
+        }
+        // Stay on the same tab if the new profile still shows it
+        self.tab_index = current_tab
+            .and_then(|tab| self.tabs.iter().position(|t| *t == tab))
+            .unwrap_or(0);
+        self.refresh_interval = Duration::from_millis(profile.refresh_ms.max(100));
+        self.thresholds = profile.thresholds;
+        self.profile_name = name.to_string();
+    }
+
+    fn next_profile(&mut self) {
+        let names = self.config.profile_names();
+        let index = names.iter().position(|n| *n == self.profile_name).unwrap_or(0);
+        let next = names[(index + 1) % names.len()].clone();
+        self.apply_profile(&next);
+    }
+
+    fn restore_workspace(&mut self, workspace: &Workspace) {
+        if let Some(profile) = &workspace.profile {
+            if self.config.profile_names().contains(profile) {
+                self.apply_profile(profile);
+            }
+        }
+        let tab = workspace.tab.as_deref().and_then(Tab::from_name);
+        if let Some(index) = tab.and_then(|tab| self.tabs.iter().position(|t| *t == tab)) {
+            self.tab_index = index;
+        }
+        if let Some(columns) = &workspace.columns {
+            self.columns = ColumnLayout::from_specs(columns);
+        }
+        if workspace.split_process_tables {
+            self.process_view = ProcessView::Split;
+        }
+        if let Some(sort) = workspace.process_sort.as_deref().and_then(ProcessSort::from_name) {
+            self.process_sort = sort;
+        }
+        self.process_sort_reversed = workspace.process_sort_reversed;
+        self.show_commands = workspace.process_commands;
+        self.process_groups.grouping = workspace.process_grouping.as_deref().and_then(Grouping::from_name);
+        self.silences.manual = workspace.silences.clone();
+    }
+
+    fn workspace(&self) -> Workspace {
+        Workspace {
+            profile: Some(self.profile_name.clone()),
+            tab: Some(self.current_tab().name().to_string()),
+            columns: Some(self.columns.to_specs()),
+            split_process_tables: self.process_view == ProcessView::Split,
+            process_sort: Some(self.process_sort.name().to_string()),
+            process_sort_reversed: self.process_sort_reversed,
+            process_commands: self.show_commands,
+            process_grouping: self.process_groups.grouping.map(|grouping| grouping.name().to_string()),
+            silences: self.silences.manual.clone(),
+        }
+    }
+
+    fn handle_setup_key(&mut self, code: KeyCode) {
+        let Some(setup) = self.setup.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => setup.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => setup.move_selection(-1),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => setup.change(true),
+            KeyCode::Left | KeyCode::Char('h') => setup.change(false),
+            KeyCode::Enter => self.finish_setup(false),
+            KeyCode::Esc => self.finish_setup(true),
+            _ => {}
+        }
+    }
+
+    // Write the config file and switch to the new settings; Esc writes the
+    // defaults so the overlay doesn't come back on the next start
+    fn finish_setup(&mut self, keep_defaults: bool) {
+        let Some(mut setup) = self.setup.take() else {
+            return;
+        };
+        if keep_defaults {
+            setup = SetupWizard::new(&Config::default());
+        }
+        if setup.save().is_err() {
+            self.setup = Some(setup);
+            return;
+        }
+        setup.apply_to(&mut self.config);
+        units::apply(&self.config.units);
+        self.capabilities = Capabilities::detect(self.config.allow_privileged);
+        self.apply_profile(&self.profile_name.clone());
+    }
+
+    fn save_state(&mut self) -> Vec<String> {
+        self.state_saved = Instant::now();
+        let mut errors = Vec::new();
+        if let Err(err) = self.workspace().save() {
+            errors.push(format!("Could not save workspace: {err}"));
+        }
+        if let Err(err) = self.anomalies.save() {
+            errors.push(format!("Could not save the anomaly baseline: {err}"));
+        }
+        errors
+    }
+
+    // On exit: state plus the archive's unfinished minute
+    fn shutdown(&mut self) -> Vec<String> {
+        let mut errors = self.save_state();
+        if let Some(archive) = &mut self.archive {
+            if let Err(err) = archive.flush(&self.history, &self.disks) {
+                errors.push(format!("Could not write the history archive: {err}"));
+            }
+        }
+        errors
+    }
+
+    // Apply an edited config.toml; an invalid one leaves everything as it is
+    fn reload_config(&mut self) {
+        let loaded = Config::load().and_then(|config| check_config(&config).map(|parsed| (config, parsed)));
+        let (config, (pinned, rules)) = match loaded {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                // TOML errors go on to quote the line, the status bar has room for one
+                let message = err.to_string().lines().next().unwrap_or_default().to_string();
+                self.config_notice = Some(Notice::Invalid(message));
+                return;
+            }
+        };
+        let old = std::mem::replace(&mut self.config, config);
+        let config = &self.config;
+        units::apply(&config.units);
+        // Also gives collectors turned off for overrunning another chance
+        self.budgets = Budgets::new(&config.collector_budget);
+        self.pinned = pinned;
+        self.rules = rules;
+        self.read_only |= config.read_only;
+        self.silences.windows = config.maintenance_windows.clone();
+        // Rebuilt only when changed, they keep state between refreshes
+        if reload::changed(&old.health_checks, &config.health_checks) {
+            self.health_checks = config.health_checks.iter().cloned().map(HealthCheck::http).collect();
+        }
+        if reload::changed(&old.port_checks, &config.port_checks) {
+            self.port_checks = config.port_checks.iter().cloned().map(HealthCheck::port).collect();
+        }
+        if reload::changed(&old.kubernetes, &config.kubernetes) {
+            self.kubernetes = Kubernetes::start(&config.kubernetes);
+        }
+        if reload::changed(&old.cloud, &config.cloud) {
+            self.cloud = config.cloud.enabled.then(Cloud::start);
+        }
+        if reload::changed(&old.statsd, &config.statsd) || reload::changed(&old.graphite, &config.graphite) {
+            self.sinks = config
+                .statsd
+                .iter()
+                .map(|c| MetricSink::new(SinkKind::Statsd, c.clone()))
+                .chain(config.graphite.iter().map(|c| MetricSink::new(SinkKind::Graphite, c.clone())))
+                .collect();
+        }
+        if reload::changed(&old.channels(), &config.channels()) || reload::changed(&old.alert_routes, &config.alert_routes) {
+            self.notifier = Notifier::new(config.channels(), config.alert_routes.clone());
+        }
+        // Stay on the current profile unless it was removed
+        let profile = if config.profile_names().contains(&self.profile_name) {
+            self.profile_name.clone()
+        } else {
+            config.initial_profile()
+        };
+        self.config_notice = Some(Notice::Reloaded { at: Instant::now(), restart: reload::restart_needed(&old, config) });
+        self.apply_profile(&profile);
+    }
+
+    fn handle_settings_key(&mut self, code: KeyCode) {
+        let Some(settings) = self.settings.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => settings.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => settings.move_selection(-1),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => settings.change(&mut self.config, true),
+            KeyCode::Left | KeyCode::Char('h') => settings.change(&mut self.config, false),
+            KeyCode::Enter => {
+                if settings.save(&self.config) {
+                    self.settings = None;
+                }
+                return;
+            }
+            KeyCode::Esc => {
+                settings.revert(&mut self.config);
+                self.settings = None;
+            }
+            _ => return,
+        }
+        // Show the change right away
+        units::apply(&self.config.units);
+        self.apply_profile(&self.profile_name.clone());
+    }
+
+    fn open_column_chooser(&mut self) {
+        let table = match self.current_tab() {
+            Tab::Overview => TableKind::Network,
+            _ => TableKind::Processes,
+        };
+        self.column_chooser = Some(ColumnChooser::new(table));
+    }
+
+    fn handle_column_chooser_key(&mut self, code: KeyCode) {
+        let Some(chooser) = self.column_chooser.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Esc | KeyCode::F(2) => self.column_chooser = None,
+            KeyCode::Tab => chooser.next_table(),
+            KeyCode::Down | KeyCode::Char('j') => chooser.move_selection(&self.columns, true),
+            KeyCode::Up | KeyCode::Char('k') => chooser.move_selection(&self.columns, false),
+            KeyCode::Char(' ') | KeyCode::Enter => chooser.toggle(&mut self.columns),
+            KeyCode::Char('J') => chooser.shift(&mut self.columns, true),
+            KeyCode::Char('K') => chooser.shift(&mut self.columns, false),
+            KeyCode::Char('+') | KeyCode::Char('=') => chooser.resize(&mut self.columns, true),
+            KeyCode::Char('-') => chooser.resize(&mut self.columns, false),
+            _ => {}
+        }
+    }
+
+    // Scroll the tables on the current tab sideways
+    fn scroll_columns(&mut self, right: bool) {
+        let tables: &[TableKind] = match self.current_tab() {
+            Tab::Processes => &[TableKind::Processes],
+            Tab::Overview => &[TableKind::Network, TableKind::Disks],
+            #[allow(unreachable_patterns)]
+            _ => &[],
+        };
+        for table in tables {
+            self.columns.scroll(*table, right);
+        }
+    }
+
+    // Processes in table order; the split view's selection runs down the top
+    // CPU table and on into the top memory one
+    fn process_list(&self) -> Vec<Pid> {
+        match self.process_view {
+            ProcessView::Single => {
+                let mut processes = self.filtered_processes(self.process_sort);
+                if self.process_sort_reversed {
+                    processes.reverse();
+                }
+                processes
+            }
+            ProcessView::Split => {
+                let mut processes = self.filtered_processes(ProcessSort::Cpu);
+                processes.truncate(SPLIT_ROWS);
+                let mut memory = self.filtered_processes(ProcessSort::Memory);
+                memory.truncate(SPLIT_ROWS);
+                processes.extend(memory);
+                processes
+            }
+        }
+    }
+
+    fn disk_rates(&self, pid: Pid) -> Option<(f64, f64)> {
+        self.process_io.get(&pid).and_then(|io| io.rates)
+    }
+
+    // Processes matching the / filter by name, PID or command line, ignoring
+    // case, and owned by the u user; only these lookups touch every process
+    fn filtered_processes(&self, sort: ProcessSort) -> Vec<Pid> {
+        let mut orders = self.process_orders.borrow_mut();
+        let order = orders.entry(sort).or_default();
+        order.update(self.last_update, &self.processes, |pid, process| {
+            // Rates and counts aren't on the process, sorting by them needs the maps
+            let rate = |write: bool| self.disk_rates(pid).map_or(0.0, |(read, written)| if write { written } else { read });
+            match sort {
+                ProcessSort::DiskRead => SortKey::Number(-rate(false)),
+                ProcessSort::DiskWrite => SortKey::Number(-rate(true)),
+                ProcessSort::Fds => SortKey::Number(self.fd_counts.get(&pid).map_or(f64::INFINITY, |count| -(*count as f64))),
+                _ => sort_key(sort, pid, process),
+            }
+        });
+        if self.process_user.is_none() && self.process_filter.is_empty() {
+            return order.pids().to_vec();
+        }
+        let filter = self.process_filter.to_lowercase();
+        order
+            .pids()
+            .iter()
+            .copied()
+            .filter(|pid| {
+                let Some(process) = self.processes.process(*pid) else { return false };
+                if self.process_user.is_some() && process.user_id() != self.process_user.as_ref() {
+                    return false;
+                }
+                filter.is_empty()
+                    || pid.to_string().contains(&filter)
+                    || process.name().to_string_lossy().to_lowercase().contains(&filter)
+                    || process.cmd().iter().any(|arg| arg.to_string_lossy().to_lowercase().contains(&filter))
+            })
+            .collect()
+    }
+
+    // The processes of rows about to be drawn or acted on
+    fn resolve(&self, pids: &[Pid]) -> Vec<(Pid, &Process)> {
+        pids.iter().filter_map(|pid| self.processes.process(*pid).map(|process| (*pid, process))).collect()
+    }
+
+    // Whether an exited process would still pass the u user and the / filter
+    // (by name or PID, its command line is gone)
+    fn shows_exited(&self, exited: &Exited) -> bool {
+        if self.process_user.is_some() && self.process_user != exited.user {
+            return false;
+        }
+        let filter = self.process_filter.to_lowercase();
+        exited.pid.to_string().contains(&filter) || exited.name.to_lowercase().contains(&filter)
+    }
+
+    fn handle_user_picker_key(&mut self, code: KeyCode) {
+        let Some(picker) = self.user_picker.as_mut() else { return };
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => picker.move_selection(true),
+            KeyCode::Up | KeyCode::Char('k') => picker.move_selection(false),
+            KeyCode::Enter => {
+                self.process_user = picker.chosen();
+                self.user_picker = None;
+                self.selected_process = 0;
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('u') => self.user_picker = None,
+            _ => {}
+        }
+    }
+
+    // Typing after /: Enter keeps the filter, Esc clears it
+    fn handle_filter_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.filter_editing = false,
+            KeyCode::Esc => {
+                self.filter_editing = false;
+                self.process_filter.clear();
+            }
+            KeyCode::Backspace => {
+                self.process_filter.pop();
+            }
+            KeyCode::Char(c) => self.process_filter.push(c),
+            _ => return,
+        }
+        self.selected_process = 0;
+    }
+
+    // None while the table shows groups, whose keys act on the selected group
+    fn selected_process(&self) -> Option<(Pid, &Process)> {
+        if self.process_groups.grouping.is_some() {
+            return None;
+        }
+        let processes = self.process_list();
+        let index = self.selected_process.min(processes.len().saturating_sub(1));
+        let pid = *processes.get(index)?;
+        self.processes.process(pid).map(|process| (pid, process))
+    }
+
+    fn move_process_page(&mut self, down: bool) {
+        let (_, page) = self.process_scroll.get();
+        let page = page.max(1) as isize;
+        self.move_process_selection(if down { page } else { -page });
+    }
+
+    fn move_process_selection(&mut self, delta: isize) {
+        let len = self.process_list().len();
+        self.selected_process = self.selected_process
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+        // While following, the process moved to is followed instead
+        if delta != 0 && self.followed.is_some() {
+            self.followed = self
+                .selected_process()
+                .map(|(pid, process)| (pid, process.start_time(), process.name().to_string_lossy().into_owned()));
+        }
+    }
+
+    // c/m/P/n or a header click: the full table sorted by that column, the
+    // same one again reverses it
+    fn sort_processes(&mut self, sort: ProcessSort) {
+        if self.process_view == ProcessView::Single && self.process_sort == sort {
+            self.process_sort_reversed = !self.process_sort_reversed;
+        } else {
+            self.process_sort = sort;
+            self.process_sort_reversed = false;
+        }
+        self.process_view = ProcessView::Single;
+        self.move_process_selection(0);
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) || self.current_tab() != Tab::Processes {
+            return;
+        }
+        let clicked = self.process_headers.borrow().iter().find_map(|(cell, column)| {
+            let inside = (cell.x..cell.x + cell.width).contains(&mouse.column) && cell.y == mouse.row;
+            inside.then_some(*column)
+        });
+        if let Some(sort) = clicked.and_then(ProcessSort::from_column) {
+            self.sort_processes(sort);
+        }
+    }
+
+    // What k, +/- and E act on: the marked processes, or else the selected
+    // one, or every process of the selected group
+    fn action_targets(&self) -> Vec<(Pid, &Process)> {
+        if !self.marks.is_empty() {
+            return self.marks.processes(&self.processes);
+        }
+        if self.process_groups.grouping.is_some() {
+            let groups = self.process_group_list();
+            let Some(group) = groups.get(self.process_groups.selected(groups.len())) else { return Vec::new() };
+            return group.pids.iter().filter_map(|pid| self.processes.process(*pid).map(|process| (*pid, process))).collect();
+        }
+        self.selected_process().into_iter().collect()
+    }
+
+    // Which of those action_targets are, for the dialog and result wording
+    fn action_batch(&self) -> Batch {
+        if !self.marks.is_empty() {
+            return Batch::Marked;
+        }
+        if self.process_groups.grouping.is_some() {
+            let groups = self.process_group_list();
+            if let Some(group) = groups.get(self.process_groups.selected(groups.len())) {
+                return Batch::Group(group.key.clone());
+            }
+        }
+        Batch::Selected
+    }
+
+    // The grouped table's rows, after the / filter and u user
+    fn process_group_list(&self) -> Vec<Group> {
+        let processes = self.resolve(&self.filtered_processes(ProcessSort::Pid));
+        self.process_groups.groups(&processes, self.process_sort == ProcessSort::Memory, |pid| self.disk_rates(pid))
+    }
+
+    fn move_group_selection(&mut self, delta: isize) {
+        let count = self.process_group_list().len();
+        self.process_groups.move_selection(delta, count);
+    }
+
+    // Space marks or unmarks the selected process and moves on, so a run of
+    // rows is marked by holding it down
+    fn toggle_mark(&mut self) {
+        if let Some((pid, start_time)) = self.selected_process().map(|(pid, process)| (pid, process.start_time())) {
+            self.marks.toggle(pid, start_time);
+            self.move_process_selection(1);
+        }
+    }
+
+    // Enter on the Windows tab: the window's process, selected on the
+    // Processes tab with the filters that would hide it cleared
+    fn show_window_process(&mut self) {
+        let Some(pid) = self.windows.selected_pid() else { return };
+        let Some(index) = self.tabs.iter().position(|tab| *tab == Tab::Processes) else {
+            self.action_result = Some(ActionResult::new("The processes tab is hidden; add it to tabs".to_string(), true));
+            return;
+        };
+        self.tab_index = index;
+        self.process_filter.clear();
+        self.process_user = None;
+        self.process_view = ProcessView::Single;
+        let pid = Pid::from_u32(pid);
+        self.selected_process = self.process_list().iter().position(|listed| *listed == pid).unwrap_or(0);
+    }
+
+    // k on the Windows tab, the same dialog as on the Processes tab
+    fn confirm_window_kill(&mut self) {
+        let Some(pid) = self.windows.selected_pid().map(Pid::from_u32) else { return };
+        if let Some(process) = self.processes.process(pid) {
+            self.kill_confirm = Some(KillRequest::new(&[(pid, process)], Batch::Selected));
+        }
+    }
+
+    // y on the Processes tab: the marked PIDs, space separated for a shell's
+    // kill or renice, or the selected one
+    fn copy_pids(&mut self) {
+        let pids: Vec<String> = self.action_targets().iter().map(|(pid, _)| pid.to_string()).collect();
+        let what = match &pids[..] {
+            [] => return,
+            [pid] => format!("PID {}", pid),
+            pids => format!("{} PIDs", pids.len()),
+        };
+        self.copy(&pids.join(" "), &what);
+    }
+
+    // Y on the Processes tab
+    fn copy_command_line(&mut self) {
+        let Some((pid, process)) = self.selected_process() else { return };
+        let (command, what) = (command_line(process), format!("the command line of {}", pid));
+        self.copy(&command, &what);
+    }
+
+    // y in the details popup
+    fn copy_process_details(&mut self) {
+        let Some(details) = &self.process_details else { return };
+        let text = details.text(self.processes.process(details.pid), self.outdated.files(details.pid.as_u32()));
+        let what = format!("the details of {}", details.pid);
+        match text {
+            Some(text) => self.copy(&text, &what),
+            None => self.action_result = Some(ActionResult::new("The process has exited".to_string(), true)),
+        }
+    }
+
+    // Through the terminal, see clipboard.rs; the outcome shows like k's
+    fn copy(&mut self, text: &str, what: &str) {
+        let result = match clipboard::copy(text) {
+            Ok(()) => ActionResult::new(format!("📋 Copied {} to the terminal's clipboard", what), false),
+            Err(err) => ActionResult::new(format!("❌ Copying {} failed: {}", what, err), true),
+        };
+        self.action_result = Some(result);
+    }
+
+    // t on the Processes tab: the selected process's threads below the
+    // tables, or closes them when they are already shown
+    fn toggle_threads(&mut self) {
+        let Some((pid, process)) = self.selected_process() else { return };
+        self.thread_list = match &self.thread_list {
+            Some(list) if list.pid == pid => None,
+            _ => Some(ThreadList::open(pid, process)),
+        };
+    }
+
+    // o on the Processes tab: the selected process's open files below the
+    // tables, or closes them when they are already shown
+    fn toggle_fds(&mut self) {
+        let Some((pid, process)) = self.selected_process() else { return };
+        self.fd_list = match &self.fd_list {
+            Some(list) if list.pid == pid => None,
+            _ => Some(FdList::open(pid, process)),
+        };
+    }
+
+    // F on the Processes tab: keep the selection on the selected process
+    // while the table re-sorts; moving the selection follows the process
+    // moved to, F again stops
+    fn toggle_follow(&mut self) {
+        if let Some((pid, _, name)) = self.followed.take() {
+            self.action_result = Some(ActionResult::new(format!("👣 Stopped following {} ({})", name, pid), false));
+            return;
+        }
+        let Some((pid, process)) = self.selected_process() else { return };
+        let name = process.name().to_string_lossy().into_owned();
+        let message = format!("👣 Following {} ({}); the selection stays on it, F stops", name, pid);
+        self.followed = Some((pid, process.start_time(), name));
+        self.action_result = Some(ActionResult::new(message, false));
+    }
+
+    // Before each frame: puts the selection back on the followed process,
+    // wherever the sort moved it
+    fn follow_selection(&mut self) {
+        let Some((pid, start_time, name)) = &self.followed else { return };
+        if self.processes.process(*pid).is_none_or(|process| process.start_time() != *start_time) {
+            let message = format!("👣 Stopped following {} ({}): it exited", name, pid);
+            self.followed = None;
+            self.action_result = Some(ActionResult::new(message, false));
+            return;
+        }
+        // Hidden by the filter or user for now, the selection stays put
+        if let Some(row) = self.process_list().iter().position(|listed| listed == pid) {
+            self.selected_process = row;
+        }
+    }
+
+    // W on the Processes tab
+    fn toggle_watch(&mut self) {
+        let Some((pid, process)) = self.selected_process() else { return };
+        let name = process.name().to_string_lossy().into_owned();
+        let message = match self.watched.toggle(pid, process.start_time()) {
+            true => format!("👁 Watching {} ({}); it stays above the tables until it exits", name, pid),
+            false => format!("👁 Stopped watching {} ({})", name, pid),
+        };
+        self.action_result = Some(ActionResult::new(message, false));
+    }
+
+    // k on the Processes tab asks which signal first; see handle_kill_key
+    fn confirm_kill(&mut self) {
+        let targets = self.action_targets();
+        if !targets.is_empty() {
+            self.kill_confirm = Some(KillRequest::new(&targets, self.action_batch()));
+        }
+    }
+
+    fn export_processes(&mut self) {
+        let targets = self.action_targets();
+        if targets.is_empty() {
+            return;
+        }
+        let result = match marks::export(&targets, &self.user_names, |pid| self.disk_rates(pid)) {
+            Ok(path) => ActionResult::new(format!("📄 Exported {} processes to {}", targets.len(), path.display()), false),
+            Err(err) => ActionResult::new(format!("❌ Export failed: {}", err), true),
+        };
+        self.action_result = Some(result);
+    }
+
+    // The working directory and owner aren't part of the regular refresh
+    fn show_process_details(&mut self) {
+        let Some((pid, _)) = self.selected_process() else { return };
+        let kind = ProcessRefreshKind::nothing().with_cwd(UpdateKind::Always).with_user(UpdateKind::OnlyIfNotSet);
+        self.processes.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), false, kind);
+        if let Some(process) = self.processes.process(pid) {
+            self.process_details = Some(ProcessDetails::new(pid, process));
+        }
+    }
+
+    // A on the Processes tab
+    fn open_affinity(&mut self) {
+        let Some((pid, process)) = self.selected_process() else { return };
+        self.affinity = Some(AffinityEditor::open(pid, process, self.system.cpus().len()));
+    }
+
+    fn handle_affinity_key(&mut self, code: KeyCode) {
+        let Some(editor) = self.affinity.as_mut() else { return };
+        let editable = !self.read_only && editor.error.is_none();
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('A') => self.affinity = None,
+            KeyCode::Left if editable => editor.move_cursor(-1),
+            KeyCode::Right if editable => editor.move_cursor(1),
+            KeyCode::Up if editable => editor.move_cursor(-(affinity::PER_LINE as isize)),
+            KeyCode::Down if editable => editor.move_cursor(affinity::PER_LINE as isize),
+            KeyCode::Char(' ') if editable => editor.toggle(),
+            KeyCode::Char('a') if editable => editor.select_all(),
+            KeyCode::Char('i') if editable => editor.invert(),
+            KeyCode::Enter if editable => {
+                self.action_result = Some(editor.apply(self.processes.process(editor.pid)));
+                self.affinity = None;
+            }
+            _ => {}
+        }
+    }
+
+    // e in the details popup; the environment isn't part of the regular refresh
+    fn toggle_environment(&mut self) {
+        let Some(details) = self.process_details.as_mut() else { return };
+        let pid = details.pid;
+        if details.environment.is_none() {
+            let kind = ProcessRefreshKind::nothing().with_environ(UpdateKind::Always);
+            self.processes.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), false, kind);
+        }
+        if let Some(details) = self.process_details.as_mut() {
+            details.toggle_environment(self.processes.process(pid));
+        }
+    }
+
+    // The environment in the details popup; Esc goes back to the details
+    fn handle_environment_key(&mut self, code: KeyCode) {
+        let Some(environment) = self.process_details.as_mut().and_then(|details| details.environment.as_mut()) else { return };
+        if environment.editing {
+            environment.handle_search_key(code);
+            return;
+        }
+        match code {
+            KeyCode::Esc | KeyCode::Char('e') | KeyCode::Char('q') => self.toggle_environment(),
+            KeyCode::Char('/') => environment.editing = true,
+            KeyCode::Char('y') => self.copy_process_details(),
+            KeyCode::Down => environment.scroll(1),
+            KeyCode::Up => environment.scroll(-1),
+            KeyCode::PageDown => environment.scroll(10),
+            KeyCode::PageUp => environment.scroll(-10),
+            _ => {}
+        }
+    }
+
+    // +/- on the Processes tab; no dialog, each step is easily undone
+    fn renice_targets(&mut self, delta: i32) {
+        let result = match &self.action_targets()[..] {
+            [] => None,
+            [(pid, process)] if self.marks.is_empty() => Some(renice::renice(*pid, process, delta)),
+            targets => Some(renice::renice_all(targets, &self.action_batch(), delta)),
+        };
+        if result.is_some() {
+            self.action_result = result;
+        }
+    }
+
+    fn handle_kill_key(&mut self, code: KeyCode) {
+        let signal = match code {
+            KeyCode::Char('t') | KeyCode::Enter => Signal::Term,
+            KeyCode::Char('k') => Signal::Kill,
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.kill_confirm = None;
+                return;
+            }
+            _ => return,
+        };
+        if let Some(request) = self.kill_confirm.take() {
+            self.action_result = Some(kill::send(&request, signal, &self.processes));
+        }
+    }
+
+    fn move_removable_selection(&mut self, delta: isize) {
+        self.selected_removable = self.selected_removable
+            .saturating_add_signed(delta)
+            .min(self.topology.removable().len().saturating_sub(1));
+    }
+
+    // e on the Devices tab asks first; see handle_eject_key
+    fn confirm_eject(&mut self) {
+        if let Some(drive) = self.topology.removable().get(self.selected_removable) {
+            self.eject_confirm = Some(EjectRequest::new(drive));
+        }
+    }
+
+    fn handle_eject_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                if let Some(request) = self.eject_confirm.take() {
+                    self.eject = Some(Eject::start(request));
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Esc => self.eject_confirm = None,
+            _ => {}
+        }
+    }
+
+    // J on the Overview asks first
+    fn handle_power_key(&mut self, code: KeyCode) {
+        let action = match code {
+            KeyCode::Char('r') => PowerAction::Reboot,
+            KeyCode::Char('o') => PowerAction::PowerOff,
+            KeyCode::Char('c') if self.power.scheduled() => PowerAction::Cancel,
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.power_confirm = false;
+                return;
+            }
+            _ => return,
+        };
+        self.power_confirm = false;
+        self.power.start(action);
+    }
+
+    fn handle_vacuum_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.vacuum_confirm = false;
+                self.vacuum = Some(Vacuum::start(self.config.journal_vacuum_size.clone()));
+            }
+            KeyCode::Char('n') | KeyCode::Esc => self.vacuum_confirm = false,
+            _ => {}
+        }
+    }
+
+    fn move_alert_selection(&mut self, delta: isize) {
+        self.selected_alert = self.selected_alert
+            .saturating_add_signed(delta)
+            .min(self.alerts.len().saturating_sub(1));
+    }
+
+    // z silences the selected alert, Z everything; pressing again adds more time
+    fn silence_alerts(&mut self, all: bool) {
+        let pattern = if all {
+            "*".to_string()
+        } else {
+            match self.alerts.get(self.selected_alert) {
+                Some(alert) => alert.source.clone(),
+                None => return,
+            }
+        };
+        self.silences.silence(&pattern, self.config.silence_minutes.max(1));
+    }
+
+    fn lift_silences(&mut self, all: bool) {
+        if all {
+            self.silences.lift_all();
+        } else if let Some(alert) = self.alerts.get(self.selected_alert) {
+            self.silences.lift(alert);
+        }
+    }
+
+    fn start_stack_sampler(&mut self) {
+        if !self.config.allow_privileged {
+            return;
+        }
+        if let Some((pid, process)) = self.selected_process() {
+            let name = process.name().to_string_lossy().into_owned();
+            let duration = Duration::from_secs(self.config.sampler_seconds.max(1));
+            self.stack_sampler = Some(StackSampler::start(pid.as_u32(), name, duration));
+        }
+    }
+
+    fn start_strace(&mut self) {
+        if !self.config.allow_privileged {
+            return;
+        }
+        if let Some((pid, process)) = self.selected_process() {
+            let name = process.name().to_string_lossy().into_owned();
+            self.strace = Some(StraceView::start(pid.as_u32(), name));
         }
     }
 
+    // Cycle the network panel through the namespaces we can see
+    fn next_network_namespace(&mut self) {
+        let namespaces = netns::list();
+        let current = self.netns.as_ref().map(|n| n.namespace.inode).or_else(netns::own_inode);
+        let index = namespaces.iter().position(|ns| Some(ns.inode) == current).unwrap_or(0);
+        self.netns = namespaces
+            .get((index + 1) % namespaces.len().max(1))
+            .filter(|ns| Some(ns.inode) != netns::own_inode())
+            .cloned()
+            .map(NamespaceNetworks::new);
+    }
+
+    // /proc/<pid>/net shows the network namespace that process lives in
+    fn proc_net(&self) -> String {
+        match &self.netns {
+            Some(netns) => format!("/proc/{}/net", netns.namespace.pid),
+            None => "/proc/self/net".to_string(),
+        }
+    }
+
+    // Interfaces of the selected network namespace
+    fn interfaces(&self) -> Vec<InterfaceStat> {
+        match &self.netns {
+            Some(netns) => netns.interfaces.clone(),
+            None => self.networks.iter().map(|(name, network)| InterfaceStat::from_sysinfo(name, network)).collect(),
+        }
+    }
+
+    fn current_tab(&self) -> Tab {
+        self.tabs[self.tab_index]
+    }
+
+    // Set up any collector the current tab or a pinned metric needs for the first time
+    fn ensure_collectors(&mut self) {
+        let needed: Vec<Collector> = self.current_tab().collectors().iter().copied()
+            .chain(self.pinned.iter().filter_map(|p| p.collector()))
+            .chain(self.service_collectors())
+            .collect();
+        for collector in needed {
+            if self.collectors.contains(&collector) {
+                continue;
+            }
+            match collector {
+                Collector::Processes => {
+                    // The spare too, so its first turn has CPU usage to show
+                    for system in [&mut self.processes, &mut self.spare_processes] {
+                        system.refresh_processes_specifics(ProcessesToUpdate::All, true, process_refresh_kind());
+                    }
+                    self.user_names.update(&self.processes);
+                    self.process_groups.update(&self.processes);
+                    update_process_io(&mut self.process_io, &self.processes);
+                    self.marks.prune(&self.processes);
+                    self.watched.update(&self.processes, &self.config.watched);
+                }
+                Collector::Disks => self.disks = Disks::new_with_refreshed_list(),
+                Collector::Components => self.components = Components::new_with_refreshed_list(),
+                Collector::Networks => self.networks = Networks::new_with_refreshed_list(),
+                Collector::Sockets => self.sockets.refresh_sockets(&self.proc_net()),
+                Collector::Dbus => self.dbus = Some(DbusMonitor::start()),
+                // The tab explains what's missing instead
+                #[cfg(feature = "ebpf")]
+                Collector::Ebpf if !self.capabilities.ebpf.available() => {}
+                #[cfg(feature = "ebpf")]
+                Collector::Ebpf => self.ebpf = Some(ebpf::EbpfTracer::start()),
+            }
+            self.collectors.push(collector);
+        }
+    }
+
+    // Collectors background services need whatever tab is open
+    fn service_collectors(&self) -> Vec<Collector> {
+        let mut collectors: Vec<Collector> = self.rules.iter().flat_map(|rule| rule.collectors()).collect();
+        // Disk alerts must fire even when no tab shows disks
+        if self.events.enabled() || !self.notifier.is_empty() {
+            collectors.push(Collector::Disks);
+        }
+        if self.config.health_score.enabled {
+            collectors.extend([Collector::Disks, Collector::Components]);
+        }
+        collectors.extend(self.anomalies.collectors());
+        // Reports list the busiest processes and disk growth
+        if self.archive.is_some() {
+            collectors.extend([Collector::Processes, Collector::Disks]);
+        }
+        #[cfg(feature = "api")]
+        if self.api.is_some() {
+            collectors.extend([Collector::Processes, Collector::Networks]);
+        }
+        collectors
+    }
+
     fn refresh(&mut self) {
-        if self.last_update.elapsed() >= Duration::from_secs(1) {
+        self.collect_late();
+        self.ensure_collectors();
+        if self.last_update.elapsed() >= self.refresh_interval {
+            let started = Instant::now();
             self.system.refresh_cpu_all();
-            self.system.refresh_processes(ProcessesToUpdate::All, true);
             self.system.refresh_memory();
-            self.disks.refresh(true);
-            self.components.refresh(true);
-            self.networks.refresh(true);
+            self.self_metrics.record("cpu and memory", started.elapsed());
+            let collected: Vec<Collector> = self
+                .collectors
+                .iter()
+                .copied()
+                .filter(|collector| !self.in_flight.contains(collector) && self.budgets.should_run(collector.name()))
+                .collect();
+            let started = Instant::now();
+            let finished = self.run_collectors(&collected);
+            for (collector, elapsed) in &finished {
+                self.self_metrics.record(collector.name(), *elapsed);
+                self.budgets.record(collector.name(), *elapsed);
+            }
+            // The slowest collector, now that they run side by side
+            self.self_metrics.record("all collectors", started.elapsed());
+            let now = Instant::now();
+            let processes_ran = finished.iter().any(|(collector, _)| *collector == Collector::Processes);
+            // Skipped and late collectors keep the age of their last data
+            self.collected.retain(|(collector, _)| !finished.iter().any(|(done, _)| done == collector));
+            self.collected.extend(finished.iter().map(|(collector, _)| (*collector, now)));
+            self.sample_secs = self.last_update.elapsed().as_secs_f64();
             self.last_update = Instant::now();
+            self.sockets.refresh_counters(&self.proc_net(), self.sample_secs);
+            self.page_cache.update();
+            self.steal.update();
+            self.tmpfs.update();
+            self.ssh.update();
+            self.modules.update_taint();
+            self.locks.update();
+            self.zombies.update();
+            self.power.update();
+            self.outdated.scan_if_stale();
+            // History first, so rules averaging over it see this sample too
+            self.record_history();
+            self.update_gauges();
+            self.anomalies.update(&self.history);
+            self.alerts = alerts::evaluate(self);
+            self.selected_alert = self.selected_alert.min(self.alerts.len().saturating_sub(1));
+            let changes = self.silences.changes(&self.alerts);
+            if self.config.health_score.enabled {
+                self.health_score = Some(score::compute(self));
+            }
+            self.events.update(&changes);
+            self.notifier.notify(&changes);
+            if let Some(archive) = &mut self.archive {
+                archive.update(processes_ran.then_some(&self.processes), &changes, &self.history, &self.disks);
+            }
+            if let Some(reporter) = &mut self.reporter {
+                reporter.run_if_due(&mut self.notifier);
+            }
+            if self.archive.is_some() && self.current_tab() == Tab::Accounting {
+                self.accounting.reload_if_stale();
+            }
+            if self.current_tab() == Tab::Logins {
+                self.logins.reload_if_stale();
+            }
+            if self.current_tab() == Tab::Health {
+                self.posture.update_if_stale();
+            }
+            if self.current_tab() == Tab::Modules {
+                self.modules.update();
+            }
+            if self.current_tab() == Tab::Windows {
+                self.windows.reload_if_stale();
+            }
+            if self.current_tab() == Tab::Overview {
+                self.home_scan.rescan_if_stale();
+                self.log_scan.rescan_if_stale();
+                self.slices.update(self.system.cpus().len());
+            }
+            if self.current_tab() == Tab::Processes {
+                self.blocked.update();
+                if let Some(list) = &mut self.thread_list {
+                    list.update(self.processes.process(list.pid));
+                }
+                if let Some(list) = &mut self.fd_list {
+                    list.update(self.processes.process(list.pid));
+                }
+            }
+            if self.current_tab() == Tab::Interrupts && self.capabilities.interrupts.available() {
+                self.interrupts.update();
+            }
+            if matches!(self.current_tab(), Tab::Overview | Tab::Interrupts) && self.capabilities.interrupts.available() {
+                self.softirqs.update();
+            }
+            if self.current_tab() == Tab::Devices && self.capabilities.block_devices.available() {
+                self.topology.update();
+                self.selected_removable = self.selected_removable.min(self.topology.removable().len().saturating_sub(1));
+            }
+            for sink in &mut self.sinks {
+                sink.flush_if_due(&self.history);
+            }
+            if self.state_saved.elapsed() >= Duration::from_secs(300) {
+                self.save_state();
+            }
+            #[cfg(feature = "api")]
+            if let Some(api) = &self.api {
+                api.publish(self);
+            }
+            // Everything after the collectors: small panels, history, alerts and sinks
+            self.self_metrics.record("panels and alerts", now.elapsed());
+            self.self_metrics.update();
+        }
+    }
+
+    // Each collector refreshes its own sources, so they run on a thread each
+    // and a refresh takes as long as the slowest one rather than all of them
+    // together. One still running at the end of its budget (a statvfs stuck
+    // on a dead NFS server) is left to finish in the background rather than
+    // freezing the monitor: the processes stay as they were and other panels
+    // go empty, with their age in the title, until it's back. Returns how
+    // long each that finished took, late ones from earlier refreshes included.
+    fn run_collectors(&mut self, collectors: &[Collector]) -> Vec<(Collector, Duration)> {
+        let mut finished = std::mem::take(&mut self.late);
+        // Before the networks collector takes the namespace along
+        let proc_net = self.proc_net();
+        let started = Instant::now();
+        let mut waiting: Vec<(Collector, Option<Instant>)> = Vec::new();
+        for collector in collectors {
+            let mut sources = self.take_sources(*collector, &proc_net);
+            let (sender, collector) = (self.finished.0.clone(), *collector);
+            thread::spawn(move || {
+                let started = Instant::now();
+                let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| sources.refresh())).map(|()| sources);
+                // App is gone when the monitor quit meanwhile
+                let _ = sender.send((collector, outcome, started.elapsed()));
+            });
+            // Without budgets, as long as it takes
+            let deadline = self.config.collector_budget.enabled.then(|| started + self.budgets.budget(collector.name()));
+            waiting.push((collector, deadline));
+            self.in_flight.push(collector);
+        }
+        while !waiting.is_empty() {
+            let received = match waiting.iter().filter_map(|(_, deadline)| *deadline).min() {
+                Some(deadline) => self.finished.1.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => self.finished.1.recv().map_err(mpsc::RecvTimeoutError::from),
+            };
+            match received {
+                Ok(result) => {
+                    let (collector, elapsed) = self.restore_sources(result);
+                    waiting.retain(|(waiting, _)| *waiting != collector);
+                    finished.push((collector, elapsed));
+                }
+                Err(_) => {
+                    let now = Instant::now();
+                    for (collector, _) in waiting.iter().filter(|(_, deadline)| deadline.is_some_and(|deadline| deadline <= now)) {
+                        self.budgets.overdue(collector.name());
+                    }
+                    waiting.retain(|(_, deadline)| deadline.is_none_or(|deadline| deadline > now));
+                }
+            }
+        }
+        finished
+    }
+
+    // Before every frame, so a late collector's panels fill again as soon as it's back
+    fn collect_late(&mut self) {
+        while let Ok(result) = self.finished.1.try_recv() {
+            let finished = self.restore_sources(result);
+            self.late.push(finished);
+        }
+    }
+
+    fn take_sources(&mut self, collector: Collector, proc_net: &str) -> Sources {
+        use std::mem::take;
+        match collector {
+            Collector::Processes => Sources::Processes(Box::new((
+                take(&mut self.spare_processes),
+                self.user_names.clone(),
+                self.process_io.clone(),
+                HashMap::new(),
+                self.churn.clone(),
+            ))),
+            Collector::Disks => Sources::Disks(Box::new((take(&mut self.disks), take(&mut self.disk_latency), take(&mut self.disk_growth)))),
+            Collector::Components => Sources::Components(take(&mut self.components)),
+            Collector::Networks => Sources::Networks(take(&mut self.networks), self.netns.take()),
+            Collector::Sockets => Sources::Sockets(Box::new(take(&mut self.sockets)), proc_net.to_string()),
+            Collector::Dbus => Sources::Dbus(self.dbus.take()),
+            #[cfg(feature = "ebpf")]
+            Collector::Ebpf => Sources::Ebpf,
+        }
+    }
+
+    // A collector that panicked takes the monitor down, as it did on one thread
+    fn restore_sources(&mut self, (collector, outcome, elapsed): Finished) -> (Collector, Duration) {
+        self.in_flight.retain(|running| *running != collector);
+        match outcome.unwrap_or_else(|panic| std::panic::resume_unwind(panic)) {
+            Sources::Processes(sources) => {
+                let processes;
+                (processes, self.user_names, self.process_io, self.fd_counts, self.churn) = *sources;
+                self.spare_processes = std::mem::replace(&mut self.processes, processes);
+                self.process_groups.update(&self.processes);
+                self.marks.prune(&self.processes);
+                self.watched.update(&self.processes, &self.config.watched);
+            }
+            Sources::Disks(sources) => (self.disks, self.disk_latency, self.disk_growth) = *sources,
+            Sources::Components(components) => self.components = components,
+            Sources::Networks(networks, netns) => {
+                self.networks = networks;
+                // Unless n picked another namespace meanwhile
+                if self.netns.is_none() {
+                    self.netns = netns;
+                }
+            }
+            Sources::Sockets(sockets, _) => self.sockets = *sockets,
+            Sources::Dbus(dbus) => self.dbus = dbus,
+            #[cfg(feature = "ebpf")]
+            Sources::Ebpf => {}
+        }
+        (collector, elapsed)
+    }
+
+    fn record_history(&mut self) {
+        let percent = |used: u64, total: u64| if total > 0 { used as f64 / total as f64 * 100.0 } else { 0.0 };
+        self.history.record("cpu", self.system.global_cpu_usage() as f64);
+        self.history.record("memory", percent(self.system.used_memory(), self.system.total_memory()));
+        self.history.record("swap", percent(self.system.used_swap(), self.system.total_swap()));
+        self.history.record("load", System::load_average().one);
+        if self.collectors.contains(&Collector::Networks) {
+            let (rx, tx, _) = get_network_summary(&self.interfaces());
+            self.history.record("net_rx", rx as f64 / self.sample_secs);
+            self.history.record("net_tx", tx as f64 / self.sample_secs);
         }
+        self.page_cache.record(&mut self.history);
+        self.steal.record(&mut self.history);
+    }
+
+    fn update_gauges(&mut self) {
+        let config = &self.config.gauges;
+        for (gauge, metric) in [
+            (&mut self.gauges.cpu, "cpu"),
+            (&mut self.gauges.memory, "memory"),
+            (&mut self.gauges.swap, "swap"),
+        ] {
+            if let Some(value) = self.history.latest(metric) {
+                gauge.update(value, self.sample_secs, config);
+            }
+        }
+        // Bursts between two refreshes still reach the CPU peak marker
+        if let Some(high_res) = &mut self.high_res {
+            for sample in high_res.drain() {
+                self.gauges.cpu.observe(sample.at, sample.cpu, config);
+            }
+        }
+    }
+
+    fn collected_at(&self, collector: Collector) -> Option<Instant> {
+        self.collected.iter().find(|(c, _)| *c == collector).map(|(_, at)| *at)
     }
 
     fn next_tab(&mut self) {
-        self.tab_index = (self.tab_index + 1) % 2;
+        self.tab_index = (self.tab_index + 1) % self.tabs.len();
     }
 
     fn previous_tab(&mut self) {
         if self.tab_index > 0 {
             self.tab_index -= 1;
         } else {
-            self.tab_index = 1;
+            self.tab_index = self.tabs.len() - 1;
         }
     }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+
+    // Before the config, which these don't need and shouldn't fail on
+    match &cli.command {
+        Some(Command::Query { expr }) => {
+            println!("{}", expr.parse::<Query>()?.run()?);
+            return Ok(());
+        }
+        Some(Command::Completions { shell }) => {
+            cli::print_completions(*shell);
+            return Ok(());
+        }
+        Some(Command::Man) => {
+            cli::print_man()?;
+            return Ok(());
+        }
+        Some(Command::Schema) => {
+            schema::print()?;
+            return Ok(());
+        }
+        None => {}
+    }
+
+    // Load config before touching the terminal so errors are readable
+    let config = Config::load()?;
+    let (pinned, rules) = check_config(&config)?;
+    units::apply(&config.units);
+
+    // On-demand report and accounting from the archived history, no UI
+    if let Some(period) = cli.report {
+        let format = config.report.as_ref().map_or(ReportFormat::Text, |report| report.format);
+        print!("{}", report::generate(period, format).body);
+        return Ok(());
+    }
+    if let Some(period) = cli.accounting {
+        accounting::print(period);
+        return Ok(());
+    }
+    if cli.capabilities {
+        capabilities::print(&Capabilities::detect(config.allow_privileged));
+        return Ok(());
+    }
+
+    // Lock ourselves down before the first thread starts: capabilities,
+    // no_new_privs and Landlock only bind the thread that sets them
+    let read_only = cli.read_only || config.read_only;
+    let mut hardened = None;
+    if cli.harden || config.harden {
+        let mut writable: Vec<PathBuf> = [Config::path(), Workspace::path()]
+            .into_iter()
+            .flatten()
+            .filter_map(|path| path.parent().map(Path::to_path_buf))
+            .collect();
+        // Reports may be written outside the data directory
+        writable.extend(config.report.as_ref().and_then(|report| report.directory()));
+        for dir in &writable {
+            fs::create_dir_all(dir)?;
+        }
+        hardened = Some(harden::apply(&writable, read_only)?.summary());
+    }
+
+    // Create app
+    let mut app = App::new(config, pinned, rules);
+    app.read_only = read_only;
+    app.hardened = hardened;
+    if cli.high_res || app.config.high_resolution.enabled {
+        app.high_res = Some(HighRes::start(&app.config.high_resolution));
+    }
+    if cli.trace_exec || app.config.exec_trace.enabled {
+        app.exec_tracer = Some(ExecTracer::start(&app.config.exec_trace, &app.capabilities.exec_trace));
+    }
+    app.restore_workspace(&Workspace::load());
+
+    #[cfg(feature = "api")]
+    if let Some(address) = cli.api.clone().or_else(|| app.config.api_listen.clone()) {
+        let server = api::ApiServer::start(&address)
+            .map_err(|e| format!("cannot serve the API on {}: {}", address, e))?;
+        app.api = Some(server);
+    }
+
+    shutdown::handle_signals();
+
+    #[cfg(feature = "api")]
+    if cli.headless {
+        if app.api.is_none() {
+            return Err("--headless needs --api or api_listen".into());
+        }
+        while !shutdown::requested() {
+            app.refresh();
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        for err in app.shutdown() {
+            eprintln!("{err}");
+        }
+        return Ok(());
+    }
+
+    // First launch: ask for the basics before showing anything else
+    if Config::path().is_some_and(|path| !path.exists()) {
+        app.setup = Some(SetupWizard::new(&app.config));
+    }
+
     // Setup terminal
+    shutdown::restore_on_panic();
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app and run it
-    let app = App::new();
-    let res = run_app(&mut terminal, app);
+    let res = run_app(&mut terminal, &mut app);
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    // Save first: after a SIGHUP there is no terminal left to restore
+    let errors = app.shutdown();
+    drop(terminal);
+    shutdown::restore_terminal()?;
+    for err in errors {
+        eprintln!("{err}");
+    }
 
-    if let Err(err) = res {
-        println!("{err:?}");
+    match res {
+        Ok(Exit::Elevate) => {
+            let err = privilege::relaunch_elevated();
+            eprintln!("Could not restart with elevated privileges: {err}");
+        }
+        Ok(Exit::Quit) => {}
+        Err(err) => println!("{err:?}"),
     }
 
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
-    loop {
+// Everything checked before a config is used, at start-up and on reload;
+// returns the parsed pinned metrics and alert rules
+fn check_config(config: &Config) -> Result<(Vec<PinnedMetric>, Vec<AlertRule>), Box<dyn Error>> {
+    let pinned = config
+        .pinned
+        .iter()
+        .map(|spec| spec.parse::<PinnedMetric>())
+        .collect::<Result<Vec<_>, _>>()?;
+    validate_profiles(config)?;
+    config.columns.validate()?;
+    for check in &config.health_checks {
+        check.validate()?;
+    }
+    for check in &config.port_checks {
+        check.validate()?;
+    }
+    for window in &config.maintenance_windows {
+        window.validate()?;
+    }
+    config.health_score.validate()?;
+    config.anomaly.validate()?;
+    config.gauges.validate()?;
+    config.high_resolution.validate()?;
+    config.collector_budget.validate(|name| Collector::from_name(name).is_some())?;
+    config.exec_trace.validate()?;
+    if let Some(statsd) = &config.statsd {
+        statsd.validate("statsd")?;
+    }
+    if let Some(graphite) = &config.graphite {
+        graphite.validate("graphite")?;
+    }
+    channels::validate(&config.channels(), &config.alert_routes)?;
+    config.archive.validate()?;
+    if let Some(report) = &config.report {
+        report.validate(&config.channels())?;
+    }
+    let rules = config
+        .alert_rules
+        .iter()
+        .map(AlertRule::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((pinned, rules))
+}
+
+fn validate_profiles(config: &Config) -> Result<(), String> {
+    if let Some(tab) = config.tabs.iter().find(|t| Tab::from_name(t).is_none()) {
+        return Err(format!("unknown tab '{}'", tab));
+    }
+    for (name, profile) in &config.profiles {
+        if let Some(tab) = profile.tabs.iter().flatten().find(|t| Tab::from_name(t).is_none()) {
+            return Err(format!("profile '{}': unknown tab '{}'", name, tab));
+        }
+        profile.thresholds.validate().map_err(|e| format!("profile '{}': {}", name, e))?;
+    }
+    if let Some(name) = &config.profile {
+        if !config.profile_names().contains(name) {
+            return Err(format!("profile '{}' is not defined", name));
+        }
+    }
+    Ok(())
+}
+
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<Exit> {
+    while !shutdown::requested() {
+        let frame = Instant::now();
         app.refresh();
-        terminal.draw(|f| ui(f, &app))?;
+        app.follow_selection();
+        // Not under an open settings or setup overlay, both edit the config
+        if app.settings.is_none() && app.setup.is_none() && app.config_watcher.changed() {
+            app.reload_config();
+        }
+        let render = Instant::now();
+        terminal.draw(|f| ui(f, app))?;
+        app.self_metrics.record_frame(render.elapsed(), frame.elapsed());
 
-        if crossterm::event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
+        if shutdown::wait_for_input(selfmetrics::FRAME_BUDGET) && crossterm::event::poll(Duration::ZERO)? {
+            let event = event::read()?;
+            // Header clicks, only with nothing drawn over the tables
+            if let Event::Mouse(mouse) = event {
+                let overlay = app.setup.is_some()
+                    || app.settings.is_some()
+                    || app.column_chooser.is_some()
+                    || app.kill_confirm.is_some()
+                    || app.process_details.is_some()
+                    || app.affinity.is_some()
+                    || app.user_picker.is_some()
+                    || app.strace.is_some()
+                    || app.stack_sampler.is_some()
+                    || app.show_score_breakdown
+                    || app.show_self_metrics;
+                if !overlay {
+                    app.handle_mouse(mouse);
+                }
+            }
+            if let Event::Key(key) = event {
+                if app.setup.is_some() {
+                    app.handle_setup_key(key.code);
+                    continue;
+                }
+                if app.settings.is_some() {
+                    app.handle_settings_key(key.code);
+                    continue;
+                }
+                if app.column_chooser.is_some() {
+                    app.handle_column_chooser_key(key.code);
+                    continue;
+                }
+                if app.filter_editing {
+                    app.handle_filter_key(key.code);
+                    continue;
+                }
+                if app.kill_confirm.is_some() {
+                    app.handle_kill_key(key.code);
+                    continue;
+                }
+                if app.user_picker.is_some() {
+                    app.handle_user_picker_key(key.code);
+                    continue;
+                }
+                if app.affinity.is_some() {
+                    app.handle_affinity_key(key.code);
+                    continue;
+                }
+                if app.process_details.as_ref().is_some_and(|details| details.environment.is_some()) {
+                    app.handle_environment_key(key.code);
+                    continue;
+                }
+                if app.process_details.is_some() {
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => app.process_details = None,
+                        KeyCode::Char('y') => app.copy_process_details(),
+                        KeyCode::Char('e') => app.toggle_environment(),
+                        _ => {}
+                    }
+                    continue;
+                }
+                if app.eject_confirm.is_some() {
+                    app.handle_eject_key(key.code);
+                    continue;
+                }
+                if app.vacuum_confirm {
+                    app.handle_vacuum_key(key.code);
+                    continue;
+                }
+                if app.power_confirm {
+                    app.handle_power_key(key.code);
+                    continue;
+                }
+                if let Some(strace) = &app.strace {
+                    match key.code {
+                        KeyCode::Esc => app.strace = None,
+                        KeyCode::Backspace => strace.pop_filter(),
+                        KeyCode::Char(c) => strace.push_filter(c),
+                        _ => {}
+                    }
+                    continue;
+                }
+                if app.show_self_metrics {
+                    if matches!(key.code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::F(12)) {
+                        app.show_self_metrics = false;
+                    }
+                    continue;
+                }
+                if app.show_score_breakdown {
+                    if matches!(key.code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('H')) {
+                        app.show_score_breakdown = false;
+                    }
+                    continue;
+                }
+                if app.stack_sampler.is_some() {
+                    if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+                        app.stack_sampler = None;
+                    }
+                    continue;
+                }
                 match key.code {
-                    KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Char('q') => return Ok(Exit::Quit),
+                    KeyCode::Char('!') if !privilege::is_root() && app.config.allow_privileged && !harden::active() => return Ok(Exit::Elevate),
                     KeyCode::Right | KeyCode::Tab => app.next_tab(),
                     KeyCode::Left => app.previous_tab(),
+                    KeyCode::Char('p') => app.next_profile(),
+                    KeyCode::Char(',') if !app.read_only => app.settings = Some(SettingsEditor::new(&app.config, &app.profile_name)),
+                    KeyCode::F(2) => app.open_column_chooser(),
+                    KeyCode::F(12) => app.show_self_metrics = true,
+                    KeyCode::Char('H') if app.health_score.is_some() => app.show_score_breakdown = true,
+                    KeyCode::Char('v') if app.current_tab() == Tab::Processes => {
+                        app.process_view = match app.process_view {
+                            ProcessView::Split => ProcessView::Single,
+                            ProcessView::Single => ProcessView::Split,
+                        };
+                        app.move_process_selection(0);
+                    }
+                    KeyCode::Char('s') if app.current_tab() == Tab::Accounting => app.accounting.next_sort(),
+                    KeyCode::Char('s') if app.current_tab() == Tab::Processes && app.process_view == ProcessView::Single => {
+                        app.process_sort = app.process_sort.next();
+                        app.process_sort_reversed = false;
+                    }
+                    KeyCode::Char('c') if app.current_tab() == Tab::Processes => app.sort_processes(ProcessSort::Cpu),
+                    KeyCode::Char('m') if app.current_tab() == Tab::Processes => app.sort_processes(ProcessSort::Memory),
+                    KeyCode::Char('P') if app.current_tab() == Tab::Processes => app.sort_processes(ProcessSort::Pid),
+                    KeyCode::Char('n') if app.current_tab() == Tab::Processes => app.sort_processes(ProcessSort::Name),
+                    KeyCode::Char('/') if app.current_tab() == Tab::Processes => app.filter_editing = true,
+                    KeyCode::Char('w') if app.current_tab() == Tab::Processes => {
+                        app.show_commands = !app.show_commands;
+                        app.command_offset = 0;
+                    }
+                    KeyCode::Char(']') if app.current_tab() == Tab::Processes && app.show_commands => app.command_offset += COMMAND_STEP,
+                    KeyCode::Char('[') if app.current_tab() == Tab::Processes && app.show_commands => {
+                        app.command_offset = app.command_offset.saturating_sub(COMMAND_STEP);
+                    }
+                    KeyCode::Esc if app.current_tab() == Tab::Processes && !app.process_filter.is_empty() => {
+                        app.handle_filter_key(KeyCode::Esc);
+                    }
+                    KeyCode::Esc if app.current_tab() == Tab::Processes && app.process_user.is_some() => {
+                        app.process_user = None;
+                        app.selected_process = 0;
+                    }
+                    KeyCode::Esc if app.current_tab() == Tab::Processes && !app.marks.is_empty() => app.marks.clear(),
+                    KeyCode::Esc if app.current_tab() == Tab::Processes && app.thread_list.is_some() => app.thread_list = None,
+                    KeyCode::Esc if app.current_tab() == Tab::Processes && app.fd_list.is_some() => app.fd_list = None,
+                    KeyCode::Char('t') if app.current_tab() == Tab::Processes => app.toggle_threads(),
+                    KeyCode::Char('o') if app.current_tab() == Tab::Processes => app.toggle_fds(),
+                    KeyCode::Char('F') if app.current_tab() == Tab::Processes => app.toggle_follow(),
+                    KeyCode::Char('A') if app.current_tab() == Tab::Processes => app.open_affinity(),
+                    KeyCode::Char(' ') if app.current_tab() == Tab::Processes => app.toggle_mark(),
+                    KeyCode::Char('E') if app.current_tab() == Tab::Processes => app.export_processes(),
+                    KeyCode::Char('W') if app.current_tab() == Tab::Processes => app.toggle_watch(),
+                    KeyCode::Char('y') if app.current_tab() == Tab::Processes => app.copy_pids(),
+                    KeyCode::Char('Y') if app.current_tab() == Tab::Processes => app.copy_command_line(),
+                    KeyCode::Char('u') if app.current_tab() == Tab::Processes => {
+                        app.user_picker = Some(UserPicker::new(&app.processes, &app.user_names, app.process_user.as_ref()));
+                    }
+                    KeyCode::Char('g') if app.current_tab() == Tab::Processes => {
+                        app.process_groups.next();
+                        app.process_groups.update(&app.processes);
+                    }
+                    KeyCode::Down if app.current_tab() == Tab::Processes && app.process_groups.grouping.is_some() => app.move_group_selection(1),
+                    KeyCode::Up if app.current_tab() == Tab::Processes && app.process_groups.grouping.is_some() => app.move_group_selection(-1),
+                    KeyCode::Home if app.current_tab() == Tab::Processes && app.process_groups.grouping.is_some() => app.move_group_selection(isize::MIN),
+                    KeyCode::End if app.current_tab() == Tab::Processes && app.process_groups.grouping.is_some() => app.move_group_selection(isize::MAX),
+                    KeyCode::Down if app.current_tab() == Tab::Processes => app.move_process_selection(1),
+                    KeyCode::Up if app.current_tab() == Tab::Processes => app.move_process_selection(-1),
+                    KeyCode::PageDown if app.current_tab() == Tab::Processes => app.move_process_page(true),
+                    KeyCode::PageUp if app.current_tab() == Tab::Processes => app.move_process_page(false),
+                    KeyCode::Home if app.current_tab() == Tab::Processes => app.move_process_selection(isize::MIN),
+                    KeyCode::End if app.current_tab() == Tab::Processes => app.move_process_selection(isize::MAX),
+                    KeyCode::Char('S') if app.current_tab() == Tab::Processes => app.start_stack_sampler(),
+                    KeyCode::Char('x') if app.current_tab() == Tab::Processes && !app.read_only => app.start_strace(),
+                    KeyCode::Char('k') if app.current_tab() == Tab::Processes && !app.read_only => app.confirm_kill(),
+                    KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::F(8) if app.current_tab() == Tab::Processes && !app.read_only => {
+                        app.renice_targets(1);
+                    }
+                    KeyCode::Char('-') | KeyCode::F(7) if app.current_tab() == Tab::Processes && !app.read_only => app.renice_targets(-1),
+                    KeyCode::Enter if app.current_tab() == Tab::Processes => app.show_process_details(),
+                    KeyCode::Down if app.current_tab() == Tab::Devices => app.move_removable_selection(1),
+                    KeyCode::Up if app.current_tab() == Tab::Devices => app.move_removable_selection(-1),
+                    KeyCode::Char('e') if app.current_tab() == Tab::Devices && !app.read_only => app.confirm_eject(),
+                    KeyCode::Char('J') if app.current_tab() == Tab::Overview && !app.read_only => app.vacuum_confirm = true,
+                    KeyCode::Char('R') if app.current_tab() == Tab::Overview && app.config.power.actions && !app.read_only => {
+                        app.power_confirm = true;
+                    }
+                    KeyCode::Down if app.current_tab() == Tab::Alerts => app.move_alert_selection(1),
+                    KeyCode::Up if app.current_tab() == Tab::Alerts => app.move_alert_selection(-1),
+                    KeyCode::Char('z') if app.current_tab() == Tab::Alerts => app.silence_alerts(false),
+                    KeyCode::Char('Z') if app.current_tab() == Tab::Alerts => app.silence_alerts(true),
+                    KeyCode::Char('u') if app.current_tab() == Tab::Alerts => app.lift_silences(false),
+                    KeyCode::Char('U') if app.current_tab() == Tab::Alerts => app.lift_silences(true),
+                    KeyCode::Down if app.current_tab() == Tab::Accounting => app.accounting.move_selection(1),
+                    KeyCode::Up if app.current_tab() == Tab::Accounting => app.accounting.move_selection(-1),
+                    KeyCode::Char('t') if app.current_tab() == Tab::Accounting => app.accounting.next_period(),
+                    KeyCode::Down if app.current_tab() == Tab::Logins => app.logins.move_selection(1),
+                    KeyCode::Up if app.current_tab() == Tab::Logins => app.logins.move_selection(-1),
+                    KeyCode::Down if app.current_tab() == Tab::Modules => app.modules.move_selection(1),
+                    KeyCode::Up if app.current_tab() == Tab::Modules => app.modules.move_selection(-1),
+                    KeyCode::Down if app.current_tab() == Tab::Locks => app.locks.move_selection(1),
+                    KeyCode::Up if app.current_tab() == Tab::Locks => app.locks.move_selection(-1),
+                    KeyCode::Down if app.current_tab() == Tab::Windows => app.windows.move_selection(1),
+                    KeyCode::Up if app.current_tab() == Tab::Windows => app.windows.move_selection(-1),
+                    KeyCode::Enter if app.current_tab() == Tab::Windows => app.show_window_process(),
+                    KeyCode::Char('k') if app.current_tab() == Tab::Windows && !app.read_only => app.confirm_window_kill(),
+                    KeyCode::Char('N') if matches!(app.current_tab(), Tab::Overview | Tab::Sockets) => app.next_network_namespace(),
+                    KeyCode::Char('>') => app.scroll_columns(true),
+                    KeyCode::Char('<') => app.scroll_columns(false),
                     _ => {}
                 }
             }
         }
     }
+    Ok(Exit::Quit)
 }
 
 fn ui(f: &mut Frame, app: &App) {
@@ -129,14 +2456,54 @@ fn ui(f: &mut Frame, app: &App) {
         ])
         .split(size);
 
-    // Title bar
-    let title = Paragraph::new("🖥️  System Monitor TUI")
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+    // Title bar, followed by any pinned metrics
+    let mut title_spans = vec![Span::styled(
+        "🖥️  System Monitor TUI",
+        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+    )];
+    if let Some(score) = &app.health_score {
+        title_spans.push(Span::styled("  │  ", Style::default().fg(Color::DarkGray)));
+        title_spans.push(Span::styled(
+            format!("❤️ {:.0}", score.total),
+            Style::default().fg(score::score_color(score.total)).add_modifier(Modifier::BOLD),
+        ));
+    }
+    for metric in &app.pinned {
+        title_spans.push(Span::styled("  │  ", Style::default().fg(Color::DarkGray)));
+        title_spans.push(Span::styled(metric.render(app), Style::default().fg(Color::White)));
+    }
+    if app.read_only {
+        title_spans.push(Span::styled("  │  ", Style::default().fg(Color::DarkGray)));
+        title_spans.push(Span::styled("🔒 READ-ONLY", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)));
+    }
+    // Most severe alert first, the rest only counted; silenced ones just counted
+    let (silenced, active): (Vec<&Alert>, Vec<&Alert>) =
+        app.alerts.iter().partition(|alert| app.silences.silenced_by(alert).is_some());
+    if let Some(alert) = active.first() {
+        let (icon, color) = match alert.severity {
+            Severity::Critical => ("🚨", Color::Red),
+            Severity::Warning => ("⚠️ ", Color::Yellow),
+        };
+        let more = match active.len() {
+            1 => String::new(),
+            n => format!(" (+{} more)", n - 1),
+        };
+        title_spans.push(Span::styled("  │  ", Style::default().fg(Color::DarkGray)));
+        title_spans.push(Span::styled(
+            format!("{} {}{}", icon, alert.message, more),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if !silenced.is_empty() {
+        title_spans.push(Span::styled("  │  ", Style::default().fg(Color::DarkGray)));
+        title_spans.push(Span::styled(format!("🔕 {} silenced", silenced.len()), Style::default().fg(Color::DarkGray)));
+    }
+    let title = Paragraph::new(Line::from(title_spans))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, chunks[0]);
 
     // Tabs
-    let tab_titles = vec!["📊 Overview", "💾 Processes"];
+    let tab_titles: Vec<&str> = app.tabs.iter().map(|t| t.title()).collect();
     let tabs = Tabs::new(tab_titles)
         .block(Block::default().borders(Borders::ALL))
         .select(app.tab_index)
@@ -145,26 +2512,192 @@ fn ui(f: &mut Frame, app: &App) {
     f.render_widget(tabs, chunks[1]);
 
     // Content based on selected tab
-    match app.tab_index {
-        0 => draw_overview_tab(f, chunks[2], app),
-        1 => draw_processes_tab(f, chunks[2], app),
-        _ => {}
+    match app.current_tab() {
+        Tab::Overview => draw_overview_tab(f, chunks[2], app),
+        Tab::Processes => draw_processes_tab(f, chunks[2], app),
+        Tab::Sockets => sockets::draw_sockets_tab(f, chunks[2], app),
+        Tab::Health => health::draw_health_tab(f, chunks[2], app),
+        Tab::Alerts => alerts::draw_alerts_tab(f, chunks[2], app),
+        Tab::Accounting => accounting::draw_accounting_tab(f, chunks[2], app),
+        Tab::Logins => logins::draw_logins_tab(f, chunks[2], app),
+        Tab::Interrupts => interrupts::draw_interrupts_tab(f, chunks[2], app),
+        Tab::Devices => blockdev::draw_devices_tab(f, chunks[2], app),
+        Tab::Modules => modules::draw_modules_tab(f, chunks[2], &app.modules),
+        Tab::Locks => locks::draw_locks_tab(f, chunks[2], &app.locks),
+        Tab::Windows => windows::draw_windows_tab(f, chunks[2], app),
+        Tab::Dbus => dbus::draw_dbus_tab(f, chunks[2], app.dbus.as_ref()),
+        #[cfg(feature = "ebpf")]
+        Tab::Ebpf => ebpf::draw_ebpf_tab(f, chunks[2], app),
     }
 
     // Status bar
-    let status = format!("Last updated: {} | Press 'q' to quit | ←/→ or Tab to switch tabs", 
-                        Local::now().format("%H:%M:%S"));
-    let status_bar = Paragraph::new(status)
-        .style(Style::default().fg(Color::Gray));
+    let status = format!("Profile: {} | Press 'q' to quit | ←/→ or Tab to switch tabs | 'p' next profile",
+                        app.profile_name);
+    let notice = app.config_notice.as_ref().and_then(Notice::text).or_else(|| app.budgets.notice());
+    let status_bar = match notice {
+        Some((text, true)) => Paragraph::new(text).style(Style::default().fg(Color::Red)),
+        Some((text, false)) => Paragraph::new(text).style(Style::default().fg(Color::Green)),
+        None => Paragraph::new(status).style(Style::default().fg(Color::Gray)),
+    };
     f.render_widget(status_bar, chunks[3]);
+
+    if let Some(chooser) = &app.column_chooser {
+        chooser.draw(f, centered_rect(60, 50, size), &app.columns);
+    }
+    if let Some(sampler) = &app.stack_sampler {
+        sampler.draw(f, centered_rect(70, 70, size));
+    }
+    if let Some(details) = &app.process_details {
+        details.draw(f, centered_rect(80, 70, size), app.processes.process(details.pid), app.outdated.files(details.pid.as_u32()));
+    }
+    if let Some(editor) = &app.affinity {
+        editor.draw(f, centered_rect(70, 50, size), app.system.cpus(), app.read_only);
+    }
+    if let Some(picker) = &app.user_picker {
+        picker.draw(f, centered_rect(50, 50, size));
+    }
+    if let Some(request) = &app.kill_confirm {
+        kill::draw_confirm(f, centered_rect(60, 40, size), request);
+    }
+    if let Some(request) = &app.eject_confirm {
+        eject::draw_confirm(f, centered_rect(60, 40, size), request);
+    }
+    if app.vacuum_confirm {
+        logs::draw_confirm(f, centered_rect(60, 40, size), &app.config.journal_vacuum_size, app.log_scan.usage());
+    }
+    if app.power_confirm {
+        power::draw_confirm(f, centered_rect(60, 40, size), &app.power);
+    }
+    if let Some(strace) = &app.strace {
+        strace.draw(f, centered_rect(90, 80, size));
+    }
+    if let (true, Some(score)) = (app.show_score_breakdown, &app.health_score) {
+        score::draw_breakdown(f, centered_rect(70, 40, size), score);
+    }
+    if app.show_self_metrics {
+        selfmetrics::draw(f, centered_rect(70, 60, size), &app.self_metrics, &app.budgets);
+    }
+    if let Some(settings) = &app.settings {
+        settings.draw(f, centered_rect(70, 60, size), &app.config);
+    }
+    if let Some(setup) = &app.setup {
+        setup.draw(f, centered_rect(70, 60, size));
+    }
+
+    // Recolor last; the setup overlay previews the theme being picked
+    let theme = app.setup.as_ref().map_or(app.config.theme, |setup| setup.theme);
+    theme.apply(f.buffer_mut());
 }
 
 fn draw_overview_tab(f: &mut Frame, area: Rect, app: &App) {
+    // System info panel - compact
+    let mut system_info = Vec::new();
+    
+    // Load average
+    let load_avg = System::load_average();
+    system_info.push(ListItem::new(format!("📊 Load Average: {:.2} {:.2} {:.2} (1m 5m 15m)", 
+                                          load_avg.one, load_avg.five, load_avg.fifteen)));
+    system_info.push(ListItem::new(app.responsiveness.status()));
+    if let Some(steal) = app.steal.status(&app.history, &app.thresholds) {
+        system_info.push(ListItem::new(steal));
+    }
+    
+    // CPU temperature, which WSL has no way to read; its VM memory instead
+    match &app.wsl {
+        Some(wsl) => {
+            let (total, used) = (app.system.total_memory(), app.system.used_memory());
+            system_info.extend(wsl.status(total, used, app.page_cache.cached).into_iter().map(ListItem::new));
+        }
+        None => {
+            let temp_info = match &app.capabilities.sensors {
+                Support::Available => get_cpu_temperature(&app.components, &app.thresholds),
+                missing => format!("🌡️ CPU Temperature: {}", missing.placeholder()),
+            };
+            system_info.push(ListItem::new(temp_info));
+        }
+    }
+    
+    // Uptime
+    system_info.push(ListItem::new(format!("⏰ Uptime: {}", format_uptime(System::uptime()))));
+    if let Some(environment) = &app.environment {
+        system_info.push(ListItem::new(environment.status()));
+    }
+    for (text, color) in app.power.status() {
+        system_info.push(ListItem::new(text).style(Style::default().fg(color)));
+    }
+    if let Some(cloud) = &app.cloud {
+        system_info.push(ListItem::new(cloud.status()));
+    }
+    if let Some(slices) = app.slices.status() {
+        system_info.push(ListItem::new(slices));
+    }
+    if let Some(softirqs) = app.softirqs.status() {
+        system_info.push(ListItem::new(softirqs));
+    }
+    system_info.extend(app.page_cache.status(&app.history).into_iter().map(ListItem::new));
+    if let Some(taint) = app.modules.status() {
+        system_info.push(ListItem::new(taint));
+    }
+    if let Some(wait) = app.locks.status() {
+        system_info.push(ListItem::new(wait).style(Style::default().fg(Color::Red)));
+    }
+    if let Some(outdated) = app.outdated.status() {
+        system_info.push(ListItem::new(outdated).style(Style::default().fg(Color::Yellow)));
+    }
+    if let Some(zombies) = app.zombies.status() {
+        system_info.push(ListItem::new(zombies).style(Style::default().fg(Color::Magenta)));
+    }
+
+    // Privileges, so missing data isn't a mystery
+    if !app.config.allow_privileged {
+        system_info.push(ListItem::new("🔐 Privileges: root-only collectors turned off (allow_privileged = false)"));
+    } else if privilege::is_root() {
+        system_info.push(ListItem::new("🔐 Privileges: root, no collector limited by permissions"));
+    } else {
+        system_info.push(ListItem::new(format!(
+            "🔐 Privileges: user, some sensors and other users' process details {}",
+            privilege::root_hint()
+        )));
+    }
+    if let Some(hardened) = &app.hardened {
+        system_info.push(ListItem::new(hardened.as_str()));
+    }
+    if let Some(status) = app.capabilities.status() {
+        system_info.push(ListItem::new(status));
+    }
+    for sink in &app.sinks {
+        system_info.push(ListItem::new(sink.status()));
+    }
+    if app.events.enabled() {
+        system_info.push(ListItem::new(app.events.status()));
+    }
+    if !app.notifier.is_empty() {
+        system_info.push(ListItem::new(app.notifier.status()));
+    }
+    if app.anomalies.enabled() {
+        system_info.push(ListItem::new(app.anomalies.status()));
+    }
+    if let Some(archive) = &app.archive {
+        system_info.push(ListItem::new(archive.status()));
+    }
+    if let Some(reporter) = &app.reporter {
+        system_info.push(ListItem::new(reporter.status()));
+    }
+    if let Some(high_res) = &app.high_res {
+        let window = Duration::from_secs(app.config.gauges.peak_hold_seconds.max(1));
+        system_info.push(ListItem::new(high_res.status(window)));
+    }
+    #[cfg(feature = "api")]
+    if let Some(api) = &app.api {
+        system_info.push(ListItem::new(format!("🌍 API: http://{}", api.address)));
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // CPU, Memory, Swap gauges on same line
-            Constraint::Length(8), // System info
+            // System info, taller when services add status lines
+            Constraint::Length((system_info.len() as u16 + 2).max(8)),
             Constraint::Min(0),    // Network and storage info
         ])
         .split(area);
@@ -176,37 +2709,24 @@ fn draw_overview_tab(f: &mut Frame, area: Rect, app: &App) {
         .split(chunks[0]);
 
     // CPU gauge
-    let cpu_usage = app.system.global_cpu_usage();
-    let cpu_gauge = Gauge::default()
-        .block(Block::default().title("🖥️ CPU").borders(Borders::ALL))
-        .gauge_style(Style::default().fg(Color::Green))
-        .percent(cpu_usage as u16)
-        .label(format!("{:.1}%", cpu_usage));
-    f.render_widget(cpu_gauge, gauge_chunks[0]);
+    let gauge_config = &app.config.gauges;
+    let cpu_block = Block::default().title("🖥️ CPU").borders(Borders::ALL);
+    gauges::draw(f, gauge_chunks[0], cpu_block, Color::Green, &app.gauges.cpu, gauge_config);
 
     // Memory gauge
     let total_memory = app.system.total_memory();
     let used_memory = app.system.used_memory();
-    let memory_usage = (used_memory as f64 / total_memory as f64) * 100.0;
-    let memory_gauge = Gauge::default()
-        .block(Block::default().title(format!("💾 Memory {}/{}", format_bytes(used_memory), format_bytes(total_memory))).borders(Borders::ALL))
-        .gauge_style(Style::default().fg(Color::Blue))
-        .percent(memory_usage as u16)
-        .label(format!("{:.1}%", memory_usage));
-    f.render_widget(memory_gauge, gauge_chunks[1]);
+    let memory_block = Block::default().title(format!("💾 Memory {}/{}", format_bytes(used_memory), format_bytes(total_memory))).borders(Borders::ALL);
+    gauges::draw(f, gauge_chunks[1], memory_block, Color::Blue, &app.gauges.memory, gauge_config);
 
     // Swap gauge
     let total_swap = app.system.total_swap();
     let used_swap = app.system.used_swap();
     if total_swap > 0 {
-        let swap_usage = (used_swap as f64 / total_swap as f64) * 100.0;
-        let swap_color = if swap_usage > 50.0 { Color::Red } else if swap_usage > 10.0 { Color::Yellow } else { Color::Green };
-        let swap_gauge = Gauge::default()
-            .block(Block::default().title(format!("🔄 Swap {}/{}", format_bytes(used_swap), format_bytes(total_swap))).borders(Borders::ALL))
-            .gauge_style(Style::default().fg(swap_color))
-            .percent(swap_usage as u16)
-            .label(format!("{:.1}%", swap_usage));
-        f.render_widget(swap_gauge, gauge_chunks[2]);
+        let swap_usage = app.gauges.swap.value();
+        let swap_color = if swap_usage > app.thresholds.swap_crit { Color::Red } else if swap_usage > app.thresholds.swap_warn { Color::Yellow } else { Color::Green };
+        let swap_block = Block::default().title(format!("🔄 Swap {}/{}", format_bytes(used_swap), format_bytes(total_swap))).borders(Borders::ALL);
+        gauges::draw(f, gauge_chunks[2], swap_block, swap_color, &app.gauges.swap, gauge_config);
     } else {
         let swap_info = Paragraph::new("Not configured")
             .block(Block::default().title("🔄 Swap").borders(Borders::ALL))
@@ -214,23 +2734,8 @@ fn draw_overview_tab(f: &mut Frame, area: Rect, app: &App) {
         f.render_widget(swap_info, gauge_chunks[2]);
     }
 
-    // System info panel - compact
-    let mut system_info = Vec::new();
-    
-    // Load average
-    let load_avg = System::load_average();
-    system_info.push(ListItem::new(format!("📊 Load Average: {:.2} {:.2} {:.2} (1m 5m 15m)", 
-                                          load_avg.one, load_avg.five, load_avg.fifteen)));
-    
-    // CPU temperature
-    let temp_info = get_cpu_temperature(&app.components);
-    system_info.push(ListItem::new(temp_info));
-    
-    // Uptime
-    system_info.push(ListItem::new(format!("⏰ Uptime: {}", format_uptime(System::uptime()))));
-
     let system_list = List::new(system_info)
-        .block(Block::default().title("📈 System Information").borders(Borders::ALL))
+        .block(Block::default().title(freshness::title("📈 System Information", "", Some(app.last_update), false)).borders(Borders::ALL))
         .style(Style::default().fg(Color::White));
     f.render_widget(system_list, chunks[1]);
 
@@ -240,198 +2745,709 @@ fn draw_overview_tab(f: &mut Frame, area: Rect, app: &App) {
         .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
         .split(chunks[2]);
 
-    // Network summary
+    // Network summary, followed by a table of the active interfaces
+    let network_title = match &app.netns {
+        Some(netns) => format!("🌐 Network I/O — netns {}", netns.namespace.label),
+        None => "🌐 Network I/O".to_string(),
+    };
+    let network_title = freshness::title(&network_title, "", app.collected_at(Collector::Networks), false);
+    let network_block = Block::default().title(network_title).borders(Borders::ALL);
+    let network_area = network_block.inner(bottom_chunks[0]);
+    f.render_widget(network_block, bottom_chunks[0]);
+    let network_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Min(0)])
+        .split(network_area);
+
+    let interfaces = app.interfaces();
     let mut network_info = Vec::new();
-    let (total_rx, total_tx, interface_count) = get_network_summary(&app.networks);
+    if let Some(error) = app.netns.as_ref().and_then(|n| n.error.as_ref()) {
+        network_info.push(ListItem::new(format!("❌ {}", error)).style(Style::default().fg(Color::Red)));
+    }
+    let (total_rx, total_tx, interface_count) = get_network_summary(&interfaces);
     network_info.push(ListItem::new(format!("📡 Active Interfaces: {}", interface_count)));
     network_info.push(ListItem::new(format!("📥 Total Received: {}", format_bytes(total_rx))));
     network_info.push(ListItem::new(format!("📤 Total Transmitted: {}", format_bytes(total_tx))));
-    
-    // Add per-interface breakdown for active ones
-    let mut interface_count = 0;
-    for (interface_name, network) in &app.networks {
-        let received = network.received();
-        let transmitted = network.transmitted();
-        
-        if (received > 0 || transmitted > 0) && interface_count < 4 { // Show top 4 interfaces
-            network_info.push(ListItem::new(format!("  {} | RX: {} TX: {}", 
-                                                   truncate_name(interface_name, 10),
-                                                   format_bytes(received), 
-                                                   format_bytes(transmitted))));
-            interface_count += 1;
-        }
-    }
-
     let network_list = List::new(network_info)
-        .block(Block::default().title("🌐 Network I/O").borders(Borders::ALL))
         .style(Style::default().fg(Color::White));
-    f.render_widget(network_list, bottom_chunks[0]);
+    f.render_widget(network_list, network_chunks[0]);
+
+    let network_columns = app.columns.displayed(TableKind::Network);
+    let network_rows: Vec<Row> = interfaces
+        .iter()
+        .filter(|interface| interface.received > 0 || interface.transmitted > 0)
+        .map(|interface| {
+            Row::new(network_columns.iter().map(|c| network_cell(c.column, interface, app.sample_secs)))
+        })
+        .collect();
+    let network_table = columns_table(network_rows, &network_columns, app.columns.offset(TableKind::Network), None);
+    f.render_widget(network_table, network_chunks[1]);
 
     // Combined Storage and Home directory info
+    let home = app.home_scan.usage();
+    let storage_title = freshness::title(
+        &freshness::title("💽 Storage & Home Directory", "", app.collected_at(Collector::Disks), false),
+        "scan",
+        home.as_ref().map(|(_, at)| *at),
+        app.home_scan.scanning(),
+    );
+    let storage_block = Block::default().title(storage_title).borders(Borders::ALL);
+    let storage_area = storage_block.inner(bottom_chunks[1]);
+    f.render_widget(storage_block, bottom_chunks[1]);
+
+    let disk_columns = app.columns.displayed(TableKind::Disks);
+    let mut disk_rows = Vec::new();
     let mut storage_info = Vec::new();
-    
+
     // Storage summary
     let mut total_storage = 0u64;
     let mut total_used = 0u64;
     let mut disk_count = 0;
-    
+
     for disk in &app.disks {
         let total_space = disk.total_space();
         let available_space = disk.available_space();
         let used_space = total_space - available_space;
-        
+
         if total_space > 0 {
             total_storage += total_space;
             total_used += used_space;
             disk_count += 1;
-            
-            let usage_percent = (used_space as f64 / total_space as f64) * 100.0;
+
             if disk_count <= 4 { // Show details for first 4 disks
-                storage_info.push(ListItem::new(format!("💽 {} | {:.1}% | {}/{}", 
-                                                       truncate_name(&disk.name().to_string_lossy(), 20),
-                                                       usage_percent,
-                                                       format_bytes(used_space),
-                                                       format_bytes(total_space))));
+                disk_rows.push(Row::new(disk_columns.iter().map(|c| disk_cell(c.column, disk, app))));
             }
         }
     }
-    
-    // Add storage summary at the top
+
+    let mut summary = Vec::new();
     if total_storage > 0 {
         let total_usage = (total_used as f64 / total_storage as f64) * 100.0;
-        storage_info.insert(0, ListItem::new(format!("📊 {} Disks Total | {:.1}% | {}/{}", 
-                                                    disk_count, total_usage, 
-                                                    format_bytes(total_used), 
-                                                    format_bytes(total_storage))));
-        storage_info.insert(1, ListItem::new("".to_string())); // Separator
+        summary.push(ListItem::new(format!("📊 {} Disks Total | {:.1}% | {}/{}",
+                                           disk_count, total_usage,
+                                           format_bytes(total_used),
+                                           format_bytes(total_storage))));
     }
-    
-    // Add home directory information
-    if let Some(home_dir) = dirs::home_dir() {
-        storage_info.push(ListItem::new("🏠 Home Directory:".to_string()));
-        storage_info.push(ListItem::new(format!("   📂 Path: {}", truncate_name(&home_dir.display().to_string(), 35))));
-        
-        match calculate_directory_size(&home_dir) {
-            Ok(size) => {
-                storage_info.push(ListItem::new(format!("   📊 Size: {}", format_bytes(size))));
-                
-                // Show largest subdirectories
-                let common_dirs = ["Downloads", "Documents", "Pictures", "Videos", "Desktop", "Music"];
-                let mut dir_sizes = Vec::new();
-                
-                for dir_name in &common_dirs {
-                    let dir_path = home_dir.join(dir_name);
-                    if dir_path.exists() && dir_path.is_dir() {
-                        if let Ok(dir_size) = calculate_directory_size(&dir_path) {
-                            if dir_size > 0 {
-                                dir_sizes.push((dir_name, dir_size));
-                            }
-                        }
+    if let Some(tmpfs) = app.tmpfs.status(&app.thresholds) {
+        summary.push(ListItem::new(tmpfs));
+    }
+
+    let storage_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(summary.len() as u16),
+            Constraint::Length(disk_rows.len() as u16 + 2),
+            Constraint::Min(0),
+        ])
+        .split(storage_area);
+    f.render_widget(List::new(summary).style(Style::default().fg(Color::White)), storage_chunks[0]);
+    let disk_table = columns_table(disk_rows, &disk_columns, app.columns.offset(TableKind::Disks), None);
+    f.render_widget(disk_table, storage_chunks[1]);
+
+    // Logs, and what share of their filesystem's growth they are
+    let log_disk = app
+        .disks
+        .iter()
+        .filter(|disk| Path::new(logs::LOG_DIR).starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len());
+    let log_disk = log_disk.map(|disk| {
+        let used = disk.total_space().saturating_sub(disk.available_space());
+        (disk.mount_point().to_str().unwrap_or_default(), app.disk_growth.per_hour(disk.mount_point(), used))
+    });
+    if let Some(logs) = app.log_scan.status(log_disk) {
+        storage_info.push(ListItem::new(logs));
+    }
+    if let Some(vacuum) = &app.vacuum {
+        let (text, color) = vacuum.status();
+        storage_info.push(ListItem::new(text).style(Style::default().fg(color)));
+    }
+
+    // Home directory information, from the background scan
+    match home {
+        Some((home, _)) => {
+            storage_info.push(ListItem::new("🏠 Home Directory:".to_string()));
+            storage_info.push(ListItem::new(format!("   📂 Path: {}", truncate_name(&home.path.display().to_string(), 35))));
+            match home.size {
+                Some(size) => {
+                    storage_info.push(ListItem::new(format!("   📊 Size: {}", format_bytes(size))));
+                    // Top 2 of the usual subdirectories
+                    for (dir_name, size) in home.largest.iter().take(2) {
+                        storage_info.push(ListItem::new(format!("   📁 {}: {}", dir_name, format_bytes(*size))));
                     }
                 }
-                
-                // Sort and show top 2 directories
-                dir_sizes.sort_by(|a, b| b.1.cmp(&a.1));
-                for (dir_name, size) in dir_sizes.iter().take(2) {
-                    storage_info.push(ListItem::new(format!("   📁 {}: {}", dir_name, format_bytes(*size))));
-                }
-            }
-            Err(_) => {
-                storage_info.push(ListItem::new("   ❌ Could not calculate size".to_string()));
+                None => storage_info.push(ListItem::new("   ❌ Could not calculate size".to_string())),
             }
         }
+        None if dirs::home_dir().is_some() => storage_info.push(ListItem::new("🏠 Home Directory: scanning…".to_string())),
+        None => {}
     }
 
     let storage_list = List::new(storage_info)
-        .block(Block::default().title("💽 Storage & Home Directory").borders(Borders::ALL))
         .style(Style::default().fg(Color::White));
-    f.render_widget(storage_list, bottom_chunks[1]);
+    f.render_widget(storage_list, storage_chunks[2]);
 }
 
 fn draw_processes_tab(f: &mut Frame, area: Rect, app: &App) {
+    let columns = app.columns.displayed(TableKind::Processes);
+    let offset = app.columns.offset(TableKind::Processes);
+
+    // Without root, other users' processes lack command line, exe and I/O details
+    let area = match limited_process_count(app) {
+        0 => area,
+        limited => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(area);
+            let notice = Paragraph::new(format!(
+                "🔐 {} processes belong to other users; their details {}",
+                limited,
+                privilege::root_hint()
+            ))
+            .style(Style::default().fg(Color::DarkGray));
+            f.render_widget(notice, chunks[0]);
+            chunks[1]
+        }
+    };
+
+    // The / filter, while typing it or while one is set, and the u user
+    let area = if app.filter_editing || !app.process_filter.is_empty() || app.process_user.is_some() {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        let mut spans = Vec::new();
+        if let Some(uid) = &app.process_user {
+            spans.push(Span::styled(format!("👤 User: {}", app.user_names.name(Some(uid))), Style::default().fg(Color::Yellow)));
+            spans.push(Span::styled("  u change  ", Style::default().fg(Color::DarkGray)));
+        }
+        if app.filter_editing || !app.process_filter.is_empty() {
+            let (cursor, hint) = if app.filter_editing { ("▏", "Enter keep · Esc clear") } else { ("", "/ edit · Esc clear") };
+            spans.push(Span::styled(format!("🔍 Filter: {}{}", app.process_filter, cursor), Style::default().fg(Color::Yellow)));
+            spans.push(Span::styled(format!("  {}", hint), Style::default().fg(Color::DarkGray)));
+        } else {
+            spans.push(Span::styled("Esc show all users", Style::default().fg(Color::DarkGray)));
+        }
+        let filter = Line::from(spans);
+        f.render_widget(Paragraph::new(filter), chunks[0]);
+        chunks[1]
+    } else {
+        area
+    };
+
+    // Outcome of the last k or renice, for a few seconds
+    let area = match app.action_result.as_ref().and_then(ActionResult::text) {
+        None => area,
+        Some((text, failed)) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(area);
+            let color = if failed { Color::Red } else { Color::Green };
+            f.render_widget(Paragraph::new(text).style(Style::default().fg(color)), chunks[0]);
+            chunks[1]
+        }
+    };
+
+    // Watched processes on top, wherever they sort
+    let area = match app.watched.is_empty() && app.config.watched.is_empty() {
+        true => area,
+        false => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(app.watched.height()), Constraint::Min(0)])
+                .split(area);
+            watched::draw(f, chunks[0], app, &columns, offset);
+            chunks[1]
+        }
+    };
+
+    // Threads of the process opened with t
+    let area = match &app.thread_list {
+        None => area,
+        Some(list) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(12)])
+                .split(area);
+            threads::draw(f, chunks[1], list);
+            chunks[0]
+        }
+    };
+
+    // Open files of the process opened with o
+    let area = match &app.fd_list {
+        None => area,
+        Some(list) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(12)])
+                .split(area);
+            fds::draw(f, chunks[1], list);
+            chunks[0]
+        }
+    };
+
+    // Processes too short-lived for the tables, with --trace-exec
+    let area = match &app.exec_tracer {
+        None => area,
+        Some(tracer) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(12)])
+                .split(area);
+            exectrace::draw_exited(f, chunks[1], app, tracer);
+            chunks[0]
+        }
+    };
+
+    // Threads stuck in uninterruptible sleep, only while there are any
+    let area = match app.blocked.tasks.len() {
+        0 => area,
+        blocked => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length((blocked as u16 + 3).min(12))])
+                .split(area);
+            blocked::draw(f, chunks[1], &app.blocked);
+            chunks[0]
+        }
+    };
+
+    // Defunct processes and their parents, only while there are any
+    let area = match app.zombies.zombies.len() {
+        0 => area,
+        zombies => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length((zombies as u16 + 3).min(10))])
+                .split(area);
+            zombies::draw(f, chunks[1], &app.zombies);
+            chunks[0]
+        }
+    };
+
+    // g: one row per name or application instead of per process
+    if let Some(grouping) = app.process_groups.grouping {
+        let groups = app.process_group_list();
+        let processes: usize = groups.iter().map(|group| group.pids.len()).sum();
+        let title = freshness::title(
+            &format!(
+                "📦 {} processes in {} groups by {} (sorted by {}) · k + - E act on a whole group, g {}{}",
+                processes,
+                groups.len(),
+                grouping.name(),
+                if app.process_sort == ProcessSort::Memory { "memory" } else { "CPU" },
+                if grouping == Grouping::Name { "by application" } else { "ungroup" },
+                marks_hint(app)
+            ),
+            "",
+            app.collected_at(Collector::Processes),
+            false,
+        );
+        groups::draw(f, area, &app.process_groups, &groups, title);
+        return;
+    }
+
+    if app.process_view == ProcessView::Single {
+        let processes = app.process_list();
+        // Recent exits take the last rows, leaving live ones at least one
+        let exited: Vec<&Exited> = app
+            .churn
+            .exited
+            .iter()
+            .filter(|exited| app.shows_exited(exited))
+            .take(churn::MAX_SHOWN.min(area.height.saturating_sub(4) as usize))
+            .collect();
+        // Only the rows on screen are built; at least one, however small the terminal
+        let page = ((area.height.saturating_sub(3) as usize).saturating_sub(exited.len())).max(1);
+        let selected = app.selected_process.min(processes.len().saturating_sub(1));
+        let (first_row, _) = app.process_scroll.get();
+        let first_row = scroll_to(first_row, selected, page, processes.len());
+        let visible = app.resolve(&processes[first_row..(first_row + page).min(processes.len())]);
+        prepare_process_rows(app, &columns, &visible);
+        let cache = app.process_rows.borrow();
+        let mut rows: Vec<Row> = visible.iter().map(|(pid, _)| highlighted(cache.row(*pid), *pid, app)).collect();
+        rows.extend(exited.iter().map(|exited| churn::exited_row(exited, &columns, app.user_names.name(exited.user.as_ref()))));
+        let descending = app.process_sort.descending() != app.process_sort_reversed;
+        let title = freshness::title(
+            &format!(
+                "💾 Processes (sorted by {} {}) · c m P n sort, again to reverse{}{}{}",
+                app.process_sort.title(),
+                if descending { "↓" } else { "↑" },
+                commands_hint(app),
+                marks_hint(app),
+                follow_hint(app)
+            ),
+            "",
+            app.collected_at(Collector::Processes),
+            false,
+        );
+        *app.process_headers.borrow_mut() = header_cells(area, &columns);
+        let table = columns_table(rows, &columns, offset, Some((app.process_sort.column(), descending)))
+            .block(Block::default().title(title).borders(Borders::ALL));
+        let mut state = TableState::default().with_selected(Some(selected.saturating_sub(first_row)));
+        f.render_stateful_widget(table, area, &mut state);
+        app.process_scroll.set((first_row, page));
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(area);
 
-    // Top CPU processes
-    let mut cpu_processes: Vec<_> = app.system.processes()
-        .iter()
-        .map(|(pid, process)| (*pid, process))
-        .collect();
-    cpu_processes.sort_by(|a, b| {
-        b.1.cpu_usage().partial_cmp(&a.1.cpu_usage()).unwrap_or(std::cmp::Ordering::Equal)
-    });
+    let mut top_cpu = app.filtered_processes(ProcessSort::Cpu);
+    top_cpu.truncate(SPLIT_ROWS);
+    let top_cpu = app.resolve(&top_cpu);
+    let mut top_memory = app.filtered_processes(ProcessSort::Memory);
+    top_memory.truncate(SPLIT_ROWS);
+    let top_memory = app.resolve(&top_memory);
+    prepare_process_rows(app, &columns, top_cpu.iter().chain(&top_memory));
+    let cache = app.process_rows.borrow();
 
-    let cpu_header = Row::new(vec!["PID", "Name", "CPU %"])
-        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
-    let cpu_rows: Vec<Row> = cpu_processes
-        .iter()
-        .take(15)
-        .map(|(pid, process)| {
-            Row::new(vec![
-                Cell::from(format!("{}", pid)),
-                Cell::from(truncate_name(process.name().to_string_lossy().as_ref(), 25)),
-                Cell::from(format!("{:.1}%", process.cpu_usage())),
-            ])
-        })
-        .collect();
+    // Top CPU processes
+    let cpu_rows: Vec<Row> = top_cpu.iter().map(|(pid, _)| highlighted(cache.row(*pid), *pid, app)).collect();
 
-    let cpu_table = Table::new(
-        cpu_rows,
-        &[Constraint::Length(8), Constraint::Min(20), Constraint::Length(8)]
-    )
-        .header(cpu_header)
-        .block(Block::default().title("⚡ Top CPU Processes").borders(Borders::ALL))
-        .column_spacing(1);
-    f.render_widget(cpu_table, chunks[0]);
+    let cpu_count = cpu_rows.len();
+    let cpu_table = columns_table(cpu_rows, &columns, offset, Some((Column::Cpu, true)))
+        .block(Block::default().title(freshness::title(&format!("⚡ Top CPU Processes{}{}{}", commands_hint(app), marks_hint(app), follow_hint(app)), "", app.collected_at(Collector::Processes), false)).borders(Borders::ALL));
+    let mut state = TableState::default().with_selected((app.selected_process < cpu_count).then_some(app.selected_process));
+    f.render_stateful_widget(cpu_table, chunks[0], &mut state);
 
     // Top Memory processes
-    let mut mem_processes: Vec<_> = app.system.processes()
+    let mem_rows: Vec<Row> = top_memory.iter().map(|(pid, _)| highlighted(cache.row(*pid), *pid, app)).collect();
+
+    let mem_table = columns_table(mem_rows, &columns, offset, Some((Column::Memory, true)))
+        .block(Block::default().title(freshness::title(&format!("💾 Top Memory Processes{}{}", commands_hint(app), marks_hint(app)), "", app.collected_at(Collector::Processes), false)).borders(Borders::ALL));
+    let mut state = TableState::default().with_selected(app.selected_process.checked_sub(cpu_count));
+    f.render_stateful_widget(mem_table, chunks[1], &mut state);
+    let mut headers = header_cells(chunks[0], &columns);
+    headers.extend(header_cells(chunks[1], &columns));
+    *app.process_headers.borrow_mut() = headers;
+}
+
+// Where a bordered columns_table puts each header, laid out the way Table
+// lays out its columns
+fn header_cells(area: Rect, columns: &[ColumnSetting]) -> Vec<(Rect, Column)> {
+    let inner = area.inner(Margin::new(1, 1));
+    let header = Rect { height: inner.height.min(1), ..inner };
+    let cells = Layout::horizontal(columns.iter().map(ColumnSetting::constraint))
+        .flex(Flex::Legacy)
+        .spacing(1)
+        .split(header);
+    cells.iter().copied().zip(columns.iter().map(|c| c.column)).collect()
+}
+
+fn limited_process_count(app: &App) -> usize {
+    if privilege::is_root() {
+        return 0;
+    }
+    app.processes.processes()
+        .values()
+        .filter(|p| p.thread_kind().is_none())
+        .filter(|p| p.user_id().is_some_and(privilege::is_other_user))
+        .count()
+}
+
+// What refresh_processes reads, plus command lines for the / filter
+fn process_refresh_kind() -> ProcessRefreshKind {
+    ProcessRefreshKind::nothing()
+        .with_memory()
+        .with_cpu()
+        .with_disk_usage()
+        .with_exe(UpdateKind::OnlyIfNotSet)
+        .with_cmd(UpdateKind::OnlyIfNotSet)
+        .with_tasks()
+        // Always, a process may switch users after it starts (su, sshd)
+        .with_user(UpdateKind::Always)
+}
+
+// After every process refresh: disk rates from the change in each process's
+// byte counters since its previous refresh
+fn update_process_io(io: &mut HashMap<Pid, ProcessIo>, system: &System) {
+    let now = Instant::now();
+    let processes = system.processes();
+    io.retain(|pid, _| processes.contains_key(pid));
+    for (pid, process) in processes {
+        let disk = process.disk_usage();
+        let (read, written) = (disk.total_read_bytes, disk.total_written_bytes);
+        let rates = io.get(pid).map(|last| {
+            let secs = now.duration_since(last.at).as_secs_f64().max(0.001);
+            // A reused PID starts its counters over
+            (read.saturating_sub(last.read) as f64 / secs, written.saturating_sub(last.written) as f64 / secs)
+        });
+        io.insert(*pid, ProcessIo { read, written, at: now, rates });
+    }
+}
+
+// Biggest consumers first for CPU/memory/disk, ascending for PID/name; the
+// table keeps its own order (see order.rs)
+#[cfg(feature = "api")]
+fn sorted_processes(system: &System, sort: ProcessSort) -> Vec<(Pid, &Process)> {
+    let mut processes: Vec<_> = system.processes()
         .iter()
+        .filter(|(_, process)| process.thread_kind().is_none())
         .map(|(pid, process)| (*pid, process))
         .collect();
-    mem_processes.sort_by(|a, b| b.1.memory().cmp(&a.1.memory()));
+    processes.sort_by_key(|(pid, _)| *pid);
+    processes.sort_by(|a, b| sort_key(sort, a.0, a.1).compare(&sort_key(sort, b.0, b.1)));
+    processes
+}
+
+// Where a process goes in the table; disk rates and FDs are the App's and
+// set in filtered_processes, PID orders the idle ones here
+fn sort_key(sort: ProcessSort, pid: Pid, process: &Process) -> SortKey {
+    match sort {
+        ProcessSort::Cpu => SortKey::Number(-process.cpu_usage() as f64),
+        ProcessSort::Memory => SortKey::Number(-(process.memory() as f64)),
+        ProcessSort::Threads => SortKey::Number(thread_count(process).map_or(f64::INFINITY, |count| -(count as f64))),
+        // Longest-running first
+        ProcessSort::Started => SortKey::Number(process.start_time() as f64),
+        ProcessSort::CpuTime => SortKey::Number(-(process.accumulated_cpu_time() as f64)),
+        ProcessSort::Name => SortKey::Text(process.name().to_ascii_lowercase().to_string_lossy().into_owned()),
+        ProcessSort::Pid | ProcessSort::DiskRead | ProcessSort::DiskWrite | ProcessSort::Fds => SortKey::Number(pid.as_u32() as f64),
+    }
+}
 
-    let mem_header = Row::new(vec!["PID", "Name", "Memory"])
+// Table with a header row built from the chosen columns. When scrolled
+// horizontally the first scrolled header is marked so hidden columns are obvious,
+// and the column the rows are sorted by gets an arrow.
+fn columns_table<'a>(rows: Vec<Row<'a>>, columns: &[ColumnSetting], offset: usize, sorted: Option<(Column, bool)>) -> Table<'a> {
+    let header = Row::new(columns.iter().enumerate().map(|(i, c)| {
+        let mut header = if i == 1 && offset > 0 {
+            format!("« {}", c.column.header())
+        } else {
+            c.column.header().to_string()
+        };
+        match sorted {
+            Some((column, true)) if column == c.column => header.push_str(" ↓"),
+            Some((column, false)) if column == c.column => header.push_str(" ↑"),
+            _ => {}
+        }
+        header
+    }))
         .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
-    let mem_rows: Vec<Row> = mem_processes
-        .iter()
-        .take(15)
-        .map(|(pid, process)| {
-            Row::new(vec![
-                Cell::from(format!("{}", pid)),
-                Cell::from(truncate_name(process.name().to_string_lossy().as_ref(), 25)),
-                Cell::from(format_bytes(process.memory())),
-            ])
-        })
-        .collect();
+    Table::new(rows, columns.iter().map(|c| c.constraint()))
+        .header(header)
+        .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .column_spacing(1)
+}
+
+// For the few rows outside the cached tables, such as the watched ones
+fn process_row(pid: Pid, process: &Process, columns: &[ColumnSetting], app: &App) -> Row<'static> {
+    let row = Row::new(columns.iter().map(|c| {
+        let (text, style) = process_cell(c.column, pid, process, app);
+        Cell::from(text).style(style)
+    }));
+    highlighted(row, pid, app)
+}
+
+// The first of `len` rows on a page of `page` that shows `selected`,
+// scrolling the way Table does: the first row stays put until the selection
+// leaves the page
+fn scroll_to(first_row: usize, selected: usize, page: usize, len: usize) -> usize {
+    let page = page.max(1);
+    first_row.min(selected).max((selected + 1).saturating_sub(page)).min(len.saturating_sub(page))
+}
+
+// Formats the rows of the process tables the cache lacks for this refresh
+fn prepare_process_rows<'a>(app: &App, columns: &[ColumnSetting], processes: impl IntoIterator<Item = &'a (Pid, &'a Process)>) {
+    let key = CacheKey {
+        refreshed: app.last_update,
+        columns: columns.iter().map(|c| c.column).collect(),
+        show_commands: app.show_commands,
+        command_offset: app.command_offset,
+        units: app.config.units.clone(),
+    };
+    app.process_rows
+        .borrow_mut()
+        .prepare(key, processes, |column, pid, process| process_cell(column, pid, process, app));
+}
+
+// Marked processes stand out in cyan, ones new since the last refresh in green
+fn highlighted<'a>(row: Row<'a>, pid: Pid, app: &App) -> Row<'a> {
+    if app.marks.contains(pid) {
+        row.style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+    } else if app.churn.is_new(pid) {
+        row.style(Style::default().fg(Color::Green))
+    } else {
+        row
+    }
+}
+
+fn process_cell(column: Column, pid: Pid, process: &Process, app: &App) -> (String, Style) {
+    let text = match column {
+        Column::Pid => pid.to_string(),
+        Column::User => app.user_names.name(process.user_id()),
+        Column::Name => {
+            let name = match app.show_commands {
+                true => command_line(process).chars().skip(app.command_offset).collect::<String>(),
+                false => process.name().to_string_lossy().into_owned(),
+            };
+            // Still running code an upgrade deleted
+            match app.outdated.contains(pid.as_u32()) {
+                true => return (format!("♻ {}", name), Style::default().fg(Color::Yellow)),
+                false => name,
+            }
+        }
+        Column::Cpu => format!("{:.1}%", process.cpu_usage()),
+        Column::Memory => format_bytes(process.memory()),
+        Column::Threads => thread_count(process).map(|count| count.to_string()).unwrap_or_else(|| "-".to_string()),
+        Column::Fds => app.fd_counts.get(&pid).map(|count| count.to_string()).unwrap_or_else(|| "-".to_string()),
+        Column::Started => format_uptime(process.run_time()),
+        Column::CpuTime => time_plus(process.accumulated_cpu_time()),
+        Column::DiskRead | Column::DiskWrite => {
+            let rate = app.disk_rates(pid).map(|(read, written)| if column == Column::DiskRead { read } else { written });
+            match rate {
+                Some(rate) => format!("{}/s", format_bytes(rate as u64)),
+                None => "…".to_string(),
+            }
+        }
+        _ => String::new(),
+    };
+    (text, Style::default())
+}
+
+// Title note while the Name column shows command lines
+fn commands_hint(app: &App) -> String {
+    match (app.show_commands, app.command_offset) {
+        (false, _) => String::new(),
+        (true, 0) => " · command lines, [ ] scroll, w names".to_string(),
+        (true, offset) => format!(" · command lines from character {}, [ ] scroll, w names", offset + 1),
+    }
+}
+
+// Title note while F keeps the selection on one process
+fn follow_hint(app: &App) -> String {
+    match &app.followed {
+        Some((pid, _, name)) => format!(" · 👣 following {} ({}), F stops", name, pid),
+        None => String::new(),
+    }
+}
+
+// Title note while processes are marked
+fn marks_hint(app: &App) -> String {
+    match app.marks.len() {
+        0 => String::new(),
+        marked => format!(" · {} marked for k +/- E, Esc unmarks", marked),
+    }
+}
+
+// tasks() leaves out the main thread; None where the platform doesn't list
+// threads, and for the threads sysinfo also lists as processes
+fn thread_count(process: &Process) -> Option<usize> {
+    process.tasks().map(|tasks| tasks.len() + 1)
+}
+
+// "123:45.67" minutes, seconds and hundredths from milliseconds, like TIME+ in top
+fn time_plus(millis: u64) -> String {
+    format!("{}:{:02}.{:02}", millis / 60_000, millis / 1000 % 60, millis % 1000 / 10)
+}
+
+// Kernel threads and zombies have no command line; shown as [name] like ps
+fn command_line(process: &Process) -> String {
+    if process.cmd().is_empty() {
+        return format!("[{}]", process.name().to_string_lossy());
+    }
+    let arguments: Vec<String> = process.cmd().iter().map(|arg| arg.to_string_lossy().into_owned()).collect();
+    arguments.join(" ")
+}
+
+fn network_cell(column: Column, interface: &InterfaceStat, sample_secs: f64) -> Cell<'static> {
+    match column {
+        Column::Interface => Cell::from(interface.name.clone()),
+        Column::Rx => Cell::from(format!("{}/s", format_bytes((interface.received as f64 / sample_secs) as u64))),
+        Column::Tx => Cell::from(format!("{}/s", format_bytes((interface.transmitted as f64 / sample_secs) as u64))),
+        Column::TotalRx => Cell::from(format_bytes(interface.total_received)),
+        Column::TotalTx => Cell::from(format_bytes(interface.total_transmitted)),
+        _ => Cell::from(""),
+    }
+}
 
-    let mem_table = Table::new(
-        mem_rows,
-        &[Constraint::Length(8), Constraint::Min(20), Constraint::Length(12)]
-    )
-        .header(mem_header)
-        .block(Block::default().title("💾 Top Memory Processes").borders(Borders::ALL))
-        .column_spacing(1);
-    f.render_widget(mem_table, chunks[1]);
+fn disk_cell(column: Column, disk: &Disk, app: &App) -> Cell<'static> {
+    let total = disk.total_space();
+    let used = total - disk.available_space();
+    let latency = || app.disk_latency.for_device(&disk.name().to_string_lossy());
+    // "-" until enough I/O went through to tell
+    let milliseconds = |ms: Option<f64>| match ms {
+        Some(ms) => {
+            let thresholds = &app.thresholds;
+            let style = if ms >= thresholds.io_latency_crit {
+                Style::default().fg(Color::Red)
+            } else if ms >= thresholds.io_latency_warn {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            Cell::from(format!("{:.1}", ms)).style(style)
+        }
+        None => Cell::from("-"),
+    };
+    match column {
+        Column::Disk => Cell::from(disk.name().to_string_lossy().into_owned()),
+        Column::Mount => Cell::from(disk.mount_point().display().to_string()),
+        Column::Usage => Cell::from(format!("{:.1}%", used as f64 / total as f64 * 100.0)),
+        Column::Used => Cell::from(format_bytes(used)),
+        Column::Total => Cell::from(format_bytes(total)),
+        Column::Growth => {
+            let (text, style) = growth::cell(app.disk_growth.per_hour(disk.mount_point(), used), disk.available_space());
+            Cell::from(text).style(style)
+        }
+        Column::ReadLatency => milliseconds(latency().and_then(|l| l.read)),
+        Column::WriteLatency => milliseconds(latency().and_then(|l| l.write)),
+        Column::Busy => Cell::from(latency().map(|l| format!("{:.0}%", l.busy)).unwrap_or_default()),
+        Column::Queue => Cell::from(latency().map(|l| format!("{:.1}", l.queue)).unwrap_or_default()),
+        _ => Cell::from(""),
+    }
 }
 
 // Helper functions
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+// Write through a temporary file renamed into place, so a crash mid-write
+// leaves the previous file instead of half of the new one
+fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    // Replace a symlink's target, not the link
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let temp = path.with_extension("tmp");
+    let mut file = fs::File::create(&temp)?;
+    file.write_all(contents.as_ref())?;
+    file.sync_all()?;
+    fs::rename(&temp, &path)
+}
+
 fn format_bytes(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let (step, names) = units::byte_units();
     let mut size = bytes as f64;
     let mut unit_index = 0;
     
-    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-        size /= 1024.0;
+    while size >= step && unit_index < names.len() - 1 {
+        size /= step;
         unit_index += 1;
     }
     
     if unit_index == 0 {
-        format!("{} {}", bytes, UNITS[unit_index])
+        format!("{} {}", bytes, names[unit_index])
     } else {
-        format!("{:.1} {}", size, UNITS[unit_index])
+        format!("{:.1} {}", size, names[unit_index])
     }
 }
 
@@ -457,7 +3473,7 @@ fn truncate_name(name: &str, max_len: usize) -> String {
     }
 }
 
-fn get_cpu_temperature(components: &Components) -> String {
+fn get_cpu_temperature(components: &Components, thresholds: &Thresholds) -> String {
     let mut cpu_temps = Vec::new();
     
     for component in components {
@@ -475,28 +3491,37 @@ fn get_cpu_temperature(components: &Components) -> String {
         let avg_temp = cpu_temps.iter().sum::<f32>() / cpu_temps.len() as f32;
         let max_temp = cpu_temps.iter().fold(0.0f32, |a, &b| a.max(b));
         
-        let temp_status = if avg_temp > 80.0 {
+        let temp_status = if avg_temp > thresholds.temp_crit {
             "🔴"
-        } else if avg_temp > 70.0 {
+        } else if avg_temp > thresholds.temp_warn {
             "🟡"
         } else {
             "🟢"
         };
         
-        format!("🌡️ CPU Temp: {:.1}°C (max: {:.1}°C) {}", avg_temp, max_temp, temp_status)
+        format!(
+            "🌡️ CPU Temp: {} (max: {}) {}",
+            units::temperature(avg_temp, 1),
+            units::temperature(max_temp, 1),
+            temp_status
+        )
     } else {
-        "🌡️ CPU Temperature: Not available".to_string()
+        if privilege::is_root() {
+            "🌡️ CPU Temperature: Not available".to_string()
+        } else {
+            format!("🌡️ CPU Temperature: Not available (some sensors {})", privilege::root_hint())
+        }
     }
 }
 
-fn get_network_summary(networks: &Networks) -> (u64, u64, usize) {
+fn get_network_summary(interfaces: &[InterfaceStat]) -> (u64, u64, usize) {
     let mut total_received = 0;
     let mut total_transmitted = 0;
     let mut active_interfaces = 0;
     
-    for (_interface_name, network) in networks {
-        let received = network.received();
-        let transmitted = network.transmitted();
+    for interface in interfaces {
+        let received = interface.received;
+        let transmitted = interface.transmitted;
         
         if received > 0 || transmitted > 0 {
             active_interfaces += 1;
@@ -508,27 +3533,42 @@ fn get_network_summary(networks: &Networks) -> (u64, u64, usize) {
     (total_received, total_transmitted, active_interfaces)
 }
 
-fn calculate_directory_size(path: &Path) -> Result<u64, std::io::Error> {
-    let mut total_size = 0u64;
-    
-    if !path.is_dir() {
-        return Ok(0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_stays_put_while_the_selection_is_on_it() {
+        assert_eq!(scroll_to(2, 4, 5, 20), 2);
+        assert_eq!(scroll_to(2, 6, 5, 20), 2);
     }
-    
-    match fs::read_dir(path) {
-        Ok(entries) => {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    if let Ok(metadata) = entry.metadata() {
-                        if metadata.is_file() {
-                            total_size += metadata.len();
-                        }
-                    }
-                }
-            }
+
+    #[test]
+    fn page_follows_the_selection_off_either_end() {
+        assert_eq!(scroll_to(0, 10, 5, 20), 6);
+        assert_eq!(scroll_to(8, 3, 5, 20), 3);
+    }
+
+    #[test]
+    fn page_never_runs_past_the_end() {
+        assert_eq!(scroll_to(18, 19, 5, 20), 15);
+        // The list shrank under the page
+        assert_eq!(scroll_to(18, 4, 5, 6), 1);
+        assert_eq!(scroll_to(7, 0, 5, 0), 0);
+    }
+
+    #[test]
+    fn empty_page_still_shows_the_selection() {
+        for (first_row, selected, len) in [(0, 0, 1), (5, 3, 10), (0, 9, 10), (9, 9, 10)] {
+            let first = scroll_to(first_row, selected, 0, len);
+            assert_eq!(first, selected);
         }
-        Err(e) => return Err(e),
     }
-    
-    Ok(total_size)
+
+    #[test]
+    fn time_plus_reads_like_top() {
+        assert_eq!(time_plus(0), "0:00.00");
+        assert_eq!(time_plus(61_230), "1:01.23");
+        assert_eq!(time_plus(7_384_560), "123:04.56");
+    }
 }
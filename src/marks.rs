@@ -0,0 +1,104 @@
+use crate::schema::{self, SCHEMA_VERSION};
+use crate::users::UserNames;
+use chrono::{Local, TimeZone};
+use std::{collections::HashMap, fs, path::PathBuf};
+use sysinfo::{Pid, Process, System};
+
+// Processes tagged with Space on the Processes tab, like htop's tagging, so
+// k, +/- and E act on all of them at once: a fork bomb or a stuck worker pool
+// leaves dozens of identical processes nobody wants to end one by one. A mark
+// remembers the start time, so it goes away with its process instead of
+// passing to a new one that gets the PID.
+#[derive(Default)]
+pub struct Marks {
+    marked: HashMap<Pid, u64>,
+}
+
+impl Marks {
+    pub fn toggle(&mut self, pid: Pid, start_time: u64) {
+        if self.marked.remove(&pid).is_none() {
+            self.marked.insert(pid, start_time);
+        }
+    }
+
+    pub fn contains(&self, pid: Pid) -> bool {
+        self.marked.contains_key(&pid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.marked.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marked.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.marked.clear();
+    }
+
+    // After each process refresh
+    pub fn prune(&mut self, system: &System) {
+        self.marked.retain(|pid, start_time| system.process(*pid).is_some_and(|process| process.start_time() == *start_time));
+    }
+
+    // The marked processes by PID, including those the filter hides
+    pub fn processes<'a>(&self, system: &'a System) -> Vec<(Pid, &'a Process)> {
+        let mut processes: Vec<(Pid, &Process)> = self.marked.keys().filter_map(|pid| system.process(*pid).map(|process| (*pid, process))).collect();
+        processes.sort_by_key(|(pid, _)| *pid);
+        processes
+    }
+}
+
+// E on the Processes tab: one CSV row per process, disk rates in bytes a
+// second and empty before a second sample; the columns are schema.rs's
+pub fn export(
+    processes: &[(Pid, &Process)],
+    user_names: &UserNames,
+    disk_rates: impl Fn(Pid) -> Option<(f64, f64)>,
+) -> Result<PathBuf, String> {
+    let dir = dirs::data_dir()
+        .ok_or("no data directory available")?
+        .join("system-monitor")
+        .join("processes");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let path = dir.join(format!("processes-{}.csv", Local::now().format("%Y%m%d-%H%M%S")));
+    let header: Vec<&str> = schema::PROCESS_CSV.fields.iter().map(|field| field.name).collect();
+    let mut lines = vec![header.join(",")];
+    for (pid, process) in processes {
+        let (read, write) = match disk_rates(*pid) {
+            Some((read, write)) => (format!("{:.0}", read), format!("{:.0}", write)),
+            None => (String::new(), String::new()),
+        };
+        let started = Local
+            .timestamp_opt(process.start_time() as i64, 0)
+            .single()
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let command: Vec<String> = process.cmd().iter().map(|arg| arg.to_string_lossy().into_owned()).collect();
+        let fields = [
+            pid.to_string(),
+            user_names.name(process.user_id()),
+            process.name().to_string_lossy().into_owned(),
+            format!("{:.1}", process.cpu_usage()),
+            process.memory().to_string(),
+            read,
+            write,
+            started,
+            command.join(" "),
+            SCHEMA_VERSION.to_string(),
+        ];
+        lines.push(fields.iter().map(|field| quote(field)).collect::<Vec<_>>().join(","));
+    }
+    fs::write(&path, lines.join("\n") + "\n").map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,217 @@
+use crate::format_bytes;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+use std::fs;
+
+// Loaded kernel modules from /proc/modules and the kernel's taint flags from
+// /proc/sys/kernel/tainted, decoded. A tainted kernel (a proprietary or
+// out-of-tree driver, a past oops or machine check) explains a lot of odd
+// sensor and driver behaviour, and upstream won't look at bug reports from
+// one. The flags are read on every refresh for the Overview line, the module
+// list only while the Modules tab is open.
+const MODULES: &str = "/proc/modules";
+const TAINTED: &str = "/proc/sys/kernel/tainted";
+
+// Bit, letter and reason, from Documentation/admin-guide/tainted-kernels.rst
+const TAINT_FLAGS: [(u32, char, &str); 20] = [
+    (0, 'P', "proprietary module loaded"),
+    (1, 'F', "module force-loaded"),
+    (2, 'S', "running on out-of-spec hardware"),
+    (3, 'R', "module force-unloaded"),
+    (4, 'M', "machine check exception"),
+    (5, 'B', "bad page referenced"),
+    (6, 'U', "tainted on request from user space"),
+    (7, 'D', "kernel oops or BUG since boot"),
+    (8, 'A', "ACPI table overridden"),
+    (9, 'W', "kernel warning since boot"),
+    (10, 'C', "staging driver loaded"),
+    (11, 'I', "platform firmware bug worked around"),
+    (12, 'O', "out-of-tree module loaded"),
+    (13, 'E', "unsigned module loaded"),
+    (14, 'L', "soft lockup since boot"),
+    (15, 'K', "kernel live-patched"),
+    (16, 'X', "distribution-specific taint"),
+    (17, 'T', "built with struct randomization"),
+    (18, 'N', "in-kernel test run"),
+    (19, 'J', "fwctl debug operation used"),
+];
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub name: String,
+    pub size: u64,
+    // Modules and other holders keeping it loaded
+    pub use_count: u64,
+    pub used_by: Vec<String>,
+    // Live, Loading or Unloading
+    pub state: String,
+    // The module's own taint letters, e.g. "POE"
+    pub taint: String,
+}
+
+#[derive(Default)]
+pub struct KernelModules {
+    pub modules: Vec<Module>,
+    pub error: Option<String>,
+    // None where there is no taint file
+    pub taint: Option<u64>,
+    pub selected: usize,
+}
+
+impl KernelModules {
+    pub fn update_taint(&mut self) {
+        self.taint = fs::read_to_string(TAINTED).ok().and_then(|taint| taint.trim().parse().ok());
+    }
+
+    pub fn update(&mut self) {
+        match fs::read_to_string(MODULES) {
+            Ok(modules) => {
+                self.modules = modules.lines().filter_map(parse).collect();
+                self.modules.sort_by_key(|module| std::cmp::Reverse(module.size));
+                self.error = None;
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound && cfg!(target_os = "linux") => {
+                self.modules.clear();
+                self.error = Some("This kernel has no loadable module support; all drivers are built in.".to_string());
+            }
+            Err(err) => {
+                self.modules.clear();
+                self.error = Some(format!("{}: {}", MODULES, err));
+            }
+        }
+        self.selected = self.selected.min(self.modules.len().saturating_sub(1));
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.selected = self.selected.saturating_add_signed(delta).min(self.modules.len().saturating_sub(1));
+    }
+
+    // One line for the System Information panel while the kernel is tainted
+    pub fn status(&self) -> Option<String> {
+        let reasons = reasons(self.taint.filter(|taint| *taint != 0)?);
+        Some(format!(
+            "☣️ Kernel tainted: {}",
+            reasons.iter().map(|(letter, reason)| format!("{} ({})", reason, letter)).collect::<Vec<_>>().join(", ")
+        ))
+    }
+}
+
+// Set flags as (letter, reason); unknown bits as "bit N"
+fn reasons(taint: u64) -> Vec<(String, String)> {
+    (0..64)
+        .filter(|bit| taint & (1 << bit) != 0)
+        .map(|bit| match TAINT_FLAGS.iter().find(|(flag, _, _)| *flag == bit) {
+            Some((_, letter, reason)) => (letter.to_string(), reason.to_string()),
+            None => (format!("bit {}", bit), "unknown".to_string()),
+        })
+        .collect()
+}
+
+// "nvidia 56823808 1543 nvidia_modeset,nvidia_uvm, Live 0xffffffffc0a00000 (POE)";
+// the address reads 0 without root and the taint part is only there when set
+fn parse(line: &str) -> Option<Module> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 5 {
+        return None;
+    }
+    let used_by = match fields[3] {
+        "-" => Vec::new(),
+        holders => holders.split(',').filter(|holder| !holder.is_empty()).map(str::to_string).collect(),
+    };
+    let taint = fields.get(6).map(|taint| taint.trim_matches(|c| c == '(' || c == ')').to_string()).unwrap_or_default();
+    Some(Module {
+        name: fields[0].to_string(),
+        size: fields[1].parse().ok()?,
+        use_count: fields[2].parse().unwrap_or(0),
+        used_by,
+        state: fields[4].to_string(),
+        taint,
+    })
+}
+
+pub fn draw_modules_tab(f: &mut Frame, area: Rect, modules: &KernelModules) {
+    let mut lines = match modules.taint {
+        None => vec![Line::from("No taint information (/proc/sys/kernel/tainted is missing).")],
+        Some(0) => vec![Line::styled("✅ Not tainted", Style::default().fg(Color::Green))],
+        Some(taint) => reasons(taint)
+            .into_iter()
+            .map(|(letter, reason)| Line::styled(format!("{}  {}", letter, reason), Style::default().fg(Color::Yellow)))
+            .collect(),
+    };
+    let tainting: Vec<String> = modules
+        .modules
+        .iter()
+        .filter(|module| !module.taint.is_empty())
+        .map(|module| format!("{} ({})", module.name, module.taint))
+        .collect();
+    if !tainting.is_empty() {
+        lines.push(Line::from(format!("Modules carrying taint: {}", tainting.join(", "))));
+    }
+    let title = match modules.taint {
+        Some(taint) if taint != 0 => format!("☣️ Kernel Taint — {}", taint),
+        _ => "☣️ Kernel Taint".to_string(),
+    };
+    let height = lines.len() as u16 + 2;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(height), Constraint::Min(0)])
+        .split(area);
+    let taint = Paragraph::new(lines).wrap(Wrap { trim: true }).block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(taint, chunks[0]);
+
+    let block = Block::default().borders(Borders::ALL);
+    if let Some(error) = &modules.error {
+        let hint = Paragraph::new(error.as_str()).wrap(Wrap { trim: true }).block(block.title("🧩 Kernel Modules"));
+        f.render_widget(hint, chunks[1]);
+        return;
+    }
+    let rows: Vec<Row> = modules
+        .modules
+        .iter()
+        .map(|module| {
+            let row = Row::new(vec![
+                module.name.clone(),
+                format_bytes(module.size),
+                module.use_count.to_string(),
+                module.used_by.join(", "),
+                module.state.clone(),
+                module.taint.clone(),
+            ]);
+            if module.taint.is_empty() {
+                row
+            } else {
+                row.style(Style::default().fg(Color::Yellow))
+            }
+        })
+        .collect();
+    let header = Row::new(vec!["Module", "Size", "Used", "Used by", "State", "Taint"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let total: u64 = modules.modules.iter().map(|module| module.size).sum();
+    let title = format!(
+        "🧩 Kernel Modules ({}, {}) — largest first · ↑/↓ scroll",
+        modules.modules.len(),
+        format_bytes(total)
+    );
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(24),
+            Constraint::Length(10),
+            Constraint::Length(5),
+            Constraint::Min(20),
+            Constraint::Length(9),
+            Constraint::Length(6),
+        ],
+    )
+    .header(header)
+    .block(block.title(title))
+    .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+    .column_spacing(1);
+    let mut state = TableState::default().with_selected(Some(modules.selected));
+    f.render_stateful_widget(table, chunks[1], &mut state);
+}
@@ -0,0 +1,153 @@
+use crate::channels::{AlertChannel, Notification};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+// Publishes each notification as a JSON message with a minimal MQTT 3.1.1
+// client: connect, one QoS 0 PUBLISH, disconnect. Plain TCP only; use a
+// local broker or bridge for TLS.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    // Broker host:port
+    pub address: String,
+    // {host}, {severity} and {source} are filled in
+    pub topic: String,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub retain: bool,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        MqttConfig {
+            address: "127.0.0.1:1883".to_string(),
+            topic: "system-monitor/{host}/alerts".to_string(),
+            client_id: String::new(),
+            username: None,
+            password: None,
+            retain: false,
+        }
+    }
+}
+
+impl MqttConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.address.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {}
+            _ => return Err("address must be host:port".to_string()),
+        }
+        if self.topic.is_empty() || self.topic.contains(['+', '#']) {
+            return Err("topic must be non-empty and without wildcards".to_string());
+        }
+        if self.password.is_some() && self.username.is_none() {
+            return Err("password needs a username".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl AlertChannel for MqttConfig {
+    fn deliver(&self, notification: &Notification) -> Result<(), String> {
+        let payload = json!({
+            "event": notification.event,
+            "severity": notification.label(),
+            "source": notification.source,
+            "message": notification.message,
+            "host": notification.host,
+            "time": notification.time,
+            "detail": notification.detail,
+        });
+        let client_id = if self.client_id.is_empty() {
+            format!("system-monitor-{}", std::process::id())
+        } else {
+            self.client_id.clone()
+        };
+        publish(self, &client_id, &notification.fill(&self.topic), payload.to_string().as_bytes())
+            .map_err(|e| e.to_string().split(" (os error").next().unwrap_or_default().to_string())
+    }
+}
+
+fn publish(config: &MqttConfig, client_id: &str, topic: &str, payload: &[u8]) -> std::io::Result<()> {
+    let addr = config
+        .address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no address found"))?;
+    let timeout = Duration::from_secs(10);
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    // CONNECT: protocol "MQTT" level 4, clean session, 60s keep-alive
+    let mut flags = 0x02;
+    let mut body = Vec::new();
+    put_string(&mut body, b"MQTT");
+    body.push(4);
+    let flags_at = body.len();
+    body.push(0);
+    body.extend_from_slice(&60u16.to_be_bytes());
+    put_string(&mut body, client_id.as_bytes());
+    if let Some(username) = &config.username {
+        flags |= 0x80;
+        put_string(&mut body, username.as_bytes());
+    }
+    if let Some(password) = &config.password {
+        flags |= 0x40;
+        put_string(&mut body, password.as_bytes());
+    }
+    body[flags_at] = flags;
+    stream.write_all(&packet(0x10, &body))?;
+
+    let mut connack = [0u8; 4];
+    stream.read_exact(&mut connack)?;
+    if connack[0] != 0x20 {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "broker did not answer with CONNACK"));
+    }
+    if connack[3] != 0 {
+        let reason = match connack[3] {
+            1 => "unacceptable protocol version",
+            2 => "client id rejected",
+            3 => "server unavailable",
+            4 => "bad username or password",
+            5 => "not authorized",
+            _ => "connection refused",
+        };
+        return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, reason));
+    }
+
+    let mut body = Vec::new();
+    put_string(&mut body, topic.as_bytes());
+    body.extend_from_slice(payload);
+    stream.write_all(&packet(if config.retain { 0x31 } else { 0x30 }, &body))?;
+    stream.write_all(&[0xe0, 0x00])
+}
+
+// Fixed header with the variable-length "remaining length" field
+fn packet(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![kind];
+    let mut length = body.len();
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+fn put_string(buffer: &mut Vec<u8>, value: &[u8]) {
+    buffer.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(value);
+}
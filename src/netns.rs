@@ -0,0 +1,166 @@
+use std::{collections::HashMap, fs, path::Path};
+use sysinfo::NetworkData;
+
+// Network namespaces found through /proc/<pid>/ns/net. Another namespace's
+// interfaces are read from /proc/<pid>/net/dev of a process living in it, so
+// no setns() (and no CAP_SYS_ADMIN) is needed. Without root only namespaces of
+// our own processes are visible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetNamespace {
+    pub inode: u64,
+    // Name from `ip netns` or the first process found in it
+    pub label: String,
+    pub pid: u32,
+}
+
+// One interface's traffic during the last sample, whichever namespace it is in
+#[derive(Debug, Clone)]
+pub struct InterfaceStat {
+    pub name: String,
+    pub received: u64,
+    pub transmitted: u64,
+    pub total_received: u64,
+    pub total_transmitted: u64,
+}
+
+impl InterfaceStat {
+    pub fn from_sysinfo(name: &str, network: &NetworkData) -> InterfaceStat {
+        InterfaceStat {
+            name: name.to_string(),
+            received: network.received(),
+            transmitted: network.transmitted(),
+            total_received: network.total_received(),
+            total_transmitted: network.total_transmitted(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn inode(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).ok().map(|m| m.ino())
+}
+
+#[cfg(not(unix))]
+fn inode(_path: &Path) -> Option<u64> {
+    None
+}
+
+pub fn own_inode() -> Option<u64> {
+    inode(Path::new("/proc/self/ns/net"))
+}
+
+// Every namespace we can see, our own first
+pub fn list() -> Vec<NetNamespace> {
+    let Some(own) = own_inode() else {
+        return Vec::new();
+    };
+
+    let mut named: HashMap<u64, String> = HashMap::new();
+    if let Ok(entries) = fs::read_dir("/run/netns") {
+        for entry in entries.flatten() {
+            if let Some(ino) = inode(&entry.path()) {
+                named.insert(ino, entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    let mut pids: Vec<u32> = fs::read_dir("/proc")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .collect();
+    pids.sort_unstable();
+
+    let mut namespaces: Vec<NetNamespace> = Vec::new();
+    for pid in pids {
+        let Some(ino) = inode(Path::new(&format!("/proc/{}/ns/net", pid))) else {
+            continue;
+        };
+        if namespaces.iter().any(|ns| ns.inode == ino) {
+            continue;
+        }
+        let label = if ino == own {
+            "host".to_string()
+        } else if let Some(name) = named.get(&ino) {
+            name.clone()
+        } else {
+            let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).unwrap_or_default();
+            format!("{} ({})", comm.trim(), pid)
+        };
+        namespaces.push(NetNamespace { inode: ino, label, pid });
+    }
+    namespaces.sort_by_key(|ns| ns.inode != own);
+    namespaces
+}
+
+// Interface counters of a namespace other than ours
+pub struct NamespaceNetworks {
+    pub namespace: NetNamespace,
+    pub interfaces: Vec<InterfaceStat>,
+    pub error: Option<String>,
+}
+
+impl NamespaceNetworks {
+    pub fn new(namespace: NetNamespace) -> NamespaceNetworks {
+        let mut networks = NamespaceNetworks { namespace, interfaces: Vec::new(), error: None };
+        networks.refresh();
+        // The first read has nothing to compare against
+        for interface in &mut networks.interfaces {
+            interface.received = 0;
+            interface.transmitted = 0;
+        }
+        networks
+    }
+
+    pub fn refresh(&mut self) {
+        // The process we read through may have exited; find another one in the namespace
+        if inode(Path::new(&format!("/proc/{}/ns/net", self.namespace.pid))) != Some(self.namespace.inode) {
+            match list().into_iter().find(|ns| ns.inode == self.namespace.inode) {
+                Some(ns) => self.namespace.pid = ns.pid,
+                None => {
+                    self.error = Some("namespace no longer exists".to_string());
+                    self.interfaces.clear();
+                    return;
+                }
+            }
+        }
+
+        let contents = match fs::read_to_string(format!("/proc/{}/net/dev", self.namespace.pid)) {
+            Ok(contents) => contents,
+            Err(err) => {
+                self.error = Some(err.to_string());
+                return;
+            }
+        };
+        let previous: HashMap<String, (u64, u64)> = self
+            .interfaces
+            .iter()
+            .map(|i| (i.name.clone(), (i.total_received, i.total_transmitted)))
+            .collect();
+
+        // "  eth0: rx_bytes rx_packets ... (8 rx fields) tx_bytes ..."
+        self.interfaces = contents
+            .lines()
+            .skip(2)
+            .filter_map(|line| {
+                let (name, counters) = line.split_once(':')?;
+                let fields: Vec<u64> = counters.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+                let (total_received, total_transmitted) = (*fields.first()?, *fields.get(8)?);
+                let (last_rx, last_tx) = previous
+                    .get(name.trim())
+                    .copied()
+                    .unwrap_or((total_received, total_transmitted));
+                Some(InterfaceStat {
+                    name: name.trim().to_string(),
+                    received: total_received.saturating_sub(last_rx),
+                    transmitted: total_transmitted.saturating_sub(last_tx),
+                    total_received,
+                    total_transmitted,
+                })
+            })
+            .collect();
+        self.error = None;
+    }
+}
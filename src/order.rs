@@ -0,0 +1,177 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    time::Instant,
+};
+use sysinfo::{Pid, Process, System};
+
+// The process table's order, kept from one refresh to the next rather than
+// rebuilt from the process map on every frame and key press. Each process is
+// remembered with the key it was placed by; on a refresh, exited processes
+// are dropped, and only new ones and ones whose key changed are placed
+// again, so processes that compare equal, like the many idle ones at 0% CPU,
+// stay in the same rows instead of shuffling every second. Between refreshes
+// the order is reused as it is, and rows look their process up only when
+// they are drawn.
+#[derive(Default)]
+pub struct ProcessOrder {
+    // The refresh the order is from
+    refreshed: Option<Instant>,
+    // In table order
+    pids: Vec<Pid>,
+    // The key each process was placed by
+    keys: HashMap<Pid, SortKey>,
+}
+
+// Processes placed again one by one; more are merged in a single pass
+const INSERT_UP_TO: usize = 32;
+
+// What a process sorts by, smallest first; descending sorts negate the value
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortKey {
+    Number(f64),
+    Text(String),
+}
+
+impl SortKey {
+    pub fn compare(&self, other: &SortKey) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+            (SortKey::Number(_), SortKey::Text(_)) => Ordering::Less,
+            (SortKey::Text(_), SortKey::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl ProcessOrder {
+    // key gives each process's place, at most once per refresh. Threads are
+    // in the process map too, since the refresh lists them for the Threads
+    // column, but get no rows of their own
+    pub fn update(&mut self, refreshed: Instant, system: &System, key: impl Fn(Pid, &Process) -> SortKey) {
+        if self.refreshed == Some(refreshed) {
+            return;
+        }
+        self.refreshed = Some(refreshed);
+        let processes = system.processes();
+        let process = |pid: Pid| processes.get(&pid).filter(|process| process.thread_kind().is_none());
+        self.reorder(
+            processes.keys().copied().filter(|pid| process(*pid).is_some()),
+            |pid| process(pid).map(|process| key(pid, process)),
+        );
+    }
+
+    // current lists every process now, key_of gives its key, None for an
+    // exited one
+    fn reorder(&mut self, current: impl Iterator<Item = Pid>, key_of: impl Fn(Pid) -> Option<SortKey>) {
+        let keys = &mut self.keys;
+        let mut moved: Vec<(Pid, SortKey)> = Vec::new();
+        self.pids.retain(|pid| {
+            let Some(now) = key_of(*pid) else {
+                keys.remove(pid);
+                return false;
+            };
+            if keys.get(pid) == Some(&now) {
+                return true;
+            }
+            moved.push((*pid, now));
+            false
+        });
+        // New processes after the moved ones, lowest PID first, so ties among
+        // them are in PID order
+        let start = moved.len();
+        moved.extend(current.filter(|pid| !keys.contains_key(pid)).filter_map(|pid| Some((pid, key_of(pid)?))));
+        moved[start..].sort_by_key(|(pid, _)| *pid);
+        moved.sort_by(|a, b| a.1.compare(&b.1));
+        self.place(moved);
+    }
+
+    // Sorted processes into the sorted list, after the ones they tie with
+    fn place(&mut self, moved: Vec<(Pid, SortKey)>) {
+        let keys = &mut self.keys;
+        if moved.len() <= INSERT_UP_TO {
+            for (pid, key) in moved {
+                let at = self.pids.partition_point(|placed| keys[placed].compare(&key) != Ordering::Greater);
+                self.pids.insert(at, pid);
+                keys.insert(pid, key);
+            }
+            return;
+        }
+        let mut placed = std::mem::take(&mut self.pids).into_iter().peekable();
+        self.pids.reserve(placed.len() + moved.len());
+        for (pid, key) in moved {
+            while let Some(before) = placed.next_if(|before| keys[before].compare(&key) != Ordering::Greater) {
+                self.pids.push(before);
+            }
+            self.pids.push(pid);
+            keys.insert(pid, key);
+        }
+        self.pids.extend(placed);
+    }
+
+    pub fn pids(&self) -> &[Pid] {
+        &self.pids
+    }
+}
+
+// One order per sort, since the split view shows two at once
+pub type ProcessOrders = HashMap<crate::ProcessSort, ProcessOrder>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A refresh where each (PID, key) is a running process
+    fn refresh(order: &mut ProcessOrder, processes: &[(u32, f64)]) -> Vec<u32> {
+        let keys: HashMap<Pid, SortKey> = processes.iter().map(|(pid, key)| (Pid::from_u32(*pid), SortKey::Number(*key))).collect();
+        order.reorder(keys.keys().copied(), |pid| keys.get(&pid).cloned());
+        order.pids().iter().map(|pid| pid.as_u32()).collect()
+    }
+
+    #[test]
+    fn first_refresh_sorts_ties_by_pid() {
+        let mut order = ProcessOrder::default();
+        assert_eq!(refresh(&mut order, &[(4, 0.0), (2, 0.0), (9, -5.0), (1, 0.0), (3, -1.0)]), [9, 3, 1, 2, 4]);
+    }
+
+    #[test]
+    fn ties_keep_their_rows() {
+        let mut order = ProcessOrder::default();
+        refresh(&mut order, &[(1, 0.0), (2, 0.0), (3, 0.0), (4, 0.0)]);
+        // 3 moves to the top and back down: it goes after the others it ties with
+        assert_eq!(refresh(&mut order, &[(1, 0.0), (2, 0.0), (3, -1.0), (4, 0.0)]), [3, 1, 2, 4]);
+        assert_eq!(refresh(&mut order, &[(1, 0.0), (2, 0.0), (3, 0.0), (4, 0.0)]), [1, 2, 4, 3]);
+        // Unchanged keys, unchanged order
+        assert_eq!(refresh(&mut order, &[(1, 0.0), (2, 0.0), (3, 0.0), (4, 0.0)]), [1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn drops_exited_and_places_new_processes() {
+        let mut order = ProcessOrder::default();
+        refresh(&mut order, &[(1, -3.0), (2, -2.0), (3, -1.0)]);
+        assert_eq!(refresh(&mut order, &[(1, -3.0), (3, -1.0), (7, -2.5), (5, 0.0), (6, -1.0)]), [1, 7, 3, 6, 5]);
+    }
+
+    #[test]
+    fn many_changes_merge_into_the_same_order() {
+        let mut order = ProcessOrder::default();
+        let before: Vec<(u32, f64)> = (1..=200).map(|pid| (pid, (pid % 7) as f64)).collect();
+        refresh(&mut order, &before);
+        // More than INSERT_UP_TO change at once
+        let after: Vec<(u32, f64)> = (1..=200).map(|pid| (pid, if pid % 3 == 0 { -(pid as f64) } else { (pid % 7) as f64 })).collect();
+        let pids = refresh(&mut order, &after);
+        let key = |pid: &u32| after[*pid as usize - 1].1;
+        assert_eq!(pids.len(), 200);
+        assert!(pids.windows(2).all(|pair| key(&pair[0]) <= key(&pair[1])));
+        // Unchanged ties kept their PID order from the first refresh
+        let sevens: Vec<u32> = pids.iter().copied().filter(|pid| pid % 3 != 0 && pid % 7 == 0).collect();
+        assert!(sevens.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn text_sorts_after_numbers() {
+        assert_eq!(SortKey::Text("a".to_string()).compare(&SortKey::Text("b".to_string())), Ordering::Less);
+        assert_eq!(SortKey::Number(f64::INFINITY).compare(&SortKey::Text(String::new())), Ordering::Less);
+        assert_eq!(SortKey::Number(-1.0).compare(&SortKey::Number(0.0)), Ordering::Less);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{cgroup::Cgroup, privilege};
+use std::{
+    fs,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Processes still running code an upgrade replaced, like needrestart finds
+// them: their executable or a mapped library shows up as "(deleted)" in
+// /proc/<pid>/exe or /proc/<pid>/maps, so they keep the old, possibly
+// vulnerable version until restarted. Only files under the system
+// directories count; deleted temporary files and memfds are normal. Scanned
+// in the background every half minute, other users' processes need root.
+const SCAN_INTERVAL: Duration = Duration::from_secs(30);
+const SYSTEM_DIRS: [&str; 7] = ["/usr/", "/lib/", "/lib32/", "/lib64/", "/bin/", "/sbin/", "/opt/"];
+const DELETED: &str = " (deleted)";
+// Names listed on the Overview line before "and N more"
+const LISTED: usize = 4;
+
+#[derive(Debug, Clone)]
+struct Stale {
+    pid: u32,
+    name: String,
+    // Deleted executable and libraries, without the " (deleted)"
+    files: Vec<String>,
+    // The systemd service to restart, where there is one
+    unit: Option<String>,
+}
+
+#[derive(Default)]
+struct Scan {
+    processes: Vec<Stale>,
+    // Processes whose maps couldn't be read
+    unreadable: usize,
+}
+
+#[derive(Default)]
+struct Loaded {
+    scan: Option<(Scan, Instant)>,
+    loading: bool,
+}
+
+#[derive(Default)]
+pub struct Outdated {
+    loaded: Arc<Mutex<Loaded>>,
+}
+
+impl Outdated {
+    // Called on every refresh, for the Overview line and the process table
+    pub fn scan_if_stale(&self) {
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+        let mut loaded = self.loaded.lock().unwrap();
+        let fresh = loaded.scan.as_ref().is_some_and(|(_, at)| at.elapsed() < SCAN_INTERVAL);
+        if fresh || loaded.loading {
+            return;
+        }
+        loaded.loading = true;
+        let shared = Arc::clone(&self.loaded);
+        thread::spawn(move || {
+            let result = scan();
+            let mut loaded = shared.lock().unwrap();
+            loaded.scan = Some((result, Instant::now()));
+            loaded.loading = false;
+        });
+    }
+
+    // For the marker in the Name column
+    pub fn contains(&self, pid: u32) -> bool {
+        let loaded = self.loaded.lock().unwrap();
+        loaded.scan.as_ref().is_some_and(|(scan, _)| scan.processes.iter().any(|stale| stale.pid == pid))
+    }
+
+    // The deleted files a process has mapped, for its details
+    pub fn files(&self, pid: u32) -> Option<Vec<String>> {
+        let loaded = self.loaded.lock().unwrap();
+        let (scan, _) = loaded.scan.as_ref()?;
+        scan.processes.iter().find(|stale| stale.pid == pid).map(|stale| stale.files.clone())
+    }
+
+    // One line for the System Information panel while anything needs a restart
+    pub fn status(&self) -> Option<String> {
+        let loaded = self.loaded.lock().unwrap();
+        let (scan, _) = loaded.scan.as_ref()?;
+        if scan.processes.is_empty() {
+            return None;
+        }
+        // A service counts once however many processes it has
+        let mut names: Vec<String> = Vec::new();
+        for stale in &scan.processes {
+            let name = stale.unit.clone().unwrap_or_else(|| format!("{} ({})", stale.name, stale.pid));
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        let mut listed = names.iter().take(LISTED).cloned().collect::<Vec<_>>().join(", ");
+        if names.len() > LISTED {
+            listed.push_str(&format!(" and {} more", names.len() - LISTED));
+        }
+        let mut status = format!(
+            "♻️ Restart needed: {} {} deleted code after an upgrade: {}",
+            scan.processes.len(),
+            if scan.processes.len() == 1 { "process runs" } else { "processes run" },
+            listed
+        );
+        if scan.unreadable > 0 && !privilege::is_root() {
+            status.push_str(&format!(" (other users' processes {})", privilege::root_hint()));
+        }
+        Some(status)
+    }
+}
+
+fn scan() -> Scan {
+    let mut result = Scan::default();
+    let Ok(entries) = fs::read_dir("/proc") else { return result };
+    let own = std::process::id();
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else { continue };
+        if pid == own {
+            continue;
+        }
+        let dir = entry.path();
+        // Kernel threads have no executable and nothing mapped
+        let (exe, maps) = match (fs::read_link(dir.join("exe")), fs::read_to_string(dir.join("maps"))) {
+            (Ok(exe), Ok(maps)) => (exe, maps),
+            (Err(err), _) | (_, Err(err)) => {
+                result.unreadable += usize::from(err.kind() == std::io::ErrorKind::PermissionDenied);
+                continue;
+            }
+        };
+        let mut files: Vec<String> = Vec::new();
+        let exe = exe.to_string_lossy();
+        // The path is the rest of a maps line from its first slash, spaces
+        // included; [heap], [stack] and anonymous mappings have none
+        let paths = maps.lines().filter_map(|line| line.find('/').map(|start| &line[start..]));
+        for path in std::iter::once(exe.as_ref()).chain(paths) {
+            let Some(path) = path.strip_suffix(DELETED) else { continue };
+            if SYSTEM_DIRS.iter().any(|dir| path.starts_with(dir)) && !files.iter().any(|file| file == path) {
+                files.push(path.to_string());
+            }
+        }
+        if files.is_empty() {
+            continue;
+        }
+        let name = fs::read_to_string(dir.join("comm")).map(|comm| comm.trim().to_string()).unwrap_or_default();
+        result.processes.push(Stale { pid, name, files, unit: unit(pid) });
+    }
+    result.processes.sort_by_key(|stale| stale.pid);
+    result
+}
+
+// "/system.slice/nginx.service" gives nginx.service; user sessions and
+// scopes have no service to restart
+fn unit(pid: u32) -> Option<String> {
+    let cgroup = Cgroup::of(&pid.to_string())?;
+    cgroup
+        .path
+        .rsplit('/')
+        .find(|part| part.ends_with(".service"))
+        .filter(|service| !service.starts_with("user@"))
+        .map(str::to_string)
+}
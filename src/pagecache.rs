@@ -0,0 +1,145 @@
+use crate::format_bytes;
+use crate::history::History;
+use crate::interrupts::rate;
+use std::{fs, time::Instant};
+
+// Page cache size and what the kernel does to keep it, to tell "the disk is
+// busy" apart from "the working set no longer fits and pages are read back
+// over and over". Sizes come from /proc/meminfo, counters from /proc/vmstat;
+// rates are recorded in the history every refresh.
+
+// Refaults per second (pages evicted and read back soon after) worth a warning
+const THRASHING: f64 = 1000.0;
+// History samples behind the trend bars
+const TREND: usize = 30;
+
+#[derive(Default, Clone, Copy)]
+struct Counters {
+    faults: u64,
+    major_faults: u64,
+    // Pages scanned for reclaim by kswapd and by allocating tasks
+    scan_kswapd: u64,
+    scan_direct: u64,
+    refaults: u64,
+}
+
+#[derive(Default)]
+pub struct PageCache {
+    previous: Option<(Instant, Counters)>,
+    // Bytes
+    pub cached: u64,
+    pub dirty: u64,
+    // Per second
+    pub minor_faults: f64,
+    pub major_faults: f64,
+    pub reclaim: f64,
+    pub direct_reclaim: f64,
+    pub refaults: f64,
+    pub updated: Option<Instant>,
+}
+
+impl PageCache {
+    pub fn update(&mut self) {
+        let Some((cached, dirty)) = read_meminfo() else { return };
+        let Some(counters) = read_vmstat() else { return };
+        let now = Instant::now();
+        self.cached = cached;
+        self.dirty = dirty;
+        if let Some((at, previous)) = self.previous {
+            let seconds = now.duration_since(at).as_secs_f64();
+            if seconds > 0.0 {
+                let per_second = |now: u64, before: u64| now.saturating_sub(before) as f64 / seconds;
+                let major = counters.major_faults.saturating_sub(previous.major_faults);
+                // pgfault counts major faults too
+                let all = counters.faults.saturating_sub(previous.faults);
+                self.minor_faults = all.saturating_sub(major) as f64 / seconds;
+                self.major_faults = major as f64 / seconds;
+                self.direct_reclaim = per_second(counters.scan_direct, previous.scan_direct);
+                self.reclaim = per_second(counters.scan_kswapd, previous.scan_kswapd) + self.direct_reclaim;
+                self.refaults = per_second(counters.refaults, previous.refaults);
+                self.updated = Some(now);
+            }
+        }
+        self.previous = Some((now, counters));
+    }
+
+    pub fn record(&self, history: &mut History) {
+        if self.updated.is_none() {
+            return;
+        }
+        history.record("page_cache", self.cached as f64);
+        history.record("minor_faults", self.minor_faults);
+        history.record("major_faults", self.major_faults);
+        history.record("reclaim", self.reclaim);
+        history.record("refaults", self.refaults);
+    }
+
+    // Two lines for the System Information panel
+    pub fn status(&self, history: &History) -> Vec<String> {
+        if self.updated.is_none() {
+            return Vec::new();
+        }
+        let mut cache = format!(
+            "🗂️ Page cache: {} (dirty {}), reclaim {} pages/s (direct {}/s), refaults {}/s {}",
+            format_bytes(self.cached),
+            format_bytes(self.dirty),
+            rate(self.reclaim),
+            rate(self.direct_reclaim),
+            rate(self.refaults),
+            trend(history, "refaults")
+        );
+        if self.refaults >= THRASHING {
+            cache.push_str(" ⚠ cache thrashing");
+        }
+        let faults = format!(
+            "📄 Page faults: {}/s minor, {}/s major {}",
+            rate(self.minor_faults),
+            rate(self.major_faults),
+            trend(history, "major_faults")
+        );
+        vec![cache, faults]
+    }
+}
+
+// Recent samples of a history metric as bars, scaled to the largest
+fn trend(history: &History, metric: &str) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let Some(series) = history.series(metric) else { return String::new() };
+    let shown: Vec<f64> = series.iter().skip(series.len().saturating_sub(TREND)).map(|(_, value)| *value).collect();
+    let largest = shown.iter().copied().fold(0.0, f64::max);
+    shown
+        .iter()
+        .map(|value| if largest > 0.0 { BARS[(value / largest * (BARS.len() - 1) as f64).round() as usize] } else { BARS[0] })
+        .collect()
+}
+
+// (Cached, Dirty) in bytes
+fn read_meminfo() -> Option<(u64, u64)> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let field = |name: &str| {
+        meminfo.lines().find_map(|line| {
+            let value = line.strip_prefix(name)?.strip_prefix(':')?;
+            value.trim().trim_end_matches(" kB").parse::<u64>().ok().map(|kb| kb * 1024)
+        })
+    };
+    Some((field("Cached")?, field("Dirty").unwrap_or(0)))
+}
+
+fn read_vmstat() -> Option<Counters> {
+    let vmstat = fs::read_to_string("/proc/vmstat").ok()?;
+    let mut counters = Counters::default();
+    for line in vmstat.lines() {
+        let Some((name, value)) = line.split_once(' ') else { continue };
+        let value: u64 = value.trim().parse().unwrap_or(0);
+        match name {
+            "pgfault" => counters.faults = value,
+            "pgmajfault" => counters.major_faults = value,
+            "pgscan_kswapd" => counters.scan_kswapd = value,
+            "pgscan_direct" => counters.scan_direct = value,
+            // workingset_refault before Linux 5.9
+            "workingset_refault_file" | "workingset_refault" => counters.refaults = value,
+            _ => {}
+        }
+    }
+    Some(counters)
+}
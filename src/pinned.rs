@@ -0,0 +1,176 @@
+use crate::{format_bytes, units, App, Collector};
+use std::str::FromStr;
+
+// A metric the user asked to keep visible in the title bar regardless of tab.
+// Parsed from config strings such as "cpu", "temp:amdgpu", "process:firefox:rss"
+// or "net:eth0:tx".
+#[derive(Debug, Clone, PartialEq)]
+pub enum PinnedMetric {
+    Cpu,
+    Memory,
+    Swap,
+    Load,
+    CpuTemp,
+    Temperature(String),
+    ProcessRss(String),
+    ProcessCpu(String),
+    NetRx(String),
+    NetTx(String),
+    Disk(String),
+}
+
+impl FromStr for PinnedMetric {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let spec = spec.trim();
+        let metric = match spec {
+            "cpu" => Some(PinnedMetric::Cpu),
+            "memory" | "mem" => Some(PinnedMetric::Memory),
+            "swap" => Some(PinnedMetric::Swap),
+            "load" => Some(PinnedMetric::Load),
+            "cpu_temp" => Some(PinnedMetric::CpuTemp),
+            _ => None,
+        };
+        if let Some(metric) = metric {
+            return Ok(metric);
+        }
+
+        if let Some(label) = spec.strip_prefix("temp:") {
+            return Ok(PinnedMetric::Temperature(label.to_string()));
+        }
+        if let Some(mount) = spec.strip_prefix("disk:") {
+            return Ok(PinnedMetric::Disk(mount.to_string()));
+        }
+        if let Some(rest) = spec.strip_prefix("process:") {
+            return match rest.rsplit_once(':') {
+                Some((name, "rss")) => Ok(PinnedMetric::ProcessRss(name.to_string())),
+                Some((name, "cpu")) => Ok(PinnedMetric::ProcessCpu(name.to_string())),
+                _ => Ok(PinnedMetric::ProcessRss(rest.to_string())),
+            };
+        }
+        if let Some(rest) = spec.strip_prefix("net:") {
+            return match rest.rsplit_once(':') {
+                Some((iface, "rx")) => Ok(PinnedMetric::NetRx(iface.to_string())),
+                Some((iface, "tx")) => Ok(PinnedMetric::NetTx(iface.to_string())),
+                _ => Err(format!("pinned metric '{}' must end in :rx or :tx", spec)),
+            };
+        }
+
+        Err(format!("unknown pinned metric '{}'", spec))
+    }
+}
+
+impl PinnedMetric {
+    // Collector that has to be running for this metric to have a value
+    pub fn collector(&self) -> Option<Collector> {
+        match self {
+            PinnedMetric::Cpu | PinnedMetric::Memory | PinnedMetric::Swap | PinnedMetric::Load => None,
+            PinnedMetric::CpuTemp | PinnedMetric::Temperature(_) => Some(Collector::Components),
+            PinnedMetric::ProcessRss(_) | PinnedMetric::ProcessCpu(_) => Some(Collector::Processes),
+            PinnedMetric::NetRx(_) | PinnedMetric::NetTx(_) => Some(Collector::Networks),
+            PinnedMetric::Disk(_) => Some(Collector::Disks),
+        }
+    }
+
+    // Short "label value" text for the title bar
+    pub fn render(&self, app: &App) -> String {
+        match self {
+            PinnedMetric::Cpu => format!("CPU {:.1}%", app.system.global_cpu_usage()),
+            PinnedMetric::Memory => format!(
+                "Mem {}/{}",
+                format_bytes(app.system.used_memory()),
+                format_bytes(app.system.total_memory())
+            ),
+            PinnedMetric::Swap => format!("Swap {}", format_bytes(app.system.used_swap())),
+            PinnedMetric::Load => format!("Load {:.2}", sysinfo::System::load_average().one),
+            PinnedMetric::CpuTemp => {
+                let temps: Vec<f32> = app
+                    .components
+                    .iter()
+                    .filter(|c| {
+                        let label = c.label().to_lowercase();
+                        label.contains("cpu") || label.contains("core") || label.contains("processor")
+                    })
+                    .filter_map(|c| c.temperature())
+                    .collect();
+                match temps.iter().cloned().reduce(f32::max) {
+                    Some(temp) => format!("CPU {}", units::temperature(temp, 0)),
+                    None => format!("CPU --{}", units::temperature_symbol()),
+                }
+            }
+            PinnedMetric::Temperature(label) => {
+                let wanted = label.to_lowercase();
+                let temp = app
+                    .components
+                    .iter()
+                    .filter(|c| c.label().to_lowercase().contains(&wanted))
+                    .filter_map(|c| c.temperature())
+                    .reduce(f32::max);
+                match temp {
+                    Some(temp) => format!("{} {}", label, units::temperature(temp, 0)),
+                    None => format!("{} --{}", label, units::temperature_symbol()),
+                }
+            }
+            PinnedMetric::ProcessRss(name) => {
+                let mut found = false;
+                let mut rss = 0;
+                for process in app.processes.processes().values().filter(|process| process.thread_kind().is_none()) {
+                    if process.name().to_string_lossy() == name.as_str() {
+                        found = true;
+                        rss += process.memory();
+                    }
+                }
+                if found {
+                    format!("{} {}", name, format_bytes(rss))
+                } else {
+                    format!("{} --", name)
+                }
+            }
+            PinnedMetric::ProcessCpu(name) => {
+                let mut found = false;
+                let mut cpu = 0.0;
+                for process in app.processes.processes().values().filter(|process| process.thread_kind().is_none()) {
+                    if process.name().to_string_lossy() == name.as_str() {
+                        found = true;
+                        cpu += process.cpu_usage();
+                    }
+                }
+                if found {
+                    format!("{} {:.1}%", name, cpu)
+                } else {
+                    format!("{} --", name)
+                }
+            }
+            PinnedMetric::NetRx(iface) | PinnedMetric::NetTx(iface) => {
+                let direction = if matches!(self, PinnedMetric::NetRx(_)) { "RX" } else { "TX" };
+                let network = app.networks.iter().find(|(name, _)| name.as_str() == iface);
+                match network {
+                    Some((_, network)) => {
+                        let bytes = if matches!(self, PinnedMetric::NetRx(_)) {
+                            network.received()
+                        } else {
+                            network.transmitted()
+                        };
+                        let rate = (bytes as f64 / app.sample_secs) as u64;
+                        format!("{} {} {}/s", iface, direction, format_bytes(rate))
+                    }
+                    None => format!("{} {} --", iface, direction),
+                }
+            }
+            PinnedMetric::Disk(mount) => {
+                let disk = app
+                    .disks
+                    .iter()
+                    .find(|d| d.mount_point().to_string_lossy() == mount.as_str());
+                match disk {
+                    Some(disk) if disk.total_space() > 0 => {
+                        let used = disk.total_space() - disk.available_space();
+                        format!("{} {:.0}%", mount, used as f64 / disk.total_space() as f64 * 100.0)
+                    }
+                    _ => format!("{} --", mount),
+                }
+            }
+        }
+    }
+}
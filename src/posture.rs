@@ -0,0 +1,239 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+    Frame,
+};
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+// A hardening checklist for the Health tab: whether SELinux or AppArmor is
+// enforcing, kernel and network sysctls against the values hardening guides
+// (CIS, KSPP) recommend, and whether security updates install themselves,
+// through unattended-upgrades on Debian and Ubuntu or dnf-automatic on
+// Fedora and RHEL. Read again every minute while the tab is open; nothing is
+// changed, the Recommended column says what to set.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+enum Want {
+    AtLeast(i64),
+    Exactly(i64),
+}
+
+// Name, what it protects against, recommended value
+const SYSCTLS: [(&str, &str, Want); 17] = [
+    ("kernel.kptr_restrict", "kernel addresses hidden", Want::AtLeast(1)),
+    ("kernel.dmesg_restrict", "dmesg for root only", Want::Exactly(1)),
+    ("kernel.yama.ptrace_scope", "ptrace of own children only", Want::AtLeast(1)),
+    ("kernel.unprivileged_bpf_disabled", "no BPF for users", Want::AtLeast(1)),
+    ("kernel.perf_event_paranoid", "perf for users limited", Want::AtLeast(2)),
+    ("kernel.randomize_va_space", "full ASLR", Want::Exactly(2)),
+    ("fs.protected_symlinks", "/tmp symlink attacks", Want::Exactly(1)),
+    ("fs.protected_hardlinks", "hardlink attacks", Want::Exactly(1)),
+    ("fs.protected_fifos", "/tmp FIFO spoofing", Want::AtLeast(1)),
+    ("fs.protected_regular", "/tmp file spoofing", Want::AtLeast(1)),
+    ("fs.suid_dumpable", "no setuid core dumps", Want::Exactly(0)),
+    ("net.core.bpf_jit_harden", "BPF JIT spraying", Want::AtLeast(1)),
+    ("net.ipv4.tcp_syncookies", "SYN floods", Want::Exactly(1)),
+    ("net.ipv4.conf.all.rp_filter", "spoofed source addresses", Want::AtLeast(1)),
+    ("net.ipv4.conf.all.accept_redirects", "ICMP redirects ignored", Want::Exactly(0)),
+    ("net.ipv4.conf.all.send_redirects", "no ICMP redirects sent", Want::Exactly(0)),
+    ("net.ipv4.conf.all.accept_source_route", "source routing refused", Want::Exactly(0)),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Hardened,
+    Weak,
+    // Not there, or not readable
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+struct Check {
+    name: String,
+    value: String,
+    recommended: String,
+    state: State,
+}
+
+#[derive(Default)]
+pub struct Posture {
+    checks: Vec<Check>,
+    updated: Option<Instant>,
+}
+
+impl Posture {
+    // Called on refresh while the Health tab is open
+    pub fn update_if_stale(&mut self) {
+        // All of it is Linux
+        if !cfg!(target_os = "linux") || self.updated.is_some_and(|at| at.elapsed() < UPDATE_INTERVAL) {
+            return;
+        }
+        self.updated = Some(Instant::now());
+        self.checks = mandatory_access_control();
+        self.checks.extend(SYSCTLS.iter().map(|(name, purpose, want)| sysctl(name, purpose, *want)));
+        self.checks.push(automatic_updates());
+    }
+
+    // Rows the panel takes, 0 before the first update
+    pub fn height(&self) -> u16 {
+        if self.checks.is_empty() {
+            0
+        } else {
+            self.checks.len() as u16 + 3
+        }
+    }
+}
+
+fn mandatory_access_control() -> Vec<Check> {
+    let mut checks = Vec::new();
+    // Present once SELinux is enabled, "1" enforcing and "0" permissive
+    if let Ok(enforce) = fs::read_to_string("/sys/fs/selinux/enforce") {
+        let enforcing = enforce.trim() == "1";
+        checks.push(Check {
+            name: "SELinux".to_string(),
+            value: if enforcing { "enforcing" } else { "permissive" }.to_string(),
+            recommended: "enforcing".to_string(),
+            state: if enforcing { State::Hardened } else { State::Weak },
+        });
+    }
+    if fs::read_to_string("/sys/module/apparmor/parameters/enabled").is_ok_and(|enabled| enabled.trim() == "Y") {
+        checks.push(apparmor());
+    }
+    if checks.is_empty() {
+        checks.push(Check {
+            name: "SELinux / AppArmor".to_string(),
+            value: "neither is active".to_string(),
+            recommended: "one enforcing".to_string(),
+            state: State::Weak,
+        });
+    }
+    checks
+}
+
+// The loaded profiles, "name (enforce)" or "name (complain)", need root to list
+fn apparmor() -> Check {
+    let mut check = Check {
+        name: "AppArmor".to_string(),
+        value: String::new(),
+        recommended: "profiles enforcing".to_string(),
+        state: State::Unknown,
+    };
+    match fs::read_to_string("/sys/kernel/security/apparmor/profiles") {
+        Ok(profiles) => {
+            let enforcing = profiles.lines().filter(|line| line.ends_with("(enforce)")).count();
+            let complaining = profiles.lines().filter(|line| line.ends_with("(complain)")).count();
+            check.value = format!("{} enforcing, {} complain", enforcing, complaining);
+            check.state = if enforcing > 0 { State::Hardened } else { State::Weak };
+        }
+        Err(_) => check.value = format!("enabled, profiles {}", crate::privilege::root_hint()),
+    }
+    check
+}
+
+fn sysctl(name: &str, purpose: &str, want: Want) -> Check {
+    let path = Path::new("/proc/sys").join(name.replace('.', "/"));
+    let value = fs::read_to_string(path).ok().and_then(|value| value.trim().parse::<i64>().ok());
+    let (recommended, met) = match want {
+        Want::AtLeast(minimum) => (format!("≥ {}", minimum), value.is_some_and(|value| value >= minimum)),
+        Want::Exactly(expected) => (expected.to_string(), value == Some(expected)),
+    };
+    Check {
+        name: format!("{} ({})", name, purpose),
+        // Missing when the kernel was built without it, e.g. Yama
+        value: value.map(|value| value.to_string()).unwrap_or_else(|| "n/a".to_string()),
+        recommended,
+        state: match (value, met) {
+            (None, _) => State::Unknown,
+            (Some(_), true) => State::Hardened,
+            (Some(_), false) => State::Weak,
+        },
+    }
+}
+
+// unattended-upgrades runs when APT::Periodic::Unattended-Upgrade is set to a
+// number of days other than 0; dnf-automatic when one of its timers is enabled
+fn automatic_updates() -> Check {
+    let mut check = Check {
+        name: "Automatic security updates".to_string(),
+        value: String::new(),
+        recommended: "on".to_string(),
+        state: State::Weak,
+    };
+    if Path::new("/usr/bin/unattended-upgrade").exists() {
+        let on = apt_periodic("APT::Periodic::Unattended-Upgrade").is_some_and(|days| days != "0");
+        check.value = format!("unattended-upgrades {}", if on { "on" } else { "installed, off" });
+        check.state = if on { State::Hardened } else { State::Weak };
+    } else if let Some(timer) = dnf_automatic_timer() {
+        check.value = format!("{} on", timer);
+        check.state = State::Hardened;
+    } else if Path::new("/usr/bin/dnf-automatic").exists() {
+        check.value = "dnf-automatic installed, timer off".to_string();
+    } else if Path::new("/etc/apt").exists() || Path::new("/etc/dnf").exists() {
+        check.value = "off".to_string();
+    } else {
+        check.value = "unknown package manager".to_string();
+        check.state = State::Unknown;
+    }
+    check
+}
+
+// The last setting wins, and files are read in name order like APT does
+fn apt_periodic(key: &str) -> Option<String> {
+    let mut files: Vec<_> = fs::read_dir("/etc/apt/apt.conf.d").ok()?.flatten().map(|entry| entry.path()).collect();
+    files.sort();
+    let mut value = None;
+    for file in files {
+        let Ok(text) = fs::read_to_string(&file) else { continue };
+        for line in text.lines().map(str::trim).filter(|line| line.starts_with(key)) {
+            // APT::Periodic::Unattended-Upgrade "1";
+            if let Some(setting) = line[key.len()..].split('"').nth(1) {
+                value = Some(setting.to_string());
+            }
+        }
+    }
+    value
+}
+
+// dnf5 renamed the timer to dnf5-automatic.timer
+fn dnf_automatic_timer() -> Option<String> {
+    let wants = fs::read_dir("/etc/systemd/system/timers.target.wants").ok()?;
+    wants
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .find(|name| name.starts_with("dnf-automatic") || name.starts_with("dnf5-automatic"))
+        .map(|name| name.trim_end_matches(".timer").to_string())
+}
+
+pub fn draw(f: &mut Frame, area: Rect, posture: &Posture) {
+    let rows: Vec<Row> = posture
+        .checks
+        .iter()
+        .map(|check| {
+            let (mark, color) = match check.state {
+                State::Hardened => ("✅", Color::Green),
+                State::Weak => ("⚠️", Color::Yellow),
+                State::Unknown => ("❔", Color::Gray),
+            };
+            Row::new(vec![
+                Cell::from(format!("{} {}", mark, check.name)),
+                Cell::from(check.value.clone()).style(Style::default().fg(color)),
+                Cell::from(check.recommended.clone()),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["Setting", "Value", "Recommended"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let hardened = posture.checks.iter().filter(|check| check.state == State::Hardened).count();
+    let title = format!("🛡️ Security Posture — {} of {} hardened", hardened, posture.checks.len());
+    let table = Table::new(rows, [Constraint::Min(40), Constraint::Length(30), Constraint::Length(18)])
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .column_spacing(1);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,274 @@
+use crate::alerts::{Alert, Severity};
+use crate::{format_uptime, privilege};
+use chrono::{Local, TimeZone, Utc};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::Path,
+    process::Command,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+// Pending shutdowns and reboots: one scheduled with shutdown(8), which
+// systemd-logind keeps in /run/systemd/shutdown/scheduled, and a reboot an
+// update asked for. Debian and Ubuntu packages touch /run/reboot-required and
+// list themselves in reboot-required.pkgs; elsewhere a running kernel whose
+// modules directory an update removed, or one installed after it, gives it
+// away. Both raise a warning. With [power] actions = true, R on the Overview
+// schedules a reboot or power-off a minute out through shutdown(8), so it can
+// still be cancelled, or cancels the pending one.
+const SCHEDULED: &str = "/run/systemd/shutdown/scheduled";
+const REBOOT_REQUIRED: &str = "/run/reboot-required";
+const REBOOT_REQUIRED_PACKAGES: &str = "/run/reboot-required.pkgs";
+const MODULES_DIR: &str = "/lib/modules";
+const UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+const RESULT_SHOWN_FOR: Duration = Duration::from_secs(10);
+
+// [power] in the config
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PowerConfig {
+    // R on the Overview, unless read-only
+    pub actions: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Scheduled {
+    // "reboot", "poweroff", "halt" or "kexec"
+    mode: String,
+    // Unix seconds
+    at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerAction {
+    Reboot,
+    PowerOff,
+    Cancel,
+}
+
+impl PowerAction {
+    fn label(self) -> &'static str {
+        match self {
+            PowerAction::Reboot => "Reboot scheduled in 1 minute",
+            PowerAction::PowerOff => "Power-off scheduled in 1 minute",
+            PowerAction::Cancel => "Scheduled shutdown cancelled",
+        }
+    }
+
+    fn argument(self) -> &'static str {
+        match self {
+            PowerAction::Reboot => "-r",
+            PowerAction::PowerOff => "-P",
+            PowerAction::Cancel => "-c",
+        }
+    }
+}
+
+// The last R action, running or done
+struct Running {
+    action: PowerAction,
+    result: Arc<Mutex<Option<Result<(), String>>>>,
+    started: Instant,
+}
+
+#[derive(Default)]
+pub struct Power {
+    scheduled: Option<Scheduled>,
+    // Why a reboot is needed
+    reboot_required: Option<String>,
+    updated: Option<Instant>,
+    action: Option<Running>,
+}
+
+impl Power {
+    // Called on every refresh, the alerts show on any tab
+    pub fn update(&mut self) {
+        if self.updated.is_some_and(|at| at.elapsed() < UPDATE_INTERVAL) {
+            return;
+        }
+        self.updated = Some(Instant::now());
+        self.scheduled = read_scheduled();
+        self.reboot_required = reboot_required();
+    }
+
+    pub fn scheduled(&self) -> bool {
+        self.scheduled.is_some()
+    }
+
+    pub fn start(&mut self, action: PowerAction) {
+        let result = Arc::new(Mutex::new(None));
+        let thread_result = Arc::clone(&result);
+        thread::spawn(move || {
+            let outcome = run(action);
+            *thread_result.lock().unwrap() = Some(outcome);
+        });
+        self.action = Some(Running { action, result, started: Instant::now() });
+        // Picks up the new schedule on the next refresh
+        self.updated = None;
+    }
+
+    // Lines for the System Information panel, with colors
+    pub fn status(&self) -> Vec<(String, Color)> {
+        let mut lines = Vec::new();
+        if let Some(scheduled) = &self.scheduled {
+            lines.push((format!("⏻ {}", describe(scheduled)), Color::Yellow));
+        }
+        if let Some(reason) = &self.reboot_required {
+            lines.push((format!("🔄 Reboot required: {}", reason), Color::Yellow));
+        }
+        if let Some(running) = self.action.as_ref().filter(|running| running.started.elapsed() < RESULT_SHOWN_FOR) {
+            match &*running.result.lock().unwrap() {
+                None => {}
+                Some(Ok(())) => lines.push((format!("⏻ {}", running.action.label()), Color::Green)),
+                Some(Err(err)) => lines.push((format!("❌ shutdown {} failed: {}", running.action.argument(), err), Color::Red)),
+            }
+        }
+        lines
+    }
+
+    pub fn alerts(&self) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        if let Some(scheduled) = &self.scheduled {
+            alerts.push(Alert {
+                severity: Severity::Warning,
+                source: "power:scheduled".to_string(),
+                message: describe(scheduled),
+            });
+        }
+        if let Some(reason) = &self.reboot_required {
+            alerts.push(Alert {
+                severity: Severity::Warning,
+                source: "power:reboot".to_string(),
+                message: format!("Reboot required: {}", reason),
+            });
+        }
+        alerts
+    }
+}
+
+// "Reboot scheduled for 14:30, in 12m"
+fn describe(scheduled: &Scheduled) -> String {
+    let what = match scheduled.mode.as_str() {
+        "poweroff" => "Power-off",
+        "halt" => "Halt",
+        "kexec" => "Kexec reboot",
+        _ => "Reboot",
+    };
+    let when = Local
+        .timestamp_opt(scheduled.at, 0)
+        .single()
+        .map(|at| if at.date_naive() == Local::now().date_naive() { at.format("%H:%M") } else { at.format("%a %d %b %H:%M") }.to_string())
+        .unwrap_or_default();
+    let left = match (scheduled.at - Utc::now().timestamp()).max(0) as u64 {
+        seconds @ 0..=59 => format!("{}s", seconds),
+        seconds => format_uptime(seconds),
+    };
+    format!("{} scheduled for {}, in {}", what, when, left)
+}
+
+// "USEC=1760612400000000", "WARN_WALL=1", "MODE=reboot"; gone once cancelled
+fn read_scheduled() -> Option<Scheduled> {
+    let contents = fs::read_to_string(SCHEDULED).ok()?;
+    let value = |key: &str| contents.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix('='));
+    let usec: i64 = value("USEC")?.trim().parse().ok()?;
+    let mode = value("MODE").unwrap_or("reboot").trim();
+    // shutdown --dry-run schedules too
+    if mode.starts_with("dry-") {
+        return None;
+    }
+    Some(Scheduled { mode: mode.to_string(), at: usec / 1_000_000 })
+}
+
+fn reboot_required() -> Option<String> {
+    // dpkg-based systems say so themselves
+    if Path::new("/var/lib/dpkg").exists() {
+        if !Path::new(REBOOT_REQUIRED).exists() {
+            return None;
+        }
+        let packages = fs::read_to_string(REBOOT_REQUIRED_PACKAGES).unwrap_or_default();
+        let packages: Vec<&str> = packages.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        return Some(if packages.is_empty() { "after updates".to_string() } else { format!("updated {}", packages.join(", ")) });
+    }
+    kernel_replaced()
+}
+
+// No modules directory at all (containers, kernels without modules) tells nothing
+fn kernel_replaced() -> Option<String> {
+    let running = fs::read_to_string("/proc/sys/kernel/osrelease").ok()?.trim().to_string();
+    let installed: Vec<(String, SystemTime)> = fs::read_dir(MODULES_DIR)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| Some((entry.file_name().to_string_lossy().into_owned(), entry.metadata().ok()?.modified().ok()?)))
+        .collect();
+    if installed.is_empty() {
+        return None;
+    }
+    let Some((_, running_installed)) = installed.iter().find(|(version, _)| *version == running) else {
+        return Some(format!("the running kernel {} was removed by an update", running));
+    };
+    installed
+        .iter()
+        .filter(|(version, modified)| *version != running && modified > running_installed)
+        .max_by_key(|(_, modified)| *modified)
+        .map(|(version, _)| format!("kernel {} was installed after the running {}", version, running))
+}
+
+// shutdown(8) talks to logind, which polkit may allow for a local session
+// without root
+fn run(action: PowerAction) -> Result<(), String> {
+    let mut command = Command::new("shutdown");
+    command.arg(action.argument());
+    if action != PowerAction::Cancel {
+        command.arg("+1");
+    }
+    let output = command.output().map_err(|e| format!("shutdown: {}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error = stderr.lines().rev().find(|line| !line.trim().is_empty()).map(str::to_string);
+    let error = error.unwrap_or_else(|| output.status.to_string());
+    if privilege::is_root() {
+        Err(error)
+    } else {
+        Err(format!("{} ({})", error, privilege::root_hint()))
+    }
+}
+
+// r reboot, o power off, c cancel the scheduled one, n/Esc close
+pub fn draw_confirm(f: &mut Frame, area: Rect, power: &Power) {
+    let mut lines = vec![Line::from("Reboot or power off this machine?"), Line::from("")];
+    if let Some(scheduled) = &power.scheduled {
+        lines.push(Line::styled(format!("Pending: {}.", describe(scheduled)), Style::default().fg(Color::Yellow)));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from("Either one happens in a minute, with a warning to logged-in users,"));
+    lines.push(Line::from("and can be cancelled until then with c here or shutdown -c."));
+    if !privilege::is_root() {
+        lines.push(Line::styled(
+            format!("Without root, polkit may refuse ({}).", privilege::root_hint()),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    lines.push(Line::from(""));
+    let keys = if power.scheduled() { "r reboot · o power off · c cancel pending · n / Esc close" } else { "r reboot · o power off · n / Esc close" };
+    lines.push(Line::styled(keys, Style::default().fg(Color::Yellow)));
+    let dialog = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title("⏻ Power")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(dialog, area);
+}
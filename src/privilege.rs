@@ -0,0 +1,65 @@
+use std::io;
+
+// Whether we run with root rights, and how to get them when a collector or
+// action needs more than the current user has
+
+#[cfg(unix)]
+pub fn is_root() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(unix))]
+pub fn is_root() -> bool {
+    false
+}
+
+// True for processes owned by someone other than the user running the monitor
+#[cfg(unix)]
+pub fn is_other_user(uid: &sysinfo::Uid) -> bool {
+    **uid != unsafe { libc::getuid() }
+}
+
+#[cfg(not(unix))]
+pub fn is_other_user(_uid: &sysinfo::Uid) -> bool {
+    false
+}
+
+// Hint appended to panels whose data is incomplete without root
+pub fn root_hint() -> &'static str {
+    if crate::harden::active() {
+        // no_new_privs keeps sudo from gaining anything
+        "needs root, which --harden rules out"
+    } else if cfg!(unix) {
+        "needs root, press ! to restart with sudo"
+    } else {
+        "needs an elevated prompt"
+    }
+}
+
+// Restart the monitor through sudo (or pkexec when sudo is missing) with the
+// same arguments. Only returns if the exec itself failed; the terminal must
+// already be restored so sudo can ask for a password.
+#[cfg(unix)]
+pub fn relaunch_elevated() -> io::Error {
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(err) => return err,
+    };
+    let tool = if command_exists("sudo") { "sudo" } else { "pkexec" };
+    Command::new(tool).arg(exe).args(std::env::args().skip(1)).exec()
+}
+
+#[cfg(not(unix))]
+pub fn relaunch_elevated() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "restart from an elevated prompt instead")
+}
+
+#[cfg(unix)]
+fn command_exists(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
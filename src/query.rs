@@ -0,0 +1,328 @@
+use crate::thread_count;
+use std::{str::FromStr, thread, time::Duration};
+use sysinfo::{Disks, Networks, Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, Users};
+
+// `system-monitor query <expr>`: one value from the collectors, printed bare
+// so shell scripts don't have to parse the JSON API. Expressions are
+// target.field, with a selector for targets that have more than one:
+// "cpu.usage", "memory.available", "load.five", "process[name=nginx].rss",
+// "process[pid=1].threads", "disk[/].free", "net[eth0].rx". Bytes are
+// printed as plain numbers, percentages without the sign.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    target: Target,
+    field: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Cpu,
+    Memory,
+    Swap,
+    Load,
+    Uptime,
+    Process(Selector),
+    Disk(String),
+    Net(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Pid(Pid),
+    User(String),
+}
+
+// Rates are measured over this long
+const SAMPLE: Duration = Duration::from_secs(1);
+
+impl Target {
+    fn fields(&self) -> &'static [&'static str] {
+        match self {
+            Target::Cpu => &["usage", "cores", "frequency"],
+            Target::Memory | Target::Swap => &["total", "used", "free", "available", "usage"],
+            Target::Load => &["one", "five", "fifteen"],
+            Target::Uptime => &["seconds"],
+            Target::Process(_) => &["cpu", "rss", "virtual", "threads", "count", "pid"],
+            Target::Disk(_) => &["total", "used", "free", "usage"],
+            Target::Net(_) => &["rx", "tx", "total_rx", "total_tx"],
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let expr = expr.trim();
+        // Mount points may contain dots, so the field starts after the ]
+        let (name, selector, field) = match expr.split_once('[') {
+            Some((name, rest)) => {
+                let (selector, field) = rest
+                    .rsplit_once(']')
+                    .ok_or_else(|| format!("query '{}': missing ]", expr))?;
+                let field = field
+                    .strip_prefix('.')
+                    .ok_or_else(|| format!("query '{}': expected .field after ]", expr))?;
+                (name, Some(selector), field)
+            }
+            None => {
+                let (name, field) = expr
+                    .split_once('.')
+                    .ok_or_else(|| format!("query '{}': expected target.field, e.g. cpu.usage", expr))?;
+                (name, None, field)
+            }
+        };
+        let target = match (name, selector) {
+            ("cpu", None) => Target::Cpu,
+            ("memory" | "mem", None) => Target::Memory,
+            ("swap", None) => Target::Swap,
+            ("load", None) => Target::Load,
+            ("uptime", None) => Target::Uptime,
+            ("process", Some(selector)) => Target::Process(selector.parse()?),
+            ("disk", Some(mount)) => Target::Disk(mount.to_string()),
+            ("net", Some(iface)) => Target::Net(iface.to_string()),
+            ("process" | "disk" | "net", None) => {
+                return Err(format!("query '{}': {} needs a selector, e.g. {}", expr, name, example(name)))
+            }
+            (_, Some(_)) if ["cpu", "memory", "mem", "swap", "load", "uptime"].contains(&name) => {
+                return Err(format!("query '{}': {} takes no selector", expr, name))
+            }
+            _ => return Err(format!("query '{}': unknown target '{}'", expr, name)),
+        };
+        let field = target
+            .fields()
+            .iter()
+            .find(|known| **known == field)
+            .ok_or_else(|| format!("query '{}': {} has no field '{}' (one of {})", expr, name, field, target.fields().join(", ")))?;
+        Ok(Query { target, field })
+    }
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        match selector.split_once('=') {
+            Some(("name", name)) => Ok(Selector::Name(name.to_string())),
+            Some(("user", user)) => Ok(Selector::User(user.to_string())),
+            Some(("pid", pid)) => pid
+                .parse::<u32>()
+                .map(|pid| Selector::Pid(Pid::from_u32(pid)))
+                .map_err(|_| format!("process selector: '{}' is not a PID", pid)),
+            _ => Err(format!("process selector '{}' must be name=, pid= or user=", selector)),
+        }
+    }
+}
+
+fn example(target: &str) -> &'static str {
+    match target {
+        "process" => "process[name=nginx].rss",
+        "disk" => "disk[/].free",
+        _ => "net[eth0].rx",
+    }
+}
+
+impl Query {
+    // Whether the value is a rate, which needs two samples SAMPLE apart
+    fn needs_sample(&self) -> bool {
+        matches!(
+            (&self.target, self.field),
+            (Target::Cpu, "usage") | (Target::Process(_), "cpu") | (Target::Net(_), "rx" | "tx")
+        )
+    }
+
+    // Reads only what the query needs; Err when nothing matches the selector
+    pub fn run(&self) -> Result<String, String> {
+        let mut system = System::new();
+        match &self.target {
+            Target::Cpu => {
+                system.refresh_cpu_all();
+                if self.needs_sample() {
+                    thread::sleep(SAMPLE);
+                    system.refresh_cpu_all();
+                }
+                Ok(match self.field {
+                    "usage" => format!("{:.1}", system.global_cpu_usage()),
+                    "cores" => system.cpus().len().to_string(),
+                    _ => {
+                        let cpus = system.cpus();
+                        let total: u64 = cpus.iter().map(|cpu| cpu.frequency()).sum();
+                        (total / cpus.len().max(1) as u64).to_string()
+                    }
+                })
+            }
+            Target::Memory | Target::Swap => {
+                system.refresh_memory();
+                let (total, used, free, available) = if self.target == Target::Memory {
+                    (system.total_memory(), system.used_memory(), system.free_memory(), system.available_memory())
+                } else {
+                    (system.total_swap(), system.used_swap(), system.free_swap(), system.free_swap())
+                };
+                Ok(match self.field {
+                    "total" => total.to_string(),
+                    "used" => used.to_string(),
+                    "free" => free.to_string(),
+                    "available" => available.to_string(),
+                    _ => format!("{:.1}", percent(used, total)),
+                })
+            }
+            Target::Load => {
+                let load = System::load_average();
+                let value = match self.field {
+                    "one" => load.one,
+                    "five" => load.five,
+                    _ => load.fifteen,
+                };
+                Ok(format!("{:.2}", value))
+            }
+            Target::Uptime => Ok(System::uptime().to_string()),
+            Target::Process(selector) => {
+                let kind = ProcessRefreshKind::nothing().with_memory().with_cpu().with_tasks().with_user(sysinfo::UpdateKind::Always);
+                system.refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+                if self.needs_sample() {
+                    thread::sleep(SAMPLE);
+                    system.refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+                }
+                let users = Users::new_with_refreshed_list();
+                let mut matches: Vec<(&Pid, &Process)> = system
+                    .processes()
+                    .iter()
+                    // sysinfo also lists threads as processes on Linux
+                    .filter(|(_, process)| process.thread_kind().is_none())
+                    .filter(|(pid, process)| selector.matches(**pid, process, &users))
+                    .collect();
+                if matches.is_empty() {
+                    return Err(format!("no process matches {}", selector));
+                }
+                matches.sort_by_key(|(pid, _)| **pid);
+                // Every matching process counts, like the process: pins
+                Ok(match self.field {
+                    "cpu" => format!("{:.1}", matches.iter().map(|(_, process)| process.cpu_usage()).sum::<f32>()),
+                    "rss" => matches.iter().map(|(_, process)| process.memory()).sum::<u64>().to_string(),
+                    "virtual" => matches.iter().map(|(_, process)| process.virtual_memory()).sum::<u64>().to_string(),
+                    "threads" => matches
+                        .iter()
+                        .map(|(_, process)| thread_count(process).unwrap_or(1))
+                        .sum::<usize>()
+                        .to_string(),
+                    "count" => matches.len().to_string(),
+                    _ => matches.iter().map(|(pid, _)| pid.to_string()).collect::<Vec<_>>().join(" "),
+                })
+            }
+            Target::Disk(mount) => {
+                let disks = Disks::new_with_refreshed_list();
+                let disk = disks
+                    .iter()
+                    .find(|disk| disk.mount_point().to_string_lossy() == mount.as_str())
+                    .ok_or_else(|| format!("no disk is mounted on {}", mount))?;
+                let total = disk.total_space();
+                let used = total.saturating_sub(disk.available_space());
+                Ok(match self.field {
+                    "total" => total.to_string(),
+                    "used" => used.to_string(),
+                    "free" => disk.available_space().to_string(),
+                    _ => format!("{:.1}", percent(used, total)),
+                })
+            }
+            Target::Net(iface) => {
+                let mut networks = Networks::new_with_refreshed_list();
+                if !networks.contains_key(iface) {
+                    return Err(format!("no network interface {}", iface));
+                }
+                if self.needs_sample() {
+                    thread::sleep(SAMPLE);
+                    networks.refresh(true);
+                }
+                let network = networks.get(iface).ok_or_else(|| format!("network interface {} went away", iface))?;
+                let per_sec = |bytes: u64| (bytes as f64 / SAMPLE.as_secs_f64()) as u64;
+                Ok(match self.field {
+                    "rx" => per_sec(network.received()).to_string(),
+                    "tx" => per_sec(network.transmitted()).to_string(),
+                    "total_rx" => network.total_received().to_string(),
+                    _ => network.total_transmitted().to_string(),
+                })
+            }
+        }
+    }
+}
+
+impl Selector {
+    fn matches(&self, pid: Pid, process: &Process, users: &Users) -> bool {
+        match self {
+            Selector::Name(name) => process.name().to_string_lossy() == name.as_str(),
+            Selector::Pid(wanted) => pid == *wanted,
+            Selector::User(user) => {
+                let name = process.user_id().and_then(|uid| users.get_user_by_id(uid)).map(|user| user.name().to_string());
+                // A UID without an account is matched by number
+                name.as_deref() == Some(user.as_str()) || process.user_id().is_some_and(|uid| uid.to_string() == *user)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Selector::Name(name) => write!(f, "name={}", name),
+            Selector::Pid(pid) => write!(f, "pid={}", pid),
+            Selector::User(user) => write!(f, "user={}", user),
+        }
+    }
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 { 0.0 } else { part as f64 / total as f64 * 100.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(expr: &str) -> Result<Query, String> {
+        expr.parse()
+    }
+
+    #[test]
+    fn parses_targets_and_fields() {
+        assert_eq!(parse("cpu.usage"), Ok(Query { target: Target::Cpu, field: "usage" }));
+        assert_eq!(parse(" mem.available "), Ok(Query { target: Target::Memory, field: "available" }));
+        assert_eq!(parse("load.five"), Ok(Query { target: Target::Load, field: "five" }));
+        assert_eq!(
+            parse("process[pid=1].threads"),
+            Ok(Query { target: Target::Process(Selector::Pid(Pid::from_u32(1))), field: "threads" })
+        );
+        assert_eq!(
+            parse("process[user=www-data].count"),
+            Ok(Query { target: Target::Process(Selector::User("www-data".to_string())), field: "count" })
+        );
+        assert_eq!(parse("net[eth0].rx"), Ok(Query { target: Target::Net("eth0".to_string()), field: "rx" }));
+    }
+
+    #[test]
+    fn selectors_may_contain_dots_and_brackets() {
+        assert_eq!(parse("disk[/mnt/a.b].free"), Ok(Query { target: Target::Disk("/mnt/a.b".to_string()), field: "free" }));
+        assert_eq!(
+            parse("process[name=x[1]].rss"),
+            Ok(Query { target: Target::Process(Selector::Name("x[1]".to_string())), field: "rss" })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_queries() {
+        for (expr, error) in [
+            ("cpu", "expected target.field"),
+            ("disk[/.free", "missing ]"),
+            ("disk[/]free", "expected .field after ]"),
+            ("gpu.usage", "unknown target 'gpu'"),
+            ("cpu[0].usage", "cpu takes no selector"),
+            ("process.rss", "process needs a selector"),
+            ("memory.rss", "memory has no field 'rss'"),
+            ("process[pid=abc].rss", "'abc' is not a PID"),
+            ("process[uid=0].rss", "must be name=, pid= or user="),
+        ] {
+            let err = parse(expr).unwrap_err();
+            assert!(err.contains(error), "{}: {}", expr, err);
+        }
+    }
+}
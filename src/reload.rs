@@ -0,0 +1,96 @@
+use crate::config::Config;
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+// Edits to config.toml are picked up while the monitor runs. The file's
+// modification time is polled once a second; a changed file is loaded and
+// validated like at start-up, and only replaces the running config when it
+// is valid. The outcome is shown in the status bar.
+pub struct ConfigWatcher {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    checked: Instant,
+}
+
+impl ConfigWatcher {
+    pub fn new() -> ConfigWatcher {
+        let path = Config::path();
+        let modified = path.as_deref().and_then(modified);
+        ConfigWatcher { path, modified, checked: Instant::now() }
+    }
+
+    // True once for each change to the file
+    pub fn changed(&mut self) -> bool {
+        if self.checked.elapsed() < Duration::from_secs(1) {
+            return false;
+        }
+        self.checked = Instant::now();
+        let modified = self.path.as_deref().and_then(modified);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        // A deleted file keeps the running config
+        modified.is_some()
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+pub enum Notice {
+    // With the changed settings that are only read at start-up
+    Reloaded { at: Instant, restart: Vec<&'static str> },
+    Invalid(String),
+}
+
+impl Notice {
+    // Status bar text and whether it is an error; a successful reload is
+    // mentioned for a few seconds
+    pub fn text(&self) -> Option<(String, bool)> {
+        match self {
+            Notice::Reloaded { at, .. } if at.elapsed() > Duration::from_secs(5) => None,
+            Notice::Reloaded { restart, .. } if restart.is_empty() => Some(("✔ Config reloaded".to_string(), false)),
+            Notice::Reloaded { restart, .. } => Some((
+                format!("✔ Config reloaded; restart to apply changes to {}", restart.join(", ")),
+                false,
+            )),
+            Notice::Invalid(error) => {
+                Some((format!("⚠ Config not reloaded, still using the previous one: {}", error), true))
+            }
+        }
+    }
+}
+
+// Whether a config section differs between two versions
+pub fn changed<T: Serialize>(old: &T, new: &T) -> bool {
+    serde_json::to_value(old).ok() != serde_json::to_value(new).ok()
+}
+
+// Sections the running monitor only reads at start-up
+pub fn restart_needed(old: &Config, new: &Config) -> Vec<&'static str> {
+    let mut sections = Vec::new();
+    let mut check = |name, differs| {
+        if differs {
+            sections.push(name);
+        }
+    };
+    check("archive", changed(&old.archive, &new.archive));
+    check("report", changed(&old.report, &new.report));
+    check("anomaly", changed(&old.anomaly, &new.anomaly));
+    check("event_log", changed(&old.event_log, &new.event_log));
+    check("high_resolution", changed(&old.high_resolution, &new.high_resolution));
+    check("exec_trace", changed(&old.exec_trace, &new.exec_trace));
+    check("harden", old.harden != new.harden);
+    check("allow_privileged", old.allow_privileged != new.allow_privileged);
+    // Turning read-only on applies at once, turning it off doesn't
+    check("read_only", old.read_only && !new.read_only);
+    #[cfg(feature = "api")]
+    check("api_listen", old.api_listen != new.api_listen);
+    sections
+}
@@ -0,0 +1,124 @@
+use crate::{
+    kill::{ActionResult, Batch},
+    privilege,
+};
+use sysinfo::{Pid, Process};
+
+// Changing the nice value of the selected process, or of all marked ones,
+// from the Processes tab, one step at a time: + (or F8) makes it nicer, lower priority, and - (or F7) less
+// nice, like htop. Linux keeps a nice value per thread, so every thread of
+// the process is changed, not just the main one. Anyone may lower the
+// priority of their own processes; raising it again needs root (or
+// CAP_SYS_NICE, or an RLIMIT_NICE that allows it).
+const NICEST: i32 = 19;
+const LEAST_NICE: i32 = -20;
+
+// The nice value of a process, None when it can't be read
+#[cfg(unix)]
+pub fn nice(pid: Pid) -> Option<i32> {
+    // -1 is a valid nice value as well as the error return
+    clear_errno();
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid.as_u32() as libc::id_t) };
+    (std::io::Error::last_os_error().raw_os_error() == Some(0)).then_some(nice)
+}
+
+#[cfg(not(unix))]
+pub fn nice(_pid: Pid) -> Option<i32> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn clear_errno() {
+    unsafe { *libc::__errno_location() = 0 };
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn clear_errno() {
+    unsafe { *libc::__error() = 0 };
+}
+
+enum Step {
+    Changed(i32, i32),
+    // Already at the limit in that direction
+    AtLimit(i32),
+    // The nice value it was meant to get, if it got that far
+    Failed(Option<i32>, String),
+}
+
+fn step(pid: Pid, process: &Process, delta: i32) -> Step {
+    let Some(current) = nice(pid) else { return Step::Failed(None, "it already exited".to_string()) };
+    let wanted = (current + delta).clamp(LEAST_NICE, NICEST);
+    if wanted == current {
+        return Step::AtLimit(current);
+    }
+    match set_nice(pid, process, wanted) {
+        Ok(()) => Step::Changed(current, wanted),
+        Err(err) => Step::Failed(Some(wanted), err),
+    }
+}
+
+// delta is +1 for nicer, -1 for less nice
+pub fn renice(pid: Pid, process: &Process, delta: i32) -> ActionResult {
+    let name = process.name().to_string_lossy().into_owned();
+    match step(pid, process, delta) {
+        Step::Changed(current, wanted) => {
+            ActionResult::new(format!("⚖️ Nice of {} ({}): {} → {}", name, pid, current, wanted), false)
+        }
+        Step::AtLimit(current) => {
+            let limit = if delta > 0 { "lowest" } else { "highest" };
+            ActionResult::new(format!("{} ({}) already has the {} priority, nice {}", name, pid, limit, current), true)
+        }
+        Step::Failed(Some(wanted), err) => {
+            ActionResult::new(format!("❌ Renicing {} ({}) to {} failed: {}", name, pid, wanted, err), true)
+        }
+        Step::Failed(None, err) => ActionResult::new(format!("❌ Renicing {} ({}) failed: {}", name, pid, err), true),
+    }
+}
+
+// One step for each marked process, or each of a group's; those at the limit stay there
+pub fn renice_all(processes: &[(Pid, &Process)], batch: &Batch, delta: i32) -> ActionResult {
+    let mut changed = 0;
+    let mut failures: Vec<String> = Vec::new();
+    for (pid, process) in processes {
+        match step(*pid, process, delta) {
+            Step::Changed(..) => changed += 1,
+            Step::AtLimit(_) => {}
+            Step::Failed(_, err) => failures.push(format!("{} ({}): {}", process.name().to_string_lossy(), pid, err)),
+        }
+    }
+    let direction = if delta > 0 { "nicer" } else { "less nice" };
+    // "Made 3 of the 12 processes in the firefox group nicer"
+    let of = batch.describe(processes.len());
+    match failures.first() {
+        None => ActionResult::new(format!("⚖️ Made {} of {} {}", changed, of, direction), changed == 0),
+        Some(first) => {
+            let more = if failures.len() > 1 { format!(" and {} more", failures.len() - 1) } else { String::new() };
+            let message = format!("❌ Made {} of {} {}; {}{}", changed, of, direction, first, more);
+            ActionResult::new(message, true)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn set_nice(pid: Pid, process: &Process, nice: i32) -> Result<(), String> {
+    let set = |pid: Pid| unsafe { libc::setpriority(libc::PRIO_PROCESS, pid.as_u32() as libc::id_t, nice) } == 0;
+    // The main thread decides; a thread that exits meanwhile doesn't matter
+    if !set(pid) {
+        let err = std::io::Error::last_os_error();
+        return Err(match err.raw_os_error() {
+            Some(libc::EACCES) => format!("raising the priority {}", privilege::root_hint()),
+            Some(libc::EPERM) => format!("it belongs to another user ({})", privilege::root_hint()),
+            Some(libc::ESRCH) => "it already exited".to_string(),
+            _ => err.to_string(),
+        });
+    }
+    for task in process.tasks().into_iter().flatten().filter(|task| **task != pid) {
+        set(*task);
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_nice(_pid: Pid, _process: &Process, _nice: i32) -> Result<(), String> {
+    Err("not supported on this platform".to_string())
+}
@@ -0,0 +1,615 @@
+use crate::alerts::Severity;
+use crate::anomaly::METRICS;
+use crate::archive::{self, MinuteRecord};
+use crate::channels::{ChannelConfig, Notification, Notifier};
+use crate::{format_bytes, truncate_name};
+use chrono::{DateTime, Datelike, Days, Local, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error, fs, path::PathBuf};
+
+// Summaries of the archived minute history: peaks, the processes that used
+// the most CPU, disk growth and alerts. Written on a schedule while running,
+// or printed with --report.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReportConfig {
+    // Leave out for on-demand reports only
+    pub schedule: Option<ReportPeriod>,
+    // Local "HH:MM" the scheduled report is made
+    pub at: String,
+    // Day of weekly reports, "mon".."sun"
+    pub day: String,
+    pub format: ReportFormat,
+    // Defaults to ~/.local/share/system-monitor/reports
+    pub directory: Option<PathBuf>,
+    // Alert channels the scheduled report is also sent through
+    pub channels: Vec<String>,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        ReportConfig {
+            schedule: None,
+            at: "08:00".to_string(),
+            day: "mon".to_string(),
+            format: ReportFormat::Text,
+            directory: None,
+            channels: Vec::new(),
+        }
+    }
+}
+
+impl ReportConfig {
+    pub fn validate(&self, channels: &[ChannelConfig]) -> Result<(), String> {
+        NaiveTime::parse_from_str(&self.at, "%H:%M").map_err(|_| format!("report: at '{}' is not HH:MM", self.at))?;
+        self.day.parse::<Weekday>().map_err(|_| format!("report: unknown day '{}'", self.day))?;
+        if let Some(unknown) = self.channels.iter().find(|name| !channels.iter().any(|c| c.name() == *name)) {
+            return Err(format!("report: unknown alert channel '{}'", unknown));
+        }
+        Ok(())
+    }
+
+    pub fn directory(&self) -> Option<PathBuf> {
+        self.directory
+            .clone()
+            .or_else(|| dirs::data_dir().map(|dir| dir.join("system-monitor").join("reports")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    Daily,
+    Weekly,
+}
+
+impl ReportPeriod {
+    fn days(&self) -> u64 {
+        match self {
+            ReportPeriod::Daily => 1,
+            ReportPeriod::Weekly => 7,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ReportPeriod::Daily => "daily",
+            ReportPeriod::Weekly => "weekly",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Text,
+    Html,
+}
+
+pub struct Report {
+    pub title: String,
+    pub body: String,
+}
+
+struct MetricSummary {
+    label: &'static str,
+    average: String,
+    peak: String,
+    peak_at: i64,
+}
+
+struct DiskGrowth {
+    mount: String,
+    first: u64,
+    last: u64,
+}
+
+struct AlertSummary {
+    source: String,
+    count: usize,
+    worst: Severity,
+    message: String,
+}
+
+// The period ending now
+pub fn generate(period: ReportPeriod, format: ReportFormat) -> Report {
+    let to = Local::now();
+    let from = to - Days::new(period.days());
+    let records = archive::read(from.timestamp(), to.timestamp());
+    let host = sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string());
+    let title = format!(
+        "{} report for {}, {} – {}",
+        capitalize(period.name()),
+        host,
+        from.format("%Y-%m-%d %H:%M"),
+        to.format("%Y-%m-%d %H:%M")
+    );
+    let coverage = format!(
+        "{} of {} minutes recorded",
+        records.len(),
+        (to.timestamp() - from.timestamp()) / 60
+    );
+
+    let metrics = summarize_metrics(&records);
+    let processes = top_processes(&records);
+    let disks = disk_growth(&records);
+    let alerts = summarize_alerts(&records);
+    let body = match format {
+        ReportFormat::Text => render_text(&title, &coverage, &metrics, &processes, &disks, &alerts),
+        ReportFormat::Html => render_html(&title, &coverage, &metrics, &processes, &disks, &alerts),
+    };
+    Report { title, body }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+fn summarize_metrics(records: &[MinuteRecord]) -> Vec<MetricSummary> {
+    METRICS
+        .iter()
+        .filter_map(|m| {
+            let averages: Vec<f64> = records.iter().filter_map(|r| r.average.get(m.name).copied()).collect();
+            if averages.is_empty() {
+                return None;
+            }
+            let (peak_at, peak) = records
+                .iter()
+                .filter_map(|r| r.peak.get(m.name).map(|peak| (r.time, *peak)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))?;
+            Some(MetricSummary {
+                label: m.label,
+                average: (m.format)(averages.iter().sum::<f64>() / averages.len() as f64),
+                peak: (m.format)(peak),
+                peak_at,
+            })
+        })
+        .collect()
+}
+
+// Top ten by CPU time, with each one's share of the total
+fn top_processes(records: &[MinuteRecord]) -> Vec<(String, f64, f64)> {
+    let mut totals: BTreeMap<&str, f64> = BTreeMap::new();
+    for record in records {
+        for (name, seconds) in &record.cpu_seconds {
+            *totals.entry(name).or_default() += seconds;
+        }
+    }
+    let all: f64 = totals.values().sum();
+    let mut totals: Vec<(&str, f64)> = totals.into_iter().collect();
+    totals.sort_by(|a, b| b.1.total_cmp(&a.1));
+    totals
+        .into_iter()
+        .take(10)
+        .map(|(name, seconds)| (name.to_string(), seconds, if all > 0.0 { seconds / all * 100.0 } else { 0.0 }))
+        .collect()
+}
+
+// Fastest growing first
+fn disk_growth(records: &[MinuteRecord]) -> Vec<DiskGrowth> {
+    let mut mounts: BTreeMap<&str, DiskGrowth> = BTreeMap::new();
+    for record in records {
+        for (mount, used) in &record.disks {
+            mounts
+                .entry(mount)
+                .and_modify(|growth| growth.last = *used)
+                .or_insert(DiskGrowth { mount: mount.clone(), first: *used, last: *used });
+        }
+    }
+    let mut growth: Vec<DiskGrowth> = mounts.into_values().collect();
+    growth.sort_by_key(|g| std::cmp::Reverse(g.last as i128 - g.first as i128));
+    growth
+}
+
+fn summarize_alerts(records: &[MinuteRecord]) -> Vec<AlertSummary> {
+    let mut sources: BTreeMap<&str, AlertSummary> = BTreeMap::new();
+    for alert in records.iter().flat_map(|r| &r.alerts) {
+        let summary = sources.entry(&alert.source).or_insert(AlertSummary {
+            source: alert.source.clone(),
+            count: 0,
+            worst: alert.severity,
+            message: String::new(),
+        });
+        summary.count += 1;
+        summary.worst = summary.worst.max(alert.severity);
+        summary.message = alert.message.clone();
+    }
+    let mut alerts: Vec<AlertSummary> = sources.into_values().collect();
+    alerts.sort_by(|a, b| b.worst.cmp(&a.worst).then(b.count.cmp(&a.count)));
+    alerts
+}
+
+fn time(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|t| t.format("%b %d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+pub fn cpu_time(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    match seconds {
+        s if s >= 3600 => format!("{}h {:02}m", s / 3600, s % 3600 / 60),
+        s if s >= 60 => format!("{}m {:02}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    }
+}
+
+fn growth(disk: &DiskGrowth) -> String {
+    let delta = disk.last as i128 - disk.first as i128;
+    let sign = if delta < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format_bytes(delta.unsigned_abs() as u64))
+}
+
+fn render_text(
+    title: &str,
+    coverage: &str,
+    metrics: &[MetricSummary],
+    processes: &[(String, f64, f64)],
+    disks: &[DiskGrowth],
+    alerts: &[AlertSummary],
+) -> String {
+    let mut out = format!("{}\n{}\n", title, coverage);
+
+    out.push_str("\nPeak usage\n");
+    if metrics.is_empty() {
+        out.push_str("  No history recorded in this period.\n");
+    }
+    for m in metrics {
+        out.push_str(&format!(
+            "  {:<16} average {:>12}   peak {:>12} at {}\n",
+            m.label,
+            m.average,
+            m.peak,
+            time(m.peak_at)
+        ));
+    }
+
+    out.push_str("\nTop processes by CPU time\n");
+    if processes.is_empty() {
+        out.push_str("  None recorded.\n");
+    }
+    for (name, seconds, share) in processes {
+        out.push_str(&format!("  {:<24} {:>10}  {:>3.0}%\n", truncate_name(name, 24), cpu_time(*seconds), share));
+    }
+
+    out.push_str("\nDisk growth\n");
+    if disks.is_empty() {
+        out.push_str("  None recorded.\n");
+    }
+    let width = disks.iter().map(|d| d.mount.chars().count()).max().unwrap_or(0);
+    for disk in disks {
+        out.push_str(&format!(
+            "  {:<width$} {:>10} → {:>10}  {}\n",
+            disk.mount,
+            format_bytes(disk.first),
+            format_bytes(disk.last),
+            growth(disk)
+        ));
+    }
+
+    out.push_str(&format!("\nAlerts ({} fired)\n", alerts.iter().map(|a| a.count).sum::<usize>()));
+    if alerts.is_empty() {
+        out.push_str("  None.\n");
+    }
+    for alert in alerts {
+        out.push_str(&format!(
+            "  {:>3}×  {:<8}  {:<24} {}\n",
+            alert.count,
+            alert.worst.name(),
+            alert.source,
+            alert.message
+        ));
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn html_table(headers: &[&str], rows: Vec<Vec<String>>, empty: &str) -> String {
+    if rows.is_empty() {
+        return format!("<p>{}</p>\n", empty);
+    }
+    let mut out = String::from("<table>\n<tr>");
+    for header in headers {
+        out.push_str(&format!("<th>{}</th>", header));
+    }
+    out.push_str("</tr>\n");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            out.push_str(&format!("<td>{}</td>", escape(&cell)));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+fn render_html(
+    title: &str,
+    coverage: &str,
+    metrics: &[MetricSummary],
+    processes: &[(String, f64, f64)],
+    disks: &[DiskGrowth],
+    alerts: &[AlertSummary],
+) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 2em; }}\n\
+         table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 4px 10px; text-align: left; }}\n\
+         th {{ background: #eee; }}\n\
+         </style>\n</head>\n<body>\n<h1>{0}</h1>\n<p>{1}</p>\n",
+        escape(title),
+        escape(coverage)
+    );
+
+    out.push_str("<h2>Peak usage</h2>\n");
+    out.push_str(&html_table(
+        &["Metric", "Average", "Peak", "At"],
+        metrics
+            .iter()
+            .map(|m| vec![m.label.to_string(), m.average.clone(), m.peak.clone(), time(m.peak_at)])
+            .collect(),
+        "No history recorded in this period.",
+    ));
+
+    out.push_str("<h2>Top processes by CPU time</h2>\n");
+    out.push_str(&html_table(
+        &["Process", "CPU time", "Share"],
+        processes
+            .iter()
+            .map(|(name, seconds, share)| vec![name.clone(), cpu_time(*seconds), format!("{:.0}%", share)])
+            .collect(),
+        "None recorded.",
+    ));
+
+    out.push_str("<h2>Disk growth</h2>\n");
+    out.push_str(&html_table(
+        &["Mount", "Start", "End", "Change"],
+        disks
+            .iter()
+            .map(|d| vec![d.mount.clone(), format_bytes(d.first), format_bytes(d.last), growth(d)])
+            .collect(),
+        "None recorded.",
+    ));
+
+    out.push_str(&format!("<h2>Alerts ({} fired)</h2>\n", alerts.iter().map(|a| a.count).sum::<usize>()));
+    out.push_str(&html_table(
+        &["Count", "Severity", "Source", "Last message"],
+        alerts
+            .iter()
+            .map(|a| vec![a.count.to_string(), a.worst.name().to_string(), a.source.clone(), a.message.clone()])
+            .collect(),
+        "None.",
+    ));
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+// Makes the scheduled report when its time comes; missed times while not
+// running are not made up
+pub struct Reporter {
+    config: ReportConfig,
+    period: ReportPeriod,
+    next: DateTime<Local>,
+    last: Option<String>,
+    error: Option<String>,
+}
+
+impl Reporter {
+    pub fn new(config: ReportConfig) -> Option<Reporter> {
+        let period = config.schedule?;
+        let mut reporter = Reporter { config, period, next: Local::now(), last: None, error: None };
+        reporter.next = reporter.next_after(Local::now());
+        Some(reporter)
+    }
+
+    fn next_after(&self, now: DateTime<Local>) -> DateTime<Local> {
+        let at = NaiveTime::parse_from_str(&self.config.at, "%H:%M").unwrap_or_default();
+        let day = self.config.day.parse::<Weekday>().unwrap_or(Weekday::Mon);
+        now.date_naive()
+            .iter_days()
+            .filter(|date| self.period == ReportPeriod::Daily || date.weekday() == day)
+            .filter_map(|date| Local.from_local_datetime(&date.and_time(at)).earliest())
+            .find(|time| *time > now)
+            .unwrap_or(now + Days::new(1))
+    }
+
+    pub fn run_if_due(&mut self, notifier: &mut Notifier) {
+        let now = Local::now();
+        if now < self.next {
+            return;
+        }
+        self.next = self.next_after(now);
+
+        let report = generate(self.period, self.config.format);
+        match self.write(&report, now) {
+            Ok(path) => {
+                self.last = Some(path.display().to_string());
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string().split(" (os error").next().unwrap_or_default().to_string()),
+        }
+        if !self.config.channels.is_empty() {
+            notifier.send(&self.config.channels, Notification::report(&report, self.config.format == ReportFormat::Html));
+        }
+    }
+
+    fn write(&self, report: &Report, now: DateTime<Local>) -> Result<PathBuf, Box<dyn Error>> {
+        let dir = self.config.directory().ok_or("no data directory available")?;
+        fs::create_dir_all(&dir)?;
+        let extension = match self.config.format {
+            ReportFormat::Text => "txt",
+            ReportFormat::Html => "html",
+        };
+        let path = dir.join(format!("{}-{}.{}", self.period.name(), now.format("%Y-%m-%d"), extension));
+        fs::write(&path, &report.body)?;
+        Ok(path)
+    }
+
+    // One line for the System Information panel
+    pub fn status(&self) -> String {
+        let mut status = format!(
+            "📑 {} report next {}",
+            capitalize(self.period.name()),
+            self.next.format("%a %H:%M")
+        );
+        if let Some(last) = &self.last {
+            status.push_str(&format!(", last → {}", last));
+        }
+        if let Some(error) = &self.error {
+            status.push_str(&format!(" ❌ {}", error));
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::ArchivedAlert;
+
+    fn record(time: i64, cpu: f64, processes: &[(&str, f64)], home: u64) -> MinuteRecord {
+        MinuteRecord {
+            time,
+            average: [("cpu".to_string(), cpu)].into(),
+            peak: [("cpu".to_string(), cpu + 10.0)].into(),
+            disks: [("/home".to_string(), home), ("/".to_string(), 5_000_000)].into(),
+            cpu_seconds: processes.iter().map(|(name, seconds)| (name.to_string(), *seconds)).collect(),
+            ..MinuteRecord::default()
+        }
+    }
+
+    fn alert(severity: Severity, source: &str, message: &str) -> ArchivedAlert {
+        ArchivedAlert { severity, source: source.to_string(), message: message.to_string() }
+    }
+
+    fn local(date: &str) -> DateTime<Local> {
+        let time = chrono::NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&time).earliest().unwrap()
+    }
+
+    fn reporter(schedule: ReportPeriod, at: &str, day: &str) -> Reporter {
+        let config =
+            ReportConfig { schedule: Some(schedule), at: at.to_string(), day: day.to_string(), ..ReportConfig::default() };
+        Reporter::new(config).unwrap()
+    }
+
+    #[test]
+    fn metrics_average_and_peak() {
+        let records = [record(60, 10.0, &[], 0), record(120, 30.0, &[], 0), record(180, 20.0, &[], 0)];
+        let metrics = summarize_metrics(&records);
+        assert_eq!(metrics.len(), 1);
+        assert_eq!((metrics[0].label, metrics[0].average.as_str(), metrics[0].peak.as_str()), ("CPU", "20%", "40%"));
+        assert_eq!(metrics[0].peak_at, 120);
+        assert!(summarize_metrics(&[]).is_empty());
+    }
+
+    #[test]
+    fn top_processes_add_up_across_minutes() {
+        let records = [
+            record(60, 0.0, &[("make", 30.0), ("cc1", 50.0)], 0),
+            record(120, 0.0, &[("make", 10.0), ("sshd", 10.0)], 0),
+        ];
+        assert_eq!(
+            top_processes(&records),
+            [("cc1".to_string(), 50.0, 50.0), ("make".to_string(), 40.0, 40.0), ("sshd".to_string(), 10.0, 10.0)]
+        );
+        let many: Vec<(String, f64)> = (1..=12).map(|n| (format!("p{}", n), n as f64)).collect();
+        let many: Vec<(&str, f64)> = many.iter().map(|(name, seconds)| (name.as_str(), *seconds)).collect();
+        let top = top_processes(&[record(60, 0.0, &many, 0)]);
+        assert_eq!(top.len(), 10);
+        assert_eq!(top[0].0, "p12");
+    }
+
+    #[test]
+    fn disks_grow_from_first_to_last_minute() {
+        let records = [record(60, 0.0, &[], 3_000_000), record(120, 0.0, &[], 1_000_000)];
+        let disks = disk_growth(&records);
+        // No change sorts ahead of shrinking
+        assert_eq!(disks.iter().map(|d| d.mount.as_str()).collect::<Vec<_>>(), ["/", "/home"]);
+        assert_eq!(growth(&disks[0]), "+0 B");
+        assert_eq!(growth(&disks[1]), format!("-{}", format_bytes(2_000_000)));
+    }
+
+    #[test]
+    fn alerts_grouped_by_source_worst_first() {
+        let mut first = record(60, 0.0, &[], 0);
+        first.alerts = vec![
+            alert(Severity::Warning, "memory", "Memory at 91%"),
+            alert(Severity::Warning, "disk:/", "/ at 91%"),
+        ];
+        let mut second = record(120, 0.0, &[], 0);
+        second.alerts = vec![
+            alert(Severity::Critical, "disk:/", "/ at 97%"),
+            alert(Severity::Warning, "memory", "Memory at 92%"),
+        ];
+        let mut third = record(180, 0.0, &[], 0);
+        third.alerts = vec![alert(Severity::Warning, "memory", "Memory at 90%")];
+        let alerts = summarize_alerts(&[first, second, third]);
+        let summary: Vec<(&str, usize, Severity, &str)> =
+            alerts.iter().map(|a| (a.source.as_str(), a.count, a.worst, a.message.as_str())).collect();
+        assert_eq!(
+            summary,
+            [("disk:/", 2, Severity::Critical, "/ at 97%"), ("memory", 3, Severity::Warning, "Memory at 90%")]
+        );
+    }
+
+    #[test]
+    fn cpu_time_reads_naturally() {
+        assert_eq!(cpu_time(0.4), "0s");
+        assert_eq!(cpu_time(59.6), "1m 00s");
+        assert_eq!(cpu_time(125.0), "2m 05s");
+        assert_eq!(cpu_time(7_380.0), "2h 03m");
+    }
+
+    #[test]
+    fn text_report_says_when_sections_are_empty() {
+        let text = render_text("Daily report", "0 of 1440 minutes recorded", &[], &[], &[], &[]);
+        assert!(text.starts_with("Daily report\n0 of 1440 minutes recorded\n"));
+        assert!(text.contains("Peak usage\n  No history recorded in this period.\n"));
+        assert!(text.contains("Alerts (0 fired)\n  None.\n"));
+    }
+
+    #[test]
+    fn html_report_escapes_names() {
+        let processes = [("<script>".to_string(), 5.0, 100.0)];
+        let html = render_html("A & B", "1 of 1 minutes recorded", &[], &processes, &[], &[]);
+        assert!(html.contains("<title>A &amp; B</title>"));
+        assert!(html.contains("<td>&lt;script&gt;</td><td>5s</td><td>100%</td>"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn daily_reports_come_at_the_next_time() {
+        let reporter = reporter(ReportPeriod::Daily, "08:00", "mon");
+        assert_eq!(reporter.next_after(local("2026-10-16 07:59")), local("2026-10-16 08:00"));
+        assert_eq!(reporter.next_after(local("2026-10-16 08:00")), local("2026-10-17 08:00"));
+    }
+
+    #[test]
+    fn weekly_reports_wait_for_their_day() {
+        // 2026-10-16 is a Friday
+        let friday = reporter(ReportPeriod::Weekly, "18:30", "fri");
+        assert_eq!(friday.next_after(local("2026-10-16 12:00")), local("2026-10-16 18:30"));
+        assert_eq!(friday.next_after(local("2026-10-16 19:00")), local("2026-10-23 18:30"));
+        let monday = reporter(ReportPeriod::Weekly, "08:00", "mon");
+        assert_eq!(monday.next_after(local("2026-10-16 12:00")), local("2026-10-19 08:00"));
+    }
+
+    #[test]
+    fn validates_time_day_and_channels() {
+        assert!(ReportConfig::default().validate(&[]).is_ok());
+        assert!(ReportConfig { at: "25:00".to_string(), ..ReportConfig::default() }.validate(&[]).is_err());
+        assert!(ReportConfig { day: "someday".to_string(), ..ReportConfig::default() }.validate(&[]).is_err());
+        let channels = ReportConfig { channels: vec!["mail".to_string()], ..ReportConfig::default() };
+        assert!(channels.validate(&[]).unwrap_err().contains("unknown alert channel 'mail'"));
+    }
+}
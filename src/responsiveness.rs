@@ -0,0 +1,74 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// How late a thread wakes up from a short sleep, as a self-test of timer and
+// scheduler latency. On an idle machine a 20 ms sleep overshoots by well
+// under a millisecond; when CPUs are saturated, runqueues are long or the
+// host steals time from a VM, wake-ups slip by tens of milliseconds, which
+// load average alone doesn't show.
+const TICK: Duration = Duration::from_millis(20);
+// Wake-up delays kept for the status line
+const WINDOW: Duration = Duration::from_secs(60);
+// p99 delay above which the system counts as sluggish or overloaded
+const SLUGGISH: Duration = Duration::from_millis(10);
+const OVERLOADED: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Default)]
+pub struct Responsiveness(Arc<Mutex<VecDeque<(Instant, Duration)>>>);
+
+impl Responsiveness {
+    pub fn start() -> Responsiveness {
+        let responsiveness = Responsiveness::default();
+        let shared = responsiveness.clone();
+        thread::spawn(move || loop {
+            let before = Instant::now();
+            thread::sleep(TICK);
+            let now = Instant::now();
+            let late = now.duration_since(before).saturating_sub(TICK);
+            let mut delays = shared.0.lock().unwrap();
+            delays.push_back((now, late));
+            while delays.front().is_some_and(|(at, _)| now.duration_since(*at) > WINDOW) {
+                delays.pop_front();
+            }
+        });
+        responsiveness
+    }
+
+    // (median, p99, worst) wake-up delay over the window
+    fn delays(&self) -> Option<(Duration, Duration, Duration)> {
+        let mut delays: Vec<Duration> = self.0.lock().unwrap().iter().map(|(_, late)| *late).collect();
+        if delays.is_empty() {
+            return None;
+        }
+        delays.sort();
+        let at = |fraction: f64| delays[((delays.len() - 1) as f64 * fraction).round() as usize];
+        Some((at(0.5), at(0.99), delays[delays.len() - 1]))
+    }
+
+    // One line for the System Information panel
+    pub fn status(&self) -> String {
+        let Some((median, p99, worst)) = self.delays() else {
+            return "⏱️ Responsiveness: measuring…".to_string();
+        };
+        let verdict = if p99 >= OVERLOADED {
+            "🔴 overloaded"
+        } else if p99 >= SLUGGISH {
+            "🟡 sluggish"
+        } else {
+            "🟢 good"
+        };
+        let ms = |delay: Duration| delay.as_secs_f64() * 1000.0;
+        format!(
+            "⏱️ Responsiveness: {} (wake-up delay median {:.1} ms, p99 {:.1} ms, worst {:.1} ms over {}s)",
+            verdict,
+            ms(median),
+            ms(p99),
+            ms(worst),
+            WINDOW.as_secs()
+        )
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{columns::Column, units::UnitsConfig};
+use ratatui::{
+    style::Style,
+    widgets::{Cell, Row},
+};
+use std::{collections::HashMap, time::Instant};
+use sysinfo::{Pid, Process};
+
+// The process table's cells, formatted once per refresh rather than on every
+// frame: the values only change when the collectors run, while frames come
+// with every key press and mouse move. Rows borrow the cached text, so
+// drawing an unchanged row formats and copies nothing. The cache starts over
+// after a refresh or when the columns, the Name column's mode or the units
+// change, and keeps its allocations when it does.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheKey {
+    // The refresh the values are from
+    pub refreshed: Instant,
+    pub columns: Vec<Column>,
+    pub show_commands: bool,
+    pub command_offset: usize,
+    // Memory and disk rates are written in them
+    pub units: UnitsConfig,
+}
+
+#[derive(Default)]
+pub struct RowCache {
+    key: Option<CacheKey>,
+    // Text and style of each cell, in the order of key.columns
+    rows: HashMap<Pid, Vec<(String, Style)>>,
+    // Vecs of rows that have gone, reused for new ones
+    spare: Vec<Vec<(String, Style)>>,
+}
+
+impl RowCache {
+    // Formats the rows the cache doesn't have yet for this refresh and view
+    pub fn prepare<'a>(
+        &mut self,
+        key: CacheKey,
+        processes: impl IntoIterator<Item = &'a (Pid, &'a Process)>,
+        format: impl Fn(Column, Pid, &Process) -> (String, Style),
+    ) {
+        if self.key.as_ref() != Some(&key) {
+            self.spare.extend(self.rows.drain().map(|(_, mut cells)| {
+                cells.clear();
+                cells
+            }));
+            self.key = Some(key);
+        }
+        let Some(key) = &self.key else { return };
+        for (pid, process) in processes {
+            if self.rows.contains_key(pid) {
+                continue;
+            }
+            let mut cells = self.spare.pop().unwrap_or_default();
+            cells.extend(key.columns.iter().map(|column| format(*column, *pid, process)));
+            self.rows.insert(*pid, cells);
+        }
+    }
+
+    // A prepared row, borrowing its text
+    pub fn row(&self, pid: Pid) -> Row<'_> {
+        let cells = self.rows.get(&pid).map(Vec::as_slice).unwrap_or_default();
+        Row::new(cells.iter().map(|(text, style)| Cell::from(text.as_str()).style(*style)))
+    }
+}
@@ -0,0 +1,481 @@
+use crate::alerts::Severity;
+use crate::{App, Collector};
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+// User-defined alert conditions, written as expressions over named metrics:
+//
+//   avg_over(cpu, 5m) > 90 && load1 > cores
+//
+// Numbers may carry a duration unit (30s, 5m, 1h, in seconds). Comparisons and
+// && / || / ! work on truthiness; a metric with no data yet makes the rule
+// quietly not fire instead of raising a false alarm.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AlertRuleConfig {
+    pub name: String,
+    pub expr: String,
+    pub severity: Option<Severity>,
+    // Shown in the title bar and sent to channels; defaults to the expression
+    pub message: Option<String>,
+}
+
+// Current values: percentages, load, bytes per second, counts
+const METRICS: &[&str] = &[
+    "cpu", "memory", "swap", "load1", "load5", "load15", "cores", "net_rx", "net_tx", "retransmit", "disk",
+    "processes", "page_cache", "minor_faults", "major_faults", "reclaim", "refaults", "steal",
+];
+// Metrics kept in the history, usable in *_over functions
+const HISTORY_METRICS: &[&str] = &[
+    "cpu", "memory", "swap", "load1", "net_rx", "net_tx", "page_cache", "minor_faults", "major_faults", "reclaim",
+    "refaults", "steal",
+];
+const FUNCTIONS: &[&str] = &["avg_over", "max_over", "min_over"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Metric(String),
+    // function, metric, window in seconds
+    Over(String, String, f64),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(String, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    pub name: String,
+    pub severity: Severity,
+    pub message: String,
+    expr: Expr,
+}
+
+impl AlertRule {
+    pub fn parse(config: &AlertRuleConfig) -> Result<AlertRule, String> {
+        if config.name.is_empty() {
+            return Err(format!("alert rule '{}': name is required", config.expr));
+        }
+        let expr = Parser::new(&config.expr)
+            .and_then(|mut parser| parser.parse())
+            .map_err(|e| format!("alert rule '{}': {}", config.name, e))?;
+        Ok(AlertRule {
+            name: config.name.clone(),
+            severity: config.severity.unwrap_or(Severity::Warning),
+            message: config.message.clone().unwrap_or_else(|| format!("{}: {}", config.name, config.expr.trim())),
+            expr,
+        })
+    }
+
+    // True when the condition holds; false while any metric it needs has no data
+    pub fn matches(&self, app: &App) -> bool {
+        let leaf = |leaf: &Expr| match leaf {
+            Expr::Metric(name) => metric(name, app),
+            Expr::Over(function, name, window) => over(function, name, *window, app),
+            _ => None,
+        };
+        eval(&self.expr, &leaf).is_some_and(|value| value != 0.0)
+    }
+
+    pub fn collectors(&self) -> Vec<Collector> {
+        let mut metrics = Vec::new();
+        metric_names(&self.expr, &mut metrics);
+        metrics
+            .into_iter()
+            .filter_map(|metric| match metric {
+                "net_rx" | "net_tx" => Some(Collector::Networks),
+                "disk" => Some(Collector::Disks),
+                "processes" => Some(Collector::Processes),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn metric_names<'a>(expr: &'a Expr, names: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Number(_) => {}
+        Expr::Metric(name) | Expr::Over(_, name, _) => names.push(name),
+        Expr::Neg(inner) | Expr::Not(inner) => metric_names(inner, names),
+        Expr::Binary(_, left, right) => {
+            metric_names(left, names);
+            metric_names(right, names);
+        }
+    }
+}
+
+// leaf gives the value of a metric or *_over call, None without data
+fn eval(expr: &Expr, leaf: &dyn Fn(&Expr) -> Option<f64>) -> Option<f64> {
+    let truth = |b: bool| if b { 1.0 } else { 0.0 };
+    match expr {
+        Expr::Number(value) => Some(*value),
+        Expr::Metric(_) | Expr::Over(..) => leaf(expr),
+        Expr::Neg(inner) => eval(inner, leaf).map(|v| -v),
+        Expr::Not(inner) => eval(inner, leaf).map(|v| truth(v == 0.0)),
+        // Either side being false settles these, even if the other has no data
+        Expr::Binary(op, left, right) if op == "&&" || op == "||" => {
+            let (left, right) = (eval(left, leaf).map(|v| v != 0.0), eval(right, leaf).map(|v| v != 0.0));
+            match (op.as_str(), left, right) {
+                ("&&", Some(false), _) | ("&&", _, Some(false)) => Some(0.0),
+                ("||", Some(true), _) | ("||", _, Some(true)) => Some(1.0),
+                (_, Some(l), Some(r)) => Some(truth(if op == "&&" { l && r } else { l || r })),
+                _ => None,
+            }
+        }
+        Expr::Binary(op, left, right) => {
+            let (l, r) = (eval(left, leaf)?, eval(right, leaf)?);
+            Some(match op.as_str() {
+                "+" => l + r,
+                "-" => l - r,
+                "*" => l * r,
+                "/" if r == 0.0 => return None,
+                "/" => l / r,
+                "<" => truth(l < r),
+                "<=" => truth(l <= r),
+                ">" => truth(l > r),
+                ">=" => truth(l >= r),
+                "==" => truth(l == r),
+                "!=" => truth(l != r),
+                _ => unreachable!("operator {} is not produced by the parser", op),
+            })
+        }
+    }
+}
+
+fn metric(name: &str, app: &App) -> Option<f64> {
+    let percent = |used: u64, total: u64| (total > 0).then(|| used as f64 / total as f64 * 100.0);
+    let load = System::load_average();
+    match name {
+        "cpu" => Some(app.system.global_cpu_usage() as f64),
+        "memory" => percent(app.system.used_memory(), app.system.total_memory()),
+        "swap" => percent(app.system.used_swap(), app.system.total_swap()).or(Some(0.0)),
+        "load1" => Some(load.one),
+        "load5" => Some(load.five),
+        "load15" => Some(load.fifteen),
+        "cores" => Some(app.system.cpus().len() as f64),
+        "net_rx" | "net_tx" | "page_cache" | "minor_faults" | "major_faults" | "reclaim" | "refaults" | "steal" => {
+            app.history.latest(name)
+        }
+        "retransmit" => Some(app.sockets.retransmit_percent),
+        "disk" => app
+            .disks
+            .iter()
+            .filter_map(|disk| percent(disk.total_space() - disk.available_space(), disk.total_space()))
+            .reduce(f64::max),
+        "processes" => Some(app.processes.processes().values().filter(|process| process.thread_kind().is_none()).count() as f64),
+        _ => None,
+    }
+}
+
+fn over(function: &str, name: &str, window: f64, app: &App) -> Option<f64> {
+    let key = if name == "load1" { "load" } else { name };
+    let since = chrono::Utc::now().timestamp() - window as i64;
+    let values: Vec<f64> = app
+        .history
+        .series(key)?
+        .iter()
+        .filter(|(time, _)| *time >= since)
+        .map(|(_, value)| *value)
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    Some(match function {
+        "avg_over" => values.iter().sum::<f64>() / values.len() as f64,
+        "max_over" => values.iter().copied().fold(f64::MIN, f64::max),
+        _ => values.iter().copied().fold(f64::MAX, f64::min),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(String),
+}
+
+// Recursive descent, loosest binding first: || && ! comparison + - * / unary
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Parser, String> {
+        let chars: Vec<char> = source.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c.is_whitespace() {
+                i += 1;
+            } else if c.is_ascii_digit() || c == '.' {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let mut value: f64 = text.parse().map_err(|_| format!("bad number '{}'", text))?;
+                // A unit directly after the number makes it a duration in seconds
+                let unit = chars.get(i).filter(|c| c.is_ascii_alphabetic());
+                if let Some(unit) = unit {
+                    value *= match unit {
+                        's' => 1.0,
+                        'm' => 60.0,
+                        'h' => 3600.0,
+                        _ => return Err(format!("unknown unit '{}' after {}, use s, m or h", unit, text)),
+                    };
+                    i += 1;
+                }
+                tokens.push(Token::Number(value));
+            } else if c.is_ascii_alphabetic() || c == '_' {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            } else {
+                let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+                if ["&&", "||", "<=", ">=", "==", "!="].contains(&two.as_str()) {
+                    tokens.push(Token::Op(two));
+                    i += 2;
+                } else if "+-*/<>!(),".contains(c) {
+                    tokens.push(Token::Op(c.to_string()));
+                    i += 1;
+                } else {
+                    return Err(format!("unexpected '{}'", c));
+                }
+            }
+        }
+        Ok(Parser { tokens, pos: 0 })
+    }
+
+    fn parse(&mut self) -> Result<Expr, String> {
+        if self.tokens.is_empty() {
+            return Err("expression is empty".to_string());
+        }
+        let expr = self.binary(0)?;
+        match self.tokens.get(self.pos) {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected {} after the expression", describe(token))),
+        }
+    }
+
+    fn peek_op(&self, ops: &[&str]) -> Option<String> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if ops.contains(&op.as_str()) => Some(op.clone()),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(found)) if found == op => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(token) => Err(format!("expected '{}' but found {}", op, describe(token))),
+            None => Err(format!("expected '{}' at the end", op)),
+        }
+    }
+
+    // Binary operator levels, each left-associative
+    fn binary(&mut self, level: usize) -> Result<Expr, String> {
+        const LEVELS: &[&[&str]] = &[&["||"], &["&&"], &["<", "<=", ">", ">=", "==", "!="], &["+", "-"], &["*", "/"]];
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        while let Some(op) = self.peek_op(LEVELS[level]) {
+            self.pos += 1;
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.peek_op(&["!"]).is_some() {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.peek_op(&["-"]).is_some() {
+            self.pos += 1;
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("expression ends too early")?;
+        self.pos += 1;
+        match token {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::Op(op) if op == "(" => {
+                let inner = self.binary(0)?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Token::Ident(name) if self.peek_op(&["("]).is_some() => {
+                if !FUNCTIONS.contains(&name.as_str()) {
+                    return Err(format!("unknown function '{}', expected one of {}", name, FUNCTIONS.join(", ")));
+                }
+                self.pos += 1;
+                let metric = match self.tokens.get(self.pos) {
+                    Some(Token::Ident(metric)) if HISTORY_METRICS.contains(&metric.as_str()) => metric.clone(),
+                    _ => {
+                        return Err(format!("{} needs one of {} first", name, HISTORY_METRICS.join(", ")));
+                    }
+                };
+                self.pos += 1;
+                self.expect(",")?;
+                let window = match self.tokens.get(self.pos) {
+                    Some(Token::Number(window)) if *window > 0.0 => *window,
+                    _ => return Err(format!("{} needs a window such as 5m", name)),
+                };
+                self.pos += 1;
+                self.expect(")")?;
+                Ok(Expr::Over(name, metric, window))
+            }
+            Token::Ident(name) if METRICS.contains(&name.as_str()) => Ok(Expr::Metric(name)),
+            Token::Ident(name) => Err(format!("unknown metric '{}', expected one of {}", name, METRICS.join(", "))),
+            Token::Op(op) => Err(format!("unexpected '{}'", op)),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(value) => format!("number {}", value),
+        Token::Ident(name) => format!("'{}'", name),
+        Token::Op(op) => format!("'{}'", op),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(expr: &str) -> Result<Expr, String> {
+        Parser::new(expr).and_then(|mut parser| parser.parse())
+    }
+
+    fn metric(name: &str) -> Box<Expr> {
+        Box::new(Expr::Metric(name.to_string()))
+    }
+
+    fn binary(op: &str, left: Box<Expr>, right: Box<Expr>) -> Box<Expr> {
+        Box::new(Expr::Binary(op.to_string(), left, right))
+    }
+
+    // cpu is 95 and load1 is 2; memory and every *_over have no data
+    fn value(expr: &str) -> Option<f64> {
+        let leaf = |leaf: &Expr| match leaf {
+            Expr::Metric(name) if name == "cpu" => Some(95.0),
+            Expr::Metric(name) if name == "load1" => Some(2.0),
+            _ => None,
+        };
+        eval(&parse(expr).unwrap(), &leaf)
+    }
+
+    #[test]
+    fn binds_like_c() {
+        assert_eq!(
+            parse("cpu > 90 || load1 > 1 && !swap").unwrap(),
+            *binary(
+                "||",
+                binary(">", metric("cpu"), Box::new(Expr::Number(90.0))),
+                binary(
+                    "&&",
+                    binary(">", metric("load1"), Box::new(Expr::Number(1.0))),
+                    Box::new(Expr::Not(metric("swap")))
+                )
+            )
+        );
+        assert_eq!(value("1 + 2 * 3"), Some(7.0));
+        assert_eq!(value("(1 + 2) * 3"), Some(9.0));
+        assert_eq!(value("10 - 4 - 3"), Some(3.0));
+        assert_eq!(value("-2 * -3"), Some(6.0));
+    }
+
+    #[test]
+    fn durations_are_seconds() {
+        let over = Expr::Over("avg_over".to_string(), "cpu".to_string(), 30.0);
+        assert_eq!(parse("avg_over(cpu, 30s) > 0").unwrap(), *binary(">", Box::new(over), Box::new(Expr::Number(0.0))));
+        assert_eq!(value("5m"), Some(300.0));
+        assert_eq!(value("1.5h"), Some(5400.0));
+        assert_eq!(value("2s + 1"), Some(3.0));
+    }
+
+    #[test]
+    fn rejects_bad_expressions() {
+        for (expr, error) in [
+            ("", "expression is empty"),
+            ("cpu >", "ends too early"),
+            ("cpu > 90 90", "unexpected number 90"),
+            ("(cpu > 90", "expected ')' at the end"),
+            ("5d", "unknown unit 'd'"),
+            ("1..2", "bad number '1..2'"),
+            ("cpu # 2", "unexpected '#'"),
+            ("gpu > 1", "unknown metric 'gpu'"),
+            ("median_over(cpu, 5m)", "unknown function 'median_over'"),
+            ("avg_over(cores, 5m)", "avg_over needs one of"),
+            ("max_over(cpu)", "expected ','"),
+            ("min_over(cpu, 0)", "min_over needs a window"),
+            ("avg_over(cpu, 5m", "expected ')' at the end"),
+        ] {
+            let err = parse(expr).unwrap_err();
+            assert!(err.contains(error), "{}: {}", expr, err);
+        }
+    }
+
+    #[test]
+    fn rule_needs_a_name() {
+        let config = AlertRuleConfig { expr: "cpu > 90".to_string(), ..AlertRuleConfig::default() };
+        assert!(AlertRule::parse(&config).is_err());
+        let rule = AlertRule::parse(&AlertRuleConfig { name: "hot".to_string(), ..config }).unwrap();
+        assert_eq!((rule.severity, rule.message.as_str()), (Severity::Warning, "hot: cpu > 90"));
+    }
+
+    #[test]
+    fn comparisons_are_truth_values() {
+        assert_eq!(value("cpu > 90"), Some(1.0));
+        assert_eq!(value("cpu <= 90"), Some(0.0));
+        assert_eq!(value("load1 == 2 && load1 != 3"), Some(1.0));
+        assert_eq!(value("!cpu"), Some(0.0));
+        assert_eq!(value("1 / 0"), None);
+    }
+
+    #[test]
+    fn missing_data_only_matters_when_it_decides() {
+        // true, false and no data on either side
+        for (expr, expected) in [
+            ("cpu > 90 && memory > 90", None),
+            ("cpu < 90 && memory > 90", Some(0.0)),
+            ("memory > 90 && cpu < 90", Some(0.0)),
+            ("memory > 90 && memory < 90", None),
+            ("cpu > 90 || memory > 90", Some(1.0)),
+            ("memory > 90 || cpu > 90", Some(1.0)),
+            ("cpu < 90 || memory > 90", None),
+            ("memory > 90 || memory < 90", None),
+            ("cpu > 90 && load1 > 1", Some(1.0)),
+            ("cpu < 90 || load1 < 1", Some(0.0)),
+            ("avg_over(cpu, 5m) > 90", None),
+            ("!memory", None),
+            ("memory + 1 > 0", None),
+        ] {
+            assert_eq!(value(expr), expected, "{}", expr);
+        }
+    }
+
+    #[test]
+    fn collectors_follow_the_metrics() {
+        let rule = AlertRule::parse(&AlertRuleConfig {
+            name: "busy".to_string(),
+            expr: "processes > 500 || avg_over(net_rx, 1m) > 1000 || disk > 90".to_string(),
+            ..AlertRuleConfig::default()
+        })
+        .unwrap();
+        assert_eq!(rule.collectors(), [Collector::Processes, Collector::Networks, Collector::Disks]);
+    }
+}
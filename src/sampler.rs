@@ -0,0 +1,155 @@
+use chrono::Local;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// "Flamegraph-lite": samples the kernel stacks of every thread of a process
+// from /proc/<pid>/task/<tid>/stack for a few seconds, then shows the hottest
+// functions and writes a collapsed-stack file usable by flamegraph.pl or
+// inferno. Threads running user code have an empty kernel stack and are
+// counted as "[running in userspace]". Reading stacks needs root.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
+pub struct StackSampler {
+    pub pid: u32,
+    pub name: String,
+    duration: Duration,
+    started: Instant,
+    result: Arc<Mutex<Option<Result<SampleReport, String>>>>,
+}
+
+pub struct SampleReport {
+    pub samples: usize,
+    // (function, samples where it was on top of the stack), hottest first
+    pub hot: Vec<(String, usize)>,
+    pub export: Result<PathBuf, String>,
+}
+
+impl StackSampler {
+    pub fn start(pid: u32, name: String, duration: Duration) -> StackSampler {
+        let result = Arc::new(Mutex::new(None));
+        let thread_result = Arc::clone(&result);
+        let thread_name = name.clone();
+        thread::spawn(move || {
+            let report = sample(pid, &thread_name, duration);
+            *thread_result.lock().unwrap() = Some(report);
+        });
+        StackSampler { pid, name, duration, started: Instant::now(), result }
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect) {
+        let title = format!("🔥 Stack samples: {} ({}) — Esc close", self.name, self.pid);
+        let items: Vec<ListItem> = match &*self.result.lock().unwrap() {
+            None => {
+                let elapsed = self.started.elapsed().as_secs().min(self.duration.as_secs());
+                vec![ListItem::new(format!(
+                    "⏳ Sampling kernel stacks... {}/{}s",
+                    elapsed,
+                    self.duration.as_secs()
+                ))]
+            }
+            Some(Err(err)) => vec![ListItem::new(format!("❌ {}", err))],
+            Some(Ok(report)) => {
+                let mut items = vec![
+                    ListItem::new(match &report.export {
+                        Ok(path) => format!("💾 Collapsed stacks: {}", path.display()),
+                        Err(err) => format!("❌ Could not write collapsed stacks: {}", err),
+                    }),
+                    ListItem::new(format!("{} samples, hottest functions:", report.samples)),
+                ];
+                for (function, count) in &report.hot {
+                    let percent = *count as f64 / report.samples.max(1) as f64 * 100.0;
+                    items.push(ListItem::new(format!("{:>6.1}%  {}", percent, function)));
+                }
+                items
+            }
+        };
+
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .style(Style::default().fg(Color::White));
+        f.render_widget(Clear, area);
+        f.render_widget(list, area);
+    }
+}
+
+fn sample(pid: u32, name: &str, duration: Duration) -> Result<SampleReport, String> {
+    let task_dir = PathBuf::from(format!("/proc/{}/task", pid));
+    let mut stacks: HashMap<String, usize> = HashMap::new();
+    let mut samples = 0;
+    let started = Instant::now();
+
+    while started.elapsed() < duration {
+        let tasks = fs::read_dir(&task_dir).map_err(|e| format!("process {} is gone: {}", pid, e))?;
+        for task in tasks.flatten() {
+            let path = task.path();
+            let stack = match fs::read_to_string(path.join("stack")) {
+                Ok(stack) => stack,
+                Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                    return Err(format!(
+                        "reading kernel stacks {}",
+                        crate::privilege::root_hint()
+                    ));
+                }
+                // Thread exited between listing and reading
+                Err(_) => continue,
+            };
+            let comm = fs::read_to_string(path.join("comm")).unwrap_or_default();
+            let mut frames: Vec<&str> = stack.lines().filter_map(frame_function).collect();
+            if frames.is_empty() {
+                frames.push("[running in userspace]");
+            }
+            // Collapsed format is root-first: "comm;outer;inner count"
+            frames.reverse();
+            let key = format!("{};{}", comm.trim(), frames.join(";"));
+            *stacks.entry(key).or_insert(0) += 1;
+            samples += 1;
+        }
+        thread::sleep(SAMPLE_INTERVAL);
+    }
+
+    let mut hot: HashMap<String, usize> = HashMap::new();
+    for (stack, count) in &stacks {
+        if let Some(leaf) = stack.rsplit(';').next() {
+            *hot.entry(leaf.to_string()).or_insert(0) += count;
+        }
+    }
+    let mut hot: Vec<(String, usize)> = hot.into_iter().collect();
+    hot.sort_by_key(|h| std::cmp::Reverse(h.1));
+    hot.truncate(20);
+
+    Ok(SampleReport { samples, hot, export: export(pid, name, &stacks) })
+}
+
+// "[<0>] do_sys_poll+0x3d1/0x5c0" -> "do_sys_poll"
+fn frame_function(line: &str) -> Option<&str> {
+    let symbol = line.split_once("] ")?.1;
+    let function = symbol.split('+').next()?.trim();
+    (!function.is_empty()).then_some(function)
+}
+
+fn export(pid: u32, name: &str, stacks: &HashMap<String, usize>) -> Result<PathBuf, String> {
+    let dir = dirs::data_dir()
+        .ok_or("no data directory available")?
+        .join("system-monitor")
+        .join("stacks");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let name = name.replace('/', "_");
+    let path = dir.join(format!("{}-{}-{}.folded", name, pid, Local::now().format("%Y%m%d-%H%M%S")));
+    let mut lines: Vec<String> = stacks.iter().map(|(stack, count)| format!("{} {}", stack, count)).collect();
+    lines.sort();
+    fs::write(&path, lines.join("\n") + "\n").map_err(|e| e.to_string())?;
+    Ok(path)
+}
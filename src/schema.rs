@@ -0,0 +1,94 @@
+use serde::Serialize;
+
+// The version of everything the monitor writes for other programs: the
+// process CSV from E, the history archive's JSON lines and the JSON API.
+// Compatible changes keep the version: new JSON fields, new CSV columns at
+// the end, new API endpoints. Removing or renaming a field or column,
+// changing its type or unit, or reordering CSV columns raises it, so readers
+// only need to check it and ignore fields they don't know. `system-monitor
+// schema` prints every field below as JSON.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct Field {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    // The schema version that added it
+    pub since: u32,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Format {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub fields: &'static [Field],
+}
+
+const fn field(name: &'static str, kind: &'static str, description: &'static str) -> Field {
+    Field { name, kind, since: 1, description }
+}
+
+// In CSV column order
+pub const PROCESS_CSV: Format = Format {
+    name: "processes.csv",
+    description: "E on the Processes tab: one row per process, under ~/.local/share/system-monitor/processes",
+    fields: &[
+        field("pid", "integer", "process ID"),
+        field("user", "string", "owning user's name, or the UID when it has none"),
+        field("name", "string", "process name"),
+        field("cpu_percent", "number", "CPU since the previous refresh, percent of one core"),
+        field("memory_bytes", "integer", "resident memory"),
+        field("disk_read_per_sec", "number", "bytes read from storage a second; empty before a second sample"),
+        field("disk_write_per_sec", "number", "bytes written to storage a second; empty before a second sample"),
+        field("started", "string", "local start time, YYYY-MM-DD HH:MM:SS"),
+        field("command", "string", "command line, arguments joined by spaces"),
+        field("schema_version", "integer", "SCHEMA_VERSION of the file"),
+    ],
+};
+
+pub const ARCHIVE: Format = Format {
+    name: "history/YYYY-MM-DD.jsonl",
+    description: "the history archive: one JSON object per line and minute, under ~/.local/share/system-monitor",
+    fields: &[
+        field("schema_version", "integer", "SCHEMA_VERSION of the line; missing on lines written before versioning, which match version 1"),
+        field("time", "integer", "start of the minute, Unix seconds"),
+        field("average", "object", "metric name to its average over the minute"),
+        field("peak", "object", "metric name to its highest value in the minute"),
+        field("disks", "object", "mount point to used bytes at the end of the minute"),
+        field("cpu_seconds", "object", "process name to CPU seconds, the busiest 20"),
+        field("read_bytes", "object", "process name to bytes read from storage, the busiest 20"),
+        field("written_bytes", "object", "process name to bytes written to storage, the busiest 20"),
+        field("alerts", "array", "alerts raised in the minute: {severity, source, message}"),
+    ],
+};
+
+pub const API: Format = Format {
+    name: "api",
+    description: "the JSON API (cargo feature api); every response carries an X-Schema-Version header",
+    fields: &[
+        field("/cpu usage", "number", "CPU usage, percent of all cores"),
+        field("/cpu cores", "array", "usage of each core, percent"),
+        field("/cpu load", "object", "load averages: {one, five, fifteen}"),
+        field("/memory total", "integer", "bytes of RAM"),
+        field("/memory used", "integer", "bytes of RAM in use"),
+        field("/memory available", "integer", "bytes of RAM available"),
+        field("/memory swap_total", "integer", "bytes of swap"),
+        field("/memory swap_used", "integer", "bytes of swap in use"),
+        field("/processes", "array", "every process, busiest first: {pid, name, cpu, memory} with cpu in percent of one core and memory in bytes"),
+        field("/history", "object", "{metric, samples} with samples as [Unix time, value] pairs; without ?metric= the list of metrics"),
+    ],
+};
+
+// system-monitor schema
+pub fn print() -> serde_json::Result<()> {
+    #[derive(Serialize)]
+    struct Schema {
+        schema_version: u32,
+        formats: [&'static Format; 3],
+    }
+    let schema = Schema { schema_version: SCHEMA_VERSION, formats: [&PROCESS_CSV, &ARCHIVE, &API] };
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
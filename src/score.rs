@@ -0,0 +1,252 @@
+use crate::alerts::Severity;
+use crate::{units, App};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    process::Command,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+// One 0–100 number for "how is this machine doing", the weighted average of
+// per-area scores. Areas without data (no sensors, no checks) are left out
+// rather than counted as healthy.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ScoreConfig {
+    pub enabled: bool,
+    pub cpu: f64,
+    pub memory: f64,
+    pub disk: f64,
+    pub temperature: f64,
+    pub services: f64,
+    pub alerts: f64,
+}
+
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        ScoreConfig { enabled: true, cpu: 20.0, memory: 20.0, disk: 15.0, temperature: 10.0, services: 20.0, alerts: 15.0 }
+    }
+}
+
+impl ScoreConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let weights = [self.cpu, self.memory, self.disk, self.temperature, self.services, self.alerts];
+        if weights.iter().any(|w| *w < 0.0) {
+            return Err("health_score: weights can't be negative".to_string());
+        }
+        if self.enabled && weights.iter().sum::<f64>() <= 0.0 {
+            return Err("health_score: at least one weight must be above 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScorePart {
+    pub name: &'static str,
+    pub weight: f64,
+    pub score: f64,
+    // What the score is based on, e.g. "82% used"
+    pub detail: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthScore {
+    pub total: f64,
+    pub parts: Vec<ScorePart>,
+}
+
+pub fn score_color(score: f64) -> Color {
+    match score {
+        s if s >= 80.0 => Color::Green,
+        s if s >= 50.0 => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+// 100 up to `good`, falling linearly to 0 at `bad`
+fn ramp(value: f64, good: f64, bad: f64) -> f64 {
+    ((bad - value) / (bad - good) * 100.0).clamp(0.0, 100.0)
+}
+
+pub fn compute(app: &App) -> HealthScore {
+    let weights = &app.config.health_score;
+    let thresholds = &app.thresholds;
+    let mut parts = Vec::new();
+
+    // A minute's average so a single busy refresh doesn't dent the score
+    let since = chrono::Utc::now().timestamp() - 60;
+    let cpu_samples: Vec<f64> = app
+        .history
+        .series("cpu")
+        .map(|series| series.iter().filter(|(t, _)| *t >= since).map(|(_, v)| *v).collect())
+        .unwrap_or_default();
+    if !cpu_samples.is_empty() {
+        let cpu = cpu_samples.iter().sum::<f64>() / cpu_samples.len() as f64;
+        parts.push(ScorePart {
+            name: "CPU",
+            weight: weights.cpu,
+            score: ramp(cpu, 60.0, 100.0),
+            detail: format!("{:.0}% busy over the last minute", cpu),
+        });
+    }
+
+    let total_memory = app.system.total_memory();
+    if total_memory > 0 {
+        let used = app.system.used_memory() as f64 / total_memory as f64 * 100.0;
+        let mut score = ramp(used, 75.0, 98.0);
+        let mut detail = format!("{:.0}% used", used);
+        // Time spent stalled on memory says more than usage (Linux PSI)
+        if let Some(stall) = memory_pressure() {
+            score = score.min(ramp(stall, 1.0, 25.0));
+            detail.push_str(&format!(", {:.1}% stalled", stall));
+        }
+        parts.push(ScorePart { name: "Memory", weight: weights.memory, score, detail });
+    }
+
+    let fullest = app
+        .disks
+        .iter()
+        .filter(|disk| disk.total_space() > 0)
+        .map(|disk| {
+            let used = (disk.total_space() - disk.available_space()) as f64 / disk.total_space() as f64 * 100.0;
+            (used, disk.mount_point().display().to_string())
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+    if let Some((used, mount)) = fullest {
+        parts.push(ScorePart {
+            name: "Disk",
+            weight: weights.disk,
+            score: ramp(used, thresholds.disk_warn - 20.0, 100.0),
+            detail: format!("{} is {:.0}% full", mount, used),
+        });
+    }
+
+    let hottest = app
+        .components
+        .iter()
+        .filter_map(|c| c.temperature().filter(|t| t.is_finite() && *t > 0.0).map(|t| (t, c.label().to_string())))
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+    if let Some((temp, label)) = hottest {
+        parts.push(ScorePart {
+            name: "Temperature",
+            weight: weights.temperature,
+            score: ramp(temp as f64, thresholds.temp_warn as f64 - 10.0, thresholds.temp_crit as f64 + 10.0),
+            detail: format!("{} at {}", label, units::temperature(temp, 0)),
+        });
+    }
+
+    let mut failed: Vec<String> = app
+        .health_checks
+        .iter()
+        .chain(&app.port_checks)
+        .filter(|check| check.last().is_some_and(|r| !r.healthy()))
+        .map(|check| check.title().to_string())
+        .collect();
+    let units = app.failed_units.get();
+    failed.extend(units.iter().flatten().cloned());
+    if units.is_some() || !app.health_checks.is_empty() || !app.port_checks.is_empty() {
+        parts.push(ScorePart {
+            name: "Services",
+            weight: weights.services,
+            // Each failure halves what is left
+            score: 100.0 / 2f64.powi(failed.len() as i32),
+            detail: if failed.is_empty() { "all up".to_string() } else { format!("failed: {}", failed.join(", ")) },
+        });
+    }
+
+    let active: Vec<_> = app.alerts.iter().filter(|a| app.silences.silenced_by(a).is_none()).collect();
+    let critical = active.iter().filter(|a| a.severity == Severity::Critical).count();
+    let warning = active.len() - critical;
+    parts.push(ScorePart {
+        name: "Alerts",
+        weight: weights.alerts,
+        score: (100.0 - 40.0 * critical as f64 - 15.0 * warning as f64).max(0.0),
+        detail: format!("{} critical, {} warning", critical, warning),
+    });
+
+    let weight: f64 = parts.iter().map(|p| p.weight).sum();
+    let total = if weight > 0.0 { parts.iter().map(|p| p.score * p.weight).sum::<f64>() / weight } else { 100.0 };
+    HealthScore { total, parts }
+}
+
+// "some avg10" from /proc/pressure/memory: share of the last 10s with a task stalled
+fn memory_pressure() -> Option<f64> {
+    let pressure = fs::read_to_string("/proc/pressure/memory").ok()?;
+    let some = pressure.lines().find(|line| line.starts_with("some "))?;
+    some.split_whitespace().find_map(|field| field.strip_prefix("avg10=")?.parse().ok())
+}
+
+// systemd units in the failed state, polled in the background; only started
+// where systemd runs, None otherwise
+#[derive(Clone, Default)]
+pub struct FailedUnits(Arc<Mutex<Option<Vec<String>>>>);
+
+impl FailedUnits {
+    pub fn start() -> FailedUnits {
+        let units = FailedUnits::default();
+        let shared = units.clone();
+        thread::spawn(move || loop {
+            let output = Command::new("systemctl")
+                .args(["list-units", "--state=failed", "--no-legend", "--plain", "--no-pager"])
+                .output();
+            let failed = output.ok().filter(|o| o.status.success()).map(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .lines()
+                    .filter_map(|line| line.split_whitespace().next().map(str::to_string))
+                    .collect()
+            });
+            *shared.0.lock().unwrap() = failed;
+            thread::sleep(Duration::from_secs(60));
+        });
+        units
+    }
+
+    pub fn get(&self) -> Option<Vec<String>> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+// The H popup: each area's score, weight and what it's based on
+pub fn draw_breakdown(f: &mut Frame, area: Rect, score: &HealthScore) {
+    let weight: f64 = score.parts.iter().map(|p| p.weight).sum();
+    let rows: Vec<Row> = score
+        .parts
+        .iter()
+        .map(|part| {
+            Row::new(vec![
+                part.name.to_string(),
+                format!("{:.0}", part.score),
+                format!("{:.0}%", if weight > 0.0 { part.weight / weight * 100.0 } else { 0.0 }),
+                part.detail.clone(),
+            ])
+            .style(Style::default().fg(score_color(part.score)))
+        })
+        .collect();
+    let header = Row::new(vec!["Area", "Score", "Weight", "Based on"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let title = Line::from(format!("❤️ Health score {:.0}/100 — Esc to close", score.total));
+    let table = Table::new(
+        rows,
+        [Constraint::Length(12), Constraint::Length(6), Constraint::Length(7), Constraint::Min(20)],
+    )
+    .header(header)
+    .block(Block::default().title(title).borders(Borders::ALL))
+    .column_spacing(1);
+    f.render_widget(Clear, area);
+    f.render_widget(table, area);
+
+    let note = Paragraph::new("Weighted average of the areas with data; weights are set under [health_score].")
+        .style(Style::default().fg(Color::DarkGray));
+    let bottom = Rect { y: area.y + area.height.saturating_sub(2), height: 1, x: area.x + 1, width: area.width.saturating_sub(2) };
+    f.render_widget(note, bottom);
+}
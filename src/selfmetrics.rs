@@ -0,0 +1,184 @@
+use crate::{budget::Budgets, format_bytes};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table},
+    Frame,
+};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+// The monitor's own cost, in a panel toggled with F12: its CPU and memory,
+// how long each collector and the rest of a refresh take, how long drawing a
+// frame takes and how many frames ran over the input poll, when keys start to
+// feel sluggish. Meant for tracking down, and reporting, a slow monitor.
+pub const FRAME_BUDGET: Duration = Duration::from_millis(100);
+// Weight of the newest sample in the averages
+const SMOOTHING: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timing {
+    pub last: Duration,
+    pub average: Duration,
+    pub max: Duration,
+    pub samples: u64,
+}
+
+impl Timing {
+    pub fn record(&mut self, elapsed: Duration) {
+        self.last = elapsed;
+        self.max = self.max.max(elapsed);
+        self.average = if self.samples == 0 {
+            elapsed
+        } else {
+            self.average.mul_f64(1.0 - SMOOTHING) + elapsed.mul_f64(SMOOTHING)
+        };
+        self.samples += 1;
+    }
+}
+
+pub struct SelfMetrics {
+    pid: Option<Pid>,
+    system: System,
+    pub cpu: f32,
+    pub memory: u64,
+    // In the order first recorded, which is the refresh order
+    timings: Vec<(&'static str, Timing)>,
+    render: Timing,
+    frames: u64,
+    // Frames whose refresh and drawing together took longer than FRAME_BUDGET
+    dropped: u64,
+    started: Instant,
+}
+
+impl Default for SelfMetrics {
+    fn default() -> Self {
+        SelfMetrics {
+            pid: sysinfo::get_current_pid().ok(),
+            system: System::new(),
+            cpu: 0.0,
+            memory: 0,
+            timings: Vec::new(),
+            render: Timing::default(),
+            frames: 0,
+            dropped: 0,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl SelfMetrics {
+    // Once per refresh interval
+    pub fn update(&mut self) {
+        let Some(pid) = self.pid else { return };
+        let kind = ProcessRefreshKind::nothing().with_cpu().with_memory();
+        self.system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, kind);
+        if let Some(process) = self.system.process(pid) {
+            self.cpu = process.cpu_usage();
+            self.memory = process.memory();
+        }
+    }
+
+    pub fn record(&mut self, name: &'static str, elapsed: Duration) {
+        match self.timings.iter_mut().find(|(known, _)| *known == name) {
+            Some((_, timing)) => timing.record(elapsed),
+            None => {
+                let mut timing = Timing::default();
+                timing.record(elapsed);
+                self.timings.push((name, timing));
+            }
+        }
+    }
+
+    // frame is the whole loop iteration before waiting for input
+    pub fn record_frame(&mut self, render: Duration, frame: Duration) {
+        self.render.record(render);
+        self.frames += 1;
+        if frame > FRAME_BUDGET {
+            self.dropped += 1;
+        }
+    }
+
+    fn summary(&self) -> Vec<Line<'static>> {
+        let dropped = if self.frames == 0 { 0.0 } else { self.dropped as f64 / self.frames as f64 * 100.0 };
+        vec![
+            Line::from(format!(
+                "CPU {:.1}% · resident {} · up {}s · {} collectors timed",
+                self.cpu,
+                format_bytes(self.memory),
+                self.started.elapsed().as_secs(),
+                self.timings.len()
+            )),
+            Line::styled(
+                format!(
+                    "Frames {} · drawing {} last, {} average, {} worst · {} over {} ms ({:.1}%)",
+                    self.frames,
+                    millis(self.render.last),
+                    millis(self.render.average),
+                    millis(self.render.max),
+                    self.dropped,
+                    FRAME_BUDGET.as_millis(),
+                    dropped
+                ),
+                Style::default().fg(if self.dropped > 0 { Color::Yellow } else { Color::Reset }),
+            ),
+        ]
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
+
+pub fn draw(f: &mut Frame, area: Rect, metrics: &SelfMetrics, budgets: &Budgets) {
+    let block = Block::default()
+        .title("🔧 Monitor self-metrics · F12 / Esc close")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+    let summary = metrics.summary();
+    let height = summary.len() as u16 + 1;
+    f.render_widget(Paragraph::new(summary), Rect { height: height.min(inner.height), ..inner });
+
+    let rows: Vec<Row> = metrics
+        .timings
+        .iter()
+        .map(|(name, timing)| {
+            let (budget, state) = match budgets.tracks(name) {
+                true => (format!("{} ms", budgets.budget(name).as_millis()), budgets.state(name)),
+                false => ("-".to_string(), None),
+            };
+            let overrun = state.is_some();
+            let row = Row::new(vec![
+                name.to_string(),
+                millis(timing.last),
+                millis(timing.average),
+                millis(timing.max),
+                timing.samples.to_string(),
+                budget,
+                state.unwrap_or_default(),
+            ]);
+            if overrun || timing.average > FRAME_BUDGET / 2 {
+                row.style(Style::default().fg(Color::Red))
+            } else {
+                row
+            }
+        })
+        .collect();
+    let header = Row::new(vec!["Collector", "Last", "Average", "Worst", "Runs", "Budget", "State"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Min(18),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(9),
+        Constraint::Length(12),
+    ];
+    let table_area = Rect { y: inner.y + height, height: inner.height.saturating_sub(height), ..inner };
+    f.render_widget(Table::new(rows, widths).header(header).column_spacing(1), table_area);
+}
@@ -0,0 +1,336 @@
+use crate::config::{Config, Thresholds};
+use crate::units::{ByteUnits, TemperatureUnit};
+use crate::{theme::Theme, Tab};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use serde::Serialize;
+use std::{error::Error, fs};
+use toml_edit::{DocumentMut, Item, Table, TableLike};
+
+// The , overlay: common settings edited while the monitor runs. Changes
+// apply at once; Enter writes the ones that differ from the file into
+// config.toml, leaving the rest of it (comments included) alone, and Esc
+// goes back to how things were. Refresh rate, thresholds and tabs belong to
+// the active profile.
+
+// Config key, label and step of each threshold
+const THRESHOLDS: [(&str, &str, f64); 14] = [
+    ("swap_warn", "Swap warning", 5.0),
+    ("swap_crit", "Swap critical", 5.0),
+    ("temp_warn", "Temp warning", 1.0),
+    ("temp_crit", "Temp critical", 1.0),
+    ("retransmit_warn", "Retrans. warning", 0.5),
+    ("retransmit_crit", "Retrans. critical", 0.5),
+    ("disk_warn", "Disk warning", 1.0),
+    ("disk_crit", "Disk critical", 1.0),
+    ("io_latency_warn", "I/O lat. warning", 5.0),
+    ("io_latency_crit", "I/O lat. critical", 5.0),
+    ("tmpfs_warn", "tmpfs warning", 1.0),
+    ("tmpfs_crit", "tmpfs critical", 1.0),
+    ("steal_warn", "Steal warning", 1.0),
+    ("steal_crit", "Steal critical", 1.0),
+];
+
+enum Field {
+    Theme,
+    Bytes,
+    Temperature,
+    Refresh,
+    Threshold(usize),
+    Tab(Tab),
+}
+
+pub struct SettingsEditor {
+    // Config when the editor opened or was last saved
+    original: Config,
+    profile: String,
+    selected: usize,
+    error: Option<String>,
+}
+
+impl SettingsEditor {
+    pub fn new(config: &Config, profile: &str) -> SettingsEditor {
+        SettingsEditor { original: config.clone(), profile: profile.to_string(), selected: 0, error: None }
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        let mut fields = vec![Field::Theme, Field::Bytes, Field::Temperature, Field::Refresh];
+        fields.extend((0..THRESHOLDS.len()).map(Field::Threshold));
+        fields.extend(Tab::all().into_iter().map(Field::Tab));
+        fields
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.fields().len() - 1;
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn change(&mut self, config: &mut Config, forward: bool) {
+        let tabs = config.profile_tabs(&self.profile);
+        match self.fields().remove(self.selected) {
+            Field::Theme => {
+                let index = Theme::ALL.iter().position(|t| *t == config.theme).unwrap_or(0);
+                let len = Theme::ALL.len();
+                config.theme = Theme::ALL[if forward { (index + 1) % len } else { (index + len - 1) % len }];
+            }
+            Field::Bytes => {
+                config.units.bytes = match config.units.bytes {
+                    ByteUnits::Binary => ByteUnits::Decimal,
+                    ByteUnits::Decimal => ByteUnits::Binary,
+                }
+            }
+            Field::Temperature => {
+                config.units.temperature = match config.units.temperature {
+                    TemperatureUnit::Celsius => TemperatureUnit::Fahrenheit,
+                    TemperatureUnit::Fahrenheit => TemperatureUnit::Celsius,
+                }
+            }
+            Field::Refresh => {
+                let profile = config.profiles.entry(self.profile.clone()).or_default();
+                // Finer steps below a second
+                let step = if profile.refresh_ms < 1000 || (profile.refresh_ms == 1000 && !forward) { 100 } else { 500 };
+                profile.refresh_ms = if forward {
+                    (profile.refresh_ms + step).min(60_000)
+                } else {
+                    profile.refresh_ms.saturating_sub(step).max(100)
+                };
+            }
+            Field::Threshold(index) => {
+                let profile = config.profiles.entry(self.profile.clone()).or_default();
+                let (key, _, step) = THRESHOLDS[index];
+                let value = threshold(&profile.thresholds, key) + if forward { step } else { -step };
+                set_threshold(&mut profile.thresholds, key, value.max(0.0));
+            }
+            Field::Tab(tab) => {
+                let mut names: Vec<String> = Tab::all()
+                    .into_iter()
+                    .filter(|t| (*t == tab) != tabs.iter().any(|name| name == t.name()))
+                    .map(|t| t.name().to_string())
+                    .collect();
+                // Keep the configured order; a newly shown tab goes last
+                names.sort_by_key(|name| tabs.iter().position(|t| t == name).unwrap_or(usize::MAX));
+                if names.is_empty() {
+                    return;
+                }
+                match &mut config.profiles.entry(self.profile.clone()).or_default().tabs {
+                    Some(profile_tabs) => *profile_tabs = names,
+                    None => config.tabs = names,
+                }
+            }
+        }
+    }
+
+    // Esc: everything back to how it was
+    pub fn revert(&self, config: &mut Config) {
+        *config = self.original.clone();
+    }
+
+    // Enter: validate and write the changes; false keeps the editor open
+    pub fn save(&mut self, config: &Config) -> bool {
+        let thresholds = config.get_profile(&self.profile).thresholds;
+        let result = thresholds.validate().map_err(Into::into).and_then(|_| self.write(config));
+        self.error = result.err().map(|e| e.to_string());
+        if self.error.is_none() {
+            self.original = config.clone();
+        }
+        self.error.is_none()
+    }
+
+    fn write(&self, config: &Config) -> Result<(), Box<dyn Error>> {
+        let path = Config::path().ok_or("no config directory available")?;
+        let mut doc: DocumentMut = match fs::read_to_string(&path) {
+            Ok(contents) => contents.parse()?,
+            Err(_) => DocumentMut::new(),
+        };
+        let original = &self.original;
+        let profile = self.profile.as_str();
+        if config.theme != original.theme {
+            set(doc.as_table_mut(), "theme", item(&config.theme)?);
+        }
+        if config.units.bytes != original.units.bytes {
+            set(section(&mut doc, &["units"])?, "bytes", item(&config.units.bytes)?);
+        }
+        if config.units.temperature != original.units.temperature {
+            set(section(&mut doc, &["units"])?, "temperature", item(&config.units.temperature)?);
+        }
+
+        let (before, after) = (original.get_profile(profile), config.get_profile(profile));
+        if after.refresh_ms != before.refresh_ms {
+            set(section(&mut doc, &["profiles", profile])?, "refresh_ms", item(&after.refresh_ms)?);
+        }
+        for (key, _, _) in THRESHOLDS {
+            let value = threshold(&after.thresholds, key);
+            if value != threshold(&before.thresholds, key) {
+                set(section(&mut doc, &["profiles", profile, "thresholds"])?, key, item(&value)?);
+            }
+        }
+        if config.profile_tabs(profile) != original.profile_tabs(profile) {
+            match &after.tabs {
+                Some(tabs) => set(section(&mut doc, &["profiles", profile])?, "tabs", item(tabs)?),
+                None => set(doc.as_table_mut(), "tabs", item(&config.tabs)?),
+            };
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        crate::write_atomic(&path, doc.to_string())?;
+        Ok(())
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect, config: &Config) {
+        let profile = config.get_profile(&self.profile);
+        let tabs = config.profile_tabs(&self.profile);
+        let items: Vec<ListItem> = self
+            .fields()
+            .into_iter()
+            .map(|field| {
+                let (label, value) = match field {
+                    Field::Theme => ("Theme".to_string(), format!("◀ {} ▶", config.theme.name())),
+                    Field::Bytes => (
+                        "Sizes".to_string(),
+                        match config.units.bytes {
+                            ByteUnits::Binary => "◀ binary, 1 KB = 1024 bytes ▶".to_string(),
+                            ByteUnits::Decimal => "◀ decimal, 1 kB = 1000 bytes ▶".to_string(),
+                        },
+                    ),
+                    Field::Temperature => (
+                        "Temperatures".to_string(),
+                        match config.units.temperature {
+                            TemperatureUnit::Celsius => "◀ °C ▶".to_string(),
+                            TemperatureUnit::Fahrenheit => "◀ °F ▶".to_string(),
+                        },
+                    ),
+                    Field::Refresh => ("Refresh every".to_string(), format!("◀ {} ms ▶", profile.refresh_ms)),
+                    Field::Threshold(index) => {
+                        let (key, label, _) = THRESHOLDS[index];
+                        // Thresholds are always in °C, whatever the display unit
+                        let unit = match key {
+                            _ if key.starts_with("temp") => "°C",
+                            _ if key.starts_with("io_latency") => " ms",
+                            _ => "%",
+                        };
+                        (label.to_string(), format!("◀ {}{} ▶", threshold(&profile.thresholds, key), unit))
+                    }
+                    Field::Tab(tab) => {
+                        let shown = tabs.iter().any(|name| name == tab.name());
+                        ("Tab".to_string(), format!("{} {}", if shown { "[x]" } else { "[ ]" }, tab.title()))
+                    }
+                };
+                ListItem::new(format!("{:<18} {}", label, value))
+            })
+            .collect();
+
+        let block = Block::default()
+            .title(format!(
+                "⚙️ Settings, profile {} · ↑/↓ select · ←/→ change · Enter save · Esc discard",
+                self.profile
+            ))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(2)])
+            .split(inner);
+
+        let list = List::new(items)
+            .style(Style::default().fg(Color::White))
+            .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .highlight_symbol("▶ ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        f.render_stateful_widget(list, chunks[0], &mut state);
+
+        let path = Config::path().map(|p| p.display().to_string()).unwrap_or_default();
+        let footer = match &self.error {
+            Some(error) => Paragraph::new(format!("❌ Not saved: {}", error)).style(Style::default().fg(Color::Red)),
+            None => Paragraph::new(format!("Changes apply now; Enter writes them to {}.", path))
+                .style(Style::default().fg(Color::Gray)),
+        };
+        f.render_widget(footer.wrap(Wrap { trim: true }), chunks[1]);
+    }
+}
+
+fn threshold(thresholds: &Thresholds, key: &str) -> f64 {
+    match key {
+        "swap_warn" => thresholds.swap_warn,
+        "swap_crit" => thresholds.swap_crit,
+        "temp_warn" => thresholds.temp_warn as f64,
+        "temp_crit" => thresholds.temp_crit as f64,
+        "retransmit_warn" => thresholds.retransmit_warn,
+        "retransmit_crit" => thresholds.retransmit_crit,
+        "disk_warn" => thresholds.disk_warn,
+        "disk_crit" => thresholds.disk_crit,
+        "io_latency_warn" => thresholds.io_latency_warn,
+        "io_latency_crit" => thresholds.io_latency_crit,
+        "tmpfs_warn" => thresholds.tmpfs_warn,
+        "tmpfs_crit" => thresholds.tmpfs_crit,
+        "steal_warn" => thresholds.steal_warn,
+        "steal_crit" => thresholds.steal_crit,
+        _ => 0.0,
+    }
+}
+
+fn set_threshold(thresholds: &mut Thresholds, key: &str, value: f64) {
+    match key {
+        "swap_warn" => thresholds.swap_warn = value,
+        "swap_crit" => thresholds.swap_crit = value,
+        "temp_warn" => thresholds.temp_warn = value as f32,
+        "temp_crit" => thresholds.temp_crit = value as f32,
+        "retransmit_warn" => thresholds.retransmit_warn = value,
+        "retransmit_crit" => thresholds.retransmit_crit = value,
+        "disk_warn" => thresholds.disk_warn = value,
+        "disk_crit" => thresholds.disk_crit = value,
+        "io_latency_warn" => thresholds.io_latency_warn = value,
+        "io_latency_crit" => thresholds.io_latency_crit = value,
+        "tmpfs_warn" => thresholds.tmpfs_warn = value,
+        "tmpfs_crit" => thresholds.tmpfs_crit = value,
+        "steal_warn" => thresholds.steal_warn = value,
+        "steal_crit" => thresholds.steal_crit = value,
+        _ => {}
+    }
+}
+
+// A config value as TOML, e.g. Theme::Light as "light"
+fn item<T: Serialize>(value: &T) -> Result<Item, Box<dyn Error>> {
+    let text = toml::Value::try_from(value)?.to_string();
+    Ok(Item::Value(text.parse()?))
+}
+
+// Replace a value, keeping the comments around it
+fn set(table: &mut dyn TableLike, key: &str, mut value: Item) {
+    match table.get_mut(key) {
+        Some(old) => {
+            if let (Some(old), Some(new)) = (old.as_value(), value.as_value_mut()) {
+                *new.decor_mut() = old.decor().clone();
+            }
+            *old = value;
+        }
+        None => {
+            table.insert(key, value);
+        }
+    }
+}
+
+// The table at `path`, created as a [section] if missing
+fn section<'a>(doc: &'a mut DocumentMut, path: &[&str]) -> Result<&'a mut dyn TableLike, String> {
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for key in path {
+        if table.get(key).is_none() {
+            let mut new = Table::new();
+            // No empty [profiles] header above [profiles.x]
+            new.set_implicit(true);
+            table.insert(key, Item::Table(new));
+        }
+        table = table
+            .get_mut(key)
+            .and_then(Item::as_table_like_mut)
+            .ok_or_else(|| format!("{} in the config file is not a table", key))?;
+    }
+    Ok(table)
+}
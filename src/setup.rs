@@ -0,0 +1,222 @@
+use crate::config::{Config, Profile};
+use crate::theme::Theme;
+use crate::units::{ByteUnits, TemperatureUnit, UnitsConfig};
+use crate::Tab;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, error::Error, fs, path::PathBuf};
+
+// First-run setup: with no config file yet, an overlay asks for the basics
+// and writes them as a short config.toml the user can extend later. The
+// theme previews live behind the overlay.
+const REFRESH_CHOICES: [u64; 5] = [250, 500, 1000, 2000, 5000];
+
+enum Field {
+    Theme,
+    Bytes,
+    Temperature,
+    Refresh,
+    Tab(usize),
+    Privileged,
+}
+
+pub struct SetupWizard {
+    pub theme: Theme,
+    units: UnitsConfig,
+    refresh_ms: u64,
+    tabs: Vec<(Tab, bool)>,
+    allow_privileged: bool,
+    selected: usize,
+    error: Option<String>,
+}
+
+// What gets written; everything else keeps its default
+#[derive(Serialize)]
+struct SetupFile<'a> {
+    theme: Theme,
+    tabs: Vec<&'static str>,
+    allow_privileged: bool,
+    units: &'a UnitsConfig,
+    profiles: BTreeMap<&'static str, RefreshOnly>,
+}
+
+#[derive(Serialize)]
+struct RefreshOnly {
+    refresh_ms: u64,
+}
+
+impl SetupWizard {
+    pub fn new(config: &Config) -> SetupWizard {
+        let profile = config.get_profile(&config.initial_profile());
+        let enabled = config.profile_tabs(&config.initial_profile());
+        SetupWizard {
+            theme: config.theme,
+            units: config.units.clone(),
+            refresh_ms: profile.refresh_ms,
+            tabs: Tab::all().into_iter().map(|tab| (tab, enabled.iter().any(|t| t == tab.name()))).collect(),
+            allow_privileged: config.allow_privileged,
+            selected: 0,
+            error: None,
+        }
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        let mut fields = vec![Field::Theme, Field::Bytes, Field::Temperature, Field::Refresh];
+        fields.extend((0..self.tabs.len()).map(Field::Tab));
+        fields.push(Field::Privileged);
+        fields
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.fields().len() - 1;
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    // ←/→ step through a field's choices, Space toggles the same way
+    pub fn change(&mut self, forward: bool) {
+        let step = |len: usize, index: usize| if forward { (index + 1) % len } else { (index + len - 1) % len };
+        match self.fields().remove(self.selected) {
+            Field::Theme => {
+                let index = Theme::ALL.iter().position(|t| *t == self.theme).unwrap_or(0);
+                self.theme = Theme::ALL[step(Theme::ALL.len(), index)];
+            }
+            Field::Bytes => {
+                self.units.bytes = match self.units.bytes {
+                    ByteUnits::Binary => ByteUnits::Decimal,
+                    ByteUnits::Decimal => ByteUnits::Binary,
+                }
+            }
+            Field::Temperature => {
+                self.units.temperature = match self.units.temperature {
+                    TemperatureUnit::Celsius => TemperatureUnit::Fahrenheit,
+                    TemperatureUnit::Fahrenheit => TemperatureUnit::Celsius,
+                }
+            }
+            Field::Refresh => {
+                let index = REFRESH_CHOICES.iter().position(|ms| *ms == self.refresh_ms).unwrap_or(2);
+                self.refresh_ms = REFRESH_CHOICES[step(REFRESH_CHOICES.len(), index)];
+            }
+            Field::Tab(index) => {
+                self.tabs[index].1 = !self.tabs[index].1;
+                // Keep at least one tab
+                if self.tabs.iter().all(|(_, enabled)| !enabled) {
+                    self.tabs[index].1 = true;
+                }
+            }
+            Field::Privileged => self.allow_privileged = !self.allow_privileged,
+        }
+    }
+
+    fn tab_names(&self) -> Vec<&'static str> {
+        self.tabs.iter().filter(|(_, enabled)| *enabled).map(|(tab, _)| tab.name()).collect()
+    }
+
+    // Write config.toml; the caller then applies the choices with `apply_to`
+    pub fn save(&mut self) -> Result<PathBuf, Box<dyn Error>> {
+        let result = self.write();
+        self.error = result.as_ref().err().map(|e| e.to_string());
+        result
+    }
+
+    fn write(&self) -> Result<PathBuf, Box<dyn Error>> {
+        let path = Config::path().ok_or("no config directory available")?;
+        let file = SetupFile {
+            theme: self.theme,
+            tabs: self.tab_names(),
+            allow_privileged: self.allow_privileged,
+            units: &self.units,
+            profiles: BTreeMap::from([("default", RefreshOnly { refresh_ms: self.refresh_ms })]),
+        };
+        let contents = format!(
+            "# Written by the first-run setup; the README lists every other option\n{}",
+            toml::to_string(&file)?
+        );
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        crate::write_atomic(&path, contents)?;
+        Ok(path)
+    }
+
+    pub fn apply_to(&self, config: &mut Config) {
+        config.theme = self.theme;
+        config.units = self.units.clone();
+        config.tabs = self.tab_names().into_iter().map(str::to_string).collect();
+        config.allow_privileged = self.allow_privileged;
+        config.profiles.insert(
+            "default".to_string(),
+            Profile { refresh_ms: self.refresh_ms, ..Profile::default() },
+        );
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .fields()
+            .into_iter()
+            .map(|field| {
+                let (label, value) = match field {
+                    Field::Theme => ("Theme", format!("◀ {} ▶", self.theme.name())),
+                    Field::Bytes => (
+                        "Sizes",
+                        match self.units.bytes {
+                            ByteUnits::Binary => "◀ binary, 1 KB = 1024 bytes ▶".to_string(),
+                            ByteUnits::Decimal => "◀ decimal, 1 kB = 1000 bytes ▶".to_string(),
+                        },
+                    ),
+                    Field::Temperature => (
+                        "Temperatures",
+                        match self.units.temperature {
+                            TemperatureUnit::Celsius => "◀ °C ▶".to_string(),
+                            TemperatureUnit::Fahrenheit => "◀ °F ▶".to_string(),
+                        },
+                    ),
+                    Field::Refresh => ("Refresh every", format!("◀ {} ms ▶", self.refresh_ms)),
+                    Field::Tab(index) => {
+                        let (tab, enabled) = self.tabs[index];
+                        ("Tab", format!("{} {}", if enabled { "[x]" } else { "[ ]" }, tab.title()))
+                    }
+                    Field::Privileged => (
+                        "Privileged",
+                        format!(
+                            "{} root-only collectors: eBPF, exec tracing, stack sampler, strace, ! to restart with sudo",
+                            if self.allow_privileged { "[x]" } else { "[ ]" }
+                        ),
+                    ),
+                };
+                ListItem::new(format!("{:<14} {}", label, value))
+            })
+            .collect();
+
+        let block = Block::default()
+            .title("👋 Welcome — first-run setup · ↑/↓ select · ←/→ change · Enter save · Esc keep defaults")
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(2)])
+            .split(inner);
+
+        let list = List::new(items)
+            .style(Style::default().fg(Color::White))
+            .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .highlight_symbol("▶ ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        f.render_stateful_widget(list, chunks[0], &mut state);
+
+        let path = Config::path().map(|p| p.display().to_string()).unwrap_or_default();
+        let footer = match &self.error {
+            Some(error) => Paragraph::new(format!("❌ Could not write {}: {}", path, error))
+                .style(Style::default().fg(Color::Red)),
+            None => Paragraph::new(format!("Enter writes {}; the README lists everything else it can hold.", path))
+                .style(Style::default().fg(Color::Gray)),
+        };
+        f.render_widget(footer.wrap(Wrap { trim: true }), chunks[1]);
+    }
+}
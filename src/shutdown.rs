@@ -0,0 +1,78 @@
+use crossterm::{
+    cursor::Show,
+    event::DisableMouseCapture,
+    execute,
+    terminal::{disable_raw_mode, LeaveAlternateScreen},
+};
+use std::{
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+// Getting out cleanly when we don't quit through the UI: SIGTERM, SIGHUP
+// (terminal closed) and SIGINT ask the main loop to stop, so the usual exit
+// path saves state and flushes the archive. A panic restores the terminal
+// before the message is printed, so it is readable and the shell usable.
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+pub fn restore_on_panic() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        default_hook(info);
+    }));
+}
+
+#[cfg(unix)]
+pub fn handle_signals() {
+    extern "C" fn on_signal(_: libc::c_int) {
+        // Only async-signal-safe work in here
+        REQUESTED.store(true, Ordering::Relaxed);
+    }
+    for signal in [libc::SIGTERM, libc::SIGHUP, libc::SIGINT] {
+        unsafe {
+            libc::signal(signal, on_signal as *const () as libc::sighandler_t);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn handle_signals() {}
+
+// Wait for terminal input. Crossterm spins forever trying to read from a
+// terminal that hung up, so a hangup is caught here first and handled like
+// SIGHUP; a signal also ends the wait early. Events crossterm has already
+// read (the rest of a paste or a burst of keys) leave stdin empty, so its
+// queue is checked before waiting on stdin.
+#[cfg(unix)]
+pub fn wait_for_input(timeout: Duration) -> bool {
+    // Crossterm reads /dev/tty when stdin is redirected
+    if unsafe { libc::isatty(libc::STDIN_FILENO) } != 1 {
+        return crossterm::event::poll(timeout).unwrap_or(false);
+    }
+    if crossterm::event::poll(Duration::ZERO).unwrap_or(false) {
+        return !requested();
+    }
+    let mut stdin = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+    let ready = unsafe { libc::poll(&mut stdin, 1, timeout.as_millis() as libc::c_int) };
+    if ready > 0 && stdin.revents & (libc::POLLHUP | libc::POLLERR | libc::POLLNVAL) != 0 {
+        REQUESTED.store(true, Ordering::Relaxed);
+    }
+    ready > 0 && !requested()
+}
+
+#[cfg(not(unix))]
+pub fn wait_for_input(timeout: Duration) -> bool {
+    crossterm::event::poll(timeout).unwrap_or(false)
+}
+
+// Whether a signal asked us to exit
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::Relaxed)
+}
+
+pub fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, Show)
+}
@@ -0,0 +1,152 @@
+use crate::alerts::{self, source_matches, Alert, AlertChange};
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+
+// Alerts that stay visible but send no notifications: recurring maintenance
+// windows from the config, and silences added from the Alerts tab.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MaintenanceWindow {
+    pub name: String,
+    // Alert source, e.g. "disk:/backup"; a trailing * matches any suffix
+    #[serde(rename = "match")]
+    pub pattern: String,
+    // "mon".."sun"; empty means every day
+    pub days: Vec<String>,
+    // Local "HH:MM"; an end before the start runs past midnight
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for MaintenanceWindow {
+    fn default() -> Self {
+        MaintenanceWindow {
+            name: String::new(),
+            pattern: "*".to_string(),
+            days: Vec::new(),
+            start: String::new(),
+            end: String::new(),
+        }
+    }
+}
+
+impl MaintenanceWindow {
+    pub fn validate(&self) -> Result<(), String> {
+        let name = if self.name.is_empty() { "unnamed" } else { &self.name };
+        for time in [&self.start, &self.end] {
+            parse_time(time).ok_or_else(|| format!("maintenance window '{}': '{}' is not HH:MM", name, time))?;
+        }
+        if let Some(day) = self.days.iter().find(|day| day.parse::<Weekday>().is_err()) {
+            return Err(format!("maintenance window '{}': unknown day '{}'", name, day));
+        }
+        Ok(())
+    }
+
+    pub fn active_at(&self, now: DateTime<Local>) -> bool {
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        let on = |day: Weekday| self.days.is_empty() || self.days.iter().any(|d| d.parse::<Weekday>() == Ok(day));
+        let time = now.time();
+        if start < end {
+            on(now.weekday()) && time >= start && time < end
+        } else {
+            (on(now.weekday()) && time >= start) || (on(now.weekday().pred()) && time < end)
+        }
+    }
+
+    pub fn schedule(&self) -> String {
+        let days = if self.days.is_empty() { "daily".to_string() } else { self.days.join(",") };
+        format!("{} {}–{}", days, self.start, self.end)
+    }
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").ok()
+}
+
+// Added from the TUI; kept in the workspace so a restart doesn't end it early
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Silence {
+    #[serde(rename = "match")]
+    pub pattern: String,
+    // Unix seconds
+    pub until: i64,
+}
+
+impl Silence {
+    pub fn until(&self) -> String {
+        match Local.timestamp_opt(self.until, 0).single() {
+            Some(until) if until.date_naive() == Local::now().date_naive() => until.format("%H:%M").to_string(),
+            Some(until) => until.format("%a %H:%M").to_string(),
+            None => "?".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Silences {
+    pub windows: Vec<MaintenanceWindow>,
+    pub manual: Vec<Silence>,
+    // Unsilenced alerts as of the previous refresh
+    notified: Vec<Alert>,
+}
+
+impl Silences {
+    pub fn new(windows: Vec<MaintenanceWindow>) -> Silences {
+        Silences { windows, manual: Vec::new(), notified: Vec::new() }
+    }
+
+    // What silences the alert, for display; None while it notifies normally
+    pub fn silenced_by(&self, alert: &Alert) -> Option<String> {
+        let now = Local::now();
+        if let Some(silence) = self
+            .manual
+            .iter()
+            .find(|s| s.until > now.timestamp() && source_matches(&s.pattern, &alert.source))
+        {
+            return Some(format!("until {}", silence.until()));
+        }
+        self.windows
+            .iter()
+            .find(|w| w.active_at(now) && source_matches(&w.pattern, &alert.source))
+            .map(|w| format!("window {}", w.name))
+    }
+
+    // Silence for the given minutes, extending a silence on the same pattern
+    pub fn silence(&mut self, pattern: &str, minutes: u64) {
+        let now = Local::now().timestamp();
+        let seconds = minutes as i64 * 60;
+        match self.manual.iter_mut().find(|s| s.pattern == pattern) {
+            Some(silence) => silence.until = silence.until.max(now) + seconds,
+            None => self.manual.push(Silence { pattern: pattern.to_string(), until: now + seconds }),
+        }
+    }
+
+    // Lift the manual silences covering an alert; windows stay in force
+    pub fn lift(&mut self, alert: &Alert) {
+        self.manual.retain(|s| !source_matches(&s.pattern, &alert.source));
+    }
+
+    pub fn lift_all(&mut self) {
+        self.manual.clear();
+    }
+
+    // Changes worth a notification: alerts entering or leaving the unsilenced
+    // set. An alert that was merely silenced has not resolved, and one still
+    // firing when its silence ends is sent then.
+    pub fn changes(&mut self, alerts: &[Alert]) -> Vec<AlertChange> {
+        let now = Local::now().timestamp();
+        self.manual.retain(|s| s.until > now);
+        let notified: Vec<Alert> = alerts.iter().filter(|a| self.silenced_by(a).is_none()).cloned().collect();
+        let changes = alerts::changes(&self.notified, &notified)
+            .into_iter()
+            .filter(|change| match change {
+                AlertChange::Resolved(alert) => !alerts.iter().any(|a| a.source == alert.source),
+                AlertChange::Fired(_) => true,
+            })
+            .collect();
+        self.notified = notified;
+        changes
+    }
+}
@@ -0,0 +1,147 @@
+use crate::history::History;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Pushes the latest headline metrics to legacy pipelines: StatsD gauges over
+// UDP or Graphite plaintext over TCP, every flush interval.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SinkConfig {
+    pub address: String,
+    // Prepended to every metric name; {host} is replaced by the hostname
+    pub prefix: String,
+    pub flush_interval_ms: u64,
+}
+
+impl Default for SinkConfig {
+    fn default() -> Self {
+        SinkConfig {
+            address: String::new(),
+            prefix: "system_monitor.{host}".to_string(),
+            flush_interval_ms: 10_000,
+        }
+    }
+}
+
+impl SinkConfig {
+    pub fn validate(&self, kind: &str) -> Result<(), String> {
+        match self.address.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(()),
+            _ => Err(format!("{}: address must be host:port", kind)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SinkKind {
+    Statsd,
+    Graphite,
+}
+
+impl SinkKind {
+    fn name(&self) -> &'static str {
+        match self {
+            SinkKind::Statsd => "StatsD",
+            SinkKind::Graphite => "Graphite",
+        }
+    }
+}
+
+pub struct MetricSink {
+    kind: SinkKind,
+    config: SinkConfig,
+    prefix: String,
+    last_flush: Instant,
+    // Outcome of the latest send, written by the sending thread
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl MetricSink {
+    pub fn new(kind: SinkKind, config: SinkConfig) -> MetricSink {
+        let host = sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string());
+        // Dots would split the hostname into several Graphite path levels
+        let prefix = config.prefix.replace("{host}", &host.replace('.', "_"));
+        MetricSink {
+            kind,
+            config,
+            prefix,
+            last_flush: Instant::now(),
+            error: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn flush_if_due(&mut self, history: &History) {
+        if self.last_flush.elapsed() < Duration::from_millis(self.config.flush_interval_ms.max(1000)) {
+            return;
+        }
+        self.last_flush = Instant::now();
+
+        let timestamp = chrono::Utc::now().timestamp();
+        let lines: Vec<String> = history
+            .metrics()
+            .filter_map(|metric| {
+                let value = history.latest(metric)?;
+                Some(match self.kind {
+                    SinkKind::Statsd => format!("{}.{}:{:.2}|g", self.prefix, metric, value),
+                    SinkKind::Graphite => format!("{}.{} {:.2} {}", self.prefix, metric, value, timestamp),
+                })
+            })
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+
+        // Sending may block on DNS or a TCP connect, keep that off the UI thread
+        let kind = self.kind;
+        let address = self.config.address.clone();
+        let error = Arc::clone(&self.error);
+        thread::spawn(move || {
+            let result = match kind {
+                SinkKind::Statsd => send_statsd(&address, &lines),
+                SinkKind::Graphite => send_graphite(&address, &lines),
+            };
+            *error.lock().unwrap() = result
+                .err()
+                .map(|e| e.to_string().split(" (os error").next().unwrap_or_default().to_string());
+        });
+    }
+
+    // One line for the System Information panel
+    pub fn status(&self) -> String {
+        match &*self.error.lock().unwrap() {
+            None => format!("📤 {} → {}", self.kind.name(), self.config.address),
+            Some(error) => format!("📤 {} → {} ❌ {}", self.kind.name(), self.config.address, error),
+        }
+    }
+}
+
+fn resolve(address: &str) -> std::io::Result<SocketAddr> {
+    address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no address found"))
+}
+
+fn send_statsd(address: &str, lines: &[String]) -> std::io::Result<()> {
+    let addr = resolve(address)?;
+    let socket = UdpSocket::bind(if addr.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" })?;
+    socket.connect(addr)?;
+    // Stay below typical MTUs by sending one gauge per datagram
+    for line in lines {
+        socket.send(line.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn send_graphite(address: &str, lines: &[String]) -> std::io::Result<()> {
+    let addr = resolve(address)?;
+    let mut stream = TcpStream::connect_timeout(&addr, Duration::from_secs(5))?;
+    stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+    stream.write_all((lines.join("\n") + "\n").as_bytes())
+}
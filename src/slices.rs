@@ -0,0 +1,100 @@
+use crate::format_bytes;
+use std::{fs, path::PathBuf, time::Instant};
+
+// CPU and memory of systemd's top-level slices, for a quick "is it my apps
+// or the system" answer: services run in system.slice, login sessions in
+// user.slice and VMs/containers in machine.slice. Read from cgroup v2, or
+// the cpuacct and memory hierarchies of cgroup v1.
+const SLICES: [&str; 3] = ["system", "user", "machine"];
+
+#[derive(Debug, Clone)]
+pub struct SliceUsage {
+    pub name: &'static str,
+    // Share of all CPUs, like the CPU gauge
+    pub cpu: f64,
+    // Without a memory controller for the slice
+    pub memory: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct Slices {
+    // CPU time per slice in microseconds at the previous update
+    previous: Option<(Instant, Vec<u64>)>,
+    usage: Vec<SliceUsage>,
+}
+
+impl Slices {
+    pub fn update(&mut self, cpus: usize) {
+        let now = Instant::now();
+        let readings: Vec<(&'static str, u64, Option<u64>)> = SLICES
+            .iter()
+            .filter_map(|name| {
+                let slice = format!("{}.slice", name);
+                Some((*name, cpu_usec(&slice)?, memory(&slice)))
+            })
+            .collect();
+        let times: Vec<u64> = readings.iter().map(|(_, usec, _)| *usec).collect();
+        if let Some((at, previous)) = &self.previous {
+            let elapsed = now.duration_since(*at).as_micros() as f64 * cpus.max(1) as f64;
+            // Slices appearing or going away between updates just skip one
+            if previous.len() == times.len() && elapsed > 0.0 {
+                self.usage = readings
+                    .iter()
+                    .zip(previous)
+                    .map(|((name, usec, memory), before)| SliceUsage {
+                        name,
+                        cpu: usec.saturating_sub(*before) as f64 / elapsed * 100.0,
+                        memory: *memory,
+                    })
+                    .collect();
+            }
+        }
+        if times.is_empty() {
+            self.usage.clear();
+        }
+        self.previous = (!times.is_empty()).then_some((now, times));
+    }
+
+    // One line for the System Information panel, where there are slices
+    pub fn status(&self) -> Option<String> {
+        if self.usage.is_empty() {
+            return None;
+        }
+        let parts: Vec<String> = self
+            .usage
+            .iter()
+            .map(|slice| match slice.memory {
+                Some(memory) => format!("{} {:.1}% {}", slice.name, slice.cpu, format_bytes(memory)),
+                None => format!("{} {:.1}%", slice.name, slice.cpu),
+            })
+            .collect();
+        Some(format!("🧩 Slices: {}", parts.join(" · ")))
+    }
+}
+
+fn cgroup(hierarchy: &str, slice: &str, file: &str) -> PathBuf {
+    let mut path = PathBuf::from("/sys/fs/cgroup");
+    if !hierarchy.is_empty() {
+        path.push(hierarchy);
+    }
+    path.join(slice).join(file)
+}
+
+fn cpu_usec(slice: &str) -> Option<u64> {
+    // v2: "usage_usec N" in cpu.stat
+    if let Ok(stat) = fs::read_to_string(cgroup("", slice, "cpu.stat")) {
+        return stat.lines().find_map(|line| line.strip_prefix("usage_usec ")?.trim().parse().ok());
+    }
+    // v1: nanoseconds
+    let usage = fs::read_to_string(cgroup("cpuacct", slice, "cpuacct.usage")).ok()?;
+    usage.trim().parse::<u64>().ok().map(|ns| ns / 1000)
+}
+
+fn memory(slice: &str) -> Option<u64> {
+    fs::read_to_string(cgroup("", slice, "memory.current"))
+        .or_else(|_| fs::read_to_string(cgroup("memory", slice, "memory.usage_in_bytes")))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
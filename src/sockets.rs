@@ -0,0 +1,306 @@
+use crate::{freshness, ssh, App, Collector};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Table},
+    Frame,
+};
+use std::{collections::HashMap, fs};
+
+// Aggregate TCP/UDP statistics. Protocol counters come from /proc/net/snmp
+// and /proc/net/netstat (what `netstat -s` prints) and are cheap, so they are
+// read on every refresh for the retransmit alert. Walking the socket tables
+// in /proc/net/{tcp,tcp6,udp,udp6} only happens while the Sockets tab is used.
+const TCP_STATES: [&str; 11] = [
+    "ESTABLISHED",
+    "SYN_SENT",
+    "SYN_RECV",
+    "FIN_WAIT1",
+    "FIN_WAIT2",
+    "TIME_WAIT",
+    "CLOSE",
+    "CLOSE_WAIT",
+    "LAST_ACK",
+    "LISTEN",
+    "CLOSING",
+];
+
+// Counters that only ever grow; rates come from the difference between reads
+#[derive(Debug, Default, Clone, Copy)]
+struct Counters {
+    out_segs: u64,
+    retrans_segs: u64,
+    listen_overflows: u64,
+    listen_drops: u64,
+    active_opens: u64,
+    passive_opens: u64,
+    attempt_fails: u64,
+    estab_resets: u64,
+    udp_in_errors: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct SocketStats {
+    // Sockets per TCP state, in TCP_STATES order
+    pub tcp_states: [usize; 11],
+    pub udp_sockets: usize,
+    // (local address, accept queue length) of listeners with connections waiting
+    pub backlogs: Vec<(String, u64)>,
+    // Percentage of segments sent during the last interval that were retransmissions
+    pub retransmit_percent: f64,
+    pub segments_sent: u64,
+    pub retransmits_per_sec: f64,
+    pub listen_overflows: u64,
+    pub listen_drops: u64,
+    // Per-second rates of the remaining counters, for the counters panel
+    rates: Counters,
+    totals: Counters,
+    previous: Option<Counters>,
+    // Directory the counters were last read from
+    source: String,
+    pub error: Option<String>,
+}
+
+impl SocketStats {
+    // `proc_net` is /proc/self/net, or /proc/<pid>/net for another namespace
+    pub fn refresh_counters(&mut self, proc_net: &str, secs: f64) {
+        let mut values = read_counters(&format!("{}/snmp", proc_net));
+        values.extend(read_counters(&format!("{}/netstat", proc_net)));
+        if values.is_empty() {
+            self.error = Some(format!("cannot read {}/snmp", proc_net));
+            return;
+        }
+        self.error = None;
+
+        let get = |key: &str| values.get(key).copied().unwrap_or(0);
+        let current = Counters {
+            out_segs: get("Tcp.OutSegs"),
+            retrans_segs: get("Tcp.RetransSegs"),
+            listen_overflows: get("TcpExt.ListenOverflows"),
+            listen_drops: get("TcpExt.ListenDrops"),
+            active_opens: get("Tcp.ActiveOpens"),
+            passive_opens: get("Tcp.PassiveOpens"),
+            attempt_fails: get("Tcp.AttemptFails"),
+            estab_resets: get("Tcp.EstabResets"),
+            udp_in_errors: get("Udp.InErrors"),
+        };
+        // Counters of another namespace can't be compared with the last read
+        if self.source != proc_net {
+            self.source = proc_net.to_string();
+            self.previous = None;
+        }
+        let previous = self.previous.unwrap_or(current);
+        let delta = |now: u64, before: u64| now.saturating_sub(before);
+        let per_sec = |now: u64, before: u64| (delta(now, before) as f64 / secs.max(0.001)).round() as u64;
+
+        let sent = delta(current.out_segs, previous.out_segs);
+        let retransmitted = delta(current.retrans_segs, previous.retrans_segs);
+        self.segments_sent = sent;
+        self.retransmit_percent = if sent > 0 { retransmitted as f64 / sent as f64 * 100.0 } else { 0.0 };
+        self.retransmits_per_sec = retransmitted as f64 / secs.max(0.001);
+        self.listen_overflows = delta(current.listen_overflows, previous.listen_overflows);
+        self.listen_drops = delta(current.listen_drops, previous.listen_drops);
+        self.rates = Counters {
+            out_segs: per_sec(current.out_segs, previous.out_segs),
+            retrans_segs: per_sec(current.retrans_segs, previous.retrans_segs),
+            listen_overflows: per_sec(current.listen_overflows, previous.listen_overflows),
+            listen_drops: per_sec(current.listen_drops, previous.listen_drops),
+            active_opens: per_sec(current.active_opens, previous.active_opens),
+            passive_opens: per_sec(current.passive_opens, previous.passive_opens),
+            attempt_fails: per_sec(current.attempt_fails, previous.attempt_fails),
+            estab_resets: per_sec(current.estab_resets, previous.estab_resets),
+            udp_in_errors: per_sec(current.udp_in_errors, previous.udp_in_errors),
+        };
+        self.totals = current;
+        self.previous = Some(current);
+    }
+
+    pub fn refresh_sockets(&mut self, proc_net: &str) {
+        self.tcp_states = [0; 11];
+        self.backlogs.clear();
+        for file in ["tcp", "tcp6"] {
+            let Ok(contents) = fs::read_to_string(format!("{}/{}", proc_net, file)) else {
+                continue;
+            };
+            // "sl local_address rem_address st tx_queue:rx_queue ..."
+            for line in contents.lines().skip(1) {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let (Some(local), Some(state), Some(queues)) = (fields.get(1), fields.get(3), fields.get(4)) else {
+                    continue;
+                };
+                let Ok(state) = u8::from_str_radix(state, 16) else {
+                    continue;
+                };
+                if let Some(count) = self.tcp_states.get_mut(state.wrapping_sub(1) as usize) {
+                    *count += 1;
+                }
+                // For listeners rx_queue is the number of connections waiting to be accepted
+                let waiting = queues
+                    .split_once(':')
+                    .and_then(|(_, rx)| u64::from_str_radix(rx, 16).ok())
+                    .unwrap_or(0);
+                if state == 0x0A && waiting > 0 {
+                    self.backlogs.push((format_address(local), waiting));
+                }
+            }
+        }
+        self.backlogs.sort_by_key(|b| std::cmp::Reverse(b.1));
+
+        self.udp_sockets = ["udp", "udp6"]
+            .iter()
+            .filter_map(|file| fs::read_to_string(format!("{}/{}", proc_net, file)).ok())
+            .map(|contents| contents.lines().skip(1).count())
+            .sum();
+    }
+}
+
+// "Tcp: RtoAlgorithm RtoMin ..." followed by "Tcp: 1 200 ..." -> "Tcp.RtoMin" = 200
+fn read_counters(path: &str) -> HashMap<String, u64> {
+    let mut values = HashMap::new();
+    let Ok(contents) = fs::read_to_string(path) else {
+        return values;
+    };
+    let lines: Vec<&str> = contents.lines().collect();
+    for pair in lines.chunks(2) {
+        let [header, numbers] = pair else {
+            continue;
+        };
+        let (Some((protocol, names)), Some((_, numbers))) = (header.split_once(':'), numbers.split_once(':')) else {
+            continue;
+        };
+        for (name, value) in names.split_whitespace().zip(numbers.split_whitespace()) {
+            if let Ok(value) = value.parse::<i64>() {
+                values.insert(format!("{}.{}", protocol, name), value.max(0) as u64);
+            }
+        }
+    }
+    values
+}
+
+// "0100007F:1F90" -> "127.0.0.1:8080"; IPv6 ones to "[v6]:port"
+fn format_address(address: &str) -> String {
+    let Some((ip, port)) = address.split_once(':') else {
+        return address.to_string();
+    };
+    let port = u16::from_str_radix(port, 16).unwrap_or(0);
+    // The kernel prints the network-order address as a native integer
+    match u32::from_str_radix(ip, 16) {
+        Ok(ip) if address.len() == 13 => format!("{}:{}", std::net::Ipv4Addr::from(ip.to_ne_bytes()), port),
+        _ => format!("[v6]:{}", port),
+    }
+}
+
+pub fn draw_sockets_tab(f: &mut Frame, area: Rect, app: &App) {
+    if !app.capabilities.sockets.available() {
+        let message = Paragraph::new(format!("Socket statistics: {}", app.capabilities.sockets.placeholder()))
+            .block(Block::default().title("🔌 Sockets").borders(Borders::ALL))
+            .style(Style::default().fg(Color::Gray));
+        f.render_widget(message, area);
+        return;
+    }
+    let stats = &app.sockets;
+    // Socket tables and protocol counters are read at different times
+    let tables_at = app.collected_at(Collector::Sockets);
+
+    // Remote logins, only while there are any
+    let area = match app.ssh.sessions.len() {
+        0 => area,
+        sessions => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length((sessions as u16 + 3).min(12))])
+                .split(area);
+            ssh::draw(f, chunks[1], &app.ssh);
+            chunks[0]
+        }
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(8), Constraint::Min(0)])
+        .split(chunks[1]);
+
+    // Socket counts per TCP state
+    let header = Row::new(vec!["State", "Sockets"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let mut rows: Vec<Row> = TCP_STATES
+        .iter()
+        .zip(stats.tcp_states.iter())
+        .filter(|(_, count)| **count > 0)
+        .map(|(state, count)| Row::new(vec![Cell::from(*state), Cell::from(count.to_string())]))
+        .collect();
+    rows.push(Row::new(vec![Cell::from("UDP sockets"), Cell::from(stats.udp_sockets.to_string())]));
+    let table = Table::new(rows, [Constraint::Min(14), Constraint::Length(10)])
+        .header(header)
+        .block(Block::default().title(freshness::title("🔌 TCP States", "", tables_at, false)).borders(Borders::ALL))
+        .column_spacing(1);
+    f.render_widget(table, chunks[0]);
+
+    // Protocol counters
+    let thresholds = &app.thresholds;
+    let retransmit_color = if stats.retransmit_percent >= thresholds.retransmit_crit {
+        Color::Red
+    } else if stats.retransmit_percent >= thresholds.retransmit_warn {
+        Color::Yellow
+    } else {
+        Color::Green
+    };
+    let overflow_color = if stats.listen_overflows > 0 || stats.listen_drops > 0 { Color::Red } else { Color::White };
+    let mut items = Vec::new();
+    if let Some(error) = &stats.error {
+        items.push(ListItem::new(format!("❌ {}", error)).style(Style::default().fg(Color::Red)));
+    }
+    items.push(
+        ListItem::new(format!(
+            "🔁 Retransmits: {:.1}/s ({:.2}% of {} segments/s)",
+            stats.retransmits_per_sec, stats.retransmit_percent, stats.rates.out_segs
+        ))
+        .style(Style::default().fg(retransmit_color)),
+    );
+    items.push(
+        ListItem::new(format!(
+            "🚧 Listen queue overflows: {} (drops {}) — {} total",
+            stats.listen_overflows, stats.listen_drops, stats.totals.listen_overflows
+        ))
+        .style(Style::default().fg(overflow_color)),
+    );
+    items.push(ListItem::new(format!(
+        "📞 Connections opened: {}/s outgoing, {}/s incoming",
+        stats.rates.active_opens, stats.rates.passive_opens
+    )));
+    items.push(ListItem::new(format!(
+        "💥 Failed attempts: {}/s, resets: {}/s",
+        stats.rates.attempt_fails, stats.rates.estab_resets
+    )));
+    items.push(ListItem::new(format!("📭 UDP receive errors: {}/s", stats.rates.udp_in_errors)));
+    items.push(ListItem::new(format!(
+        "📊 Since boot: {} retransmits, {} resets",
+        stats.totals.retrans_segs, stats.totals.estab_resets
+    )));
+    let title = match &app.netns {
+        Some(netns) => format!("📈 TCP/UDP Counters — netns {}", netns.namespace.label),
+        None => "📈 TCP/UDP Counters".to_string(),
+    };
+    let title = freshness::title(&title, "", Some(app.last_update), false);
+    let counters = List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White));
+    f.render_widget(counters, right[0]);
+
+    // Listeners with connections waiting in their accept queue
+    let header = Row::new(vec!["Listening on", "Waiting"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = stats
+        .backlogs
+        .iter()
+        .map(|(address, waiting)| Row::new(vec![Cell::from(address.clone()), Cell::from(waiting.to_string())]))
+        .collect();
+    let backlog = Table::new(rows, [Constraint::Min(22), Constraint::Length(10)])
+        .header(header)
+        .block(Block::default().title(freshness::title("⏳ Accept Backlog", "", tables_at, false)).borders(Borders::ALL))
+        .column_spacing(1);
+    f.render_widget(backlog, right[1]);
+}
@@ -0,0 +1,189 @@
+use crate::freshness;
+use crate::interrupts::rate;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Row, Table},
+    Frame,
+};
+use std::{fs, time::Instant};
+
+// Softirq load per CPU: the share of CPU time spent in softirqs (from
+// /proc/stat), NET_RX/NET_TX softirqs per second (/proc/softirqs) and the
+// packets the network stack processed, dropped and left for later because
+// its budget ran out (/proc/net/softnet_stat). Heavy network traffic shows
+// up as "system" CPU; these say how much of it is the network stack.
+#[derive(Debug, Clone)]
+pub struct CpuSoftirq {
+    pub cpu: String,
+    pub percent: f64,
+    pub net_rx: f64,
+    pub net_tx: f64,
+    pub packets: f64,
+    pub dropped: f64,
+    pub squeezed: f64,
+}
+
+// Raw counters, per CPU in /proc/stat order
+#[derive(Default)]
+struct Counters {
+    names: Vec<String>,
+    // (softirq jiffies, all jiffies); the first entry is the whole machine
+    jiffies: Vec<(u64, u64)>,
+    net_rx: Vec<u64>,
+    net_tx: Vec<u64>,
+    // (processed, dropped, time squeezed)
+    softnet: Vec<(u64, u64, u64)>,
+}
+
+#[derive(Default)]
+pub struct Softirqs {
+    previous: Option<(Instant, Counters)>,
+    pub cpus: Vec<CpuSoftirq>,
+    // Whole machine
+    pub percent: f64,
+    pub updated: Option<Instant>,
+}
+
+impl Softirqs {
+    pub fn update(&mut self) {
+        let Some(counters) = read_counters() else { return };
+        let now = Instant::now();
+        if let Some((at, previous)) = &self.previous {
+            let seconds = now.duration_since(*at).as_secs_f64();
+            if seconds > 0.0 && previous.jiffies.len() == counters.jiffies.len() {
+                let share = |index: usize| {
+                    let (softirq, total) = counters.jiffies[index];
+                    let (softirq_before, total_before) = previous.jiffies[index];
+                    let total = total.saturating_sub(total_before);
+                    if total == 0 { 0.0 } else { softirq.saturating_sub(softirq_before) as f64 / total as f64 * 100.0 }
+                };
+                let rate = |now: &[u64], before: &[u64], index: usize| {
+                    let value = now.get(index).zip(before.get(index)).map_or(0, |(now, before)| now.saturating_sub(*before));
+                    value as f64 / seconds
+                };
+                let softnet = |index: usize, field: fn(&(u64, u64, u64)) -> u64| {
+                    let now: Vec<u64> = counters.softnet.iter().map(field).collect();
+                    let before: Vec<u64> = previous.softnet.iter().map(field).collect();
+                    rate(&now, &before, index)
+                };
+                self.percent = share(0);
+                self.cpus = counters
+                    .names
+                    .iter()
+                    .enumerate()
+                    .map(|(index, name)| CpuSoftirq {
+                        cpu: name.clone(),
+                        percent: share(index + 1),
+                        net_rx: rate(&counters.net_rx, &previous.net_rx, index),
+                        net_tx: rate(&counters.net_tx, &previous.net_tx, index),
+                        packets: softnet(index, |s| s.0),
+                        dropped: softnet(index, |s| s.1),
+                        squeezed: softnet(index, |s| s.2),
+                    })
+                    .collect();
+                self.updated = Some(now);
+            }
+        }
+        self.previous = Some((now, counters));
+    }
+
+    // One line for the System Information panel
+    pub fn status(&self) -> Option<String> {
+        self.updated?;
+        let sum = |value: fn(&CpuSoftirq) -> f64| self.cpus.iter().map(value).sum::<f64>();
+        let mut line = format!(
+            "📶 Softirq: {:.1}% of CPU time, NET_RX {}/s, {} packets/s",
+            self.percent,
+            rate(sum(|c| c.net_rx)),
+            rate(sum(|c| c.packets))
+        );
+        let dropped = sum(|c| c.dropped);
+        if dropped > 0.0 {
+            line.push_str(&format!(", {} dropped/s", rate(dropped)));
+        }
+        Some(line)
+    }
+}
+
+fn read_counters() -> Option<Counters> {
+    let mut counters = Counters::default();
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    for line in stat.lines().take_while(|line| line.starts_with("cpu")) {
+        let mut fields = line.split_whitespace();
+        let name = fields.next()?;
+        // user nice system idle iowait irq softirq steal; guest time is
+        // already part of user
+        let values: Vec<u64> = fields.take(8).filter_map(|v| v.parse().ok()).collect();
+        counters.jiffies.push((values.get(6).copied().unwrap_or(0), values.iter().sum()));
+        if name != "cpu" {
+            counters.names.push(name.to_uppercase());
+        }
+    }
+
+    if let Ok(softirqs) = fs::read_to_string("/proc/softirqs") {
+        for line in softirqs.lines() {
+            let Some((kind, counts)) = line.split_once(':') else { continue };
+            let counts = counts.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+            match kind.trim() {
+                "NET_RX" => counters.net_rx = counts,
+                "NET_TX" => counters.net_tx = counts,
+                _ => {}
+            }
+        }
+    }
+
+    // Hex columns: processed, dropped, time squeezed, ...
+    if let Ok(softnet) = fs::read_to_string("/proc/net/softnet_stat") {
+        counters.softnet = softnet
+            .lines()
+            .map(|line| {
+                let mut fields = line.split_whitespace().map(|v| u64::from_str_radix(v, 16).unwrap_or(0));
+                (fields.next().unwrap_or(0), fields.next().unwrap_or(0), fields.next().unwrap_or(0))
+            })
+            .collect();
+    }
+    Some(counters)
+}
+
+// Per-CPU table on the Interrupts tab
+pub fn draw(f: &mut Frame, area: Rect, softirqs: &Softirqs) {
+    let rows: Vec<Row> = softirqs
+        .cpus
+        .iter()
+        .map(|cpu| {
+            // Packets dropped or budgets exhausted: the stack can't keep up
+            let style = if cpu.dropped > 0.0 || cpu.squeezed > 0.0 { Style::default().fg(Color::Red) } else { Style::default() };
+            Row::new(vec![
+                cpu.cpu.clone(),
+                format!("{:.1}%", cpu.percent),
+                rate(cpu.net_rx),
+                rate(cpu.net_tx),
+                rate(cpu.packets),
+                rate(cpu.dropped),
+                rate(cpu.squeezed),
+            ])
+            .style(style)
+        })
+        .collect();
+    let header = Row::new(vec!["CPU", "Softirq", "NET_RX/s", "NET_TX/s", "Packets/s", "Dropped/s", "Squeezed/s"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(7),
+        Constraint::Length(8),
+        Constraint::Length(9),
+        Constraint::Length(9),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .title(freshness::title("📶 Softirqs and network stack per CPU", "", softirqs.updated, false))
+                .borders(Borders::ALL),
+        )
+        .column_spacing(1);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,386 @@
+use crate::alerts::{Alert, Severity};
+use crate::{format_bytes, format_uptime, write_atomic};
+use chrono::Utc;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Row, Table},
+    Frame,
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    net::SocketAddr,
+    path::PathBuf,
+    time::Instant,
+};
+
+// Open SSH sessions, found through the process OpenSSH runs for each one,
+// titled "sshd: alice@pts/0" ("sshd-session: ..." since 9.8; "notty" for
+// scp, sftp and remote commands). The client's address and the bytes moved
+// come from that process's TCP socket, via sock_diag. Reading another user's
+// file descriptors needs root, so without it only your own sessions show
+// where they come from. Source addresses are remembered in
+// ~/.local/share/system-monitor/ssh_sources.json; a session from one not seen
+// before raises a warning while it lasts. The first run only learns.
+const PROCESS_NAMES: [&str; 2] = ["sshd", "sshd-session"];
+
+#[derive(Debug, Clone)]
+pub struct SshSession {
+    pub pid: u32,
+    pub user: String,
+    pub tty: String,
+    pub source: Option<SocketAddr>,
+    // Seconds since the session's process started
+    pub age: Option<u64>,
+    // Bytes per second from and to the client since the last update
+    pub received: Option<f64>,
+    pub sent: Option<f64>,
+    // Total bytes both ways
+    pub total: Option<u64>,
+    pub new_source: bool,
+}
+
+#[derive(Default)]
+pub struct SshSessions {
+    pub sessions: Vec<SshSession>,
+    // Socket counters by inode at the last update, for the rates
+    previous: HashMap<u64, (u64, u64)>,
+    updated: Option<Instant>,
+    // Loaded on the first update that finds a session
+    known: Option<KnownSources>,
+    // First seen while this monitor runs
+    new_sources: HashSet<String>,
+    pub error: Option<String>,
+}
+
+impl SshSessions {
+    pub fn update(&mut self) {
+        let pids = session_processes();
+        if pids.is_empty() {
+            self.sessions.clear();
+            self.previous.clear();
+            return;
+        }
+        let sockets = match tcp_sockets() {
+            Ok(sockets) => {
+                self.error = None;
+                sockets
+            }
+            Err(err) => {
+                self.error = Some(err);
+                HashMap::new()
+            }
+        };
+        let elapsed = self.updated.map(|at| at.elapsed().as_secs_f64()).filter(|secs| *secs > 0.0);
+        self.updated = Some(Instant::now());
+        let uptime = uptime();
+        let mut previous = HashMap::new();
+        let mut sessions = Vec::new();
+        for (pid, user, tty) in pids {
+            let socket = socket_inodes(pid).into_iter().find_map(|inode| Some((inode, sockets.get(&inode)?)));
+            let mut session = SshSession {
+                pid,
+                user,
+                tty,
+                source: socket.map(|(_, socket)| socket.remote),
+                age: uptime.zip(started(pid)).map(|(uptime, started)| uptime.saturating_sub(started)),
+                received: None,
+                sent: None,
+                total: socket.map(|(_, socket)| socket.received + socket.sent),
+                new_source: false,
+            };
+            if let Some((inode, socket)) = socket {
+                if let (Some(secs), Some((received, sent))) = (elapsed, self.previous.get(&inode)) {
+                    session.received = Some(socket.received.saturating_sub(*received) as f64 / secs);
+                    session.sent = Some(socket.sent.saturating_sub(*sent) as f64 / secs);
+                }
+                previous.insert(inode, (socket.received, socket.sent));
+            }
+            sessions.push(session);
+        }
+        self.previous = previous;
+
+        let known = self.known.get_or_insert_with(KnownSources::load);
+        let mut learned = false;
+        for session in &mut sessions {
+            let Some(source) = session.source else { continue };
+            let address = source.ip().to_string();
+            if !known.sources.contains_key(&address) {
+                known.sources.insert(address.clone(), Utc::now().timestamp());
+                learned = true;
+                // Everything is new on the first run
+                if known.loaded {
+                    self.new_sources.insert(address.clone());
+                }
+            }
+            session.new_source = self.new_sources.contains(&address);
+        }
+        if learned || !known.loaded {
+            known.loaded = true;
+            known.save();
+        }
+        // Newest first
+        sessions.sort_by_key(|session| session.age);
+        self.sessions = sessions;
+    }
+
+    pub fn alerts(&self) -> Vec<Alert> {
+        self.sessions
+            .iter()
+            .filter(|session| session.new_source)
+            .filter_map(|session| {
+                let source = session.source?.ip();
+                Some(Alert {
+                    severity: Severity::Warning,
+                    source: format!("ssh:{}", source),
+                    message: format!("SSH login from a new address: {} as {} on {}", source, session.user, session.tty),
+                })
+            })
+            .collect()
+    }
+}
+
+// Source addresses seen so far, with when they were first seen (Unix seconds)
+struct KnownSources {
+    sources: BTreeMap<String, i64>,
+    // False until the file exists
+    loaded: bool,
+}
+
+impl KnownSources {
+    fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("system-monitor").join("ssh_sources.json"))
+    }
+
+    fn load() -> KnownSources {
+        let sources = KnownSources::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok());
+        KnownSources { loaded: sources.is_some(), sources: sources.unwrap_or_default() }
+    }
+
+    // Best effort: unsaved, a source is reported again after a restart
+    fn save(&self) {
+        let Some(path) = KnownSources::path() else { return };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(&self.sources) {
+            let _ = write_atomic(&path, json);
+        }
+    }
+}
+
+// (pid, user, tty) of the per-session processes. The session's privileged
+// monitor ("sshd: alice [priv]") and the listener have no "@" in their title.
+fn session_processes() -> Vec<(u32, String, String)> {
+    let Ok(entries) = fs::read_dir("/proc") else { return Vec::new() };
+    let mut sessions = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else { continue };
+        let Ok(comm) = fs::read_to_string(entry.path().join("comm")) else { continue };
+        if !PROCESS_NAMES.contains(&comm.trim()) {
+            continue;
+        }
+        let Ok(cmdline) = fs::read(entry.path().join("cmdline")) else { continue };
+        let cmdline = String::from_utf8_lossy(&cmdline).replace('\0', " ");
+        let Some((_, title)) = cmdline.split_once(": ") else { continue };
+        let Some((user, tty)) = title.split_whitespace().next().and_then(|title| title.split_once('@')) else { continue };
+        sessions.push((pid, user.to_string(), tty.to_string()));
+    }
+    sessions
+}
+
+// Inodes of the sockets a process has open, "socket:[12345]" in /proc/<pid>/fd
+fn socket_inodes(pid: u32) -> Vec<u64> {
+    let Ok(fds) = fs::read_dir(format!("/proc/{}/fd", pid)) else { return Vec::new() };
+    fds.flatten()
+        .filter_map(|fd| fs::read_link(fd.path()).ok())
+        .filter_map(|target| target.to_str()?.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok())
+        .collect()
+}
+
+fn uptime() -> Option<u64> {
+    let uptime = fs::read_to_string("/proc/uptime").ok()?;
+    uptime.split_whitespace().next()?.parse::<f64>().ok().map(|secs| secs as u64)
+}
+
+// Seconds after boot the process started, field 22 of /proc/<pid>/stat
+#[cfg(unix)]
+fn started(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let (_, rest) = stat.rsplit_once(')')?;
+    let ticks: u64 = rest.split_whitespace().nth(19)?.parse().ok()?;
+    let per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    (per_sec > 0).then(|| ticks / per_sec as u64)
+}
+
+#[cfg(not(unix))]
+fn started(_pid: u32) -> Option<u64> {
+    None
+}
+
+struct TcpSocket {
+    remote: SocketAddr,
+    received: u64,
+    sent: u64,
+}
+
+// Established TCP sockets by inode, with tcp_info's byte counters (Linux 4.2+)
+#[cfg(target_os = "linux")]
+fn tcp_sockets() -> Result<HashMap<u64, TcpSocket>, String> {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    // <linux/sock_diag.h> and <linux/inet_diag.h>
+    const SOCK_DIAG_BY_FAMILY: u16 = 20;
+    const INET_DIAG_INFO: u16 = 2;
+    const TCP_ESTABLISHED: u32 = 1;
+    const NLMSG_HEADER: usize = 16;
+    // family, protocol, ext, pad, states, then a zeroed inet_diag_sockid
+    const REQUEST: usize = 56;
+    // inet_diag_msg: 4 bytes of family/state/timer/retrans, inet_diag_sockid
+    // (sport, dport, src[16], dst[16], if, cookie) and five u32s, inode last
+    const MESSAGE: usize = 72;
+    // tcpi_bytes_acked and tcpi_bytes_received in struct tcp_info
+    const BYTES_ACKED: usize = 120;
+    const BYTES_RECEIVED: usize = 128;
+
+    let error = |what: &str| format!("{}: {}", what, std::io::Error::last_os_error().to_string().split(" (os error").next().unwrap_or_default());
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_SOCK_DIAG) };
+    if fd < 0 {
+        return Err(error("could not open sock_diag"));
+    }
+    let read_u16 = |b: &[u8], at: usize| b.get(at..at + 2).map(|b| u16::from_ne_bytes([b[0], b[1]]));
+    let read_u32 = |b: &[u8], at: usize| b.get(at..at + 4).map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]));
+    let read_u64 = |b: &[u8], at: usize| b.get(at..at + 8).and_then(|b| b.try_into().ok()).map(u64::from_ne_bytes);
+
+    let mut sockets = HashMap::new();
+    let mut buffer = vec![0u8; 32768];
+    for family in [libc::AF_INET, libc::AF_INET6] {
+        let mut request = Vec::with_capacity(NLMSG_HEADER + REQUEST);
+        request.extend_from_slice(&((NLMSG_HEADER + REQUEST) as u32).to_ne_bytes());
+        request.extend_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+        request.extend_from_slice(&((libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16).to_ne_bytes());
+        request.extend_from_slice(&[0; 8]);
+        request.extend_from_slice(&[family as u8, libc::IPPROTO_TCP as u8, 1 << (INET_DIAG_INFO - 1), 0]);
+        request.extend_from_slice(&(1u32 << TCP_ESTABLISHED).to_ne_bytes());
+        request.resize(NLMSG_HEADER + REQUEST, 0);
+        if unsafe { libc::send(fd, request.as_ptr() as *const libc::c_void, request.len(), 0) } < 0 {
+            let err = error("sock_diag request failed");
+            unsafe { libc::close(fd) };
+            return Err(err);
+        }
+        'dump: loop {
+            let received = unsafe { libc::recv(fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+            if received < 0 {
+                let err = error("sock_diag dump failed");
+                unsafe { libc::close(fd) };
+                return Err(err);
+            }
+            let mut messages = &buffer[..received as usize];
+            while let (Some(length), Some(kind)) = (read_u32(messages, 0), read_u16(messages, 4)) {
+                let length = length as usize;
+                if length < NLMSG_HEADER || length > messages.len() {
+                    break;
+                }
+                match kind as i32 {
+                    libc::NLMSG_DONE => break 'dump,
+                    libc::NLMSG_ERROR => {
+                        unsafe { libc::close(fd) };
+                        return Err("sock_diag answered with an error".to_string());
+                    }
+                    _ => {}
+                }
+                let message = &messages[NLMSG_HEADER..length];
+                if message.len() >= MESSAGE {
+                    let port = u16::from_be_bytes([message[6], message[7]]);
+                    let address: IpAddr = if message[0] as i32 == libc::AF_INET {
+                        Ipv4Addr::new(message[24], message[25], message[26], message[27]).into()
+                    } else {
+                        let bytes: [u8; 16] = message[24..40].try_into().unwrap_or_default();
+                        Ipv6Addr::from(bytes).to_canonical()
+                    };
+                    let inode = read_u32(message, 68).unwrap_or_default() as u64;
+                    // Attributes follow, each padded to 4 bytes
+                    let mut attributes = &message[MESSAGE..];
+                    let mut counters = None;
+                    while let (Some(size), Some(kind)) = (read_u16(attributes, 0), read_u16(attributes, 2)) {
+                        let size = size as usize;
+                        if size < 4 || size > attributes.len() {
+                            break;
+                        }
+                        if kind == INET_DIAG_INFO {
+                            let info = &attributes[4..size];
+                            counters = read_u64(info, BYTES_RECEIVED).zip(read_u64(info, BYTES_ACKED));
+                        }
+                        attributes = &attributes[((size + 3) & !3).min(attributes.len())..];
+                    }
+                    let (received, sent) = counters.unwrap_or_default();
+                    sockets.insert(inode, TcpSocket { remote: SocketAddr::new(address, port), received, sent });
+                }
+                messages = &messages[((length + 3) & !3).min(messages.len())..];
+            }
+        }
+    }
+    unsafe { libc::close(fd) };
+    Ok(sockets)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn tcp_sockets() -> Result<HashMap<u64, TcpSocket>, String> {
+    Err("client addresses are only available on Linux".to_string())
+}
+
+pub fn draw(f: &mut Frame, area: Rect, ssh: &SshSessions) {
+    let rate = |rate: Option<f64>| rate.map(|rate| format!("{}/s", format_bytes(rate as u64))).unwrap_or_else(|| "-".to_string());
+    let rows: Vec<Row> = ssh
+        .sessions
+        .iter()
+        .map(|session| {
+            let source = match session.source {
+                Some(source) if session.new_source => format!("🆕 {}", source),
+                Some(source) => source.to_string(),
+                None => "?".to_string(),
+            };
+            let row = Row::new(vec![
+                session.user.clone(),
+                session.tty.clone(),
+                source,
+                session.age.map(format_uptime).unwrap_or_else(|| "-".to_string()),
+                rate(session.received),
+                rate(session.sent),
+                session.total.map(format_bytes).unwrap_or_else(|| "-".to_string()),
+                session.pid.to_string(),
+            ]);
+            if session.new_source {
+                row.style(Style::default().fg(Color::Yellow))
+            } else {
+                row
+            }
+        })
+        .collect();
+    let header = Row::new(vec!["User", "TTY", "From", "Connected", "In", "Out", "Total", "PID"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(12),
+        Constraint::Length(8),
+        Constraint::Min(22),
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(10),
+        Constraint::Length(8),
+    ];
+    let mut title = format!("🔑 SSH Sessions ({})", ssh.sessions.len());
+    if let Some(error) = &ssh.error {
+        title.push_str(&format!(" · {}", error));
+    } else if ssh.sessions.iter().any(|session| session.source.is_none()) && !crate::privilege::is_root() {
+        title.push_str(&format!(" · other users' addresses {}", crate::privilege::root_hint()));
+    }
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .column_spacing(1);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,79 @@
+use crate::{config::Thresholds, history::History};
+use std::fs;
+
+// CPU steal: time this VM had work for a CPU but the hypervisor ran something
+// else, from the "steal" column of /proc/stat. It looks like a slow machine
+// from the inside while nothing in the VM is busy, so a sustained level is
+// alerted on and called what it is, contention on the host. Recorded in the
+// history as "steal", in percent of all CPU time.
+
+// Seconds averaged for the alert, and how much of it needs samples first
+const WINDOW: i64 = 300;
+const MIN_SPAN: i64 = 60;
+
+#[derive(Default)]
+pub struct Steal {
+    // Total and steal jiffies at the previous update
+    previous: Option<(u64, u64)>,
+    pub percent: Option<f64>,
+    // Any steal since start-up; bare metal never has any
+    seen: bool,
+}
+
+impl Steal {
+    pub fn update(&mut self) {
+        let Some((total, steal)) = read_stat() else { return };
+        if let Some((previous_total, previous_steal)) = self.previous {
+            let elapsed = total.saturating_sub(previous_total);
+            if elapsed > 0 {
+                let percent = steal.saturating_sub(previous_steal) as f64 / elapsed as f64 * 100.0;
+                self.seen |= percent > 0.0;
+                self.percent = Some(percent);
+            }
+        }
+        self.previous = Some((total, steal));
+    }
+
+    pub fn record(&self, history: &mut History) {
+        if let Some(percent) = self.percent {
+            history.record("steal", percent);
+        }
+    }
+
+    // One line for the System Information panel, once there was steal
+    pub fn status(&self, history: &History, thresholds: &Thresholds) -> Option<String> {
+        let percent = self.percent.filter(|_| self.seen)?;
+        let mut status = format!("🥷 CPU steal: {:.1}% now", percent);
+        if let Some(average) = sustained(history) {
+            status.push_str(&format!(", {:.1}% over 5 min", average));
+            if average >= thresholds.steal_warn {
+                status.push_str(" — host contention: other VMs on the host get this CPU time");
+            }
+        }
+        Some(status)
+    }
+}
+
+// Average steal over the last five minutes, None with less than a minute of it
+pub fn sustained(history: &History) -> Option<f64> {
+    let series = history.series("steal")?;
+    let (last, _) = *series.back()?;
+    let values: Vec<(i64, f64)> = series.iter().copied().filter(|(time, _)| *time > last - WINDOW).collect();
+    let (first, _) = *values.first()?;
+    if last - first < MIN_SPAN {
+        return None;
+    }
+    Some(values.iter().map(|(_, value)| value).sum::<f64>() / values.len() as f64)
+}
+
+// "cpu  user nice system idle iowait irq softirq steal guest guest_nice";
+// guest time is already part of user
+fn read_stat() -> Option<(u64, u64)> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let line = stat.lines().find(|line| line.starts_with("cpu "))?;
+    let fields: Vec<u64> = line.split_whitespace().skip(1).take(8).filter_map(|v| v.parse().ok()).collect();
+    if fields.len() < 8 {
+        return None;
+    }
+    Some((fields.iter().sum(), fields[7]))
+}
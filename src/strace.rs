@@ -0,0 +1,389 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+// "strace-lite": attaches to the main thread of a process with ptrace and
+// streams its syscalls into a popup. Only a limited number of lines per second
+// are kept so a busy process can't flood the view; the rest are counted. The
+// process is released again as soon as the popup is closed.
+const MAX_LINES_PER_SEC: usize = 50;
+const HISTORY: usize = 500;
+
+#[derive(Debug, Default)]
+struct TraceState {
+    lines: VecDeque<String>,
+    // Only syscalls whose name contains this are shown
+    filter: String,
+    // Syscall the process is blocked in right now, if any
+    current: Option<String>,
+    // Matching syscalls dropped by the per-second limit
+    suppressed: usize,
+    error: Option<String>,
+}
+
+impl TraceState {
+    fn push(&mut self, line: String) {
+        self.lines.push_back(line);
+        if self.lines.len() > HISTORY {
+            self.lines.pop_front();
+        }
+    }
+}
+
+pub struct StraceView {
+    pub pid: u32,
+    pub name: String,
+    state: Arc<Mutex<TraceState>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl StraceView {
+    pub fn start(pid: u32, name: String) -> StraceView {
+        let state = Arc::new(Mutex::new(TraceState::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_state = Arc::clone(&state);
+        let thread_stop = Arc::clone(&stop);
+        // ptrace requests must all come from the thread that attached
+        thread::spawn(move || {
+            if let Err(err) = trace(pid, &thread_state, &thread_stop) {
+                thread_state.lock().unwrap().error = Some(err);
+            }
+        });
+        StraceView { pid, name, state, stop }
+    }
+
+    pub fn push_filter(&self, c: char) {
+        self.state.lock().unwrap().filter.push(c);
+    }
+
+    pub fn pop_filter(&self) {
+        self.state.lock().unwrap().filter.pop();
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect) {
+        let state = self.state.lock().unwrap();
+        let filter = if state.filter.is_empty() { "type to filter".to_string() } else { state.filter.clone() };
+        let title = format!(
+            "🔎 strace {} ({}) [{}] {} rate-limited — Esc close",
+            self.name, self.pid, filter, state.suppressed
+        );
+
+        // Newest lines at the bottom, as many as fit
+        let height = area.height.saturating_sub(3) as usize;
+        let mut items: Vec<ListItem> = state
+            .lines
+            .iter()
+            .skip(state.lines.len().saturating_sub(height))
+            .map(|line| ListItem::new(line.as_str()))
+            .collect();
+        if let Some(error) = &state.error {
+            items.push(ListItem::new(format!("❌ {}", error)).style(Style::default().fg(Color::Red)));
+        } else if let Some(current) = &state.current {
+            items.push(ListItem::new(format!("⏳ {} ...", current)).style(Style::default().fg(Color::Yellow)));
+        }
+
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .style(Style::default().fg(Color::White));
+        f.render_widget(Clear, area);
+        f.render_widget(list, area);
+    }
+}
+
+impl Drop for StraceView {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn trace(pid: u32, state: &Mutex<TraceState>, stop: &AtomicBool) -> Result<(), String> {
+    use std::time::Duration;
+
+    let pid = pid as libc::pid_t;
+    let ptrace = |request: libc::c_uint, data: usize| unsafe {
+        libc::ptrace(request, pid, std::ptr::null_mut::<libc::c_void>(), data)
+    };
+
+    if ptrace(libc::PTRACE_SEIZE, libc::PTRACE_O_TRACESYSGOOD as usize) < 0 {
+        let err = std::io::Error::last_os_error();
+        return Err(match err.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                format!("attaching {} (or blocked by kernel.yama.ptrace_scope)", crate::privilege::root_hint())
+            }
+            _ => format!("could not attach: {}", err),
+        });
+    }
+    ptrace(libc::PTRACE_INTERRUPT, 0);
+
+    let mut second = Instant::now();
+    let mut shown = 0;
+    let mut entry: Option<(String, String)> = None;
+    loop {
+        if stop.load(Ordering::Relaxed) {
+            // The tracee has to be stopped to detach from it
+            ptrace(libc::PTRACE_INTERRUPT, 0);
+            let mut status = 0;
+            unsafe { libc::waitpid(pid, &mut status, libc::__WALL) };
+            let signal = pending_signal(status);
+            ptrace(libc::PTRACE_DETACH, signal as usize);
+            return Ok(());
+        }
+
+        let mut status = 0;
+        let waited = unsafe { libc::waitpid(pid, &mut status, libc::__WALL | libc::WNOHANG) };
+        if waited == 0 {
+            thread::sleep(Duration::from_millis(1));
+            continue;
+        }
+        if waited < 0 {
+            return Err(format!("lost the process: {}", std::io::Error::last_os_error()));
+        }
+        if libc::WIFEXITED(status) || libc::WIFSIGNALED(status) {
+            state.lock().unwrap().current = None;
+            return Err("process exited".to_string());
+        }
+
+        let mut signal = 0;
+        if libc::WSTOPSIG(status) == libc::SIGTRAP | 0x80 {
+            let info = syscall_info(pid);
+            let mut state = state.lock().unwrap();
+            match info {
+                Some(SyscallStop::Entry { nr, args }) => {
+                    let name = syscall_name(nr);
+                    let call = format!("{}({})", name, args.iter().map(|a| format_arg(*a)).collect::<Vec<_>>().join(", "));
+                    state.current = Some(call.clone());
+                    entry = Some((name, call));
+                }
+                Some(SyscallStop::Exit { rval }) => {
+                    state.current = None;
+                    if let Some((name, call)) = entry.take() {
+                        if second.elapsed().as_secs() >= 1 {
+                            second = Instant::now();
+                            shown = 0;
+                        }
+                        if name.contains(state.filter.as_str()) {
+                            if shown < MAX_LINES_PER_SEC {
+                                state.push(format!("{} = {}", call, format_return(rval)));
+                                shown += 1;
+                            } else {
+                                state.suppressed += 1;
+                            }
+                        }
+                    }
+                }
+                None => {}
+            }
+        } else {
+            signal = pending_signal(status);
+        }
+        ptrace(libc::PTRACE_SYSCALL, signal as usize);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn trace(_pid: u32, _state: &Mutex<TraceState>, _stop: &AtomicBool) -> Result<(), String> {
+    Err("strace-lite is only available on Linux".to_string())
+}
+
+// Signal to hand back to the tracee; ptrace's own stops carry none
+#[cfg(target_os = "linux")]
+fn pending_signal(status: libc::c_int) -> libc::c_int {
+    let signal = libc::WSTOPSIG(status);
+    let event = status >> 16;
+    if !libc::WIFSTOPPED(status) || event != 0 || signal == libc::SIGTRAP | 0x80 {
+        0
+    } else {
+        signal
+    }
+}
+
+#[cfg(target_os = "linux")]
+enum SyscallStop {
+    Entry { nr: u64, args: [u64; 6] },
+    Exit { rval: i64 },
+}
+
+// struct ptrace_syscall_info from <linux/ptrace.h> (Linux 5.3+); the union
+// holds {nr, args[6]} on entry and {rval, is_error} on exit
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Default)]
+struct PtraceSyscallInfo {
+    op: u8,
+    _reserved: u8,
+    _flags: u16,
+    _arch: u32,
+    _instruction_pointer: u64,
+    _stack_pointer: u64,
+    data: [u64; 7],
+}
+
+#[cfg(target_os = "linux")]
+fn syscall_info(pid: libc::pid_t) -> Option<SyscallStop> {
+    const PTRACE_GET_SYSCALL_INFO: libc::c_uint = 0x420e;
+    let mut info = PtraceSyscallInfo::default();
+    let size = std::mem::size_of::<PtraceSyscallInfo>();
+    let result = unsafe {
+        libc::ptrace(PTRACE_GET_SYSCALL_INFO, pid, size, &mut info as *mut PtraceSyscallInfo)
+    };
+    if result < 0 {
+        return None;
+    }
+    match info.op {
+        1 => {
+            let mut args = [0; 6];
+            args.copy_from_slice(&info.data[1..7]);
+            Some(SyscallStop::Entry { nr: info.data[0], args })
+        }
+        2 => Some(SyscallStop::Exit { rval: info.data[0] as i64 }),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn syscall_name(nr: u64) -> String {
+    SYSCALL_NAMES
+        .iter()
+        .find(|(number, _)| *number as u64 == nr)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("syscall_{}", nr))
+}
+
+// Small numbers are usually fds, sizes or flags; big ones pointers
+#[cfg(target_os = "linux")]
+fn format_arg(arg: u64) -> String {
+    if arg < 0x10000 {
+        arg.to_string()
+    } else if (arg as i64) < 0 && (arg as i64) > -0x10000 {
+        (arg as i64).to_string()
+    } else {
+        format!("{:#x}", arg)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn format_return(rval: i64) -> String {
+    if (-4095..0).contains(&rval) {
+        let err = std::io::Error::from_raw_os_error(-rval as i32).to_string();
+        let message = err.split(" (os error").next().unwrap_or_default();
+        format!("-1 ({})", message)
+    } else if rval > 0x10000 {
+        format!("{:#x}", rval)
+    } else {
+        rval.to_string()
+    }
+}
+
+// Syscalls worth naming; the rest show up as syscall_<nr>
+#[cfg(target_os = "linux")]
+const SYSCALL_NAMES: &[(libc::c_long, &str)] = &[
+    (libc::SYS_read, "read"),
+    (libc::SYS_write, "write"),
+    (libc::SYS_close, "close"),
+    (libc::SYS_fstat, "fstat"),
+    (libc::SYS_lseek, "lseek"),
+    (libc::SYS_mmap, "mmap"),
+    (libc::SYS_mprotect, "mprotect"),
+    (libc::SYS_munmap, "munmap"),
+    (libc::SYS_brk, "brk"),
+    (libc::SYS_rt_sigaction, "rt_sigaction"),
+    (libc::SYS_rt_sigprocmask, "rt_sigprocmask"),
+    (libc::SYS_rt_sigreturn, "rt_sigreturn"),
+    (libc::SYS_ioctl, "ioctl"),
+    (libc::SYS_pread64, "pread64"),
+    (libc::SYS_pwrite64, "pwrite64"),
+    (libc::SYS_readv, "readv"),
+    (libc::SYS_writev, "writev"),
+    (libc::SYS_sched_yield, "sched_yield"),
+    (libc::SYS_mremap, "mremap"),
+    (libc::SYS_madvise, "madvise"),
+    (libc::SYS_dup, "dup"),
+    (libc::SYS_dup3, "dup3"),
+    (libc::SYS_nanosleep, "nanosleep"),
+    (libc::SYS_getpid, "getpid"),
+    (libc::SYS_sendfile, "sendfile"),
+    (libc::SYS_socket, "socket"),
+    (libc::SYS_connect, "connect"),
+    (libc::SYS_accept, "accept"),
+    (libc::SYS_accept4, "accept4"),
+    (libc::SYS_sendto, "sendto"),
+    (libc::SYS_recvfrom, "recvfrom"),
+    (libc::SYS_sendmsg, "sendmsg"),
+    (libc::SYS_recvmsg, "recvmsg"),
+    (libc::SYS_sendmmsg, "sendmmsg"),
+    (libc::SYS_recvmmsg, "recvmmsg"),
+    (libc::SYS_shutdown, "shutdown"),
+    (libc::SYS_bind, "bind"),
+    (libc::SYS_listen, "listen"),
+    (libc::SYS_setsockopt, "setsockopt"),
+    (libc::SYS_getsockopt, "getsockopt"),
+    (libc::SYS_clone, "clone"),
+    (libc::SYS_clone3, "clone3"),
+    (libc::SYS_execve, "execve"),
+    (libc::SYS_exit, "exit"),
+    (libc::SYS_exit_group, "exit_group"),
+    (libc::SYS_wait4, "wait4"),
+    (libc::SYS_kill, "kill"),
+    (libc::SYS_tgkill, "tgkill"),
+    (libc::SYS_fcntl, "fcntl"),
+    (libc::SYS_flock, "flock"),
+    (libc::SYS_fsync, "fsync"),
+    (libc::SYS_fdatasync, "fdatasync"),
+    (libc::SYS_ftruncate, "ftruncate"),
+    (libc::SYS_getdents64, "getdents64"),
+    (libc::SYS_getcwd, "getcwd"),
+    (libc::SYS_chdir, "chdir"),
+    (libc::SYS_openat, "openat"),
+    (libc::SYS_newfstatat, "newfstatat"),
+    (libc::SYS_statx, "statx"),
+    (libc::SYS_readlinkat, "readlinkat"),
+    (libc::SYS_faccessat, "faccessat"),
+    (libc::SYS_mkdirat, "mkdirat"),
+    (libc::SYS_unlinkat, "unlinkat"),
+    (libc::SYS_pipe2, "pipe2"),
+    (libc::SYS_pselect6, "pselect6"),
+    (libc::SYS_ppoll, "ppoll"),
+    (libc::SYS_epoll_create1, "epoll_create1"),
+    (libc::SYS_epoll_ctl, "epoll_ctl"),
+    (libc::SYS_epoll_pwait, "epoll_pwait"),
+    (libc::SYS_eventfd2, "eventfd2"),
+    (libc::SYS_timerfd_settime, "timerfd_settime"),
+    (libc::SYS_futex, "futex"),
+    (libc::SYS_set_robust_list, "set_robust_list"),
+    (libc::SYS_set_tid_address, "set_tid_address"),
+    (libc::SYS_clock_gettime, "clock_gettime"),
+    (libc::SYS_clock_nanosleep, "clock_nanosleep"),
+    (libc::SYS_gettid, "gettid"),
+    (libc::SYS_prctl, "prctl"),
+    (libc::SYS_prlimit64, "prlimit64"),
+    (libc::SYS_getrandom, "getrandom"),
+    (libc::SYS_rseq, "rseq"),
+    (libc::SYS_io_uring_enter, "io_uring_enter"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_open, "open"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_stat, "stat"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_poll, "poll"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_select, "select"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_epoll_wait, "epoll_wait"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_access, "access"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_pipe, "pipe"),
+];
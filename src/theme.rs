@@ -0,0 +1,66 @@
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+use serde::{Deserialize, Serialize};
+
+// Panels pick their colors for a dark terminal. Other themes recolor the
+// finished frame instead of every widget: `light` darkens what would be
+// unreadable on a light background and `monochrome` drops color altogether,
+// marking highlighted rows with reverse video.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    Monochrome,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::Monochrome];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::Monochrome => "monochrome",
+        }
+    }
+
+    pub fn apply(&self, buffer: &mut Buffer) {
+        match self {
+            Theme::Dark => {}
+            Theme::Light => {
+                for cell in buffer.content.iter_mut() {
+                    cell.fg = light_foreground(cell.fg);
+                    // Row highlights
+                    if cell.bg == Color::DarkGray {
+                        cell.bg = Color::Indexed(252);
+                    }
+                }
+            }
+            Theme::Monochrome => {
+                for cell in buffer.content.iter_mut() {
+                    if cell.bg != Color::Reset {
+                        cell.modifier.insert(Modifier::REVERSED);
+                    }
+                    cell.fg = Color::Reset;
+                    cell.bg = Color::Reset;
+                }
+            }
+        }
+    }
+}
+
+fn light_foreground(color: Color) -> Color {
+    match color {
+        Color::White => Color::Black,
+        Color::Gray => Color::DarkGray,
+        // Darker shades from the 256-color palette
+        Color::Yellow => Color::Indexed(136),
+        Color::Green => Color::Indexed(28),
+        Color::Cyan => Color::Indexed(30),
+        other => other,
+    }
+}
@@ -0,0 +1,165 @@
+use crate::report::cpu_time;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+use std::{collections::HashMap, fs, time::Instant};
+use sysinfo::{Pid, Process};
+
+// The threads of one process, from t on the Processes tab, for chasing thread
+// leaks and the one thread that spins: name, state and CPU of each from
+// /proc/<pid>/task/<tid>/stat, read on every refresh while the panel is open,
+// busiest first. The title counts the threads against when the panel opened,
+// since a leak shows as a climbing count. Linux only.
+#[derive(Debug, Clone)]
+pub struct Thread {
+    pub tid: u32,
+    pub name: String,
+    // R, S, D, T, Z, I as in ps
+    pub state: char,
+    // utime + stime in clock ticks
+    ticks: u64,
+    // Percent of one core since the last refresh, None the first time
+    pub cpu: Option<f64>,
+}
+
+pub struct ThreadList {
+    pub pid: Pid,
+    start_time: u64,
+    name: String,
+    pub threads: Vec<Thread>,
+    // Threads when the panel opened
+    first_count: Option<usize>,
+    read_at: Instant,
+    pub error: Option<String>,
+}
+
+impl ThreadList {
+    pub fn open(pid: Pid, process: &Process) -> ThreadList {
+        let mut list = ThreadList {
+            pid,
+            start_time: process.start_time(),
+            name: process.name().to_string_lossy().into_owned(),
+            threads: Vec::new(),
+            first_count: None,
+            read_at: Instant::now(),
+            error: None,
+        };
+        list.update(Some(process));
+        list
+    }
+
+    // process is what has the PID now, if anything
+    pub fn update(&mut self, process: Option<&Process>) {
+        if process.is_none_or(|process| process.start_time() != self.start_time) {
+            self.threads.clear();
+            self.error = Some("The process has exited.".to_string());
+            return;
+        }
+        let entries = match fs::read_dir(format!("/proc/{}/task", self.pid)) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.error = Some(format!("/proc/{}/task: {}", self.pid, err));
+                return;
+            }
+        };
+        let secs = self.read_at.elapsed().as_secs_f64();
+        self.read_at = Instant::now();
+        let previous: HashMap<u32, u64> = self.threads.iter().map(|thread| (thread.tid, thread.ticks)).collect();
+        let per_sec = clock_ticks();
+        let mut threads: Vec<Thread> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            // A thread that ended since the directory was listed
+            .filter_map(|tid| read_thread(self.pid, tid))
+            .map(|mut thread| {
+                thread.cpu = previous
+                    .get(&thread.tid)
+                    .filter(|_| secs > 0.0)
+                    .map(|before| thread.ticks.saturating_sub(*before) as f64 / per_sec / secs * 100.0);
+                thread
+            })
+            .collect();
+        threads.sort_by(|a, b| b.cpu.unwrap_or(0.0).total_cmp(&a.cpu.unwrap_or(0.0)).then(a.tid.cmp(&b.tid)));
+        self.first_count.get_or_insert(threads.len());
+        self.threads = threads;
+        self.error = None;
+    }
+
+    fn title(&self) -> String {
+        let count = self.threads.len();
+        let change = match self.first_count.map(|first| count as i64 - first as i64) {
+            Some(change) if change != 0 => format!(" ({:+} since opened)", change),
+            _ => String::new(),
+        };
+        format!("🧵 Threads of {} ({}): {}{}, busiest first · t close", self.name, self.pid, count, change)
+    }
+}
+
+// "1234 (name with spaces) S 1 ...": utime and stime are fields 14 and 15
+fn read_thread(pid: Pid, tid: u32) -> Option<Thread> {
+    let stat = fs::read_to_string(format!("/proc/{}/task/{}/stat", pid, tid)).ok()?;
+    let (head, rest) = stat.rsplit_once(')')?;
+    let (_, name) = head.split_once('(')?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let state = fields.first()?.chars().next()?;
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(Thread { tid, name: name.to_string(), state, ticks: utime + stime, cpu: None })
+}
+
+#[cfg(unix)]
+fn clock_ticks() -> f64 {
+    let per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if per_sec > 0 { per_sec as f64 } else { 100.0 }
+}
+
+#[cfg(not(unix))]
+fn clock_ticks() -> f64 {
+    100.0
+}
+
+fn state_name(state: char) -> &'static str {
+    match state {
+        'R' => "running",
+        'S' => "sleeping",
+        'D' => "disk sleep",
+        'T' => "stopped",
+        't' => "traced",
+        'Z' => "zombie",
+        'I' => "idle",
+        _ => "other",
+    }
+}
+
+pub fn draw(f: &mut Frame, area: Rect, list: &ThreadList) {
+    let block = Block::default().title(list.title()).borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan));
+    if let Some(error) = &list.error {
+        f.render_widget(Paragraph::new(error.as_str()).wrap(Wrap { trim: true }).block(block), area);
+        return;
+    }
+    let rows: Vec<Row> = list
+        .threads
+        .iter()
+        .map(|thread| {
+            let row = Row::new(vec![
+                thread.tid.to_string(),
+                thread.name.clone(),
+                state_name(thread.state).to_string(),
+                thread.cpu.map(|cpu| format!("{:.1}%", cpu)).unwrap_or_else(|| "…".to_string()),
+                cpu_time(thread.ticks as f64 / clock_ticks()),
+            ]);
+            if thread.state == 'D' {
+                row.style(Style::default().fg(Color::Red))
+            } else {
+                row
+            }
+        })
+        .collect();
+    let header = Row::new(vec!["TID", "Name", "State", "CPU %", "CPU time"]).style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [Constraint::Length(8), Constraint::Min(16), Constraint::Length(11), Constraint::Length(8), Constraint::Length(12)];
+    let table = Table::new(rows, widths).header(header).block(block).column_spacing(1);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,126 @@
+use crate::{config::Thresholds, format_bytes};
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::fs;
+
+// Memory-backed filesystems (/tmp, /run, /dev/shm, ...), which the disk table
+// leaves out. A full /run or tmpfs /tmp breaks logins, services and package
+// managers while every disk still has room, so they get their own line on the
+// Overview and their own alerts. tmpfs also runs out of inodes when a program
+// leaves many small files behind, so usage is whichever is fuller.
+#[derive(Debug, Clone)]
+pub struct TmpfsMount {
+    pub mount: String,
+    pub total: u64,
+    pub used: u64,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+}
+
+fn percent(used: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f64 / total as f64 * 100.0
+    }
+}
+
+impl TmpfsMount {
+    pub fn usage(&self) -> f64 {
+        percent(self.used, self.total).max(percent(self.inodes_used, self.inodes_total))
+    }
+
+    // Whether usage comes from the inodes; an almost empty tmpfs is about space
+    pub fn inodes_fuller(&self) -> bool {
+        let inodes = percent(self.inodes_used, self.inodes_total);
+        inodes >= 1.0 && inodes > percent(self.used, self.total)
+    }
+}
+
+#[derive(Default)]
+pub struct Tmpfs {
+    pub mounts: Vec<TmpfsMount>,
+}
+
+impl Tmpfs {
+    pub fn update(&mut self) {
+        let contents = fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+        let mut mounts: Vec<TmpfsMount> = Vec::new();
+        for line in contents.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.get(2) != Some(&"tmpfs") {
+                continue;
+            }
+            let Some(mount) = fields.get(1).map(|mount| mount.replace("\\040", " ")) else { continue };
+            // Mounted twice (bind mounts, containers), shown once
+            if mounts.iter().any(|m| m.mount == mount) {
+                continue;
+            }
+            if let Some(tmpfs) = statvfs(&mount) {
+                mounts.push(tmpfs);
+            }
+        }
+        // The ones that matter most first, then the fullest
+        let rank = |mount: &str| match mount {
+            "/tmp" => 0,
+            "/run" => 1,
+            _ => 2,
+        };
+        mounts.sort_by(|a, b| rank(&a.mount).cmp(&rank(&b.mount)).then(b.usage().total_cmp(&a.usage())));
+        self.mounts = mounts;
+    }
+
+    // "🧠 tmpfs: /tmp 12% of 3.9 GB · /run 3% of 786 MB · ...", each colored by
+    // the tmpfs thresholds
+    pub fn status(&self, thresholds: &Thresholds) -> Option<Line<'static>> {
+        if self.mounts.is_empty() {
+            return None;
+        }
+        let mut spans = vec![Span::raw("🧠 tmpfs: ")];
+        for (i, tmpfs) in self.mounts.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(" · "));
+            }
+            let usage = tmpfs.usage();
+            let style = if usage >= thresholds.tmpfs_crit {
+                Style::default().fg(Color::Red)
+            } else if usage >= thresholds.tmpfs_warn {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            let of = if tmpfs.inodes_fuller() { "of inodes".to_string() } else { format!("of {}", format_bytes(tmpfs.total)) };
+            spans.push(Span::styled(format!("{} {:.0}% {}", tmpfs.mount, usage, of), style));
+        }
+        Some(Line::from(spans))
+    }
+}
+
+// Size limit and inodes of a tmpfs; None without a limit (size=0)
+#[cfg(unix)]
+fn statvfs(mount: &str) -> Option<TmpfsMount> {
+    let path = std::ffi::CString::new(mount).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let block = stat.f_frsize as u64;
+    let total = stat.f_blocks as u64 * block;
+    if total == 0 {
+        return None;
+    }
+    Some(TmpfsMount {
+        mount: mount.to_string(),
+        total,
+        used: total.saturating_sub(stat.f_bfree as u64 * block),
+        inodes_total: stat.f_files as u64,
+        inodes_used: (stat.f_files as u64).saturating_sub(stat.f_ffree as u64),
+    })
+}
+
+#[cfg(not(unix))]
+fn statvfs(_mount: &str) -> Option<TmpfsMount> {
+    None
+}
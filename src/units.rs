@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// How sizes and temperatures are written. Formatting happens all over the
+// UI through format_bytes, so the choice is kept process-wide and set once
+// the config is loaded (or changed from the setup overlay).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteUnits {
+    // Powers of 1024
+    #[default]
+    Binary,
+    // Powers of 1000, as disk vendors count
+    Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UnitsConfig {
+    pub bytes: ByteUnits,
+    // Only how temperatures are shown; thresholds stay in °C
+    pub temperature: TemperatureUnit,
+}
+
+static DECIMAL: AtomicBool = AtomicBool::new(false);
+static FAHRENHEIT: AtomicBool = AtomicBool::new(false);
+
+pub fn apply(config: &UnitsConfig) {
+    DECIMAL.store(config.bytes == ByteUnits::Decimal, Ordering::Relaxed);
+    FAHRENHEIT.store(config.temperature == TemperatureUnit::Fahrenheit, Ordering::Relaxed);
+}
+
+// Step and unit names for format_bytes
+pub fn byte_units() -> (f64, &'static [&'static str]) {
+    if DECIMAL.load(Ordering::Relaxed) {
+        (1000.0, &["B", "kB", "MB", "GB", "TB"])
+    } else {
+        (1024.0, &["B", "KB", "MB", "GB", "TB"])
+    }
+}
+
+pub fn temperature_symbol() -> &'static str {
+    if FAHRENHEIT.load(Ordering::Relaxed) {
+        "°F"
+    } else {
+        "°C"
+    }
+}
+
+// A °C reading in the configured unit, e.g. "54.2°C" or "129.6°F"
+pub fn temperature(celsius: f32, decimals: usize) -> String {
+    let value = if FAHRENHEIT.load(Ordering::Relaxed) { celsius * 9.0 / 5.0 + 32.0 } else { celsius };
+    format!("{:.*}{}", decimals, value, temperature_symbol())
+}
@@ -0,0 +1,107 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+use std::collections::HashMap;
+use sysinfo::{System, Uid, Users};
+
+// Who owns each process, for the User column and the u filter on the
+// Processes tab. Names come from sysinfo::Users and are looked up again only
+// when a process shows up with a UID not seen before; a UID without an
+// account (a container's, a deleted user's) is shown as the number.
+#[derive(Default, Clone)]
+pub struct UserNames {
+    names: HashMap<Uid, String>,
+}
+
+impl UserNames {
+    // After every process refresh
+    pub fn update(&mut self, system: &System) {
+        let uids = || system.processes().values().filter_map(|process| process.user_id());
+        if uids().all(|uid| self.names.contains_key(uid)) {
+            return;
+        }
+        let users = Users::new_with_refreshed_list();
+        self.names = users.iter().map(|user| (user.id().clone(), user.name().to_string())).collect();
+        for uid in uids() {
+            self.names.entry(uid.clone()).or_insert_with(|| uid.to_string());
+        }
+    }
+
+    pub fn name(&self, uid: Option<&Uid>) -> String {
+        match uid {
+            Some(uid) => self.names.get(uid).cloned().unwrap_or_else(|| uid.to_string()),
+            None => "?".to_string(),
+        }
+    }
+}
+
+// One entry of the u picker
+struct Owner {
+    uid: Uid,
+    name: String,
+    processes: usize,
+    cpu: f32,
+}
+
+// u on the Processes tab: users that own processes, busiest first, with
+// "All users" on top to lift the filter
+pub struct UserPicker {
+    owners: Vec<Owner>,
+    // 0 is "All users"
+    selected: usize,
+}
+
+impl UserPicker {
+    pub fn new(system: &System, names: &UserNames, current: Option<&Uid>) -> UserPicker {
+        let mut owners: Vec<Owner> = Vec::new();
+        for process in system.processes().values() {
+            let Some(uid) = process.user_id() else { continue };
+            match owners.iter_mut().find(|owner| owner.uid == *uid) {
+                Some(owner) => {
+                    owner.processes += 1;
+                    owner.cpu += process.cpu_usage();
+                }
+                None => owners.push(Owner {
+                    uid: uid.clone(),
+                    name: names.name(Some(uid)),
+                    processes: 1,
+                    cpu: process.cpu_usage(),
+                }),
+            }
+        }
+        owners.sort_by(|a, b| b.cpu.total_cmp(&a.cpu).then(b.processes.cmp(&a.processes)));
+        let selected = current.and_then(|uid| owners.iter().position(|owner| owner.uid == *uid)).map_or(0, |index| index + 1);
+        UserPicker { owners, selected }
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        let len = self.owners.len() + 1;
+        self.selected = if down { (self.selected + 1) % len } else { (self.selected + len - 1) % len };
+    }
+
+    // None for "All users"
+    pub fn chosen(&self) -> Option<Uid> {
+        self.selected.checked_sub(1).and_then(|index| self.owners.get(index)).map(|owner| owner.uid.clone())
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect) {
+        let mut items = vec![ListItem::new("All users")];
+        items.extend(self.owners.iter().map(|owner| {
+            ListItem::new(format!(
+                "{:<20} {:>5} processes {:>7.1}% CPU",
+                owner.name, owner.processes, owner.cpu
+            ))
+        }));
+        let list = List::new(items)
+            .block(Block::default().title("👤 Show processes of — Enter choose, Esc close").borders(Borders::ALL))
+            .style(Style::default().fg(Color::White))
+            .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .highlight_symbol("▶ ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut state);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{cgroup::Cgroup, format_bytes, wsl::Wsl};
+use std::{env, fs, path::Path};
+
+// Where the monitor itself runs: bare metal, a virtual machine (and which
+// hypervisor), a container (and which runtime), or a container inside a VM.
+// Detected once at start-up much like systemd-detect-virt does it. A
+// container sees the host's kernel counters but not its sensors, and its
+// memory and CPU are whatever its cgroup limits say; a VM has no temperature
+// sensors of its own. Panels use this to say so instead of looking broken.
+const DMI: &str = "/sys/class/dmi/id";
+const CPUINFO: &str = "/proc/cpuinfo";
+
+#[derive(Debug, Clone, Default)]
+pub struct Environment {
+    // "KVM", "VMware", "Hyper-V", ...; "unknown hypervisor" when only the
+    // CPU flag says so
+    pub hypervisor: Option<String>,
+    // DMI vendor and product, e.g. "Amazon EC2 m5.large"
+    machine: Option<String>,
+    // "Docker", "Podman", "LXC", "systemd-nspawn", "Kubernetes pod", ...
+    pub container: Option<String>,
+    // cgroup limits of the container
+    memory_limit: Option<u64>,
+    cpu_limit: Option<f64>,
+    // 1 or 2 under WSL
+    wsl: Option<u8>,
+}
+
+impl Environment {
+    // None off Linux, where none of this can be read
+    pub fn detect() -> Option<Environment> {
+        if !cfg!(target_os = "linux") {
+            return None;
+        }
+        let container = container();
+        // Inside its cgroup namespace the container's cgroup is the root
+        let usage = container.as_ref().and_then(|_| Cgroup::of("self")).map(|cgroup| cgroup.usage()).unwrap_or_default();
+        Some(Environment {
+            hypervisor: hypervisor(),
+            machine: machine(),
+            container,
+            memory_limit: usage.memory_limit.map(|(limit, _)| limit),
+            cpu_limit: usage.cpu_quota.map(|(cpus, _)| cpus),
+            wsl: Wsl::detect().map(|wsl| wsl.version),
+        })
+    }
+
+    pub fn in_container(&self) -> bool {
+        self.container.is_some()
+    }
+
+    // "Docker container in a KVM virtual machine (QEMU Standard PC)"
+    pub fn describe(&self) -> String {
+        if let Some(version) = self.wsl {
+            return match version {
+                1 => "WSL1, Linux system calls run by Windows".to_string(),
+                _ => "WSL2, a Hyper-V virtual machine".to_string(),
+            };
+        }
+        let vm = self.hypervisor.as_ref().map(|hypervisor| {
+            let mut vm = format!("{} virtual machine", hypervisor);
+            if let Some(machine) = &self.machine {
+                vm.push_str(&format!(" ({})", machine));
+            }
+            vm
+        });
+        match (&self.container, vm) {
+            (Some(container), Some(vm)) => format!("{} container in a {}", container, vm),
+            (Some(container), None) => format!("{} container", container),
+            (None, Some(vm)) => vm,
+            (None, None) => "bare metal".to_string(),
+        }
+    }
+
+    // One line for the System Information panel
+    pub fn status(&self) -> String {
+        if self.wsl.is_some() {
+            return format!("🪟 Environment: {}", self.describe());
+        }
+        if !self.in_container() {
+            let icon = if self.hypervisor.is_some() { "💻" } else { "🔩" };
+            return format!("{} Environment: {}", icon, self.describe());
+        }
+        let mut limits = Vec::new();
+        match self.cpu_limit {
+            Some(cpus) if cpus.fract() == 0.0 && cpus <= 1.0 => limits.push("1 CPU".to_string()),
+            Some(cpus) if cpus.fract() == 0.0 => limits.push(format!("{} CPUs", cpus)),
+            Some(cpus) => limits.push(format!("{:.2} CPUs", cpus)),
+            None => {}
+        }
+        if let Some(memory) = self.memory_limit {
+            limits.push(format!("{} memory", format_bytes(memory)));
+        }
+        let limits = if limits.is_empty() { "no CPU or memory limit".to_string() } else { format!("limited to {}", limits.join(", ")) };
+        format!("📦 Environment: {}, {}", self.describe(), limits)
+    }
+}
+
+// systemd and container runtimes leave a marker; the cgroup of PID 1 gives
+// away the rest
+fn container() -> Option<String> {
+    // Written by systemd when it runs as PID 1 inside a container
+    if let Ok(name) = fs::read_to_string("/run/systemd/container") {
+        return Some(runtime_name(name.trim()));
+    }
+    // container=... in PID 1's environment, readable by root
+    if let Ok(environ) = fs::read("/proc/1/environ") {
+        let name = environ.split(|byte| *byte == 0).find_map(|variable| variable.strip_prefix(b"container="));
+        if let Some(name) = name.filter(|name| !name.is_empty()) {
+            return Some(runtime_name(&String::from_utf8_lossy(name)));
+        }
+    }
+    if env::var_os("KUBERNETES_SERVICE_HOST").is_some() {
+        return Some("Kubernetes pod".to_string());
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some("Podman".to_string());
+    }
+    if Path::new("/.dockerenv").exists() {
+        return Some("Docker".to_string());
+    }
+    // OpenVZ shows /proc/vz inside containers, /proc/bc only on the host
+    if Path::new("/proc/vz").exists() && !Path::new("/proc/bc").exists() {
+        return Some("OpenVZ".to_string());
+    }
+    let cgroup = fs::read_to_string("/proc/1/cgroup").unwrap_or_default();
+    [("kubepods", "Kubernetes pod"), ("docker", "Docker"), ("libpod", "Podman"), ("lxc", "LXC"), ("containerd", "containerd")]
+        .iter()
+        .find(|(marker, _)| cgroup.contains(marker))
+        .map(|(_, name)| name.to_string())
+}
+
+// The names container= uses
+fn runtime_name(name: &str) -> String {
+    match name {
+        "docker" => "Docker",
+        "podman" => "Podman",
+        "lxc" | "lxc-libvirt" => "LXC",
+        "systemd-nspawn" => "systemd-nspawn",
+        "rkt" => "rkt",
+        "wsl" => "WSL",
+        "oci" => "OCI",
+        other => return other.to_string(),
+    }
+    .to_string()
+}
+
+fn hypervisor() -> Option<String> {
+    // Xen exposes itself here, also to paravirtualized guests
+    if fs::read_to_string("/sys/hypervisor/type").is_ok_and(|kind| kind.trim() == "xen") {
+        return Some("Xen".to_string());
+    }
+    let flagged = fs::read_to_string(CPUINFO)
+        .is_ok_and(|cpuinfo| cpuinfo.lines().any(|line| line.starts_with("flags") && line.split_whitespace().any(|flag| flag == "hypervisor")));
+    if flagged {
+        if let Some(name) = cpuid_vendor() {
+            return Some(name);
+        }
+    }
+    // DMI covers hypervisors that hide from CPUID, and ARM
+    let vendors = ["sys_vendor", "product_name", "board_vendor", "bios_vendor"].map(|field| dmi(field).unwrap_or_default());
+    let by_dmi = [
+        ("QEMU", "QEMU"),
+        ("KVM", "KVM"),
+        ("VMware", "VMware"),
+        ("VirtualBox", "VirtualBox"),
+        ("innotek", "VirtualBox"),
+        ("Xen", "Xen"),
+        ("Parallels", "Parallels"),
+        ("BHYVE", "bhyve"),
+    ];
+    let found = by_dmi.iter().find(|(marker, _)| vendors.iter().any(|vendor| vendor.contains(marker)));
+    if let Some((_, name)) = found {
+        return Some(name.to_string());
+    }
+    if vendors[0] == "Microsoft Corporation" && vendors[1] == "Virtual Machine" {
+        return Some("Hyper-V".to_string());
+    }
+    flagged.then(|| "unknown hypervisor".to_string())
+}
+
+// The hypervisor's signature in CPUID leaf 0x40000000
+#[cfg(target_arch = "x86_64")]
+fn cpuid_vendor() -> Option<String> {
+    let leaf = std::arch::x86_64::__cpuid(0x4000_0000);
+    let signature: Vec<u8> = [leaf.ebx, leaf.ecx, leaf.edx].iter().flat_map(|register| register.to_le_bytes()).collect();
+    let name = match String::from_utf8_lossy(&signature).trim_end_matches('\0') {
+        "KVMKVMKVM" => "KVM",
+        "Microsoft Hv" => "Hyper-V",
+        "VMwareVMware" => "VMware",
+        "XenVMMXenVMM" => "Xen",
+        "VBoxVBoxVBox" => "VirtualBox",
+        "TCGTCGTCGTCG" => "QEMU",
+        "bhyve bhyve " => "bhyve",
+        " prl hyperv " | " lrpepyh  vr" => "Parallels",
+        "ACRNACRNACRN" => "ACRN",
+        "QNXQVMBSQG" => "QNX",
+        "Linux KVM Hv" => "KVM",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn cpuid_vendor() -> Option<String> {
+    None
+}
+
+fn dmi(field: &str) -> Option<String> {
+    let value = fs::read_to_string(Path::new(DMI).join(field)).ok()?;
+    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+// Vendor and product, without the vendor repeated
+fn machine() -> Option<String> {
+    let vendor = dmi("sys_vendor");
+    let product = dmi("product_name");
+    match (vendor, product) {
+        (Some(vendor), Some(product)) if product.starts_with(&vendor) => Some(product),
+        (Some(vendor), Some(product)) => Some(format!("{} {}", vendor, product)),
+        (vendor, product) => vendor.or(product),
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    columns::{Column, ColumnSetting},
+    columns_table,
+    freshness::ago,
+    process_row, App,
+};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row},
+    Frame,
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+use sysinfo::{Pid, Process, System};
+
+// Processes kept in a Watched section at the top of the Processes tab whether
+// or not they are among the busiest: the ones watched with W, by PID, and
+// those matching the `watched` config entries, each a PID or a name pattern
+// matched like the / filter against the name and command line, ignoring case.
+// A watched process that exits stays in the section in red for a minute, so
+// a crashed service gets noticed.
+const EXIT_SHOWN: Duration = Duration::from_secs(60);
+// Rows of the section before it scrolls off
+const MAX_ROWS: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct Exit {
+    pub pid: Pid,
+    pub name: String,
+    pub at: Instant,
+}
+
+#[derive(Default)]
+pub struct Watched {
+    // Watched with W, with the start time so the watch ends with the process
+    pids: HashMap<Pid, u64>,
+    // Watched processes at the last refresh: name and start time
+    seen: HashMap<Pid, (String, u64)>,
+    exits: Vec<Exit>,
+}
+
+impl Watched {
+    // W on the Processes tab; true when the process is now watched
+    pub fn toggle(&mut self, pid: Pid, start_time: u64) -> bool {
+        if self.pids.remove(&pid).is_some() {
+            return false;
+        }
+        self.pids.insert(pid, start_time);
+        true
+    }
+
+    // After each process refresh; patterns are the `watched` config entries
+    pub fn update(&mut self, system: &System, patterns: &[String]) {
+        let patterns: Vec<String> = patterns.iter().map(|pattern| pattern.trim().to_lowercase()).filter(|pattern| !pattern.is_empty()).collect();
+        let current: HashMap<Pid, (String, u64)> = system
+            .processes()
+            .iter()
+            .filter(|(_, process)| process.thread_kind().is_none())
+            .filter(|(pid, process)| self.pids.get(pid) == Some(&process.start_time()) || matches(&patterns, **pid, process))
+            .map(|(pid, process)| (*pid, (process.name().to_string_lossy().into_owned(), process.start_time())))
+            .collect();
+        // Leaving the section because the config changed isn't exiting
+        for (pid, (name, start_time)) in &self.seen {
+            let running = system.process(*pid).is_some_and(|process| process.start_time() == *start_time);
+            if !running {
+                self.exits.push(Exit { pid: *pid, name: name.clone(), at: Instant::now() });
+            }
+        }
+        self.pids.retain(|pid, start_time| current.get(pid).is_some_and(|(_, started)| started == start_time));
+        self.seen = current;
+        self.exits.retain(|exit| exit.at.elapsed() < EXIT_SHOWN);
+    }
+
+    // The running watched processes by PID
+    pub fn processes<'a>(&self, system: &'a System) -> Vec<(Pid, &'a Process)> {
+        let mut processes: Vec<(Pid, &Process)> = self.seen.keys().filter_map(|pid| system.process(*pid).map(|process| (*pid, process))).collect();
+        processes.sort_by_key(|(pid, _)| *pid);
+        processes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pids.is_empty() && self.seen.is_empty() && self.exits.is_empty()
+    }
+
+    // Lines the section needs: rows, header and borders
+    pub fn height(&self) -> u16 {
+        (self.seen.len() + self.exits.len()).clamp(1, MAX_ROWS) as u16 + 3
+    }
+}
+
+// A PID, or a pattern found in the name or command line
+fn matches(patterns: &[String], pid: Pid, process: &Process) -> bool {
+    patterns.iter().any(|pattern| match pattern.parse::<u32>() {
+        Ok(watched) => pid.as_u32() == watched,
+        Err(_) => {
+            process.name().to_string_lossy().to_lowercase().contains(pattern)
+                || process.cmd().iter().any(|arg| arg.to_string_lossy().to_lowercase().contains(pattern))
+        }
+    })
+}
+
+pub fn draw(f: &mut Frame, area: Rect, app: &App, columns: &[ColumnSetting], offset: usize) {
+    let watched = &app.watched;
+    let mut rows: Vec<Row> = watched.processes(&app.processes).iter().map(|(pid, process)| process_row(*pid, process, columns, app)).collect();
+    // Exits newest first, under the running ones
+    for exit in watched.exits.iter().rev() {
+        let row = message_row(columns, Some(exit.pid), format!("✖ {} exited {}", exit.name, ago(exit.at)));
+        rows.push(row.style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
+    }
+    if rows.is_empty() {
+        rows.push(message_row(columns, None, "No watched process is running".to_string()).style(Style::default().fg(Color::DarkGray)));
+    }
+    let title = format!("👁 Watched ({}) · W watch / unwatch the selected process", watched.seen.len());
+    let table = columns_table(rows, columns, offset, None).block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(table, area);
+}
+
+// The text goes in the Name column, where there is room
+fn message_row(columns: &[ColumnSetting], pid: Option<Pid>, text: String) -> Row<'static> {
+    Row::new(columns.iter().map(|c| match c.column {
+        Column::Pid => Cell::from(pid.map(|pid| pid.to_string()).unwrap_or_default()),
+        Column::Name => Cell::from(text.clone()),
+        _ => Cell::from(""),
+    }))
+}
@@ -0,0 +1,348 @@
+use crate::{format_bytes, freshness, kill::ActionResult, App};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+use serde_json::Value;
+use std::{
+    env,
+    process::{Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use sysinfo::{Pid, ProcessStatus};
+
+// The desktop's windows and the processes behind them, to find the process
+// of a frozen window and inspect or end it. Hyprland (hyprctl) and Sway
+// (swaymsg) list every window with its PID; on X11 the window manager's
+// client list is read with xprop, which finds the PID in _NET_WM_PID. Other
+// Wayland compositors (GNOME, KDE) don't let other programs list windows, so
+// there only XWayland windows show. Needs to run inside the desktop session,
+// re-listed off the UI thread every 2 seconds while the tab is open.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+// CPU share of one core above which a window's process is highlighted; a
+// window that stopped responding is often spinning
+const SPINNING: f32 = 90.0;
+
+#[derive(Debug, Clone)]
+pub struct Window {
+    pub title: String,
+    // Wayland app id or X11 class
+    pub app: String,
+    pub workspace: String,
+    pub pid: Option<u32>,
+    pub focused: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    Hyprland,
+    Sway,
+    X11,
+}
+
+impl Source {
+    // The compositor's own tool where there is one, xprop on X11 and XWayland
+    fn detect() -> Result<Source, String> {
+        if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Ok(Source::Hyprland)
+        } else if env::var_os("SWAYSOCK").is_some() {
+            Ok(Source::Sway)
+        } else if env::var_os("DISPLAY").is_some() {
+            Ok(Source::X11)
+        } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+            Err("This Wayland compositor doesn't let other programs list its windows; Hyprland and Sway do, and XWayland windows show when DISPLAY is set.".to_string())
+        } else {
+            Err("No graphical session: neither DISPLAY nor WAYLAND_DISPLAY is set. Run the monitor from a terminal inside the desktop session.".to_string())
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Source::Hyprland => "hyprctl",
+            Source::Sway => "swaymsg",
+            Source::X11 => "xprop",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Loaded {
+    windows: Option<(Result<Vec<Window>, String>, Instant)>,
+    loading: bool,
+}
+
+#[derive(Default)]
+pub struct Windows {
+    pub selected: usize,
+    loaded: Arc<Mutex<Loaded>>,
+}
+
+impl Windows {
+    // Called on refresh while the tab is open
+    pub fn reload_if_stale(&self) {
+        let mut loaded = self.loaded.lock().unwrap();
+        let fresh = loaded.windows.as_ref().is_some_and(|(_, at)| at.elapsed() < RELOAD_INTERVAL);
+        if fresh || loaded.loading {
+            return;
+        }
+        loaded.loading = true;
+        let shared = Arc::clone(&self.loaded);
+        thread::spawn(move || {
+            let windows = Source::detect().and_then(list);
+            let mut loaded = shared.lock().unwrap();
+            loaded.windows = Some((windows, Instant::now()));
+            loaded.loading = false;
+        });
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let count = self.listed().len();
+        self.selected = self.selected.saturating_add_signed(delta).min(count.saturating_sub(1));
+    }
+
+    // For Enter and k
+    pub fn selected_pid(&self) -> Option<u32> {
+        self.listed().get(self.selected).and_then(|window| window.pid)
+    }
+
+    fn listed(&self) -> Vec<Window> {
+        match &self.loaded.lock().unwrap().windows {
+            Some((Ok(windows), _)) => windows.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn windows(&self) -> Option<(Result<Vec<Window>, String>, Instant)> {
+        self.loaded.lock().unwrap().windows.clone()
+    }
+
+    fn loading(&self) -> bool {
+        self.loaded.lock().unwrap().loading
+    }
+}
+
+// Focused window first, then by workspace and title
+fn list(source: Source) -> Result<Vec<Window>, String> {
+    let mut windows = match source {
+        Source::Hyprland => parse_hyprland(&run("hyprctl", &["clients", "-j"])?)?,
+        Source::Sway => parse_sway(&run("swaymsg", &["-t", "get_tree", "-r"])?)?,
+        Source::X11 => list_x11()?,
+    };
+    windows.sort_by(|a, b| b.focused.cmp(&a.focused).then_with(|| a.workspace.cmp(&b.workspace)).then_with(|| a.title.cmp(&b.title)));
+    Ok(windows)
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| format!("could not run {}: {}", program, err))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", program, stderr.lines().last().unwrap_or("no output")));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// hyprctl clients -j: [{"pid": 1234, "title": ..., "class": ..., "workspace":
+// {"name": "1"}, "focusHistoryID": 0, "mapped": true}, ...]; focusHistoryID
+// 0 is the focused window
+fn parse_hyprland(json: &str) -> Result<Vec<Window>, String> {
+    let clients: Vec<Value> = serde_json::from_str(json).map_err(|err| format!("hyprctl output: {}", err))?;
+    Ok(clients
+        .iter()
+        .filter(|client| client["mapped"].as_bool() != Some(false))
+        .map(|client| Window {
+            title: client["title"].as_str().unwrap_or_default().to_string(),
+            app: client["class"].as_str().unwrap_or_default().to_string(),
+            workspace: client["workspace"]["name"].as_str().unwrap_or_default().to_string(),
+            pid: client["pid"].as_i64().filter(|pid| *pid > 0).map(|pid| pid as u32),
+            focused: client["focusHistoryID"].as_i64() == Some(0),
+        })
+        .collect())
+}
+
+// swaymsg -t get_tree: outputs hold workspaces, which hold containers down
+// to the windows, tiled under "nodes" and floating under "floating_nodes";
+// a window is a leaf with a pid
+fn parse_sway(json: &str) -> Result<Vec<Window>, String> {
+    let tree: Value = serde_json::from_str(json).map_err(|err| format!("swaymsg output: {}", err))?;
+    let mut windows = Vec::new();
+    collect_sway(&tree, "", &mut windows);
+    Ok(windows)
+}
+
+fn collect_sway(node: &Value, workspace: &str, windows: &mut Vec<Window>) {
+    let workspace = match node["type"].as_str() {
+        Some("workspace") => node["name"].as_str().unwrap_or_default(),
+        _ => workspace,
+    };
+    if let Some(pid) = node["pid"].as_i64() {
+        // XWayland windows have a class instead of an app id
+        let app = node["app_id"].as_str().or_else(|| node["window_properties"]["class"].as_str()).unwrap_or_default();
+        windows.push(Window {
+            title: node["name"].as_str().unwrap_or_default().to_string(),
+            app: app.to_string(),
+            workspace: workspace.to_string(),
+            pid: Some(pid as u32).filter(|pid| *pid > 0),
+            focused: node["focused"].as_bool() == Some(true),
+        });
+    }
+    for children in [&node["nodes"], &node["floating_nodes"]] {
+        for child in children.as_array().into_iter().flatten() {
+            collect_sway(child, workspace, windows);
+        }
+    }
+}
+
+// The window manager's _NET_CLIENT_LIST on the root window, then the PID,
+// title, class and desktop of each window
+fn list_x11() -> Result<Vec<Window>, String> {
+    let root = run("xprop", &["-root", "_NET_CLIENT_LIST", "_NET_ACTIVE_WINDOW"])?;
+    let clients = window_ids(&root, "_NET_CLIENT_LIST").ok_or("the window manager doesn't publish _NET_CLIENT_LIST")?;
+    let active = window_ids(&root, "_NET_ACTIVE_WINDOW").and_then(|ids| ids.first().copied());
+    let mut windows = Vec::new();
+    for id in clients {
+        let id_arg = format!("0x{:x}", id);
+        // A window closed since the list was read
+        let Ok(properties) = run("xprop", &["-id", &id_arg, "_NET_WM_PID", "_NET_WM_NAME", "WM_NAME", "WM_CLASS", "_NET_WM_DESKTOP"]) else { continue };
+        let title = property(&properties, "_NET_WM_NAME").or_else(|| property(&properties, "WM_NAME")).map(|title| unquote(&title));
+        // WM_CLASS is "instance", "Class"
+        let app = property(&properties, "WM_CLASS").and_then(|class| class.rsplit(", ").next().map(unquote));
+        windows.push(Window {
+            title: title.unwrap_or_default(),
+            app: app.unwrap_or_default(),
+            // 0xFFFFFFFF is every desktop
+            workspace: match property(&properties, "_NET_WM_DESKTOP").and_then(|desktop| desktop.parse::<u32>().ok()) {
+                Some(u32::MAX) => "all".to_string(),
+                Some(desktop) => (desktop + 1).to_string(),
+                None => String::new(),
+            },
+            pid: property(&properties, "_NET_WM_PID").and_then(|pid| pid.parse().ok()),
+            focused: active == Some(id),
+        });
+    }
+    Ok(windows)
+}
+
+// "_NET_CLIENT_LIST(WINDOW): window id # 0x1200003, 0x1400006"
+fn window_ids(output: &str, name: &str) -> Option<Vec<u64>> {
+    let line = output.lines().find(|line| line.starts_with(&format!("{}(", name)))?;
+    let (_, ids) = line.split_once('#')?;
+    Some(ids.split(',').filter_map(|id| u64::from_str_radix(id.trim().trim_start_matches("0x"), 16).ok()).filter(|id| *id != 0).collect())
+}
+
+// "_NET_WM_PID(CARDINAL) = 1234"; a missing one reads "_NET_WM_PID:  not found."
+fn property(output: &str, name: &str) -> Option<String> {
+    let line = output.lines().find(|line| line.starts_with(&format!("{}(", name)))?;
+    line.split_once(" = ").map(|(_, value)| value.to_string())
+}
+
+// xprop quotes strings and escapes quotes and backslashes in them
+fn unquote(value: &str) -> String {
+    let value = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value);
+    value.replace("\\\"", "\"").replace("\\\\", "\\")
+}
+
+// "wayland · Hyprland · WAYLAND_DISPLAY wayland-1 · DISPLAY :0"
+fn session() -> String {
+    let var = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+    let kind = var("XDG_SESSION_TYPE").or_else(|| match (var("WAYLAND_DISPLAY"), var("DISPLAY")) {
+        (Some(_), _) => Some("wayland".to_string()),
+        (None, Some(_)) => Some("x11".to_string()),
+        (None, None) => None,
+    });
+    let mut parts = vec![kind.unwrap_or_else(|| "no graphical session".to_string())];
+    parts.extend(var("XDG_CURRENT_DESKTOP"));
+    parts.extend(var("WAYLAND_DISPLAY").map(|display| format!("WAYLAND_DISPLAY {}", display)));
+    parts.extend(var("DISPLAY").map(|display| format!("DISPLAY {}", display)));
+    parts.join(" · ")
+}
+
+pub fn draw_windows_tab(f: &mut Frame, area: Rect, app: &App) {
+    let result = app.action_result.as_ref().and_then(ActionResult::text);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(u16::from(result.is_some())), Constraint::Min(0)])
+        .split(area);
+    let mut session = format!("💻 Session: {}", session());
+    match Source::detect() {
+        Ok(Source::X11) if env::var_os("WAYLAND_DISPLAY").is_some() => session.push_str(" · windows from xprop, XWayland only"),
+        Ok(source) => session.push_str(&format!(" · windows from {}", source.name())),
+        Err(_) => {}
+    }
+    f.render_widget(Paragraph::new(session).style(Style::default().fg(Color::Gray)), chunks[0]);
+    // Outcome of k, as on the Processes tab
+    if let Some((text, failed)) = result {
+        let color = if failed { Color::Red } else { Color::Green };
+        f.render_widget(Paragraph::new(text).style(Style::default().fg(color)), chunks[1]);
+    }
+
+    let windows = &app.windows;
+    let block = Block::default().borders(Borders::ALL);
+    let (listed, read_at) = match windows.windows() {
+        Some((Ok(listed), at)) => (listed, at),
+        Some((Err(err), at)) => {
+            let title = freshness::title("🪟 Windows", "listed", Some(at), windows.loading());
+            f.render_widget(Paragraph::new(err).wrap(Wrap { trim: true }).block(block.title(title)), chunks[2]);
+            return;
+        }
+        None => {
+            let title = freshness::title("🪟 Windows", "listed", None, windows.loading());
+            f.render_widget(Paragraph::new("Listing windows…").block(block.title(title)), chunks[2]);
+            return;
+        }
+    };
+
+    let rows: Vec<Row> = listed
+        .iter()
+        .map(|window| {
+            let process = window.pid.and_then(|pid| app.processes.process(Pid::from_u32(pid)));
+            let (name, cpu, memory, state) = match process {
+                Some(process) => (
+                    format!("{} ({})", process.name().to_string_lossy(), process.pid()),
+                    format!("{:.1}%", process.cpu_usage()),
+                    format_bytes(process.memory()),
+                    process.status().to_string(),
+                ),
+                None => (window.pid.map(|pid| pid.to_string()).unwrap_or_else(|| "no PID".to_string()), String::new(), String::new(), String::new()),
+            };
+            let title = if window.focused { format!("● {}", window.title) } else { window.title.clone() };
+            let row = Row::new(vec![title, window.app.clone(), window.workspace.clone(), name, cpu, memory, state]);
+            // Stopped, stuck in the kernel or spinning: likely why it froze
+            let stuck = process.is_some_and(|process| matches!(process.status(), ProcessStatus::Stop | ProcessStatus::UninterruptibleDiskSleep | ProcessStatus::Zombie));
+            match process {
+                _ if stuck => row.style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Some(process) if process.cpu_usage() >= SPINNING => row.style(Style::default().fg(Color::Yellow)),
+                _ => row,
+            }
+        })
+        .collect();
+    let header = Row::new(vec!["Window", "App", "Workspace", "Process", "CPU", "Memory", "State"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let heading = format!("🪟 Windows ({}) · Enter show its process · k end it · ↑/↓ select", listed.len());
+    let title = freshness::title(&heading, "listed", Some(read_at), windows.loading());
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(30),
+            Constraint::Length(18),
+            Constraint::Length(9),
+            Constraint::Length(26),
+            Constraint::Length(7),
+            Constraint::Length(10),
+            Constraint::Length(12),
+        ],
+    )
+    .header(header)
+    .block(block.title(title))
+    .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+    .column_spacing(1);
+    let mut state = TableState::default().with_selected((!listed.is_empty()).then_some(windows.selected));
+    f.render_stateful_widget(table, chunks[2], &mut state);
+}
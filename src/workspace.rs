@@ -0,0 +1,45 @@
+use crate::columns::ColumnSpecs;
+use crate::silence::Silence;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::PathBuf};
+
+// UI state saved on exit and restored on the next launch, stored in
+// ~/.local/share/system-monitor/workspace.toml
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Workspace {
+    pub profile: Option<String>,
+    pub tab: Option<String>,
+    pub columns: Option<ColumnSpecs>,
+    pub split_process_tables: bool,
+    pub process_sort: Option<String>,
+    pub process_sort_reversed: bool,
+    // Command lines instead of names
+    pub process_commands: bool,
+    // Grouped by "name" or "application"
+    pub process_grouping: Option<String>,
+    pub silences: Vec<Silence>,
+}
+
+impl Workspace {
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("system-monitor").join("workspace.toml"))
+    }
+
+    // A missing or unreadable workspace just means starting fresh
+    pub fn load() -> Workspace {
+        Workspace::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path = Workspace::path().ok_or("no data directory available")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::write_atomic(&path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
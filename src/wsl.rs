@@ -0,0 +1,124 @@
+use crate::format_bytes;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+// Running inside Windows Subsystem for Linux. WSL has no view of the host's
+// sensors, so those are hidden, and under WSL2 Linux runs in a VM whose size
+// Windows sets: the memory total is the VM's limit (from .wslconfig, or half
+// of Windows' memory), and the page cache Linux keeps counts against Windows
+// as memory used by the VM until it is given back, which is what the
+// autoMemoryReclaim setting controls.
+const OSRELEASE: &str = "/proc/sys/kernel/osrelease";
+const WINDOWS_USERS: &str = "/mnt/c/Users";
+
+#[derive(Debug, Clone)]
+pub struct Wsl {
+    // WSL1 translates system calls and has no VM
+    pub version: u8,
+    // [wsl2] memory
+    pub memory: Option<u64>,
+    // [experimental] autoMemoryReclaim: disabled, gradual or dropcache
+    pub auto_reclaim: String,
+}
+
+impl Wsl {
+    // None outside WSL
+    pub fn detect() -> Option<Wsl> {
+        let release = fs::read_to_string(OSRELEASE).ok()?.to_lowercase();
+        if !release.contains("microsoft") {
+            return None;
+        }
+        // "5.15.90.1-microsoft-standard-WSL2" or "4.4.0-19041-Microsoft"
+        let version = if release.contains("wsl2") || release.contains("microsoft-standard") { 2 } else { 1 };
+        let settings = find_wslconfig().and_then(|path| fs::read_to_string(path).ok()).unwrap_or_default();
+        Some(Wsl {
+            version,
+            memory: setting(&settings, "wsl2", "memory").and_then(|value| size(&value)),
+            auto_reclaim: setting(&settings, "experimental", "autoMemoryReclaim").unwrap_or_else(|| "disabled".to_string()),
+        })
+    }
+
+    // Lines for the System Information panel: the VM's memory limit and what
+    // Windows sees it using
+    pub fn status(&self, total: u64, used: u64, cached: u64) -> Vec<String> {
+        if self.version == 1 {
+            return vec!["🪟 WSL1: no VM, memory is shared with Windows directly".to_string()];
+        }
+        let mut limit = format!("🪟 WSL2 VM: memory limit {}", format_bytes(total));
+        match self.memory {
+            // The VM is a little smaller than configured; more than 10% off is a change
+            Some(memory) if memory.abs_diff(total) > memory / 10 => limit.push_str(&format!(
+                " (.wslconfig now says {}, applies after `wsl --shutdown`)",
+                format_bytes(memory)
+            )),
+            Some(_) => limit.push_str(" (.wslconfig)"),
+            None => limit.push_str(" (default: half of Windows' memory)"),
+        }
+        limit.push_str(&format!(" · {} in use", format_bytes(used)));
+        let reclaim = match self.auto_reclaim.to_lowercase().as_str() {
+            "gradual" => "is handed back gradually while idle (autoMemoryReclaim=gradual)",
+            "dropcache" => "is dropped after a while idle (autoMemoryReclaim=dropcache)",
+            _ => "stays with the VM until Linux drops it (autoMemoryReclaim=disabled)",
+        };
+        vec![
+            limit,
+            format!(
+                "🪟 Windows sees the VM using about {}: page cache ({}) {}",
+                format_bytes(used + cached),
+                format_bytes(cached),
+                reclaim
+            ),
+        ]
+    }
+}
+
+// %UserProfile%\.wslconfig: the Windows user named like this one, or the
+// only one with a .wslconfig
+fn find_wslconfig() -> Option<PathBuf> {
+    let users: Vec<PathBuf> = fs::read_dir(WINDOWS_USERS)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path().join(".wslconfig"))
+        .filter(|path| path.is_file())
+        .collect();
+    let user = env::var("USER").unwrap_or_default().to_lowercase();
+    let same_name = users.iter().find(|path| {
+        path.parent().and_then(Path::file_name).is_some_and(|name| name.to_string_lossy().to_lowercase() == user)
+    });
+    same_name.or(users.first()).cloned()
+}
+
+// key=value in an INI [section], keys compared without case like WSL does
+fn setting(contents: &str, section: &str, key: &str) -> Option<String> {
+    let mut current = String::new();
+    for line in contents.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            current = name.trim().to_lowercase();
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else { continue };
+        if current == section && name.trim().eq_ignore_ascii_case(key) {
+            // Comments may follow the value
+            let value = value.split('#').next().unwrap_or_default().trim();
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+// "8GB", "512MB", "4G": WSL counts in powers of 1024
+fn size(value: &str) -> Option<u64> {
+    let value = value.trim().to_uppercase();
+    let value = value.strip_suffix('B').unwrap_or(&value);
+    let (number, factor) = match value.chars().last()? {
+        'K' => (&value[..value.len() - 1], 1u64 << 10),
+        'M' => (&value[..value.len() - 1], 1 << 20),
+        'G' => (&value[..value.len() - 1], 1 << 30),
+        'T' => (&value[..value.len() - 1], 1 << 40),
+        _ => (value, 1),
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    Some((number * factor as f64) as u64)
+}
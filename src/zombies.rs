@@ -0,0 +1,141 @@
+use crate::freshness::ago;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Row, Table},
+    Frame,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    time::Instant,
+};
+
+// Zombie (defunct) processes: exited, but their parent never collected the
+// exit status, so they keep a PID until it does or exits itself. A few come
+// and go; a growing number means a parent that doesn't reap its children,
+// which is the process to fix or restart, so each zombie is listed with its
+// parent. Read from /proc/<pid>/stat on every refresh for the Overview count;
+// the list appears under the Processes tab while there are any. Linux only.
+#[derive(Debug, Clone)]
+pub struct Zombie {
+    pub pid: u32,
+    pub name: String,
+    pub ppid: u32,
+    // None when the parent is gone too (reparented to init by then)
+    pub parent: Option<String>,
+    // When this refresh loop first saw it defunct
+    pub since: Instant,
+}
+
+#[derive(Default)]
+pub struct Zombies {
+    pub zombies: Vec<Zombie>,
+}
+
+impl Zombies {
+    pub fn update(&mut self) {
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+        let Ok(entries) = fs::read_dir("/proc") else { return };
+        let mut names: HashMap<u32, String> = HashMap::new();
+        let mut defunct: Vec<(u32, String, u32)> = Vec::new();
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else { continue };
+            let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else { continue };
+            // "pid (comm) S ppid ...", comm may contain spaces and parentheses
+            let Some((head, rest)) = stat.rsplit_once(')') else { continue };
+            let name = head.split_once('(').map(|(_, name)| name.to_string()).unwrap_or_default();
+            let mut fields = rest.split_whitespace();
+            let state = fields.next();
+            let ppid = fields.next().and_then(|ppid| ppid.parse::<u32>().ok()).unwrap_or(0);
+            if state == Some("Z") {
+                defunct.push((pid, name.clone(), ppid));
+            }
+            names.insert(pid, name);
+        }
+        let seen: HashMap<u32, Instant> = self.zombies.iter().map(|zombie| (zombie.pid, zombie.since)).collect();
+        let now = Instant::now();
+        let mut zombies: Vec<Zombie> = defunct
+            .into_iter()
+            .map(|(pid, name, ppid)| Zombie {
+                pid,
+                name,
+                ppid,
+                parent: names.get(&ppid).cloned(),
+                since: seen.get(&pid).copied().unwrap_or(now),
+            })
+            .collect();
+        zombies.sort_by_key(|zombie| (zombie.ppid, zombie.pid));
+        self.zombies = zombies;
+    }
+
+    // "🧟 Zombies: 3, 2 of php-fpm (812) and 1 of cron (640) · see Processes"
+    pub fn status(&self) -> Option<String> {
+        if self.zombies.is_empty() {
+            return None;
+        }
+        let parents: Vec<String> = self
+            .by_parent()
+            .iter()
+            .map(|((ppid, parent), count)| format!("{} of {} ({})", count, parent, ppid))
+            .collect();
+        Some(format!("🧟 Zombies: {}, {} · see Processes", self.zombies.len(), join(&parents)))
+    }
+
+    // (ppid, parent name) → zombies, the parent with the most first
+    fn by_parent(&self) -> Vec<((u32, &str), usize)> {
+        let mut counts: BTreeMap<(u32, &str), usize> = BTreeMap::new();
+        for zombie in &self.zombies {
+            *counts.entry((zombie.ppid, zombie.parent.as_deref().unwrap_or("?"))).or_default() += 1;
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        counts
+    }
+}
+
+// "a", "a and b", "a, b and 2 more"
+fn join(parts: &[String]) -> String {
+    match parts {
+        [] => String::new(),
+        [one] => one.clone(),
+        [first, second] => format!("{} and {}", first, second),
+        [first, second, rest @ ..] => format!("{}, {} and {} more", first, second, rest.len()),
+    }
+}
+
+pub fn draw(f: &mut Frame, area: Rect, zombies: &Zombies) {
+    let rows: Vec<Row> = zombies
+        .zombies
+        .iter()
+        .map(|zombie| {
+            Row::new(vec![
+                zombie.pid.to_string(),
+                format!("{} <defunct>", zombie.name),
+                zombie.ppid.to_string(),
+                zombie.parent.clone().unwrap_or_else(|| "-".to_string()),
+                ago(zombie.since),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["PID", "Name", "PPID", "Parent", "First seen"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(8),
+        Constraint::Min(20),
+        Constraint::Length(8),
+        Constraint::Length(20),
+        Constraint::Length(14),
+    ];
+    let title = match zombies.zombies.len() {
+        1 => "🧟 Zombie processes: 1 · reaped when its parent waits for it or exits".to_string(),
+        n => format!("🧟 Zombie processes: {} · reaped when their parent waits for them or exits", n),
+    };
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL).border_style(Style::default().fg(Color::Magenta)))
+        .column_spacing(1);
+    f.render_widget(table, area);
+}